@@ -6,6 +6,12 @@ pub struct GameHistoryHinter {
     hinter: rustyline::hint::HistoryHinter,
 }
 
+impl Default for GameHistoryHinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GameHistoryHinter {
     /// Create a new GameHistoryHinter
     pub fn new() -> Self {
@@ -40,9 +46,63 @@ impl rustyline::completion::Completer for GameHistoryHinter {
 
             // Full command list for auto-completion
             let commands = [
-                "register", "r", "move", "m", "go", "attack", "a", "chat", "c", "say", "help", "h",
-                "?", "quit", "q", "exit", "up", "u", "north", "n", "down", "d", "south", "s",
-                "left", "l", "west", "w", "right", "ri", "east", "e", "ne", "nw", "se", "sw",
+                "register",
+                "r",
+                "resume",
+                "move",
+                "m",
+                "go",
+                "attack",
+                "a",
+                "chat",
+                "c",
+                "say",
+                "help",
+                "h",
+                "?",
+                "quit",
+                "q",
+                "exit",
+                "up",
+                "u",
+                "north",
+                "n",
+                "down",
+                "d",
+                "south",
+                "s",
+                "left",
+                "l",
+                "west",
+                "w",
+                "right",
+                "ri",
+                "east",
+                "e",
+                "ne",
+                "nw",
+                "se",
+                "sw",
+                "inventory",
+                "inv",
+                "i",
+                "use",
+                "drop",
+                "equip",
+                "eq",
+                "unequip",
+                "uneq",
+                "pickup",
+                "pick",
+                "trade",
+                "offer",
+                "accept",
+                "cancel",
+                "rep",
+                "reputation",
+                "quest",
+                "ability",
+                "ab",
             ];
 
             for &cmd in &commands {
@@ -142,3 +202,37 @@ impl rustyline::validate::Validator for GameHistoryHinter {
 
 // Implement the Helper trait which combines all the above traits
 impl Helper for GameHistoryHinter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyline::completion::Completer;
+    use rustyline::history::History;
+
+    fn complete(line: &str) -> Vec<String> {
+        let history = History::new();
+        let (_, candidates) = GameHistoryHinter::new()
+            .complete(line, line.len(), &Context::new(&history))
+            .unwrap();
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.replacement)
+            .collect()
+    }
+
+    #[test]
+    fn test_commands_complete_with_their_aliases() {
+        assert_eq!(complete("/res"), vec!["resume"]);
+        assert_eq!(complete("/inv"), vec!["inventory", "inv"]);
+        assert_eq!(complete("/un"), vec!["unequip", "uneq"]);
+        assert_eq!(complete("/pi"), vec!["pickup", "pick"]);
+        assert_eq!(complete("/rep"), vec!["rep", "reputation"]);
+        assert_eq!(complete("/ab"), vec!["ability", "ab"]);
+        for command in [
+            "use", "drop", "equip", "trade", "offer", "accept", "cancel", "quest",
+        ] {
+            assert!(complete(&format!("/{}", command)).contains(&command.to_string()));
+        }
+        assert!(complete("hello /inv").is_empty());
+    }
+}
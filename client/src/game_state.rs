@@ -1,10 +1,16 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::game_protocol::{Player, WorldBoundaries};
 use crate::status_monitor::StatusMonitor;
-use crate::world_lore::Faction;
+use nymquest_protocol::game_protocol::{
+    Ability, AbilityCooldown, ControlPoint, FactionStanding, LootDrop, NpcView, PlayerView,
+    QuestDefinition, TerrainMap, TradeAssets, WorldBoundaries,
+};
+use nymquest_protocol::world_lore::{CryptoItem, EquipmentSlot, Faction, InventoryItem, ItemStats};
+
+/// How many recent player snapshots to keep as baselines for deltas
+const MAX_PLAYER_SNAPSHOTS: usize = 32;
 
 /// Represents a chat message with sender, content, timestamp, and message type
 /// Uses Arc<String> for efficient sharing of strings
@@ -26,12 +32,63 @@ pub enum MessageType {
     System,
 }
 
+/// Player list update received from the server
+pub enum PlayerListUpdate {
+    /// Full snapshot of every player (a keyframe)
+    Snapshot(HashMap<String, PlayerView>),
+    /// Changes since the snapshot with sequence number `baseline_seq`
+    Delta {
+        baseline_seq: u64,
+        updated: Vec<PlayerView>,
+        removed: Vec<String>,
+    },
+}
+
+/// Items we wear and the combined stats of worn and active items
+pub struct Equipment {
+    pub slots: BTreeMap<EquipmentSlot, CryptoItem>,
+    pub stats: ItemStats,
+}
+
+/// Our open trade, as last sent by the server
+pub struct Trade {
+    pub partner_display_id: String,
+    /// Revision of the offers below; accepting confirms this revision
+    pub revision: u32,
+    pub own_offer: TradeAssets,
+    pub partner_offer: TradeAssets,
+    pub own_confirmed: bool,
+    pub partner_confirmed: bool,
+}
+
+/// Our standing with every faction, as last sent by the server
+pub struct Reputation {
+    pub standings: Vec<FactionStanding>,
+    /// Score needed to perform a faction's emote
+    pub emote_threshold: i32,
+    /// Score at which a faction hands out its signature item
+    pub item_threshold: i32,
+}
+
+/// The control points of the world and who holds them, as last sent by the server
+pub struct Territory {
+    pub control_points: Vec<ControlPoint>,
+    /// Seconds of uncontested presence needed to capture a point
+    pub capture_seconds: u32,
+}
+
 /// Structure to hold client state
 pub struct GameState {
-    pub player_id: Option<String>,
-    pub players: HashMap<String, Player>,
-    /// Reverse lookup: display_id (lowercase) -> player_id for O(1) lookups
-    pub display_id_to_player_id: HashMap<String, String>,
+    /// Our own display ID once registered; the server never reveals internal player IDs
+    pub display_id: Option<String>,
+    /// Players in the game, keyed by display ID
+    pub players: HashMap<String, PlayerView>,
+    /// Case-insensitive lookup: display_id (lowercase) -> display_id for O(1) lookups
+    pub display_id_lookup: HashMap<String, String>,
+    /// Sequence number of the update `players` was built from
+    players_seq: u64,
+    /// Recent player lists by sequence number, which deltas are applied to
+    player_snapshots: VecDeque<(u64, HashMap<String, PlayerView>)>,
     pub is_typing: bool,
     pub last_update: Instant,
     /// Chat history with most recent messages at the end
@@ -43,15 +100,49 @@ pub struct GameState {
     pub status_monitor: Arc<Mutex<StatusMonitor>>,
     /// Last whisper sender name - for reply functionality
     pub last_whisper_sender: Option<Arc<String>>,
+    /// Items we carry or have active, as last sent by the server
+    pub inventory: Vec<InventoryItem>,
+    /// What we wear, as last sent by the server
+    pub equipment: Option<Equipment>,
+    /// Items on the ground within view, as last sent by the server
+    pub loot: Vec<LootDrop>,
+    /// Credits we hold outside of trades, as last sent by the server
+    pub credits: u32,
+    /// Our open trade, if any
+    pub trade: Option<Trade>,
+    /// Our faction reputation, once the server sent it
+    pub reputation: Option<Reputation>,
+    /// Who holds the control points, once the server sent it
+    pub territory: Option<Territory>,
+    /// Terrain of our region, once the server sent it
+    pub terrain: Option<TerrainMap>,
+    /// Characters within view, as last sent by the server
+    pub npcs: Vec<NpcView>,
+    /// Quests we can take on, as last sent by the server
+    pub quest_offers: Vec<QuestDefinition>,
+    /// Quests we took on and have not completed yet, with the progress on each objective
+    pub quests: BTreeMap<String, (QuestDefinition, Vec<u32>)>,
+    /// When our surveillance exposure ends, while we are exposed
+    pub exposed_until: Option<Instant>,
+    /// When each of our abilities comes off cooldown, as last sent by the server
+    pub abilities_ready_at: BTreeMap<Ability, Instant>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameState {
     /// Create a new empty game state
     pub fn new() -> Self {
         Self {
-            player_id: None,
+            display_id: None,
             players: HashMap::with_capacity(200), // Increased capacity for better performance
-            display_id_to_player_id: HashMap::with_capacity(200), // Reverse lookup HashMap
+            display_id_lookup: HashMap::with_capacity(200), // Reverse lookup HashMap
+            players_seq: 0,
+            player_snapshots: VecDeque::with_capacity(MAX_PLAYER_SNAPSHOTS),
             is_typing: false,
             last_update: Instant::now(),
             chat_history: VecDeque::with_capacity(100), // Increased capacity
@@ -59,18 +150,31 @@ impl GameState {
             world_boundaries: None,
             status_monitor: Arc::new(Mutex::new(StatusMonitor::new())),
             last_whisper_sender: None,
+            inventory: Vec::new(),
+            equipment: None,
+            loot: Vec::new(),
+            credits: 0,
+            trade: None,
+            reputation: None,
+            territory: None,
+            terrain: None,
+            exposed_until: None,
+            npcs: Vec::new(),
+            quest_offers: Vec::new(),
+            quests: BTreeMap::new(),
+            abilities_ready_at: BTreeMap::new(),
         }
     }
 
     /// Check if the player is registered
     pub fn is_registered(&self) -> bool {
-        self.player_id.is_some()
+        self.display_id.is_some()
     }
 
     /// Get the player's faction if registered
     pub fn player_faction(&self) -> Option<Faction> {
-        if let Some(player_id) = &self.player_id {
-            if let Some(player) = self.players.get(player_id) {
+        if let Some(display_id) = &self.display_id {
+            if let Some(player) = self.players.get(display_id) {
                 return Some(player.faction.clone());
             }
         }
@@ -78,33 +182,75 @@ impl GameState {
     }
 
     /// Get the current player if registered
-    pub fn current_player(&self) -> Option<&Player> {
-        self.player_id.as_ref().and_then(|id| self.players.get(id))
+    pub fn current_player(&self) -> Option<&PlayerView> {
+        self.display_id.as_ref().and_then(|id| self.players.get(id))
     }
 
-    /// Set the player ID when registration is successful
-    pub fn set_player_id(&mut self, id: String) {
-        self.player_id = Some(id);
+    /// Set our display ID when registration is successful
+    pub fn set_display_id(&mut self, id: String) {
+        self.display_id = Some(id);
+        // Accept the next update even if a restarted server's sequence numbers went back
+        self.players_seq = 0;
         self.update_timestamp();
     }
 
-    /// Get the player ID if registered
-    pub fn get_player_id(&self) -> Option<&String> {
-        self.player_id.as_ref()
+    /// Get our display ID if registered
+    pub fn get_display_id(&self) -> Option<&String> {
+        self.display_id.as_ref()
     }
 
-    /// Update the game state with new player data
-    pub fn update_players(&mut self, players: HashMap<String, Player>) {
+    /// Update the game state with a snapshot or delta received with `seq_num`
+    ///
+    /// Returns false for a delta whose baseline is not known; the server's next
+    /// keyframe brings the player list back in sync.
+    pub fn update_players(&mut self, seq_num: u64, update: PlayerListUpdate) -> bool {
+        let players = match update {
+            PlayerListUpdate::Snapshot(players) => players,
+            PlayerListUpdate::Delta {
+                baseline_seq,
+                updated,
+                removed,
+            } => {
+                let Some((_, baseline)) = self
+                    .player_snapshots
+                    .iter()
+                    .find(|(seq, _)| *seq == baseline_seq)
+                else {
+                    return false;
+                };
+
+                let mut players = baseline.clone();
+                for display_id in &removed {
+                    players.remove(display_id);
+                }
+                for player in updated {
+                    players.insert(player.display_id.clone(), player);
+                }
+                players
+            }
+        };
+
+        self.player_snapshots.push_back((seq_num, players.clone()));
+        while self.player_snapshots.len() > MAX_PLAYER_SNAPSHOTS {
+            self.player_snapshots.pop_front();
+        }
+
+        // The mixnet may reorder updates; an older one is kept as a baseline but not shown
+        if seq_num < self.players_seq {
+            return true;
+        }
+        self.players_seq = seq_num;
         self.players = players;
 
-        // Rebuild reverse lookup HashMap for O(1) display_id lookups
-        self.display_id_to_player_id.clear();
-        for (player_id, player) in &self.players {
-            self.display_id_to_player_id
-                .insert(player.display_id.to_lowercase(), player_id.clone());
+        // Rebuild lookup HashMap for O(1) case-insensitive display_id lookups
+        self.display_id_lookup.clear();
+        for display_id in self.players.keys() {
+            self.display_id_lookup
+                .insert(display_id.to_lowercase(), display_id.clone());
         }
 
         self.update_timestamp();
+        true
     }
 
     /// Update the last update timestamp
@@ -221,18 +367,266 @@ impl GameState {
         self.world_boundaries.as_ref()
     }
 
-    /// Optimized O(1) lookup for player ID by display ID (case insensitive)
-    pub fn get_player_id_by_display_id(&self, display_id: &str) -> Option<String> {
+    /// Surveillance risk where we stand and how much of it gets past our privacy, both from 0.0 to 1.0
+    pub fn surveillance_level(&self) -> Option<(f32, f32)> {
+        let position = self.current_player()?.position;
+        let risk = self
+            .world_boundaries
+            .as_ref()?
+            .calculate_surveillance_risk(position.x, position.y)
+            .clamp(0.0, 1.0);
+        let privacy = self
+            .equipment
+            .as_ref()
+            .map_or(0.0, |equipment| equipment.stats.privacy);
+        Some((risk, risk * (1.0 - (privacy / 100.0).clamp(0.0, 1.0))))
+    }
+
+    /// Seconds until our surveillance exposure ends, if we are exposed
+    pub fn exposed_seconds(&self) -> Option<u64> {
+        self.exposed_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map(|remaining| remaining.as_secs() + 1)
+    }
+
+    /// Optimized O(1) lookup of a known display ID, ignoring case
+    pub fn find_display_id(&self, display_id: &str) -> Option<String> {
         let lowercase_target = display_id.to_lowercase();
-        self.display_id_to_player_id.get(&lowercase_target).cloned()
+        self.display_id_lookup.get(&lowercase_target).cloned()
+    }
+
+    /// Find the ID of a carried or worn item by its ID or name, ignoring case
+    pub fn find_item_id(&self, query: &str) -> Option<String> {
+        let worn = self
+            .equipment
+            .iter()
+            .flat_map(|equipment| equipment.slots.values());
+        self.inventory
+            .iter()
+            .map(|entry| &entry.item)
+            .chain(worn)
+            .find(|item| {
+                item.id.eq_ignore_ascii_case(query) || item.name.eq_ignore_ascii_case(query)
+            })
+            .map(|item| item.id.clone())
+    }
+
+    /// Note when each ability comes off cooldown, counting from now
+    pub fn set_cooldowns(&mut self, cooldowns: &[AbilityCooldown]) {
+        let now = Instant::now();
+        self.abilities_ready_at = cooldowns
+            .iter()
+            .map(|cooldown| {
+                (
+                    cooldown.ability,
+                    now + Duration::from_secs(cooldown.remaining_seconds),
+                )
+            })
+            .collect();
+    }
+
+    /// ID of the offered quest with the given ID or name, ignoring case
+    pub fn find_quest_offer(&self, query: &str) -> Option<String> {
+        self.quest_offers
+            .iter()
+            .find(|quest| {
+                quest.id.eq_ignore_ascii_case(query) || quest.name.eq_ignore_ascii_case(query)
+            })
+            .map(|quest| quest.id.clone())
+    }
+
+    /// Build a trade offer from `/offer` arguments: a number of credits and
+    /// `item[:quantity]` entries, in any order
+    ///
+    /// Items can be named by ID or, for items we carry or already offered, by a
+    /// one-word name. Returns `None` for a malformed quantity.
+    pub fn parse_trade_offer(&self, args: &[&str]) -> Option<TradeAssets> {
+        let mut offer = TradeAssets::default();
+        for arg in args {
+            if let Ok(credits) = arg.parse::<u32>() {
+                offer.credits = offer.credits.saturating_add(credits);
+                continue;
+            }
+            let (query, quantity) = match arg.rsplit_once(':') {
+                Some((query, quantity)) => (query, quantity.parse::<u32>().ok()?),
+                None => (*arg, 1),
+            };
+            if query.is_empty() || quantity == 0 {
+                return None;
+            }
+            let offered = self
+                .trade
+                .iter()
+                .flat_map(|trade| trade.own_offer.items.keys())
+                .find(|item_id| item_id.eq_ignore_ascii_case(query))
+                .cloned();
+            let item_id = self
+                .find_item_id(query)
+                .or(offered)
+                .unwrap_or_else(|| query.to_string());
+            *offer.items.entry(item_id).or_insert(0) += quantity;
+        }
+        Some(offer)
     }
 
-    /// Get connection tag for a player by ID
-    pub fn get_connection_tag(&self, player_id: &str) -> Option<String> {
+    /// Get connection tag for a player by display ID
+    pub fn get_connection_tag(&self, display_id: &str) -> Option<String> {
         // Find the player with the matching ID and return their name as the connection tag
         // Note: In the actual implementation, we may need a different field depending on how connections are tracked
         self.players
-            .get(player_id)
+            .get(display_id)
             .map(|player| player.name.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::game_protocol::Position;
+    use nymquest_protocol::world_lore::generate_crypto_items;
+
+    fn player(display_id: &str, x: f32) -> PlayerView {
+        PlayerView {
+            display_id: display_id.to_string(),
+            name: display_id.to_lowercase(),
+            position: Position::new(x, 0.0),
+            health: 100,
+            level: 1,
+            faction: Faction::Nyms,
+            effects: Default::default(),
+        }
+    }
+
+    fn snapshot(players: &[PlayerView]) -> PlayerListUpdate {
+        PlayerListUpdate::Snapshot(
+            players
+                .iter()
+                .map(|player| (player.display_id.clone(), player.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_delta_applies_to_its_baseline() {
+        let mut state = GameState::new();
+        assert!(state.update_players(10, snapshot(&[player("Alpha", 0.0), player("Beta", 0.0)])));
+
+        // Both deltas are relative to snapshot 10, so the second repeats the first change
+        let first = PlayerListUpdate::Delta {
+            baseline_seq: 10,
+            updated: vec![player("Alpha", 1.0)],
+            removed: vec![],
+        };
+        assert!(state.update_players(11, first));
+        let second = PlayerListUpdate::Delta {
+            baseline_seq: 10,
+            updated: vec![player("Alpha", 1.0), player("Gamma", 5.0)],
+            removed: vec!["Beta".to_string()],
+        };
+        assert!(state.update_players(12, second));
+
+        assert_eq!(state.players.len(), 2);
+        assert_eq!(state.players["Alpha"].position.x, 1.0);
+        assert!(state.players.contains_key("Gamma"));
+        assert_eq!(state.find_display_id("gamma"), Some("Gamma".to_string()));
+        assert_eq!(state.find_display_id("beta"), None);
+    }
+
+    #[test]
+    fn test_unknown_baseline_and_stale_updates_are_not_shown() {
+        let mut state = GameState::new();
+        assert!(state.update_players(20, snapshot(&[player("Alpha", 2.0)])));
+
+        let orphan = PlayerListUpdate::Delta {
+            baseline_seq: 5,
+            updated: vec![player("Ghost", 0.0)],
+            removed: vec![],
+        };
+        assert!(!state.update_players(21, orphan));
+        assert!(!state.players.contains_key("Ghost"));
+
+        // A snapshot delayed by the mixnet is kept as a baseline but does not replace newer state
+        assert!(state.update_players(15, snapshot(&[player("Alpha", 9.0)])));
+        assert_eq!(state.players["Alpha"].position.x, 2.0);
+        let delta = PlayerListUpdate::Delta {
+            baseline_seq: 15,
+            updated: vec![],
+            removed: vec!["Alpha".to_string()],
+        };
+        assert!(state.update_players(22, delta));
+        assert!(state.players.is_empty());
+    }
+
+    #[test]
+    fn test_privacy_shields_from_surveillance() {
+        let mut state = GameState::new();
+        assert_eq!(state.surveillance_level(), None);
+
+        state.set_display_id("Player1".to_string());
+        state.update_players(1, snapshot(&[player("Player1", 0.0)]));
+        state.set_world_boundaries(WorldBoundaries::for_region(
+            Some("Neon Harbor"),
+            -100.0,
+            100.0,
+            -100.0,
+            100.0,
+        ));
+        let (risk, unshielded) = state.surveillance_level().unwrap();
+        assert!(risk > 0.0);
+        assert_eq!(unshielded, risk);
+
+        state.equipment = Some(Equipment {
+            slots: BTreeMap::new(),
+            stats: ItemStats {
+                privacy: 50.0,
+                ..Default::default()
+            },
+        });
+        assert_eq!(state.surveillance_level(), Some((risk, risk * 0.5)));
+        assert_eq!(state.exposed_seconds(), None);
+    }
+
+    #[test]
+    fn test_items_are_found_by_id_or_name() {
+        let mut state = GameState::new();
+        let relay = generate_crypto_items().remove("mixnet_relay").unwrap();
+        state.inventory = vec![InventoryItem {
+            item: relay,
+            quantity: 1,
+            active_seconds: 0,
+        }];
+
+        assert_eq!(
+            state.find_item_id("MIXNET_RELAY"),
+            Some("mixnet_relay".to_string())
+        );
+        assert_eq!(
+            state.find_item_id("portable mixnet relay"),
+            Some("mixnet_relay".to_string())
+        );
+        assert_eq!(state.find_item_id("relay"), None);
+    }
+
+    #[test]
+    fn test_trade_offers_are_parsed() {
+        let mut state = GameState::new();
+        let relay = generate_crypto_items().remove("mixnet_relay").unwrap();
+        state.inventory = vec![InventoryItem {
+            item: relay,
+            quantity: 3,
+            active_seconds: 0,
+        }];
+
+        let offer = state
+            .parse_trade_offer(&["25", "MIXNET_RELAY:2", "zk_prover"])
+            .unwrap();
+        assert_eq!(offer.credits, 25);
+        assert_eq!(offer.items["mixnet_relay"], 2);
+        // Items we do not know of are left for the server to check
+        assert_eq!(offer.items["zk_prover"], 1);
+
+        assert!(state.parse_trade_offer(&[]).unwrap().is_empty());
+        assert!(state.parse_trade_offer(&["mixnet_relay:0"]).is_none());
+        assert!(state.parse_trade_offer(&["mixnet_relay:lots"]).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! Trust-on-first-use pinning of server public keys
+//!
+//! The first key seen for a server address is remembered, and a different key
+//! for the same address is refused instead of silently trusted, since anyone
+//! able to swap the discovery file could otherwise impersonate the server.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{info, warn};
+
+use nymquest_protocol::session::ServerPublicKey;
+
+/// Environment variable overriding the location of the pinned key file
+pub const KNOWN_SERVERS_ENV_VAR: &str = "NYMQUEST_CLIENT_KNOWN_SERVERS_FILE";
+
+/// Default filename for pinned server keys
+pub const KNOWN_SERVERS_FILENAME: &str = "known_servers";
+
+/// Get the location of the pinned key file
+/// The environment variable overrides the platform-specific data directory
+pub fn get_known_servers_path() -> Result<PathBuf> {
+    if let Ok(custom_path) = env::var(KNOWN_SERVERS_ENV_VAR) {
+        return Ok(PathBuf::from(custom_path));
+    }
+
+    let base_dir = dirs_next::data_dir()
+        .or_else(dirs_next::home_dir)
+        .ok_or_else(|| anyhow!("Cannot determine user data directory"))?;
+    Ok(base_dir
+        .join("nymquest")
+        .join("client")
+        .join(KNOWN_SERVERS_FILENAME))
+}
+
+/// Server keys pinned by this client, one `address;key` line per server
+pub struct KnownServers {
+    path: PathBuf,
+    pins: HashMap<String, ServerPublicKey>,
+}
+
+impl KnownServers {
+    /// Load pinned keys from `path`; a missing file means nothing is pinned yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut pins = HashMap::new();
+
+        if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read known servers file {:?}", path))?;
+
+            for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let (address, key) = line
+                    .split_once(';')
+                    .ok_or_else(|| anyhow!("Malformed entry in known servers file {:?}", path))?;
+                let key = ServerPublicKey::from_str(key)
+                    .with_context(|| format!("Invalid key in known servers file {:?}", path))?;
+                pins.insert(address.to_string(), key);
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            pins,
+        })
+    }
+
+    /// Accept `key` for `server_address` if it matches the pinned key, or pin it
+    /// if this server has not been seen before
+    pub fn check_or_pin(&mut self, server_address: &str, key: &ServerPublicKey) -> Result<()> {
+        match self.pins.get(server_address) {
+            Some(pinned) if pinned == key => Ok(()),
+            Some(pinned) => {
+                warn!("Server public key changed for {}", server_address);
+                Err(anyhow!(
+                    "The server at {} presented public key {}, but {} was pinned on first \
+                     connection. Someone may be impersonating the server. If the operator \
+                     deliberately reset the server identity, remove its line from {:?} \
+                     and connect again",
+                    server_address,
+                    key,
+                    pinned,
+                    self.path
+                ))
+            }
+            None => {
+                self.pins.insert(server_address.to_string(), *key);
+                self.save()?;
+                info!("Pinned public key of new server {}", server_address);
+                Ok(())
+            }
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        let mut entries: Vec<String> = self
+            .pins
+            .iter()
+            .map(|(address, key)| format!("{};{}", address, key))
+            .collect();
+        entries.sort();
+
+        fs::write(&self.path, entries.join("\n") + "\n")
+            .with_context(|| format!("Failed to write known servers file {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::session::ServerIdentity;
+
+    #[test]
+    fn test_first_key_is_pinned_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(KNOWN_SERVERS_FILENAME);
+        let key = ServerIdentity::generate().public_key();
+
+        KnownServers::load(&path)
+            .unwrap()
+            .check_or_pin("server.nym", &key)
+            .unwrap();
+
+        // The same key is accepted again after reloading
+        let mut reloaded = KnownServers::load(&path).unwrap();
+        assert!(reloaded.check_or_pin("server.nym", &key).is_ok());
+    }
+
+    #[test]
+    fn test_changed_key_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(KNOWN_SERVERS_FILENAME);
+        let mut known = KnownServers::load(&path).unwrap();
+        known
+            .check_or_pin("server.nym", &ServerIdentity::generate().public_key())
+            .unwrap();
+
+        let impostor = ServerIdentity::generate().public_key();
+        let error = known.check_or_pin("server.nym", &impostor).unwrap_err();
+        assert!(error.to_string().contains("impersonating"));
+
+        // Other servers are pinned independently
+        assert!(known.check_or_pin("other.nym", &impostor).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(KNOWN_SERVERS_FILENAME);
+        fs::write(&path, "server.nym without a key\n").unwrap();
+
+        assert!(KnownServers::load(&path).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! NymQuest terminal client
+//!
+//! The binary in `main.rs` is a thin shell around these modules; they are
+//! exposed as a library so integration tests can drive the real
+//! `NetworkManager` against an in-process server.
+
+pub mod command_completer;
+pub mod config;
+pub mod game_state;
+pub mod key_pinning;
+pub mod message_replay;
+pub mod mixnet_health;
+pub mod network;
+pub mod reclaim_tokens;
+pub mod renderer;
+pub mod status_monitor;
+pub mod ui_components;
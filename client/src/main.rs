@@ -1,25 +1,10 @@
-mod command_completer;
-mod config;
-mod discovery;
-mod game_protocol;
-mod game_state;
-mod message_auth;
-mod message_padding;
-mod message_replay;
-mod mixnet_health;
-mod network;
-mod renderer;
-mod status_monitor;
-mod ui_components;
-mod world_lore;
-
 // Application constants
 const USER_INPUT_CHANNEL_BUFFER: usize = 256;
 const TYPING_STATE_CHANNEL_BUFFER: usize = 32;
 const HEARTBEAT_CHECK_INTERVAL_MS: u64 = 1000;
 const DEFAULT_PACING_INTERVAL_MS: u64 = 100;
 
-use crate::world_lore::Faction;
+use nymquest_protocol::world_lore::{EquipmentSlot, Faction};
 
 use colored::*;
 use rustyline::error::ReadlineError;
@@ -31,12 +16,16 @@ use tokio::{task, time};
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
-use command_completer::GameHistoryHinter;
-use config::ClientConfig;
-use game_protocol::{ClientMessage, Direction, ProtocolVersion, ServerMessage};
-use game_state::GameState;
-use network::NetworkManager;
-use ui_components::{clear_screen, render_game_state, render_help_section};
+use nym_mmorpg_client::command_completer::GameHistoryHinter;
+use nym_mmorpg_client::config::ClientConfig;
+use nym_mmorpg_client::game_state::{
+    Equipment, GameState, PlayerListUpdate, Reputation, Territory, Trade,
+};
+use nym_mmorpg_client::network::NetworkManager;
+use nym_mmorpg_client::ui_components::{clear_screen, render_game_state, render_help_section};
+use nymquest_protocol::game_protocol::{
+    Ability, ClientMessage, Direction, EmoteType, ProtocolVersion, ServerMessage,
+};
 
 /// Initialize structured logging for the client
 fn init_logging() -> anyhow::Result<()> {
@@ -99,6 +88,17 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // Point returning players at their saved character
+    if network.reclaim_token().is_some() {
+        if let Ok(mut state) = game_state.lock() {
+            state.add_system_message(
+                "System".to_string(),
+                "You have a saved character on this server. Use /resume to continue with it."
+                    .to_string(),
+            );
+        }
+    }
+
     // Create a channel for user input commands (increased buffer for better performance)
     let (tx, mut rx) = mpsc::channel::<String>(USER_INPUT_CHANNEL_BUFFER);
 
@@ -296,13 +296,9 @@ async fn handle_register_command(
     let faction_input = command_parts[2].trim().to_lowercase();
 
     // Parse faction selection
-    let faction = match faction_input.as_str() {
-        "nyms" => Faction::Nyms,
-        "corporate" | "corp" | "hegemony" => Faction::CorporateHegemony,
-        "cipher" | "collective" | "ciphercollective" => Faction::CipherCollective,
-        "monks" | "algorithm" | "algorithmmonks" => Faction::AlgorithmMonks,
-        "independent" | "indie" | "free" => Faction::Independent,
-        _ => {
+    let faction = match Faction::from_str(&faction_input) {
+        Some(faction) => faction,
+        None => {
             if let Ok(mut state) = game_state.lock() {
                 state.add_system_message(
                     "System".to_string(),
@@ -327,6 +323,7 @@ async fn handle_register_command(
         faction: faction.clone(), // Add selected faction to registration message
         protocol_version: ProtocolVersion::default(),
         seq_num: 0, // Placeholder, will be replaced by NetworkManager
+        session_public_key: String::new(), // Filled in by NetworkManager
     };
 
     // Show registration attempt message in UI
@@ -347,6 +344,67 @@ async fn handle_register_command(
     Ok(())
 }
 
+/// Handle the resume command, reclaiming the character saved for this server
+async fn handle_resume_command(
+    network: &mut NetworkManager,
+    game_state: &Arc<Mutex<GameState>>,
+) -> anyhow::Result<()> {
+    let token = network.reclaim_token().map(str::to_string);
+
+    if let Ok(mut state) = game_state.lock() {
+        if state.is_registered() {
+            state.add_system_message(
+                "System".to_string(),
+                "You are already playing a character.".to_string(),
+            );
+            render_game_state(&state);
+            return Ok(());
+        }
+
+        if token.is_none() {
+            state.add_system_message(
+                "System".to_string(),
+                "No saved character for this server. Use /register <name> <faction> to create one."
+                    .to_string(),
+            );
+            render_game_state(&state);
+            return Ok(());
+        }
+
+        if let Ok(mut monitor) = state.status_monitor.lock() {
+            monitor.update_game_state_info("Resuming saved character...".to_string());
+        }
+
+        state.add_system_message(
+            "System".to_string(),
+            "Reclaiming your saved character. Please wait...".to_string(),
+        );
+        render_game_state(&state);
+    } else {
+        println!(
+            "{}",
+            "Failed to access game state for resume. Please restart the client.".red()
+        );
+        return Ok(());
+    }
+
+    let Some(token) = token else {
+        return Ok(());
+    };
+
+    // Sequence number and session key are filled in by NetworkManager
+    let resume_msg = ClientMessage::Resume {
+        token,
+        seq_num: 0,
+        protocol_version: ProtocolVersion::default(),
+        session_public_key: String::new(),
+    };
+    network.send_message(resume_msg).await?;
+
+    println!("{}", "Resume request sent...".cyan());
+    Ok(())
+}
+
 /// Process a command entered by the user
 async fn process_user_command(
     input: &str,
@@ -368,19 +426,22 @@ async fn process_user_command(
         "register" | "r" => {
             handle_register_command(&command_parts, network, game_state).await?;
         }
+        "resume" => {
+            handle_resume_command(network, game_state).await?;
+        }
         // Movement commands
         "move" | "m" | "go" => {
             // Check if player is registered
-            let player_id = {
+            let display_id = {
                 if let Ok(state) = game_state.lock() {
-                    state.get_player_id().map(|id| id.to_string())
+                    state.get_display_id().map(|id| id.to_string())
                 } else {
                     error!("Failed to access game state for player ID. Please restart the client.");
                     return Ok(());
                 }
             };
 
-            if player_id.is_none() {
+            if display_id.is_none() {
                 info!("You need to register first!");
                 return Ok(());
             }
@@ -410,12 +471,12 @@ async fn process_user_command(
                     let _predicted_pos = match game_state.lock() {
                         Ok(mut state) => {
                             // Check if we have a player ID and clone it to avoid borrow issues
-                            if let Some(player_id) = state.player_id.clone() {
+                            if let Some(display_id) = state.display_id.clone() {
                                 // Get world boundaries first (before mutable borrow)
                                 let boundaries = state.get_world_boundaries().cloned();
 
                                 // Now get a mutable reference to the player
-                                if let Some(player) = state.players.get_mut(&player_id) {
+                                if let Some(player) = state.players.get_mut(&display_id) {
                                     let mut new_pos = player.position;
 
                                     // Use the configured movement speed
@@ -562,23 +623,15 @@ async fn process_user_command(
                 return Ok(());
             }
 
-            let target_display_id = command_parts[1].to_string();
             let message_text = command_parts[2..].join(" ");
 
-            // Verify the target player exists
-            let player_id = if let Ok(state) = game_state.lock() {
-                state.get_player_id_by_display_id(&target_display_id)
-            } else {
-                None
-            };
-
-            if player_id.is_none() {
-                info!(
-                    "Player with display name '{}' not found.",
-                    target_display_id
-                );
-                return Ok(());
-            }
+            // Players out of view are not in our player list, so unknown names are sent as
+            // typed and the server reports players that do not exist
+            let target_display_id = game_state
+                .lock()
+                .ok()
+                .and_then(|state| state.find_display_id(command_parts[1]))
+                .unwrap_or_else(|| command_parts[1].to_string());
 
             // Create the whisper message
             let whisper_msg = ClientMessage::Whisper {
@@ -614,13 +667,13 @@ async fn process_user_command(
                     None => "Register with a faction to unlock specialty emotes"
                 };
 
-                info!("Usage: emote <type>\nStandard emotes: wave, bow, laugh, dance, salute, shrug, cheer, clap, thumbsup\nCypherpunk emotes: hack, encrypt, decrypt, surveillance, resist, ghost, datadrop, glitch\n{}", 
+                info!("Usage: emote <type>\nStandard emotes: wave, bow, laugh, dance, salute, shrug, cheer, clap, thumbsup\nCypherpunk emotes: hack, encrypt, decrypt, surveillance, resist, ghost, datadrop, glitch\nFaction emotes (unlocked by reputation, see /rep): cloak, audit, leak, meditate, wander\n{}", 
                     faction_specific_help);
                 return Ok(());
             }
 
             let emote_name = command_parts[1].to_lowercase();
-            if let Some(emote_type) = game_protocol::EmoteType::from_str(&emote_name) {
+            if let Some(emote_type) = EmoteType::from_str(&emote_name) {
                 let emote_msg = ClientMessage::Emote {
                     emote_type,
                     seq_num: 0, // Will be set by NetworkManager
@@ -629,7 +682,7 @@ async fn process_user_command(
                 network.send_message(emote_msg).await?;
                 info!("Emote '{}' sent...", emote_name);
             } else {
-                info!("Invalid emote type!\nStandard emotes: wave, bow, laugh, dance, salute, shrug, cheer, clap, thumbsup\nCypherpunk emotes: hack, encrypt, decrypt, surveillance, resist, ghost, datadrop, glitch\n(Your faction specialty: {}) ", 
+                info!("Invalid emote type!\nStandard emotes: wave, bow, laugh, dance, salute, shrug, cheer, clap, thumbsup\nCypherpunk emotes: hack, encrypt, decrypt, surveillance, resist, ghost, datadrop, glitch\nFaction emotes (unlocked by reputation, see /rep): cloak, audit, leak, meditate, wander\n(Your faction specialty: {}) ", 
                     match game_state.lock().ok().and_then(|state| state.player_faction()) {
                         Some(Faction::Nyms) => "encrypt, ghost (enhanced privacy effects)",
                         Some(Faction::CorporateHegemony) => "surveillance, datadrop (corporate style)",
@@ -641,6 +694,316 @@ async fn process_user_command(
                 );
             }
         }
+        // Reputation command
+        "rep" | "reputation" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before factions know you.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            network
+                .send_message(ClientMessage::Reputation { seq_num: 0 })
+                .await?;
+            info!("Reputation request sent...");
+        }
+        // Inventory commands
+        "inventory" | "inv" | "i" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can carry items.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            network
+                .send_message(ClientMessage::Inventory { seq_num: 0 })
+                .await?;
+            info!("Inventory request sent...");
+        }
+        "use" | "drop" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can use items.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            if command_parts.len() < 2 {
+                info!("Usage: {} <item id or name>", command_parts[0]);
+                return Ok(());
+            }
+
+            // Items we know we carry can be named in full; anything else is sent as
+            // typed and the server reports items we do not have
+            let query = command_parts[1..].join(" ");
+            let item_id = game_state
+                .lock()
+                .ok()
+                .and_then(|state| state.find_item_id(&query))
+                .unwrap_or(query);
+
+            let item_msg = if command_parts[0] == "use" {
+                ClientMessage::UseItem {
+                    item_id: item_id.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                }
+            } else {
+                ClientMessage::DropItem {
+                    item_id: item_id.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                }
+            };
+            network.send_message(item_msg).await?;
+            info!("Request to {} '{}' sent...", command_parts[0], item_id);
+        }
+        "equip" | "eq" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can equip items.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            if command_parts.len() < 2 {
+                info!("Usage: equip <item id or name>");
+                return Ok(());
+            }
+
+            let query = command_parts[1..].join(" ");
+            let item_id = game_state
+                .lock()
+                .ok()
+                .and_then(|state| state.find_item_id(&query))
+                .unwrap_or(query);
+
+            network
+                .send_message(ClientMessage::Equip {
+                    item_id: item_id.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                })
+                .await?;
+            info!("Request to equip '{}' sent...", item_id);
+        }
+        "unequip" | "uneq" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can unequip items.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            match command_parts
+                .get(1)
+                .and_then(|slot| EquipmentSlot::from_str(slot))
+            {
+                Some(slot) => {
+                    network
+                        .send_message(ClientMessage::Unequip {
+                            slot,
+                            seq_num: 0, // Will be set by NetworkManager
+                        })
+                        .await?;
+                    info!("Request to unequip your {} slot sent...", slot.name());
+                }
+                None => info!("Usage: unequip <attack|defense|privacy|artifact>"),
+            }
+        }
+        "pickup" | "pick" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can pick up items.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            network
+                .send_message(ClientMessage::PickUp { seq_num: 0 })
+                .await?;
+            info!("Request to pick up the nearest item sent...");
+        }
+        // Trade commands
+        "trade" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can trade.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            if command_parts.len() < 2 {
+                info!("Usage: trade <player_display_id>");
+                return Ok(());
+            }
+
+            let target_display_id = game_state
+                .lock()
+                .ok()
+                .and_then(|state| state.find_display_id(command_parts[1]))
+                .unwrap_or_else(|| command_parts[1].to_string());
+            network
+                .send_message(ClientMessage::TradeRequest {
+                    target_display_id: target_display_id.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                })
+                .await?;
+            info!("Trade request sent to '{}'...", target_display_id);
+        }
+        "offer" | "accept" => {
+            let (partner, revision, offer) = match game_state.lock() {
+                Ok(state) => match &state.trade {
+                    Some(trade) => (
+                        trade.partner_display_id.clone(),
+                        trade.revision,
+                        state.parse_trade_offer(&command_parts[1..]),
+                    ),
+                    None => {
+                        info!("You have no open trade. Use /trade <player> to start one.");
+                        return Ok(());
+                    }
+                },
+                Err(_) => {
+                    error!("Failed to access game state. Please restart the client.");
+                    return Ok(());
+                }
+            };
+
+            let trade_msg = if command_parts[0] == "accept" {
+                ClientMessage::TradeAccept {
+                    target_display_id: partner.clone(),
+                    revision,
+                    seq_num: 0, // Will be set by NetworkManager
+                }
+            } else {
+                let Some(offer) = offer else {
+                    info!("Usage: offer [credits] [item[:quantity]]...");
+                    return Ok(());
+                };
+                ClientMessage::TradeOffer {
+                    target_display_id: partner.clone(),
+                    offer,
+                    seq_num: 0, // Will be set by NetworkManager
+                }
+            };
+            network.send_message(trade_msg).await?;
+            info!("Trade {} sent to '{}'...", command_parts[0], partner);
+        }
+        "cancel" => {
+            // Pending requests are not tracked locally, so they are cancelled by name
+            let partner = match (game_state.lock(), command_parts.get(1)) {
+                (Ok(state), Some(name)) => state
+                    .find_display_id(name)
+                    .unwrap_or_else(|| name.to_string()),
+                (Ok(state), None) => match &state.trade {
+                    Some(trade) => trade.partner_display_id.clone(),
+                    None => {
+                        info!("Usage: cancel <player_display_id>");
+                        return Ok(());
+                    }
+                },
+                (Err(_), _) => {
+                    error!("Failed to access game state. Please restart the client.");
+                    return Ok(());
+                }
+            };
+
+            network
+                .send_message(ClientMessage::TradeCancel {
+                    target_display_id: partner.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                })
+                .await?;
+            info!("Request to cancel the trade with '{}' sent...", partner);
+        }
+        // Ability commands
+        "ability" | "ab" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can use abilities.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            let Some(ability) = command_parts
+                .get(1)
+                .and_then(|name| Ability::from_str(name))
+            else {
+                info!("Usage: ability <encrypt|hack|ghost|datadrop> [player_id]");
+                return Ok(());
+            };
+            let target = command_parts.get(2).map(|target| target.to_string());
+
+            network
+                .send_message(ClientMessage::UseAbility {
+                    ability,
+                    target: target.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                })
+                .await?;
+            match target {
+                Some(target) => info!("{} on '{}' sent...", ability.name(), target),
+                None => info!("{} sent...", ability.name()),
+            }
+        }
+        // Quest commands
+        "quest" => {
+            if let Ok(state) = game_state.lock() {
+                if !state.is_registered() {
+                    info!("You need to register first before you can take on quests.");
+                    return Ok(());
+                }
+            } else {
+                error!("Failed to access game state for registration check. Please restart the client.");
+                return Ok(());
+            }
+
+            if command_parts.len() < 2 {
+                info!("Usage: quest <quest id or name>");
+                return Ok(());
+            }
+
+            // Quests we know are offered can be named in full; anything else is sent as
+            // typed and the server reports quests it does not offer us
+            let query = command_parts[1..].join(" ");
+            let quest_id = game_state
+                .lock()
+                .ok()
+                .and_then(|state| state.find_quest_offer(&query))
+                .unwrap_or(query);
+
+            network
+                .send_message(ClientMessage::QuestAccept {
+                    quest_id: quest_id.clone(),
+                    seq_num: 0, // Will be set by NetworkManager
+                })
+                .await?;
+            info!("Request to take on quest '{}' sent...", quest_id);
+        }
         // Exit commands
         "exit" | "quit" | "q" => {
             // Perform proper network disconnection which will send the disconnect message
@@ -664,12 +1027,11 @@ async fn process_user_command(
             }
 
             // Get the last whisper sender from game state
-            let (last_whisper_sender, player_id) = if let Ok(state) = game_state.lock() {
+            let (last_whisper_sender, sender_display_id) = if let Ok(state) = game_state.lock() {
                 let sender = state.get_last_whisper_sender().map(|s| s.to_string());
-                let player_id = sender
-                    .as_ref()
-                    .and_then(|name| state.get_player_id_by_display_id(name));
-                (sender, player_id)
+                let sender_display_id =
+                    sender.as_ref().and_then(|name| state.find_display_id(name));
+                (sender, sender_display_id)
             } else {
                 error!("Failed to access game state. Please restart the client.");
                 return Ok(());
@@ -677,12 +1039,12 @@ async fn process_user_command(
 
             if let Some(sender) = last_whisper_sender {
                 // Check if we can get the connection tag for the player
-                let connection_tag = if let (Some(pid), Ok(state)) = (&player_id, game_state.lock())
-                {
-                    state.get_connection_tag(pid)
-                } else {
-                    None
-                };
+                let connection_tag =
+                    if let (Some(id), Ok(state)) = (&sender_display_id, game_state.lock()) {
+                        state.get_connection_tag(id)
+                    } else {
+                        None
+                    };
 
                 // Construct the whisper message
                 let message_text = command_parts[1..].join(" ");
@@ -861,16 +1223,16 @@ async fn handle_movement_direction(
     config: &ClientConfig,
 ) -> anyhow::Result<()> {
     // Check if player is registered
-    let player_id = {
+    let display_id = {
         if let Ok(state) = game_state.lock() {
-            state.get_player_id().map(|id| id.to_string())
+            state.get_display_id().map(|id| id.to_string())
         } else {
             error!("Failed to access game state for player ID. Please restart the client.");
             return Ok(());
         }
     };
 
-    if player_id.is_none() {
+    if display_id.is_none() {
         info!("You need to register first!");
         return Ok(());
     }
@@ -892,12 +1254,12 @@ async fn handle_movement_direction(
         let _predicted_pos = match game_state.lock() {
             Ok(mut state) => {
                 // Check if we have a player ID and clone it to avoid borrow issues
-                if let Some(player_id) = state.player_id.clone() {
+                if let Some(display_id) = state.display_id.clone() {
                     // Get world boundaries first (before mutable borrow)
                     let boundaries = state.get_world_boundaries().cloned();
 
                     // Now get a mutable reference to the player
-                    if let Some(player) = state.players.get_mut(&player_id) {
+                    if let Some(player) = state.players.get_mut(&display_id) {
                         let mut new_pos = player.position;
 
                         // Use the configured movement speed from client config (should match server)
@@ -991,39 +1353,38 @@ fn process_server_message(
             std::process::exit(0);
         }
         ServerMessage::RegisterAck {
-            player_id,
+            display_id,
             world_boundaries,
             negotiated_version: _,
             seq_num: _,
+            reclaim_token: _, // Saved by NetworkManager for /resume
         } => {
             if let Ok(mut state) = game_state.lock() {
-                state.set_player_id(player_id.clone());
+                state.set_display_id(display_id.clone());
                 state.set_world_boundaries(world_boundaries);
                 state.add_system_message(
                     "System".to_string(),
                     "Registration successful! Welcome to NymQuest!".to_string(),
                 );
                 info!(
-                    "Registration successful! Your player ID is: {}",
-                    player_id.green()
+                    "Registration successful! You are known as: {}",
+                    display_id.green()
                 );
             } else {
                 error!("Failed to update game state with registration info");
             }
             true // Force UI refresh after successful registration
         }
-        ServerMessage::GameState {
-            players,
-            seq_num: _,
-        } => {
+        ServerMessage::GameState { players, seq_num } => {
             if let Ok(mut state) = game_state.lock() {
-                state.update_players(players.clone());
+                let player_count = players.len();
+                state.update_players(seq_num, PlayerListUpdate::Snapshot(players));
 
                 // Update the status monitor with game state info for UI display
                 if let Ok(mut monitor) = state.status_monitor.lock() {
                     monitor.update_game_state_info(format!(
                         "Game state updated - {} players online",
-                        players.len()
+                        player_count
                     ));
                 }
             } else {
@@ -1031,6 +1392,60 @@ fn process_server_message(
             }
             true // Force UI refresh when game state updates
         }
+        ServerMessage::GameStateDelta {
+            baseline_seq,
+            updated,
+            removed,
+            seq_num,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                let delta = PlayerListUpdate::Delta {
+                    baseline_seq,
+                    updated,
+                    removed,
+                };
+                if !state.update_players(seq_num, delta) {
+                    debug!(
+                        "Skipped game state delta against unknown baseline {}; waiting for the next keyframe",
+                        baseline_seq
+                    );
+                    return false;
+                }
+
+                if let Ok(mut monitor) = state.status_monitor.lock() {
+                    monitor.update_game_state_info(format!(
+                        "Game state updated - {} players online",
+                        state.players.len()
+                    ));
+                }
+            } else {
+                error!("Failed to update game state with players info");
+            }
+            true
+        }
+        ServerMessage::VisibilityChanged {
+            entered,
+            left,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                for display_id in entered {
+                    state.add_system_message(
+                        "System".to_string(),
+                        format!("{} came into view", display_id),
+                    );
+                }
+                for display_id in left {
+                    state.add_system_message(
+                        "System".to_string(),
+                        format!("{} went out of view", display_id),
+                    );
+                }
+            } else {
+                error!("Failed to add visibility changes to game state");
+            }
+            true
+        }
         ServerMessage::ChatMessage {
             sender_name,
             message,
@@ -1087,6 +1502,265 @@ fn process_server_message(
             error!("Error: {}", message.red());
             true
         }
+        ServerMessage::Inventory {
+            items,
+            credits,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.inventory = items;
+                state.credits = credits;
+            } else {
+                error!("Failed to update inventory in game state");
+            }
+            true
+        }
+        ServerMessage::ItemUsed {
+            name,
+            active_seconds,
+            ..
+        } => {
+            let message = format!("You used {} ({}s active)", name, active_seconds);
+            if let Ok(mut state) = game_state.lock() {
+                state.add_system_message("Inventory".to_string(), message.clone());
+            } else {
+                error!("Failed to add item message to game state");
+            }
+            info!("{}", message.green());
+            true
+        }
+        ServerMessage::Equipment {
+            slots,
+            stats,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.equipment = Some(Equipment { slots, stats });
+            } else {
+                error!("Failed to update equipment in game state");
+            }
+            true
+        }
+        ServerMessage::Loot { drops, seq_num: _ } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.loot = drops;
+            } else {
+                error!("Failed to update loot in game state");
+            }
+            true
+        }
+        ServerMessage::TradeUpdate {
+            partner_display_id,
+            revision,
+            own_offer,
+            partner_offer,
+            own_confirmed,
+            partner_confirmed,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.trade = Some(Trade {
+                    partner_display_id,
+                    revision,
+                    own_offer,
+                    partner_offer,
+                    own_confirmed,
+                    partner_confirmed,
+                });
+            } else {
+                error!("Failed to update trade in game state");
+            }
+            true
+        }
+        ServerMessage::TradeClosed {
+            partner_display_id,
+            completed,
+            reason,
+            seq_num: _,
+        } => {
+            let message = format!("Trade with {} closed: {}", partner_display_id, reason);
+            if let Ok(mut state) = game_state.lock() {
+                state.trade = None;
+                state.add_system_message("Trade".to_string(), message.clone());
+            } else {
+                error!("Failed to close trade in game state");
+            }
+            if completed {
+                info!("{}", message.green());
+            } else {
+                info!("{}", message.yellow());
+            }
+            true
+        }
+        ServerMessage::ReputationUpdate {
+            standings,
+            emote_threshold,
+            item_threshold,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.reputation = Some(Reputation {
+                    standings,
+                    emote_threshold,
+                    item_threshold,
+                });
+            } else {
+                error!("Failed to update reputation in game state");
+            }
+            true
+        }
+        ServerMessage::TerritoryUpdate {
+            control_points,
+            capture_seconds,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.territory = Some(Territory {
+                    control_points,
+                    capture_seconds,
+                });
+            } else {
+                error!("Failed to update territory in game state");
+            }
+            true
+        }
+        ServerMessage::RegionChanged {
+            world_boundaries,
+            seq_num: _,
+        } => {
+            let message = format!(
+                "Entered {} (security: {}, surveillance: {:.0}%)",
+                world_boundaries.name,
+                world_boundaries.security_level,
+                world_boundaries.surveillance_density * 100.0
+            );
+            if let Ok(mut state) = game_state.lock() {
+                state.set_world_boundaries(world_boundaries);
+                state.add_system_message("World".to_string(), message.clone());
+            } else {
+                error!("Failed to update world boundaries in game state");
+            }
+            info!("{}", message);
+            true
+        }
+        ServerMessage::Terrain {
+            terrain,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.terrain = Some(terrain);
+            } else {
+                error!("Failed to update terrain in game state");
+            }
+            true
+        }
+        ServerMessage::AbilityCooldowns {
+            cooldowns,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.set_cooldowns(&cooldowns);
+            } else {
+                error!("Failed to update ability cooldowns in game state");
+            }
+            true
+        }
+        ServerMessage::QuestOffer { quests, seq_num: _ } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.quest_offers = quests;
+            } else {
+                error!("Failed to update quest offers in game state");
+            }
+            true
+        }
+        ServerMessage::QuestProgress {
+            quest,
+            progress,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.quests.insert(quest.id.clone(), (quest, progress));
+            } else {
+                error!("Failed to update quest progress in game state");
+            }
+            true
+        }
+        ServerMessage::QuestComplete {
+            quest_id,
+            name,
+            experience,
+            items,
+            seq_num: _,
+        } => {
+            let mut message = format!("Quest complete: {} (+{} XP", name, experience);
+            for item in &items {
+                message.push_str(&format!(", {}", item));
+            }
+            message.push(')');
+            if let Ok(mut state) = game_state.lock() {
+                state.quests.remove(&quest_id);
+                state.add_system_message("Quest".to_string(), message.clone());
+            } else {
+                error!("Failed to complete quest in game state");
+            }
+            info!("{}", message.green());
+            true
+        }
+        ServerMessage::NpcUpdate { npcs, seq_num: _ } => {
+            if let Ok(mut state) = game_state.lock() {
+                state.npcs = npcs;
+            } else {
+                error!("Failed to update characters in game state");
+            }
+            true
+        }
+        ServerMessage::SurveillanceAlert {
+            display_id,
+            name,
+            faction,
+            position,
+            region,
+            duration_seconds,
+            seq_num: _,
+        } => {
+            if let Ok(mut state) = game_state.lock() {
+                let message = if state.get_display_id() == Some(&display_id) {
+                    state.exposed_until = Some(
+                        std::time::Instant::now()
+                            + std::time::Duration::from_secs(duration_seconds),
+                    );
+                    format!(
+                        "📡 Surveillance caught you in {}: the Corporate Hegemony knows who you are for {}s",
+                        region, duration_seconds
+                    )
+                } else {
+                    format!(
+                        "📡 Surveillance: {} [{}] of the {} spotted at ({:.1}, {:.1}) in {}",
+                        name,
+                        display_id,
+                        faction.display_name(),
+                        position.x,
+                        position.y,
+                        region
+                    )
+                };
+                state.add_system_message("Surveillance".to_string(), message.clone());
+                info!("{}", message);
+            } else {
+                error!("Failed to record surveillance alert in game state");
+            }
+            true
+        }
+        ServerMessage::ItemDropped { name, .. } => {
+            let message = format!("You dropped {}", name);
+            if let Ok(mut state) = game_state.lock() {
+                state.add_system_message("Inventory".to_string(), message.clone());
+            } else {
+                error!("Failed to add item message to game state");
+            }
+            info!("{}", message);
+            true
+        }
         ServerMessage::Ack { .. } => {
             // Acknowledgments are handled in the NetworkManager
             false
@@ -1107,10 +1781,8 @@ fn process_server_message(
         } => {
             if let Ok(mut state) = game_state.lock() {
                 // Find the player with matching display_id and update their position and health
-                let player_id_to_update = state.get_player_id_by_display_id(&display_id);
-
-                if let Some(player_id) = player_id_to_update {
-                    if let Some(player) = state.players.get_mut(&player_id) {
+                if let Some(known_display_id) = state.find_display_id(&display_id) {
+                    if let Some(player) = state.players.get_mut(&known_display_id) {
                         player.position = position;
                         player.health = health;
                         state.update_timestamp(); // Mark state as updated
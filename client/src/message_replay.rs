@@ -1,12 +1,6 @@
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::sync::Mutex;
-use tracing::debug;
-
-use crate::config::ClientConfig;
-
 /// Structure to manage replay protection using a sliding window approach
 /// This is used to prevent replay attacks by tracking received message sequence numbers
+/// Each connection owns its own window, so several clients in one process stay independent
 pub struct ReplayProtectionWindow {
     /// Highest sequence number seen so far
     highest_seq: u64,
@@ -97,67 +91,3 @@ impl ReplayProtectionWindow {
         false // Not a replay
     }
 }
-
-// Tracking received server messages to prevent replays
-lazy_static! {
-    pub static ref REPLAY_PROTECTION: Mutex<HashMap<String, ReplayProtectionWindow>> =
-        Mutex::new(HashMap::new());
-}
-
-/// Check if we've seen this message before (replay protection)
-/// Returns true if the message is a replay, false if it's new
-pub fn is_message_replay(server_address: &str, seq_num: u64) -> bool {
-    // Get the configured window size or use default if config access fails
-    let window_size = get_replay_protection_window_size();
-
-    match REPLAY_PROTECTION.lock() {
-        Ok(mut protection) => {
-            // Check if window exists first
-            let needs_creation = !protection.contains_key(server_address);
-
-            // Get or create the replay protection window for this server
-            let window = protection
-                .entry(server_address.to_string())
-                .or_insert_with(|| ReplayProtectionWindow::new(window_size));
-
-            // Check and update the window
-            let result = window.process(seq_num);
-
-            // Log after releasing the lock if this was a new window
-            if needs_creation {
-                drop(protection); // Explicitly release the lock
-                debug!(
-                    "Created replay protection window with size: {}",
-                    window_size
-                );
-            }
-
-            result
-        }
-        Err(e) => {
-            tracing::error!("Warning: Failed to access replay protection data: {}", e);
-            // In case of mutex poisoning, err on the side of caution and allow the message
-            false
-        }
-    }
-}
-
-/// Get the configured replay protection window size
-/// Returns the window size from config, or default 64 if config can't be loaded
-fn get_replay_protection_window_size() -> u8 {
-    // Default window size if we can't access the config
-    const DEFAULT_WINDOW_SIZE: u8 = 64;
-
-    // Try to load the client configuration
-    match ClientConfig::load() {
-        Ok(config) => config.replay_protection_window_size,
-        Err(e) => {
-            tracing::warn!(
-                "Failed to load config for replay protection window size: {}",
-                e
-            );
-            tracing::warn!("Using default window size of {}", DEFAULT_WINDOW_SIZE);
-            DEFAULT_WINDOW_SIZE
-        }
-    }
-}
@@ -72,6 +72,12 @@ pub enum ConnectionQuality {
     Down,
 }
 
+impl Default for MixnetHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MixnetHealth {
     /// Create a new MixnetHealth instance
     pub fn new() -> Self {
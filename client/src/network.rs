@@ -1,33 +1,33 @@
 use anyhow::{anyhow, Context, Result};
-use futures::StreamExt;
-use nym_sdk::mixnet::{
-    IncludedSurbs, MixnetClient, MixnetClientBuilder, MixnetMessageSender, Recipient, StoragePaths,
-};
 use rand::Rng;
 use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::time;
 use tracing::{debug, error, info, trace, warn};
-use uuid::Uuid;
 
 // Import message authentication module
-use crate::message_auth::{AuthKey, AuthenticatedMessage};
-use crate::message_replay::is_message_replay;
+use crate::message_replay::ReplayProtectionWindow;
+use nymquest_protocol::message_auth::{AuthKey, AuthenticatedMessage};
+use nymquest_protocol::session::{ClientHandshake, ServerPublicKey};
 // Import mixnet health monitoring
 use crate::mixnet_health::MixnetHealth;
 // Import message padding for enhanced privacy
-use crate::message_padding::{pad_message, unpad_message, PaddedMessage};
-
-use crate::game_protocol::{
-    ClientMessage, ClientMessageType, Direction, EmoteType, ProtocolVersion, ServerMessage,
-    ServerMessageType,
+use nymquest_protocol::message_padding::{pad_message, unpad_message, PaddedMessage};
+// Transport the client talks to the server over
+use nymquest_protocol::nym_transport::NymConnector;
+use nymquest_protocol::transport::{GameTransport, TransportConnector};
+
+use nymquest_protocol::game_protocol::{
+    Ability, ClientMessage, ClientMessageType, Direction, EmoteType, ProtocolVersion,
+    ServerMessage, ServerMessageType, TradeAssets,
 };
-use crate::world_lore::Faction;
+use nymquest_protocol::world_lore::{EquipmentSlot, Faction};
 
 use crate::config::ClientConfig;
+use crate::key_pinning::{get_known_servers_path, KnownServers};
+use crate::reclaim_tokens::{get_reclaim_tokens_path, ReclaimTokens};
 
 use crate::status_monitor::StatusMonitor;
 
@@ -80,22 +80,71 @@ pub enum OriginalMessage {
     Emote {
         emote_type: EmoteType,
     },
+    UseItem {
+        item_id: String,
+    },
+    DropItem {
+        item_id: String,
+    },
+    Inventory,
+    Equip {
+        item_id: String,
+    },
+    Unequip {
+        slot: EquipmentSlot,
+    },
+    PickUp,
+    TradeRequest {
+        target_display_id: String,
+    },
+    TradeOffer {
+        target_display_id: String,
+        offer: TradeAssets,
+    },
+    TradeAccept {
+        target_display_id: String,
+        revision: u32,
+    },
+    TradeCancel {
+        target_display_id: String,
+    },
+    QuestAccept {
+        quest_id: String,
+    },
+    UseAbility {
+        ability: Ability,
+        target: Option<String>,
+    },
+    Reputation,
     Disconnect,
     Heartbeat,
     Whisper {
         target_display_id: String,
         message: String,
     },
+    Resume {
+        token: String,
+        protocol_version: ProtocolVersion,
+    },
 }
 
-pub struct NetworkManager {
-    client: Option<MixnetClient>,
+pub struct NetworkManager<C: TransportConnector = NymConnector> {
+    /// Creates transport connections, used again on reconnect
+    connector: C,
+    client: Option<C::Transport>,
     server_address: String,
+    /// Server identity key from the discovery file
+    server_key: ServerPublicKey,
+    /// Key pair sent in `Register` for the current connection
+    handshake: ClientHandshake,
+    /// Session key agreed with the server for the current connection
     auth_key: AuthKey,
     pending_acks: HashMap<u64, (Instant, ClientMessageType)>,
     retry_count: HashMap<u64, usize>,
     /// Bounded queue for received server message IDs (replay protection)
     received_server_msgs: VecDeque<u64>,
+    /// Sliding replay protection window for server sequence numbers
+    replay_window: ReplayProtectionWindow,
     seq_counter: u64,
     original_messages: HashMap<u64, OriginalMessage>,
     #[allow(dead_code)] // Part of complete network API for future use
@@ -107,6 +156,10 @@ pub struct NetworkManager {
     last_rate_limit_update: Instant,
     /// Negotiated protocol version for this session
     negotiated_protocol_version: Option<u16>,
+    /// Token for reclaiming our character, from the last RegisterAck
+    reclaim_token: Option<String>,
+    /// Where reclaim tokens are kept between runs; None keeps them in memory only
+    reclaim_tokens: Option<ReclaimTokens>,
     /// Last time a message was sent (for pacing)
     last_message_sent: Option<Instant>,
     /// Message pacing interval in milliseconds
@@ -119,6 +172,12 @@ pub struct NetworkManager {
     mixnet_health: Arc<Mutex<MixnetHealth>>,
     /// Is reconnection in progress
     reconnection_in_progress: bool,
+    /// Time to wait for the first acknowledgement before resending
+    initial_ack_timeout_ms: u64,
+    /// Time to wait for acknowledgements of resent messages
+    subsequent_ack_timeout_ms: u64,
+    /// Resend attempts before a message is given up on
+    max_retries: usize,
 }
 
 /// Calculate maximum jitter in milliseconds based on base interval and jitter percentage
@@ -135,6 +194,14 @@ fn calculate_max_jitter(base_interval_ms: u64, jitter_percent: u8) -> u64 {
     (base_interval_ms * capped_percent) / 100
 }
 
+/// Whether a message registers the player, which gets extra time on the mixnet
+fn is_registration(msg_type: ClientMessageType) -> bool {
+    matches!(
+        msg_type,
+        ClientMessageType::Register | ClientMessageType::Resume
+    )
+}
+
 /// Apply message pacing with jitter to enhance privacy by preventing timing correlation attacks
 /// Returns the applied jitter in milliseconds
 async fn apply_message_pacing(
@@ -182,37 +249,69 @@ async fn apply_message_pacing(
     jitter_ms
 }
 
-impl NetworkManager {
+impl NetworkManager<NymConnector> {
     /// Create a new NetworkManager and connect to the Nym network
     pub async fn new(
         config: &ClientConfig,
         status_monitor: Arc<Mutex<StatusMonitor>>,
     ) -> Result<Self> {
         // Use discovery mechanism to find server connection information
-        let (server_address, auth_key_b64) = crate::discovery::load_server_connection_info()
-            .with_context(|| "Failed to discover server connection information")?;
+        let (server_address, server_key) =
+            nymquest_protocol::discovery::load_server_connection_info()
+                .with_context(|| "Failed to discover server connection information")?;
+
+        // Parse the server's public key
+        let server_key = ServerPublicKey::from_str(&server_key)
+            .with_context(|| "Failed to parse server public key from discovery file")?;
 
-        // Parse the authentication key
-        let auth_key = AuthKey::from_base64(&auth_key_b64)
-            .with_context(|| "Failed to parse authentication key from discovery file")?;
+        // Refuse to talk to a server whose key differs from the one pinned on first use
+        let known_servers_path = get_known_servers_path()?;
+        KnownServers::load(&known_servers_path)?.check_or_pin(&server_address, &server_key)?;
 
         info!("Successfully discovered server at: {}", server_address);
 
-        // Configure Nym client with a unique directory for each instance
-        // Generate a unique ID for this client to prevent connection conflicts
-        let unique_id = Uuid::new_v4().to_string();
-        let config_dir = PathBuf::from(format!("/tmp/nym_mmorpg_client_{}", unique_id));
-        let storage_paths = StoragePaths::new_from_dir(&config_dir)?;
+        // Load the character saved for this server, if any, so it can be resumed
+        let reclaim_tokens = ReclaimTokens::load(&get_reclaim_tokens_path()?)?;
 
-        info!("Initializing Nym client with unique ID...");
-        let client = MixnetClientBuilder::new_with_default_storage(storage_paths)
-            .await?
-            .build()?;
+        // Each connection gets a unique storage directory to prevent connection conflicts
+        let mut manager = Self::with_connector(
+            NymConnector::default(),
+            server_address,
+            server_key,
+            config,
+            status_monitor,
+        )
+        .await?;
+        manager.reclaim_token = reclaim_tokens
+            .get(&manager.server_address)
+            .map(str::to_string);
+        manager.reclaim_tokens = Some(reclaim_tokens);
+        Ok(manager)
+    }
+}
 
-        let client = client.connect_to_mixnet().await?;
+impl<C: TransportConnector> NetworkManager<C> {
+    /// Create a NetworkManager talking to `server_address` over transports from `connector`
+    ///
+    /// `server_key` is the server's public key; a session key is agreed with it
+    /// for every connection.
+    pub async fn with_connector(
+        connector: C,
+        server_address: String,
+        server_key: ServerPublicKey,
+        config: &ClientConfig,
+        status_monitor: Arc<Mutex<StatusMonitor>>,
+    ) -> Result<Self> {
+        info!("Initializing Nym client with unique ID...");
+        let client = connector.connect().await?;
 
         info!("Connected to Nym network!");
 
+        let handshake = ClientHandshake::new();
+        let auth_key = handshake
+            .session_key(&server_key)
+            .with_context(|| "Failed to agree session key with server")?;
+
         // Initialize mixnet health monitoring
         let mixnet_health = Arc::new(Mutex::new(MixnetHealth::new()));
 
@@ -222,12 +321,16 @@ impl NetworkManager {
             .with_context(|| "Failed to start mixnet health monitoring")?;
 
         Ok(Self {
+            connector,
             client: Some(client),
             server_address,
+            server_key,
+            handshake,
             auth_key,
             pending_acks: HashMap::with_capacity(MAX_PENDING_ACKS),
             retry_count: HashMap::with_capacity(MAX_PENDING_ACKS),
             received_server_msgs: VecDeque::with_capacity(MAX_RECEIVED_MSG_HISTORY),
+            replay_window: ReplayProtectionWindow::new(config.replay_protection_window_size),
             seq_counter: 1,
             original_messages: HashMap::new(),
             config: config.clone(),
@@ -235,12 +338,17 @@ impl NetworkManager {
             rate_limit_tokens: MAX_BURST_SIZE,
             last_rate_limit_update: Instant::now(),
             negotiated_protocol_version: None,
+            reclaim_token: None,
+            reclaim_tokens: None,
             last_message_sent: None,
             pacing_interval_ms: config.message_pacing_interval_ms,
             pacing_enabled: config.enable_message_pacing,
             last_applied_jitter_ms: 0,
             mixnet_health,
             reconnection_in_progress: false,
+            initial_ack_timeout_ms: INITIAL_ACK_TIMEOUT_MS,
+            subsequent_ack_timeout_ms: SUBSEQUENT_ACK_TIMEOUT_MS,
+            max_retries: MAX_RETRIES,
         })
     }
 
@@ -250,16 +358,12 @@ impl NetworkManager {
         if let ClientMessage::Ack { .. } = message {
             if let Some(client) = &mut self.client {
                 let authenticated_msg = AuthenticatedMessage::new(message, &self.auth_key)?;
-                let message_str = serde_json::to_string(&authenticated_msg)?;
+                // The server only accepts padded messages
+                let padded_msg = pad_message(authenticated_msg)?;
+                let message_str = serde_json::to_string(&padded_msg)?;
                 debug!("Sending acknowledgment message");
-                let recipient = Recipient::from_str(&self.server_address)
-                    .map_err(|e| anyhow!("Invalid server address: {}", e))?;
                 client
-                    .send_message(
-                        recipient,
-                        message_str.into_bytes(),
-                        IncludedSurbs::default(),
-                    )
+                    .send_to_address(&self.server_address, message_str.into_bytes())
                     .await?;
             }
             return Ok(());
@@ -306,6 +410,7 @@ impl NetworkManager {
         // Record time of message being sent (for future pacing)
         self.last_message_sent = Some(Instant::now());
 
+        let session_public_key = self.handshake.public_key();
         if let Some(client) = &mut self.client {
             // For all other message types, attach sequence number
             let message_with_seq = match message {
@@ -319,6 +424,7 @@ impl NetworkManager {
                     faction, // Use original faction from registration
                     protocol_version,
                     seq_num,
+                    session_public_key, // Lets the server derive this session's key
                 },
                 ClientMessage::Move { direction, .. } => ClientMessage::Move { direction, seq_num },
                 ClientMessage::Attack {
@@ -341,8 +447,69 @@ impl NetworkManager {
                     emote_type,
                     seq_num,
                 },
+                ClientMessage::UseItem { item_id, .. } => {
+                    ClientMessage::UseItem { item_id, seq_num }
+                }
+                ClientMessage::DropItem { item_id, .. } => {
+                    ClientMessage::DropItem { item_id, seq_num }
+                }
+                ClientMessage::Inventory { .. } => ClientMessage::Inventory { seq_num },
+                ClientMessage::Equip { item_id, .. } => ClientMessage::Equip { item_id, seq_num },
+                ClientMessage::Unequip { slot, .. } => ClientMessage::Unequip { slot, seq_num },
+                ClientMessage::PickUp { .. } => ClientMessage::PickUp { seq_num },
+                ClientMessage::TradeRequest {
+                    target_display_id, ..
+                } => ClientMessage::TradeRequest {
+                    target_display_id,
+                    seq_num,
+                },
+                ClientMessage::TradeOffer {
+                    target_display_id,
+                    offer,
+                    ..
+                } => ClientMessage::TradeOffer {
+                    target_display_id,
+                    offer,
+                    seq_num,
+                },
+                ClientMessage::TradeAccept {
+                    target_display_id,
+                    revision,
+                    ..
+                } => ClientMessage::TradeAccept {
+                    target_display_id,
+                    revision,
+                    seq_num,
+                },
+                ClientMessage::TradeCancel {
+                    target_display_id, ..
+                } => ClientMessage::TradeCancel {
+                    target_display_id,
+                    seq_num,
+                },
+                ClientMessage::QuestAccept { quest_id, .. } => {
+                    ClientMessage::QuestAccept { quest_id, seq_num }
+                }
+                ClientMessage::UseAbility {
+                    ability, target, ..
+                } => ClientMessage::UseAbility {
+                    ability,
+                    target,
+                    seq_num,
+                },
+                ClientMessage::Reputation { .. } => ClientMessage::Reputation { seq_num },
                 ClientMessage::Disconnect { .. } => ClientMessage::Disconnect { seq_num },
                 ClientMessage::Heartbeat { .. } => ClientMessage::Heartbeat { seq_num },
+                ClientMessage::Resume {
+                    token,
+                    protocol_version,
+                    ..
+                } => ClientMessage::Resume {
+                    token,
+                    protocol_version,
+                    seq_num,
+                    session_public_key, // Lets the server derive this session's key
+                },
                 ClientMessage::Ack { .. } => unreachable!(), // Handled above
             };
 
@@ -386,8 +553,64 @@ impl NetworkManager {
                 ClientMessage::Emote { emote_type, .. } => OriginalMessage::Emote {
                     emote_type: *emote_type,
                 },
+                ClientMessage::UseItem { item_id, .. } => OriginalMessage::UseItem {
+                    item_id: item_id.clone(),
+                },
+                ClientMessage::DropItem { item_id, .. } => OriginalMessage::DropItem {
+                    item_id: item_id.clone(),
+                },
+                ClientMessage::Inventory { .. } => OriginalMessage::Inventory,
+                ClientMessage::Equip { item_id, .. } => OriginalMessage::Equip {
+                    item_id: item_id.clone(),
+                },
+                ClientMessage::Unequip { slot, .. } => OriginalMessage::Unequip { slot: *slot },
+                ClientMessage::PickUp { .. } => OriginalMessage::PickUp,
+                ClientMessage::TradeRequest {
+                    target_display_id, ..
+                } => OriginalMessage::TradeRequest {
+                    target_display_id: target_display_id.clone(),
+                },
+                ClientMessage::TradeOffer {
+                    target_display_id,
+                    offer,
+                    ..
+                } => OriginalMessage::TradeOffer {
+                    target_display_id: target_display_id.clone(),
+                    offer: offer.clone(),
+                },
+                ClientMessage::TradeAccept {
+                    target_display_id,
+                    revision,
+                    ..
+                } => OriginalMessage::TradeAccept {
+                    target_display_id: target_display_id.clone(),
+                    revision: *revision,
+                },
+                ClientMessage::TradeCancel {
+                    target_display_id, ..
+                } => OriginalMessage::TradeCancel {
+                    target_display_id: target_display_id.clone(),
+                },
+                ClientMessage::QuestAccept { quest_id, .. } => OriginalMessage::QuestAccept {
+                    quest_id: quest_id.clone(),
+                },
+                ClientMessage::UseAbility {
+                    ability, target, ..
+                } => OriginalMessage::UseAbility {
+                    ability: *ability,
+                    target: target.clone(),
+                },
+                ClientMessage::Reputation { .. } => OriginalMessage::Reputation,
                 ClientMessage::Disconnect { .. } => OriginalMessage::Disconnect,
                 ClientMessage::Heartbeat { .. } => OriginalMessage::Heartbeat,
+                ClientMessage::Resume {
+                    token,
+                    protocol_version,
+                    ..
+                } => OriginalMessage::Resume {
+                    token: token.clone(),
+                    protocol_version: protocol_version.clone(),
+                },
                 ClientMessage::Ack { .. } => unreachable!(), // Handled above
             };
 
@@ -404,19 +627,9 @@ impl NetworkManager {
 
             debug!("Applied message padding for enhanced privacy against size correlation attacks");
 
-            // Create recipient from server address
-            let server_address = match Recipient::from_str(&self.server_address) {
-                Ok(addr) => addr,
-                Err(e) => return Err(anyhow!("Invalid server address: {}", e)),
-            };
-
             debug!("Sending message with seq_num: {}", seq_num);
             match client
-                .send_message(
-                    server_address,
-                    message_str.into_bytes(),
-                    IncludedSurbs::default(),
-                )
+                .send_to_address(&self.server_address, message_str.into_bytes())
                 .await
             {
                 Ok(_) => {
@@ -443,7 +656,9 @@ impl NetworkManager {
                     // If this is a critical message type, try to reconnect for future messages
                     if matches!(
                         message_with_seq.get_type(),
-                        ClientMessageType::Register | ClientMessageType::Heartbeat
+                        ClientMessageType::Register
+                            | ClientMessageType::Resume
+                            | ClientMessageType::Heartbeat
                     ) {
                         // Mark client as disconnected to force reconnection on next send
                         self.client = None;
@@ -467,6 +682,11 @@ impl NetworkManager {
 
     /// Check for messages that need to be resent due to missing acknowledgements
     pub async fn check_for_resends(&mut self) -> Result<()> {
+        // Move to the next session key when it is due; the server rotates on the same schedule
+        if self.auth_key.check_and_rotate()? {
+            debug!("Rotated session key to epoch {}", self.auth_key.epoch());
+        }
+
         let now = Instant::now();
         let mut to_resend = Vec::new();
         let mut to_remove = Vec::new();
@@ -477,13 +697,13 @@ impl NetworkManager {
 
             // Use a longer timeout for the first retry attempt and for registration messages
             let base_timeout = if self.retry_count.get(&seq_num).copied().unwrap_or(0) == 0 {
-                INITIAL_ACK_TIMEOUT_MS
+                self.initial_ack_timeout_ms
             } else {
-                SUBSEQUENT_ACK_TIMEOUT_MS
+                self.subsequent_ack_timeout_ms
             };
 
             // Add extra time for registration messages which often take longer
-            let timeout = if msg_type == ClientMessageType::Register {
+            let timeout = if is_registration(msg_type) {
                 base_timeout + REGISTRATION_TIMEOUT_EXTRA_MS
             } else {
                 base_timeout
@@ -493,7 +713,7 @@ impl NetworkManager {
             if elapsed > timeout {
                 let retry_count = self.retry_count.get(&seq_num).copied().unwrap_or(0);
 
-                if retry_count < MAX_RETRIES {
+                if retry_count < self.max_retries {
                     to_resend.push((seq_num, msg_type));
                     self.retry_count.insert(seq_num, retry_count + 1);
 
@@ -505,7 +725,7 @@ impl NetworkManager {
                     // Too many retries, mark for removal
                     warn!(
                         "Message {} of type {:?} not acknowledged after {} retries",
-                        seq_num, msg_type, MAX_RETRIES
+                        seq_num, msg_type, self.max_retries
                     );
                     to_remove.push(seq_num);
                 }
@@ -527,7 +747,7 @@ impl NetworkManager {
         // Resend messages
         for (seq_num, msg_type) in to_resend {
             // For registration messages, add additional delay between retries to prevent fragment overlap
-            if msg_type == ClientMessageType::Register {
+            if is_registration(msg_type) {
                 debug!("Adding extra delay before retrying registration message");
                 // Add a random delay between 500-1500ms before resending registration messages
                 // This helps prevent fragment overlap in the mixnet
@@ -555,6 +775,7 @@ impl NetworkManager {
                             faction: faction.clone(),
                             protocol_version: protocol_version.clone(),
                             seq_num,
+                            session_public_key: self.handshake.public_key(),
                         }
                     }
                     OriginalMessage::Move { direction } => {
@@ -596,6 +817,97 @@ impl NetworkManager {
                             seq_num,
                         }
                     }
+                    OriginalMessage::UseItem { item_id } => {
+                        debug!("Resending UseItem for {}", item_id);
+                        ClientMessage::UseItem {
+                            item_id: item_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::DropItem { item_id } => {
+                        debug!("Resending DropItem for {}", item_id);
+                        ClientMessage::DropItem {
+                            item_id: item_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::Inventory => {
+                        debug!("Resending Inventory");
+                        ClientMessage::Inventory { seq_num }
+                    }
+                    OriginalMessage::Equip { item_id } => {
+                        debug!("Resending Equip for {}", item_id);
+                        ClientMessage::Equip {
+                            item_id: item_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::Unequip { slot } => {
+                        debug!("Resending Unequip for {:?}", slot);
+                        ClientMessage::Unequip {
+                            slot: *slot,
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::PickUp => {
+                        debug!("Resending PickUp");
+                        ClientMessage::PickUp { seq_num }
+                    }
+                    OriginalMessage::TradeRequest { target_display_id } => {
+                        debug!("Resending TradeRequest to {}", target_display_id);
+                        ClientMessage::TradeRequest {
+                            target_display_id: target_display_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::TradeOffer {
+                        target_display_id,
+                        offer,
+                    } => {
+                        debug!("Resending TradeOffer to {}", target_display_id);
+                        ClientMessage::TradeOffer {
+                            target_display_id: target_display_id.clone(),
+                            offer: offer.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::TradeAccept {
+                        target_display_id,
+                        revision,
+                    } => {
+                        debug!("Resending TradeAccept to {}", target_display_id);
+                        ClientMessage::TradeAccept {
+                            target_display_id: target_display_id.clone(),
+                            revision: *revision,
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::TradeCancel { target_display_id } => {
+                        debug!("Resending TradeCancel to {}", target_display_id);
+                        ClientMessage::TradeCancel {
+                            target_display_id: target_display_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::QuestAccept { quest_id } => {
+                        debug!("Resending QuestAccept for {}", quest_id);
+                        ClientMessage::QuestAccept {
+                            quest_id: quest_id.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::UseAbility { ability, target } => {
+                        debug!("Resending UseAbility {}", ability.name());
+                        ClientMessage::UseAbility {
+                            ability: *ability,
+                            target: target.clone(),
+                            seq_num,
+                        }
+                    }
+                    OriginalMessage::Reputation => {
+                        debug!("Resending Reputation");
+                        ClientMessage::Reputation { seq_num }
+                    }
                     OriginalMessage::Whisper {
                         target_display_id,
                         message,
@@ -607,6 +919,18 @@ impl NetworkManager {
                             seq_num,
                         }
                     }
+                    OriginalMessage::Resume {
+                        token,
+                        protocol_version,
+                    } => {
+                        debug!("Resending Resume");
+                        ClientMessage::Resume {
+                            token: token.clone(),
+                            protocol_version: protocol_version.clone(),
+                            seq_num,
+                            session_public_key: self.handshake.public_key(),
+                        }
+                    }
                 }
             } else {
                 // Fallback if original message is somehow not available
@@ -617,9 +941,10 @@ impl NetworkManager {
                         faction: Faction::Independent, // Default to Independent for fallback
                         protocol_version: ProtocolVersion::default(),
                         seq_num,
+                        session_public_key: self.handshake.public_key(),
                     },
                     ClientMessageType::Move => {
-                        use crate::game_protocol::Direction;
+                        use nymquest_protocol::game_protocol::Direction;
                         ClientMessage::Move {
                             direction: Direction::Up,
                             seq_num,
@@ -653,6 +978,41 @@ impl NetworkManager {
                         // We don't resend acks
                         continue;
                     }
+                    ClientMessageType::UseItem
+                    | ClientMessageType::DropItem
+                    | ClientMessageType::Equip
+                    | ClientMessageType::Unequip => {
+                        // Without the item there is nothing sensible to resend
+                        continue;
+                    }
+                    ClientMessageType::TradeRequest
+                    | ClientMessageType::TradeOffer
+                    | ClientMessageType::TradeAccept
+                    | ClientMessageType::TradeCancel => {
+                        // Without the partner and offer there is nothing sensible to resend
+                        continue;
+                    }
+                    ClientMessageType::QuestAccept => {
+                        // Without the quest there is nothing sensible to resend
+                        continue;
+                    }
+                    ClientMessageType::UseAbility => {
+                        // Without the ability and target there is nothing sensible to resend
+                        continue;
+                    }
+                    ClientMessageType::Inventory => ClientMessage::Inventory { seq_num },
+                    ClientMessageType::PickUp => ClientMessage::PickUp { seq_num },
+                    ClientMessageType::Reputation => ClientMessage::Reputation { seq_num },
+                    ClientMessageType::Resume => match &self.reclaim_token {
+                        Some(token) => ClientMessage::Resume {
+                            token: token.clone(),
+                            protocol_version: ProtocolVersion::default(),
+                            seq_num,
+                            session_public_key: self.handshake.public_key(),
+                        },
+                        // Nothing to resume with
+                        None => continue,
+                    },
                     ClientMessageType::Heartbeat => ClientMessage::Heartbeat { seq_num },
                 }
             };
@@ -661,18 +1021,13 @@ impl NetworkManager {
             if let Some(client) = &mut self.client {
                 // Create an authenticated message with HMAC tag
                 let authenticated_msg = AuthenticatedMessage::new(message, &self.auth_key)?;
-                let message_str = String::from_utf8(serde_json::to_vec(&authenticated_msg)?)?;
 
-                // Create recipient from server address
-                let recipient = Recipient::from_str(&self.server_address)
-                    .map_err(|e| anyhow!("Invalid server address: {}", e))?;
+                // Resends are padded like the original so they are accepted and indistinguishable
+                let padded_msg = pad_message(authenticated_msg)?;
+                let message_str = String::from_utf8(serde_json::to_vec(&padded_msg)?)?;
 
                 client
-                    .send_message(
-                        recipient,
-                        message_str.into_bytes(),
-                        IncludedSurbs::default(),
-                    )
+                    .send_to_address(&self.server_address, message_str.into_bytes())
                     .await?;
 
                 debug!(
@@ -687,6 +1042,36 @@ impl NetworkManager {
         Ok(())
     }
 
+    /// Check a server message against the session key and the server signature
+    ///
+    /// The session key shows the message belongs to this connection; only the
+    /// signature shows the server produced it.
+    fn verify_server_message(
+        &self,
+        authenticated_message: AuthenticatedMessage<ServerMessage>,
+    ) -> Option<ServerMessage> {
+        let verified = authenticated_message
+            .verify(&self.auth_key)
+            .and_then(|authentic| {
+                Ok(authentic && authenticated_message.verify_signature(&self.server_key)?)
+            });
+
+        match verified {
+            Ok(true) => Some(authenticated_message.message),
+            Ok(false) => {
+                warn!("Dropped server message that failed authentication or was not signed by the server");
+                None
+            }
+            Err(e) => {
+                // Sanitize the error message to not reveal sensitive information
+                error!("Error verifying message authenticity: Authentication error");
+                // Log the full error for debugging but keep it private
+                debug!("Debug info [not displayed to user]: {}", e);
+                None
+            }
+        }
+    }
+
     /// Wait for the next message from the server and handle acknowledgements
     pub async fn receive_message(&mut self) -> Option<ServerMessage> {
         // Check if we need to reconnect before receiving
@@ -707,7 +1092,7 @@ impl NetworkManager {
         };
 
         // Wait for the next message
-        let received_message = match client.next().await {
+        let received_message = match client.next_message().await {
             Some(msg) => {
                 // Record successful message reception in health monitor
                 if let Ok(mut health) = self.mixnet_health.lock() {
@@ -725,12 +1110,12 @@ impl NetworkManager {
         };
 
         // Check for empty messages
-        if received_message.message.is_empty() {
+        if received_message.payload.is_empty() {
             return None;
         }
 
         // Try to convert bytes to UTF-8 string
-        let message_str = match String::from_utf8(received_message.message) {
+        let message_str = match String::from_utf8(received_message.payload) {
             Ok(str) => str,
             Err(e) => {
                 error!("Error parsing message: {}", e);
@@ -738,90 +1123,40 @@ impl NetworkManager {
             }
         };
 
-        // First try to deserialize as a padded authenticated message
-        let server_message = match serde_json::from_str::<
+        // Server messages must be padded or plain authenticated messages; anything
+        // unauthenticated is dropped
+        let authenticated_message = match serde_json::from_str::<
             PaddedMessage<AuthenticatedMessage<ServerMessage>>,
         >(&message_str)
         {
-            Ok(padded_message) => {
-                // Extract the authenticated message from padding
-                let authenticated_message = unpad_message(padded_message);
-                // Verify message authenticity
-                match authenticated_message.verify(&self.auth_key) {
-                    Ok(true) => {
-                        // Message is authentic, extract the actual server message
-                        authenticated_message.message
-                    }
-                    Ok(false) => {
-                        // Instead of rejecting immediately, log the issue but still process the message
-                        // This allows for better compatibility during transitions or minor desync issues
-                        warn!("Message authentication weak - proceeding with caution");
-                        authenticated_message.message
-                    }
-                    Err(e) => {
-                        // Sanitize the error message to not reveal sensitive information
-                        error!("Error verifying message authenticity: Authentication error");
-                        // Log the full error for debugging but keep it private
-                        debug!("Debug info [not displayed to user]: {}", e);
-                        return None;
-                    }
-                }
-            }
-            // If deserialization as padded authenticated message fails, try as regular authenticated message
+            Ok(padded_message) => unpad_message(padded_message),
             Err(_) => {
                 match serde_json::from_str::<AuthenticatedMessage<ServerMessage>>(&message_str) {
-                    Ok(auth_message) => {
-                        match auth_message.verify(&self.auth_key) {
-                            Ok(true) => {
-                                // Message is authentic, extract the actual server message
-                                debug!("Received legacy authenticated message without padding");
-                                auth_message.message
-                            }
-                            Ok(false) => {
-                                // Instead of rejecting immediately, log the issue but still process the message
-                                // This allows for better compatibility during transitions or minor desync issues
-                                warn!("Message authentication weak - proceeding with caution");
-                                auth_message.message
-                            }
-                            Err(e) => {
-                                // Sanitize the error message to not reveal sensitive information
-                                error!(
-                                    "Error verifying message authenticity: Authentication error"
-                                );
-                                // Log the full error for debugging but keep it private
-                                debug!("Debug info [not displayed to user]: {}", e);
-                                return None;
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // Finally try as a plain ServerMessage (for backward compatibility)
-                        match serde_json::from_str::<ServerMessage>(&message_str) {
-                            Ok(msg) => {
-                                debug!("Received non-authenticated message (this is expected during transition)");
-                                msg
-                            }
-                            Err(e) => {
-                                error!("Error deserializing server message: {}", e);
-                                return None;
-                            }
-                        }
+                    Ok(authenticated_message) => authenticated_message,
+                    Err(e) => {
+                        error!("Error deserializing server message: {}", e);
+                        return None;
                     }
                 }
             }
         };
 
+        let server_message = self.verify_server_message(authenticated_message)?;
+
         // Extract the sequence number from the server message
         let seq_num = server_message.get_seq_num();
         let msg_type = server_message.get_type();
 
-        // Handle protocol version negotiation for RegisterAck
+        // Handle protocol version negotiation for RegisterAck and keep its reclaim token
         if let ServerMessage::RegisterAck {
-            negotiated_version, ..
+            negotiated_version,
+            reclaim_token,
+            ..
         } = &server_message
         {
             self.negotiated_protocol_version = Some(*negotiated_version);
             info!("Protocol version negotiated: v{}", negotiated_version);
+            self.remember_reclaim_token(reclaim_token);
         }
 
         // Handle explicit acknowledgements first
@@ -873,7 +1208,7 @@ impl NetworkManager {
         }
 
         // Check for message replay attacks using the replay protection window
-        if seq_num > 0 && is_message_replay(&self.server_address, seq_num) {
+        if seq_num > 0 && self.replay_window.process(seq_num) {
             warn!(
                 "Possible replay attack detected: message with seq_num {}",
                 seq_num
@@ -1037,51 +1372,36 @@ impl NetworkManager {
 
         info!("Attempting to reconnect to Nym network...");
 
-        // Configure Nym client with a unique directory for each instance
-        let unique_id = Uuid::new_v4().to_string();
-        let config_dir = PathBuf::from(format!("/tmp/nym_mmorpg_client_{}", unique_id));
-        let storage_paths = StoragePaths::new_from_dir(&config_dir)?;
-
         // Update status monitor
         if let Ok(mut monitor) = self.status_monitor.lock() {
             monitor.update_connection_status("Reconnecting...");
         }
 
         // Attempt to create and connect a new client
-        match MixnetClientBuilder::new_with_default_storage(storage_paths).await {
-            Ok(builder) => {
-                match builder.build() {
-                    Ok(client) => {
-                        match client.connect_to_mixnet().await {
-                            Ok(connected_client) => {
-                                info!("Successfully reconnected to Nym network!");
-                                self.client = Some(connected_client);
-
-                                // Reset reconnection tracking
-                                if let Ok(mut health) = self.mixnet_health.lock() {
-                                    health.reset_reconnection_attempts();
-                                }
-
-                                // Update status monitor
-                                if let Ok(mut monitor) = self.status_monitor.lock() {
-                                    monitor.update_connection_status("Connected");
-                                }
-
-                                self.reconnection_in_progress = false;
-                                return Ok(true);
-                            }
-                            Err(e) => {
-                                error!("Failed to connect client to mixnet: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to build mixnet client: {}", e);
-                    }
+        match self.connector.connect().await {
+            Ok(connected_client) => {
+                info!("Successfully reconnected to Nym network!");
+                self.client = Some(connected_client);
+
+                // The new connection needs its own session key
+                self.handshake = ClientHandshake::new();
+                self.auth_key = self.handshake.session_key(&self.server_key)?;
+
+                // Reset reconnection tracking
+                if let Ok(mut health) = self.mixnet_health.lock() {
+                    health.reset_reconnection_attempts();
+                }
+
+                // Update status monitor
+                if let Ok(mut monitor) = self.status_monitor.lock() {
+                    monitor.update_connection_status("Connected");
                 }
+
+                self.reconnection_in_progress = false;
+                return Ok(true);
             }
             Err(e) => {
-                error!("Failed to create mixnet client builder: {}", e);
+                error!("Failed to connect client to mixnet: {}", e);
             }
         }
 
@@ -1108,11 +1428,12 @@ impl NetworkManager {
         // Find the lowest sequence number of a pending message of the right type
         match server_message {
             ServerMessage::RegisterAck { .. } => {
-                // Find a Register message to acknowledge
+                // Find a Register or Resume message to acknowledge
                 self.find_pending_message_by_type(ClientMessageType::Register)
+                    .or_else(|| self.find_pending_message_by_type(ClientMessageType::Resume))
             }
-            ServerMessage::GameState { .. } => {
-                // GameState could acknowledge Move or Attack
+            ServerMessage::GameState { .. } | ServerMessage::GameStateDelta { .. } => {
+                // A state update could acknowledge Move or Attack
                 self.find_pending_message_by_type(ClientMessageType::Move)
                     .or_else(|| self.find_pending_message_by_type(ClientMessageType::Attack))
             }
@@ -1124,6 +1445,37 @@ impl NetworkManager {
                 // WhisperMessage acknowledges Whisper
                 self.find_pending_message_by_type(ClientMessageType::Whisper)
             }
+            ServerMessage::ItemUsed { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::UseItem)
+            }
+            ServerMessage::ItemDropped { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::DropItem)
+            }
+            ServerMessage::Inventory { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::Inventory)
+            }
+            ServerMessage::Equipment { .. } => self
+                .find_pending_message_by_type(ClientMessageType::Equip)
+                .or_else(|| self.find_pending_message_by_type(ClientMessageType::Unequip)),
+            ServerMessage::Loot { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::PickUp)
+            }
+            ServerMessage::TradeUpdate { .. } => self
+                .find_pending_message_by_type(ClientMessageType::TradeOffer)
+                .or_else(|| self.find_pending_message_by_type(ClientMessageType::TradeAccept))
+                .or_else(|| self.find_pending_message_by_type(ClientMessageType::TradeRequest)),
+            ServerMessage::TradeClosed { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::TradeCancel)
+            }
+            ServerMessage::ReputationUpdate { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::Reputation)
+            }
+            ServerMessage::QuestProgress { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::QuestAccept)
+            }
+            ServerMessage::AbilityCooldowns { .. } => {
+                self.find_pending_message_by_type(ClientMessageType::UseAbility)
+            }
             _ => None,
         }
     }
@@ -1169,6 +1521,21 @@ impl NetworkManager {
         self.negotiated_protocol_version
     }
 
+    /// Token for reclaiming our character on this server, if we have one
+    pub fn reclaim_token(&self) -> Option<&str> {
+        self.reclaim_token.as_deref()
+    }
+
+    /// Keep the reclaim token from a RegisterAck, saving it for later runs
+    fn remember_reclaim_token(&mut self, token: &str) {
+        self.reclaim_token = Some(token.to_string());
+        if let Some(reclaim_tokens) = &mut self.reclaim_tokens {
+            if let Err(e) = reclaim_tokens.insert(&self.server_address, token) {
+                warn!("Failed to save reclaim token: {}", e);
+            }
+        }
+    }
+
     /// Get the next sequence number and increment the counter
     fn next_seq_num(&mut self) -> u64 {
         let seq = self.seq_counter;
@@ -1234,4 +1601,21 @@ impl NetworkManager {
             self.last_applied_jitter_ms,
         )
     }
+
+    /// Modify acknowledgement timeouts and the retry limit used by check_for_resends
+    pub fn set_ack_timeouts(
+        &mut self,
+        initial_timeout_ms: u64,
+        subsequent_timeout_ms: u64,
+        max_retries: usize,
+    ) {
+        self.initial_ack_timeout_ms = initial_timeout_ms;
+        self.subsequent_ack_timeout_ms = subsequent_timeout_ms;
+        self.max_retries = max_retries;
+    }
+
+    /// Number of sent messages still waiting for an acknowledgement
+    pub fn pending_ack_count(&self) -> usize {
+        self.pending_acks.len()
+    }
 }
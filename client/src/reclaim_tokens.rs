@@ -0,0 +1,146 @@
+//! Saved reclaim tokens for characters on known servers
+//!
+//! The server hands out a reclaim token with every `RegisterAck`. Keeping it
+//! lets the player take their character back with `/resume` after the
+//! connection drops or the client restarts. The token is a secret: anyone
+//! holding it can play the character, so the file is readable by its owner only.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Environment variable overriding the location of the reclaim token file
+pub const RECLAIM_TOKENS_ENV_VAR: &str = "NYMQUEST_CLIENT_RECLAIM_TOKENS_FILE";
+
+/// Default filename for saved reclaim tokens
+pub const RECLAIM_TOKENS_FILENAME: &str = "reclaim_tokens";
+
+/// Get the location of the reclaim token file
+/// The environment variable overrides the platform-specific data directory
+pub fn get_reclaim_tokens_path() -> Result<PathBuf> {
+    if let Ok(custom_path) = env::var(RECLAIM_TOKENS_ENV_VAR) {
+        return Ok(PathBuf::from(custom_path));
+    }
+
+    let base_dir = dirs_next::data_dir()
+        .or_else(dirs_next::home_dir)
+        .ok_or_else(|| anyhow!("Cannot determine user data directory"))?;
+    Ok(base_dir
+        .join("nymquest")
+        .join("client")
+        .join(RECLAIM_TOKENS_FILENAME))
+}
+
+/// Reclaim tokens saved by this client, one `address;token` line per server
+pub struct ReclaimTokens {
+    path: PathBuf,
+    tokens: HashMap<String, String>,
+}
+
+impl ReclaimTokens {
+    /// Load saved tokens from `path`; a missing file means no characters are saved
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut tokens = HashMap::new();
+
+        if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read reclaim token file {:?}", path))?;
+
+            for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let (address, token) = line
+                    .split_once(';')
+                    .ok_or_else(|| anyhow!("Malformed entry in reclaim token file {:?}", path))?;
+                tokens.insert(address.to_string(), token.to_string());
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            tokens,
+        })
+    }
+
+    /// Token for the character on `server_address`, if one was saved
+    pub fn get(&self, server_address: &str) -> Option<&str> {
+        self.tokens.get(server_address).map(String::as_str)
+    }
+
+    /// Save `token` as the character on `server_address`, replacing any earlier one
+    pub fn insert(&mut self, server_address: &str, token: &str) -> Result<()> {
+        if self.get(server_address) == Some(token) {
+            return Ok(());
+        }
+
+        self.tokens
+            .insert(server_address.to_string(), token.to_string());
+        self.save()?;
+        debug!("Saved reclaim token for {}", server_address);
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        let mut entries: Vec<String> = self
+            .tokens
+            .iter()
+            .map(|(address, token)| format!("{};{}", address, token))
+            .collect();
+        entries.sort();
+
+        fs::write(&self.path, entries.join("\n") + "\n")
+            .with_context(|| format!("Failed to write reclaim token file {:?}", self.path))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_persist_per_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RECLAIM_TOKENS_FILENAME);
+
+        let mut tokens = ReclaimTokens::load(&path).unwrap();
+        assert_eq!(tokens.get("server.nym"), None);
+        tokens.insert("server.nym", "first").unwrap();
+        tokens.insert("other.nym", "second").unwrap();
+        // A new registration replaces the old character's token
+        tokens.insert("server.nym", "third").unwrap();
+
+        let reloaded = ReclaimTokens::load(&path).unwrap();
+        assert_eq!(reloaded.get("server.nym"), Some("third"));
+        assert_eq!(reloaded.get("other.nym"), Some("second"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_malformed_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RECLAIM_TOKENS_FILENAME);
+        fs::write(&path, "server.nym without a token\n").unwrap();
+
+        assert!(ReclaimTokens::load(&path).is_err());
+    }
+}
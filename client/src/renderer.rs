@@ -3,10 +3,11 @@ use colored::*;
 use std::io::{self, Write};
 use std::sync::{Arc, Mutex};
 
-use crate::game_protocol::Position;
 use crate::game_state::{GameState, MessageType};
+use nymquest_protocol::game_protocol::Position;
 
 /// Cached mini-map grid to avoid repeated allocations
+#[allow(clippy::needless_range_loop)] // Grid drawing reads clearer with explicit indices
 static MINI_MAP_CACHE: std::sync::LazyLock<Arc<Mutex<Vec<Vec<char>>>>> =
     std::sync::LazyLock::new(|| {
         const MAP_SIZE: usize = 15;
@@ -54,6 +55,7 @@ pub fn clear_screen() {
 /// - Other players are represented as 'O' (yellow)
 /// - Game world coordinates (-100 to 100) are scaled to fit the mini-map
 #[allow(dead_code)] // Alternative rendering function for future use
+#[allow(clippy::needless_range_loop)] // Grid drawing reads clearer with explicit indices
 pub fn render_mini_map(state: &GameState, _player_pos: &Position) {
     const MAP_SIZE: usize = 15; // Size of the mini-map (15x15 characters)
     const WORLD_MIN_X: f32 = -100.0;
@@ -108,7 +110,7 @@ pub fn render_mini_map(state: &GameState, _player_pos: &Position) {
     let _scale_y = (MAP_SIZE - 2) as f32 / (WORLD_MAX_Y - WORLD_MIN_Y);
 
     // Get current player from state
-    let current_player_id = state.player_id.clone();
+    let current_display_id = state.display_id.clone();
 
     // Debug current player position if available
     if let Some(current_player) = state.current_player() {
@@ -132,7 +134,7 @@ pub fn render_mini_map(state: &GameState, _player_pos: &Position) {
         let map_y = map_y.clamp(1, MAP_SIZE - 2);
 
         // Choose character based on whether this is the current player
-        let symbol = if Some(id) == current_player_id.as_ref() {
+        let symbol = if Some(id) == current_display_id.as_ref() {
             '@' // Current player
         } else {
             'O' // Other player
@@ -149,7 +151,7 @@ pub fn render_mini_map(state: &GameState, _player_pos: &Position) {
             player.position.y,
             map_x,
             map_y,
-            if Some(id) == current_player_id.as_ref() {
+            if Some(id) == current_display_id.as_ref() {
                 " (YOU)"
             } else {
                 ""
@@ -235,7 +237,7 @@ pub fn render_chat_history(state: &GameState, max_messages: usize) {
             match msg.message_type {
                 MessageType::Whisper => {
                     // Private whisper messages
-                    if state.player_id.as_deref() == Some(msg.sender.as_str()) {
+                    if state.display_id.as_deref() == Some(msg.sender.as_str()) {
                         // Whisper sent by current player
                         println!(
                             "{} {} {}",
@@ -266,7 +268,7 @@ pub fn render_chat_history(state: &GameState, max_messages: usize) {
                         "System Error" => {
                             println!("{} {}", time_str.yellow(), content_formatted.red().bold());
                         }
-                        sender if Some(sender) == state.player_id.as_deref() => {
+                        sender if Some(sender) == state.display_id.as_deref() => {
                             // Current player's messages
                             println!(
                                 "{} {} {}",
@@ -309,7 +311,7 @@ pub fn render_game_state(state: &GameState) {
     };
     println!("{} ({} seconds ago)", update_status, elapsed.as_secs());
 
-    match &state.player_id {
+    match &state.display_id {
         Some(id) => {
             println!("You are registered with ID: {}", id.blue());
 
@@ -344,12 +346,8 @@ pub fn render_game_state(state: &GameState) {
             );
 
             for (id, player) in &state.players {
-                if Some(id) != state.player_id.as_ref() {
-                    let distance_x = if let Some(current_player) = state
-                        .player_id
-                        .as_ref()
-                        .and_then(|pid| state.players.get(pid))
-                    {
+                if Some(id) != state.display_id.as_ref() {
+                    let distance_x = if let Some(current_player) = state.current_player() {
                         ((player.position.x - current_player.position.x).powi(2)
                             + (player.position.y - current_player.position.y).powi(2))
                         .sqrt()
@@ -384,7 +382,7 @@ pub fn render_game_state(state: &GameState) {
 
     println!("\nCommands:");
     println!("  register <n> - Register with the given name");
-    if state.player_id.is_some() {
+    if state.display_id.is_some() {
         println!("  move <direction> - Move your character one cell on the map");
         println!(
             "    (use cardinal: n,s,e,w or diagonal: ne,nw,se,sw or arrows: up,down,left,right)"
@@ -67,6 +67,12 @@ pub struct NetworkStats {
     pub estimated_hops: u32,
 }
 
+impl Default for NetworkStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NetworkStats {
     pub fn new() -> Self {
         Self {
@@ -111,6 +117,12 @@ pub struct PacingInfo {
     pub last_update: Instant,
 }
 
+impl Default for PacingInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PacingInfo {
     pub fn new() -> Self {
         Self {
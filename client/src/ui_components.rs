@@ -1,10 +1,15 @@
 use chrono::{TimeZone, Timelike, Utc};
 use colored::*;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
+use std::time::Instant;
 
-use crate::game_protocol::{Player, Position};
-use crate::game_state::{ChatMessage, GameState};
+use crate::game_state::{ChatMessage, Equipment, GameState, Reputation, Territory, Trade};
 use crate::status_monitor::{ConnectionHealth, PrivacyLevel};
+use nymquest_protocol::game_protocol::{
+    Ability, NpcKind, NpcView, PlayerView, Position, StatusEffectKind, Tile, TradeAssets,
+};
+use nymquest_protocol::world_lore::{EquipmentSlot, Faction, SecurityLevel};
 
 /// Modern Unicode box drawing characters for a sleek interface
 const DOUBLE_HORIZONTAL: &str = "═";
@@ -55,6 +60,7 @@ const ICON_CHAT: &str = "💬";
 const ICON_NETWORK: &str = "🌐";
 const ICON_TIME: &str = "🕐";
 const ICON_PRIVACY: &str = "🔒";
+const ICON_SURVEILLANCE: &str = "📡";
 const ICON_WARNING: &str = "⚠️";
 const ICON_SUCCESS: &str = "✅";
 const ICON_INFO: &str = "ℹ️";
@@ -227,8 +233,11 @@ fn strip_ansi_codes(input: &str) -> String {
 
 /// Format a player name based on their relation to the current player
 #[allow(dead_code)] // Part of complete UI API for future enhancements
-pub fn format_player_name(player: &Player, current_player_id: &Option<String>) -> ColoredString {
-    if Some(&player.id) == current_player_id.as_ref() {
+pub fn format_player_name(
+    player: &PlayerView,
+    current_display_id: &Option<String>,
+) -> ColoredString {
+    if Some(&player.display_id) == current_display_id.as_ref() {
         player.name.bright_green().bold()
     } else {
         player.name.bright_yellow()
@@ -298,6 +307,32 @@ pub fn format_distance(distance: f32) -> ColoredString {
     }
 }
 
+/// Icon shown for a status effect
+fn effect_icon(kind: StatusEffectKind) -> &'static str {
+    match kind {
+        StatusEffectKind::Shield => ICON_SHIELD,
+        StatusEffectKind::Stun => "⚡",
+        StatusEffectKind::Stealth => "👻",
+        StatusEffectKind::Corruption => "☣️",
+        StatusEffectKind::Downed => "💀",
+    }
+}
+
+/// Format the status effects on a player as icons, with stacks past the first
+pub fn format_effects(effects: &BTreeMap<StatusEffectKind, u32>) -> String {
+    effects
+        .iter()
+        .map(|(kind, stacks)| {
+            if *stacks > 1 {
+                format!("{}x{}", effect_icon(*kind), stacks)
+            } else {
+                effect_icon(*kind).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Get player attack range status with modern indicators
 pub fn get_attack_range_indicator(distance: f32) -> ColoredString {
     if distance <= 28.0 {
@@ -308,6 +343,7 @@ pub fn get_attack_range_indicator(distance: f32) -> ColoredString {
 }
 
 /// Render modern mini-map with enhanced visuals
+#[allow(clippy::needless_range_loop)] // Grid drawing reads clearer with explicit indices
 pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
     const MAP_SIZE: usize = 19;
 
@@ -323,11 +359,43 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
     // Create map grid
     let mut map = vec![vec![' '; MAP_SIZE]; MAP_SIZE];
 
-    // Fill with terrain dots
-    #[allow(clippy::needless_range_loop)]
+    // Get world boundaries for accurate positioning
+    let (min_x, max_x, min_y, max_y) = if let Some(boundaries) = state.get_world_boundaries() {
+        (
+            boundaries.min_x,
+            boundaries.max_x,
+            boundaries.min_y,
+            boundaries.max_y,
+        )
+    } else {
+        // Fallback to default values if boundaries aren't available
+        (0.0, 100.0, 0.0, 100.0)
+    };
+    let region = state.get_world_boundaries().map(|b| b.name.as_str());
+
+    // Fill with the terrain of our region, sampled at the middle of each cell
+    let terrain = state
+        .terrain
+        .as_ref()
+        .filter(|terrain| Some(terrain.region.as_str()) == region);
+    let cell_width = (max_x - min_x) / (MAP_SIZE - 2) as f32;
+    let cell_height = (max_y - min_y) / (MAP_SIZE - 2) as f32;
     for y in 1..MAP_SIZE - 1 {
         for x in 1..MAP_SIZE - 1 {
-            map[y][x] = '·';
+            let tile = match (terrain, state.get_world_boundaries()) {
+                (Some(terrain), Some(boundaries)) => terrain.tile_at(
+                    boundaries,
+                    min_x + (x as f32 - 0.5) * cell_width,
+                    min_y + (y as f32 - 0.5) * cell_height,
+                ),
+                _ => Tile::Open,
+            };
+            map[y][x] = match tile {
+                Tile::Open => '·',
+                Tile::Slow => '≈',
+                Tile::Wall => '█',
+                Tile::Safe => '+',
+            };
         }
     }
 
@@ -340,33 +408,48 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
     }
 
     // Place players
-    let current_player_id = state.player_id.clone();
+    let current_display_id = state.display_id.clone();
 
-    // Get world boundaries for accurate positioning
-    let (min_x, max_x, min_y, max_y) = if let Some(boundaries) = state.get_world_boundaries() {
-        (
-            boundaries.min_x,
-            boundaries.max_x,
-            boundaries.min_y,
-            boundaries.max_y,
-        )
-    } else {
-        // Fallback to default values if boundaries aren't available
-        (0.0, 100.0, 0.0, 100.0)
-    };
-
-    for (id, player) in &state.players {
-        // Normalize positions using actual world boundaries
-        let norm_x = (player.position.x - min_x) / (max_x - min_x);
-        let norm_y = (player.position.y - min_y) / (max_y - min_y);
+    // Normalize positions using actual world boundaries
+    let to_cell = |position: &Position| {
+        let norm_x = (position.x - min_x) / (max_x - min_x);
+        let norm_y = (position.y - min_y) / (max_y - min_y);
 
         let map_x = (norm_x * (MAP_SIZE - 2) as f32) as usize + 1;
         let map_y = (norm_y * (MAP_SIZE - 2) as f32) as usize + 1;
 
-        let map_x = map_x.clamp(1, MAP_SIZE - 2);
-        let map_y = map_y.clamp(1, MAP_SIZE - 2);
+        (map_x.clamp(1, MAP_SIZE - 2), map_y.clamp(1, MAP_SIZE - 2))
+    };
 
-        let symbol = if Some(id) == current_player_id.as_ref() {
+    // Control points go first, then items on the ground, so players standing on them stay visible
+    let mut loot_colors = vec![vec!["white"; MAP_SIZE]; MAP_SIZE];
+    let own_faction = state.current_player().map(|player| &player.faction);
+    if let Some(territory) = &state.territory {
+        for point in territory
+            .control_points
+            .iter()
+            .filter(|point| Some(point.region.as_str()) == region)
+        {
+            let (map_x, map_y) = to_cell(&point.position);
+            map[map_y][map_x] = '⚑';
+            loot_colors[map_y][map_x] = owner_color(point.owner.as_ref(), own_faction);
+        }
+    }
+    for drop in &state.loot {
+        let (map_x, map_y) = to_cell(&drop.position);
+        map[map_y][map_x] = '◆';
+        loot_colors[map_y][map_x] = drop.rarity.get_color_code();
+    }
+    for npc in &state.npcs {
+        let (map_x, map_y) = to_cell(&npc.position);
+        map[map_y][map_x] = '▲';
+        loot_colors[map_y][map_x] = npc_color(npc.kind);
+    }
+
+    for (id, player) in &state.players {
+        let (map_x, map_y) = to_cell(&player.position);
+
+        let symbol = if Some(id) == current_display_id.as_ref() {
             '@'
         } else {
             '●'
@@ -380,8 +463,13 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
 
     // World info
     map_content.push(format!(
-        "{} World: X[{:.0},{:.0}] Y[{:.0},{:.0}]",
-        ICON_LOCATION, min_x, max_x, min_y, max_y
+        "{} {}: X[{:.0},{:.0}] Y[{:.0},{:.0}]",
+        ICON_LOCATION,
+        region.unwrap_or("World"),
+        min_x,
+        max_x,
+        min_y,
+        max_y
     ));
     map_content.push(format!(
         "{} Position: ({:.1}, {:.1})",
@@ -390,13 +478,17 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
     map_content.push("".to_string());
 
     // Map display
-    for row in &map {
+    for (row, colors) in map.iter().zip(&loot_colors) {
         let mut line = String::new();
-        for &c in row {
+        for (&c, &color) in row.iter().zip(colors) {
             match c {
+                '◆' | '⚑' | '▲' => line.push_str(&c.to_string().color(color).to_string()),
                 '@' => line.push_str(&c.to_string().bright_green().bold().to_string()),
                 '●' => line.push_str(&c.to_string().bright_yellow().to_string()),
                 '·' => line.push_str(&c.to_string().blue().dimmed().to_string()),
+                '≈' => line.push_str(&c.to_string().cyan().dimmed().to_string()),
+                '█' => line.push_str(&c.to_string().bright_black().to_string()),
+                '+' => line.push_str(&c.to_string().green().to_string()),
                 '┼' => line.push_str(&c.to_string().cyan().to_string()),
                 _ => line.push(c),
             }
@@ -407,11 +499,24 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
     // Legend
     map_content.push("".to_string());
     map_content.push(format!(
-        "{} You  {} Others  {} Terrain",
+        "{} You  {} Others  {} Loot  {} Control point  {} NPC",
         "@".bright_green().bold(),
         "●".bright_yellow(),
-        "·".blue().dimmed()
+        "◆".bright_magenta(),
+        "⚑".white(),
+        "▲".bright_red()
+    ));
+    map_content.push(format!(
+        "{} Open  {} Slow  {} Wall  {} Safe zone",
+        "·".blue().dimmed(),
+        "≈".cyan().dimmed(),
+        "█".bright_black(),
+        "+".green()
     ));
+    if !state.npcs.is_empty() {
+        map_content.push("".to_string());
+        map_content.extend(state.npcs.iter().map(npc_line));
+    }
 
     draw_panel(
         "🗺️  WORLD MAP",
@@ -422,7 +527,15 @@ pub fn render_mini_map(state: &GameState, current_position: Option<&Position>) {
 }
 
 /// Render player stats with modern layout
-pub fn render_player_stats(player: &Player, is_current: bool) {
+///
+/// `equipment` adds the worn items and the stat block they and active items give, and
+/// `abilities_ready_at` the abilities with their cooldown timers.
+pub fn render_player_stats(
+    player: &PlayerView,
+    equipment: Option<&Equipment>,
+    abilities_ready_at: Option<&BTreeMap<Ability, Instant>>,
+    is_current: bool,
+) {
     let title = if is_current {
         format!("{}  PLAYER STATUS", ICON_SHIELD)
     } else {
@@ -435,7 +548,7 @@ pub fn render_player_stats(player: &Player, is_current: bool) {
         player.name.bright_yellow().to_string()
     };
 
-    let content = vec![
+    let mut content = vec![
         format!("Name: {}", name_display),
         format!("ID: {}", player.display_id.bright_cyan()),
         format!(
@@ -447,13 +560,58 @@ pub fn render_player_stats(player: &Player, is_current: bool) {
             "Level: {}",
             player.level.to_string().bright_magenta().bold()
         ),
-        // Show progress to next level
-        format!(
-            "XP: {} / {}",
-            player.experience.to_string().bright_cyan(),
-            ((player.level as u32 + 1) * 100).to_string().bright_blue()
-        ),
     ];
+    if !player.effects.is_empty() {
+        content.push(format!(
+            "Effects: {}",
+            player
+                .effects
+                .iter()
+                .map(|(kind, stacks)| format!("{} {} x{}", effect_icon(*kind), kind.name(), stacks))
+                .collect::<Vec<_>>()
+                .join("  ")
+        ));
+    }
+
+    if let Some(equipment) = equipment {
+        for slot in EquipmentSlot::ALL {
+            let worn = match equipment.slots.get(&slot) {
+                Some(item) => item.name.color(item.rarity.get_color_code()).to_string(),
+                None => "-".dimmed().to_string(),
+            };
+            content.push(format!("{:<9} {}", format!("{}:", slot.name()), worn));
+        }
+        let stats = &equipment.stats;
+        content.push(format!(
+            "ATK {:.0}  DEF {:.0}  PRIV {:.0}  STEALTH {:.0}  TRUST {:.0}",
+            stats.attack, stats.defense, stats.privacy, stats.detection_resistance, stats.trust
+        ));
+    }
+
+    if let Some(ready_at) = abilities_ready_at {
+        let now = Instant::now();
+        let abilities: Vec<String> = Ability::ALL
+            .iter()
+            .map(|ability| {
+                let unlock_level = ability.unlock_level(&player.faction);
+                let remaining = ready_at.get(ability).map_or(0, |ready_at| {
+                    ready_at.saturating_duration_since(now).as_secs()
+                });
+                if player.level < unlock_level {
+                    format!("{} lvl {}", ability.name(), unlock_level)
+                        .dimmed()
+                        .to_string()
+                } else if remaining > 0 {
+                    format!("{} {}s", ability.name(), remaining)
+                        .bright_yellow()
+                        .to_string()
+                } else {
+                    format!("{} ✓", ability.name()).bright_green().to_string()
+                }
+            })
+            .collect();
+        content.push(format!("Abilities: {}", abilities.join("  ")));
+    }
 
     draw_panel(&title, &content, PANEL_WIDTH, PanelStyle::Primary);
 }
@@ -496,7 +654,7 @@ pub fn render_nearby_players(state: &GameState) {
     let mut players_with_distance: Vec<_> = state
         .players
         .iter()
-        .filter(|(id, _)| Some(*id) != state.player_id.as_ref())
+        .filter(|(id, _)| Some(*id) != state.display_id.as_ref())
         .map(|(id, player)| {
             let distance = calculate_distance(&current_player.position, &player.position);
             (id, player, distance)
@@ -517,12 +675,13 @@ pub fn render_nearby_players(state: &GameState) {
             let range_indicator = get_attack_range_indicator(distance);
 
             content.push(format!(
-                "{}  {} {} {} {}",
+                "{}  {} {} {} {} {}",
                 ICON_BULLET,
                 player.name.bright_yellow(),
                 format!("[{}]", player.display_id).cyan(),
                 distance_colored,
-                range_indicator
+                range_indicator,
+                format_effects(&player.effects).bright_magenta()
             ));
         }
     }
@@ -535,6 +694,265 @@ pub fn render_nearby_players(state: &GameState) {
     );
 }
 
+/// Render carried and active items, colored by rarity
+pub fn render_inventory(state: &GameState) {
+    let mut content = vec![format!("{}  Credits: {}", ICON_BULLET, state.credits)
+        .bright_yellow()
+        .to_string()];
+
+    if state.inventory.is_empty() {
+        content.push(
+            format!("{}  No items (use /inventory to refresh)", ICON_INFO)
+                .dimmed()
+                .to_string(),
+        );
+    } else {
+        for entry in &state.inventory {
+            let mut line = format!(
+                "{}  {} x{} {}",
+                ICON_BULLET,
+                entry.item.name.color(entry.item.rarity.get_color_code()),
+                entry.quantity,
+                format!("[{}]", entry.item.id).bright_black()
+            );
+            if entry.active_seconds > 0 {
+                line.push_str(
+                    &format!(" active {}s", entry.active_seconds)
+                        .bright_green()
+                        .to_string(),
+                );
+            }
+            content.push(line);
+        }
+    }
+
+    draw_panel(
+        "🎒  INVENTORY",
+        &content,
+        PANEL_WIDTH,
+        PanelStyle::Secondary,
+    );
+}
+
+/// Render both offers of our open trade and who confirmed them
+pub fn render_trade(trade: &Trade) {
+    let describe = |offer: &TradeAssets| {
+        let mut parts = vec![format!("{} credits", offer.credits)];
+        parts.extend(
+            offer
+                .items
+                .iter()
+                .map(|(item_id, quantity)| format!("{} x{}", item_id, quantity)),
+        );
+        parts.join(", ")
+    };
+    let confirmed = |done: bool| {
+        if done {
+            format!("{} accepted", ICON_SUCCESS)
+                .bright_green()
+                .to_string()
+        } else {
+            "not accepted".dimmed().to_string()
+        }
+    };
+
+    let content = vec![
+        format!(
+            "{}  You offer: {} ({})",
+            ICON_BULLET,
+            describe(&trade.own_offer),
+            confirmed(trade.own_confirmed)
+        ),
+        format!(
+            "{}  {} offers: {} ({})",
+            ICON_BULLET,
+            trade.partner_display_id.bright_white(),
+            describe(&trade.partner_offer),
+            confirmed(trade.partner_confirmed)
+        ),
+        format!(
+            "{}  /offer [credits] [item[:qty]]... to change, /accept to confirm, /cancel to call off",
+            ICON_INFO
+        )
+        .dimmed()
+        .to_string(),
+    ];
+
+    draw_panel(
+        &format!("🤝  TRADE WITH {}", trade.partner_display_id.to_uppercase()),
+        &content,
+        PANEL_WIDTH,
+        PanelStyle::Accent,
+    );
+}
+
+/// Render our standing with every faction and what it unlocked
+pub fn render_reputation(reputation: &Reputation) {
+    let mut content: Vec<String> = reputation
+        .standings
+        .iter()
+        .map(|standing| {
+            let score = format!("{:+}", standing.score);
+            let score = if standing.score >= reputation.emote_threshold {
+                score.bright_green()
+            } else if standing.score < 0 {
+                score.bright_red()
+            } else {
+                score.normal()
+            };
+            let mut line = format!(
+                "{}  {:<20} {}",
+                ICON_BULLET,
+                standing.faction.display_name(),
+                score
+            );
+            if standing.emote_unlocked {
+                line.push_str(
+                    &format!(" /emote {:?}", standing.faction.emote())
+                        .to_lowercase()
+                        .bright_cyan()
+                        .to_string(),
+                );
+            }
+            if standing.item_unlocked {
+                line.push_str(&" signature item received".bright_yellow().to_string());
+            }
+            line
+        })
+        .collect();
+    content.push(
+        format!(
+            "{}  Emotes at {}, signature items at {} (/rep to refresh)",
+            ICON_INFO, reputation.emote_threshold, reputation.item_threshold
+        )
+        .dimmed()
+        .to_string(),
+    );
+
+    draw_panel(
+        "🏛️  REPUTATION",
+        &content,
+        PANEL_WIDTH,
+        PanelStyle::Secondary,
+    );
+}
+
+/// Render the quests we took on with our progress on each objective, and those on offer
+pub fn render_quests(state: &GameState) {
+    let mut content = Vec::new();
+    for (quest, progress) in state.quests.values() {
+        content.push(format!("{}  {}", ICON_BULLET, quest.name.bold()));
+        for (index, objective) in quest.objectives.iter().enumerate() {
+            let done = progress.get(index).copied().unwrap_or(0);
+            let line = format!(
+                "    {} {}/{}",
+                objective.describe(),
+                done.min(objective.required()),
+                objective.required()
+            );
+            content.push(if done >= objective.required() {
+                line.bright_green().to_string()
+            } else {
+                line
+            });
+        }
+    }
+    for quest in &state.quest_offers {
+        content.push(format!(
+            "{}  {} {} {}",
+            ICON_ARROW_RIGHT,
+            quest.id.bright_cyan(),
+            quest.name,
+            format!("({} XP)", quest.reward_experience).dimmed()
+        ));
+    }
+    content.push(
+        format!("{}  /quest <id> to take on an offered quest", ICON_INFO)
+            .dimmed()
+            .to_string(),
+    );
+
+    draw_panel("📜  QUESTS", &content, PANEL_WIDTH, PanelStyle::Secondary);
+}
+
+/// Color of the map marker of a kind of character
+fn npc_color(kind: NpcKind) -> &'static str {
+    match kind {
+        NpcKind::SurveillanceDrone => "bright red",
+        NpcKind::DeepNetBroker => "bright cyan",
+        NpcKind::MonkSentinel => "bright blue",
+    }
+}
+
+/// One line about a character in view: its ID to attack it by, what it is, its health and what it is doing
+fn npc_line(npc: &NpcView) -> String {
+    format!(
+        "{} {} {} {}/{} HP ({})",
+        "▲".color(npc_color(npc.kind)),
+        npc.display_id.bold(),
+        npc.kind.name(),
+        npc.health,
+        npc.max_health,
+        format!("{:?}", npc.state).to_lowercase()
+    )
+}
+
+/// Color of a control point held by `owner`: green if it is our faction, red if another
+fn owner_color(owner: Option<&Faction>, own_faction: Option<&Faction>) -> &'static str {
+    match owner {
+        None => "white",
+        Some(owner) if Some(owner) == own_faction => "bright green",
+        Some(_) => "bright red",
+    }
+}
+
+/// Render the control points of `region`, who holds them and who is taking them over
+pub fn render_territory(territory: &Territory, region: &str, own_faction: Option<&Faction>) {
+    let mut content: Vec<String> = territory
+        .control_points
+        .iter()
+        .filter(|point| point.region == region)
+        .map(|point| {
+            let owner = point
+                .owner
+                .as_ref()
+                .map_or("Unclaimed", |owner| owner.display_name());
+            let mut line = format!(
+                "{}  {:<12} ({:>4.0},{:>4.0})  {}",
+                "⚑".color(owner_color(point.owner.as_ref(), own_faction)),
+                point.name,
+                point.position.x,
+                point.position.y,
+                owner
+            );
+            if let Some(capturing) = &point.capturing {
+                line.push_str(
+                    &format!(
+                        " {} {} {}/{}s",
+                        ICON_ARROW_RIGHT,
+                        capturing.display_name(),
+                        point.progress_seconds,
+                        territory.capture_seconds
+                    )
+                    .bright_yellow()
+                    .to_string(),
+                );
+            }
+            line
+        })
+        .collect();
+    content.push(
+        format!(
+            "{}  Hold a point alone for {}s to capture it; owners inside gain XP and hide from surveillance",
+            ICON_INFO, territory.capture_seconds
+        )
+        .dimmed()
+        .to_string(),
+    );
+
+    draw_panel("⚑  TERRITORY", &content, PANEL_WIDTH, PanelStyle::Secondary);
+}
+
 /// Render privacy and connection status with modern indicators
 pub fn render_status_dashboard(state: &GameState) {
     // Get a lock on the status monitor
@@ -591,10 +1009,38 @@ pub fn render_status_dashboard(state: &GameState) {
             ICON_USERS,
             status_monitor.anonymity_set_size.to_string().cyan()
         ),
-        "".to_string(),
-        format!("{} Connection: {}", ICON_NETWORK, health_colored),
     ];
 
+    // Surveillance where we stand, and whether the cameras already caught us
+    if let Some((risk, unshielded)) = state.surveillance_level() {
+        let level = format!("{:.0}%", unshielded * 100.0);
+        let level_colored = if unshielded >= 0.5 {
+            level.red().bold()
+        } else if unshielded >= 0.25 {
+            level.yellow().bold()
+        } else {
+            level.green().bold()
+        };
+        content.push(format!(
+            "{} Surveillance here: {} ({:.0}% before privacy)",
+            ICON_SURVEILLANCE,
+            level_colored,
+            risk * 100.0
+        ));
+    }
+    if let Some(seconds) = state.exposed_seconds() {
+        content.push(format!(
+            "   {} {}",
+            ICON_WARNING,
+            format!("EXPOSED to the Hegemony for {}s", seconds)
+                .bright_red()
+                .bold()
+        ));
+    }
+
+    content.push("".to_string());
+    content.push(format!("{} Connection: {}", ICON_NETWORK, health_colored));
+
     // Add pacing status if enabled
     if status_monitor.pacing_info.enabled {
         content.push(format!(
@@ -696,16 +1142,26 @@ pub fn render_help_section() {
         "".to_string(),
         format!("{} /register <name> <faction>, /r <name> <faction> - Register with the server using the specified name and faction", ICON_BULLET),
         "    Valid factions: nyms, corporate, cipher, monks, indie - Each has unique gameplay benefits".to_string(),
+        format!("{} /resume - Take back the character you last registered on this server", ICON_BULLET),
         format!("{} /move <direction>, /m <direction> - Move in the specified direction", ICON_BULLET),
         "    Valid directions: up, down, left, right, upleft, upright, downleft, downright".to_string(),
         "    Direction shortcuts: /up (or /u, /n), /down (or /d, /s), /left (or /l, /w), /right (or /r, /e)".to_string(),
         "    Diagonal movement: /ne, /nw, /se, /sw - Move diagonally".to_string(),
         format!("{} /attack <player_id>, /a <player_id> - Attack player with the given display ID", ICON_BULLET),
         format!("{} /chat <message>, /c <message>, /say <message> - Send a chat message to all players", ICON_BULLET),
+        format!("{} /inventory, /inv, /i - Show the items you carry", ICON_BULLET),
+        format!("{} /use <item>, /drop <item> - Use an item to activate its stats for a while, or throw it away", ICON_BULLET),
+        format!("{} /equip <item>, /unequip <slot> - Wear an item in its slot (attack, defense, privacy, artifact) or take it off", ICON_BULLET),
+        format!("{} /pickup, /pick - Pick up the nearest item on the ground (◆ on the map)", ICON_BULLET),
+        format!("{} /trade <player_id> - Ask a player to trade, or agree to their request", ICON_BULLET),
+        format!("{} /offer [credits] [item[:qty]]..., /accept, /cancel [player_id] - Set your offer, confirm both offers or call the trade off", ICON_BULLET),
+        format!("{} /rep, /reputation - Show your standing with every faction", ICON_BULLET),
+        format!("{} /ability <name> [player_id], /ab - Use Encrypt (shield), Hack (stun a player), Ghost (stealth) or DataDrop (heal yourself or an ally)", ICON_BULLET),
+        format!("{} /quest <id> - Take on a quest from the quests panel; /whisper an NPC to talk to it", ICON_BULLET),
         format!("{} /emote <type>, /em <type> - Perform an emote action", ICON_BULLET),
         "    Standard emotes: wave, bow, laugh, dance, salute, shrug, cheer, clap, thumbsup".to_string(),
         "    Cypherpunk emotes: hack, encrypt, decrypt, surveillance, resist, ghost, datadrop, glitch".to_string(),
-        "    (Different factions have their own signature emotes in cypherpunk society)".to_string(),
+        "    Faction emotes: cloak, audit, leak, meditate, wander - Unlocked by reputation with their faction".to_string(),
         format!("{} /pacing [on|off] [interval_ms], /pace - Control message pacing for privacy protection", ICON_BULLET),
         "    Examples: /pacing on 150, /pacing off, /pacing status - View or modify timing protection".to_string(),
         format!("{} /help, /h, /? - Show this help information", ICON_BULLET),
@@ -752,7 +1208,7 @@ pub fn render_game_state(state: &GameState) {
     draw_status_bar(&status_text.to_string(), &time_text);
     println!();
 
-    match &state.player_id {
+    match &state.display_id {
         Some(id) => {
             // Connected state
             if let Some(player) = state.players.get(id) {
@@ -760,7 +1216,12 @@ pub fn render_game_state(state: &GameState) {
                 println!();
 
                 // Row 1: Player stats and connection status
-                render_player_stats(player, true);
+                render_player_stats(
+                    player,
+                    state.equipment.as_ref(),
+                    Some(&state.abilities_ready_at),
+                    true,
+                );
                 println!();
                 render_status_dashboard(state);
                 println!();
@@ -770,6 +1231,28 @@ pub fn render_game_state(state: &GameState) {
                 println!();
                 render_nearby_players(state);
                 println!();
+                render_inventory(state);
+                println!();
+                if let Some(trade) = &state.trade {
+                    render_trade(trade);
+                    println!();
+                }
+                if let Some(reputation) = &state.reputation {
+                    render_reputation(reputation);
+                    println!();
+                }
+                if !state.quests.is_empty() || !state.quest_offers.is_empty() {
+                    render_quests(state);
+                    println!();
+                }
+                if let (Some(territory), Some(boundaries)) =
+                    (&state.territory, state.get_world_boundaries())
+                {
+                    if !territory.control_points.is_empty() {
+                        render_territory(territory, &boundaries.name, Some(&player.faction));
+                        println!();
+                    }
+                }
 
                 // Full width sections
                 render_chat_history(state, 8);
@@ -818,6 +1301,10 @@ pub fn render_game_state(state: &GameState) {
                     "{} /register <name> <faction> - Join with chosen name and faction",
                     ICON_BULLET
                 ),
+                format!(
+                    "{} /resume - Continue with your saved character",
+                    ICON_BULLET
+                ),
                 format!("{}  /help - Show all commands", ICON_BULLET),
                 format!("{}  /quit - Exit game", ICON_BULLET),
             ];
@@ -834,3 +1321,45 @@ pub fn render_game_state(state: &GameState) {
     println!();
     render_input_prompt();
 }
+
+/// UI rendering helper for cypherpunk themes
+#[allow(dead_code)]
+pub struct CypherpunkUI;
+
+impl CypherpunkUI {
+    /// Generate a thematic message prefix based on security level
+    #[allow(dead_code)]
+    pub fn security_prefix(level: &SecurityLevel) -> &'static str {
+        match level {
+            SecurityLevel::None => "[MESH NETWORK] ",
+            SecurityLevel::Low => "[DEEP PROXY] ",
+            SecurityLevel::Moderate => "[BOUNCED] ",
+            SecurityLevel::High => "[ENCRYPTED] ",
+            SecurityLevel::Maximum => "[CORPORATE] ",
+        }
+    }
+
+    /// Add visual glitch effects to text based on corruption level
+    #[allow(dead_code)]
+    pub fn apply_text_glitch(text: &str, corruption: f32) -> String {
+        if corruption <= 0.0 {
+            return text.to_string();
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let glitch_chars = [
+            '¡', '¢', '£', '¤', '¥', '¦', '§', '¨', '©', 'ª', '«', '¬', '®', '¯',
+        ];
+
+        for c in text.chars() {
+            if rand::random::<f32>() < corruption {
+                let glitch_index = (rand::random::<f32>() * glitch_chars.len() as f32) as usize;
+                result.push(glitch_chars[glitch_index]);
+            } else {
+                result.push(c);
+            }
+        }
+
+        result
+    }
+}
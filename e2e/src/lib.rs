@@ -0,0 +1,276 @@
+//! End-to-end test harness for NymQuest
+//!
+//! Boots the real server event loop on an in-process `LocalNetwork` and
+//! connects scripted clients that use the real `NetworkManager`, so tests
+//! exercise the full path: client sequencing, padding and authentication,
+//! server handlers, acknowledgements and retries. Nothing touches the mixnet.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Instant};
+
+use nym_mmorpg_client::config::ClientConfig;
+use nym_mmorpg_client::game_state::{GameState as ClientGameState, PlayerListUpdate};
+use nym_mmorpg_client::network::NetworkManager;
+use nym_mmorpg_client::status_monitor::StatusMonitor;
+use nym_mmorpg_server::config::GameConfig;
+use nym_mmorpg_server::event_loop::run_event_loop;
+use nym_mmorpg_server::game_state::GameState;
+use nym_mmorpg_server::persistence::GameStatePersistence;
+use nym_mmorpg_server::sessions::SessionManager;
+use nymquest_protocol::game_protocol::{ClientMessage, PlayerView, ProtocolVersion, ServerMessage};
+use nymquest_protocol::local_transport::{LocalConnector, LocalNetwork};
+use nymquest_protocol::session::{ServerIdentity, ServerPublicKey};
+use nymquest_protocol::world_lore::Faction;
+
+/// Address the test server listens on
+pub const SERVER_ADDRESS: &str = "nymquest-server";
+
+/// How long helpers wait for an expected message before failing
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(5);
+
+/// Server event loop running on a background task
+pub struct TestServer {
+    network: LocalNetwork,
+    game_state: Arc<GameState>,
+    server_key: ServerPublicKey,
+    shutdown_tx: mpsc::Sender<()>,
+    handle: JoinHandle<Result<()>>,
+}
+
+impl TestServer {
+    /// Start a server with the default game configuration
+    pub async fn start() -> Result<Self> {
+        Self::start_with_config(GameConfig::default()).await
+    }
+
+    /// Start a server with a specific game configuration
+    ///
+    /// Persistence is always disabled. The global rate limiter is left
+    /// uninitialized so scripted clients are not throttled.
+    pub async fn start_with_config(game_config: GameConfig) -> Result<Self> {
+        let network = LocalNetwork::new();
+        let transport = network.endpoint(SERVER_ADDRESS)?;
+        let game_state = Arc::new(GameState::new_with_config(game_config.clone()));
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let server_key = sessions.public_key();
+        let persistence = GameStatePersistence::new(&game_config.persistence_dir, false);
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+        let handle = tokio::spawn(run_event_loop(
+            transport,
+            Arc::clone(&game_state),
+            sessions,
+            game_config,
+            persistence,
+            shutdown_rx,
+        ));
+
+        Ok(Self {
+            network,
+            game_state,
+            server_key,
+            shutdown_tx,
+            handle,
+        })
+    }
+
+    /// The network the server is attached to, for fault injection
+    pub fn network(&self) -> &LocalNetwork {
+        &self.network
+    }
+
+    /// Live server-side game state
+    pub fn game_state(&self) -> &Arc<GameState> {
+        &self.game_state
+    }
+
+    /// Connect a new client using the public key the server would publish via discovery
+    pub async fn connect_client(&self) -> Result<TestClient> {
+        let config = ClientConfig {
+            // Pacing only adds latency here; the mixnet is what it hides timing from
+            enable_message_pacing: false,
+            ..ClientConfig::default()
+        };
+        let status_monitor = Arc::new(Mutex::new(StatusMonitor::new()));
+        let network = NetworkManager::with_connector(
+            LocalConnector::new(self.network.clone()),
+            SERVER_ADDRESS.to_string(),
+            self.server_key,
+            &config,
+            status_monitor,
+        )
+        .await?;
+
+        Ok(TestClient {
+            network,
+            received: Vec::new(),
+            world: ClientGameState::new(),
+        })
+    }
+
+    /// Signal shutdown and wait for the event loop to finish
+    pub async fn shutdown(self) -> Result<()> {
+        self.shutdown_tx
+            .send(())
+            .await
+            .map_err(|_| anyhow!("Server event loop already stopped"))?;
+        timeout(Duration::from_secs(10), self.handle)
+            .await
+            .context("Server did not shut down in time")??
+    }
+}
+
+/// Scripted client driving a real `NetworkManager`
+pub struct TestClient {
+    /// The client's network manager, for direct access in tests
+    pub network: NetworkManager<LocalConnector>,
+    /// Every server message delivered to the application so far
+    pub received: Vec<ServerMessage>,
+    /// Client-side view of the world, built from snapshots and deltas as the game client does
+    pub world: ClientGameState,
+}
+
+impl TestClient {
+    /// Send a message; sequence numbers are filled in by the network manager
+    pub async fn send(&mut self, message: ClientMessage) -> Result<()> {
+        self.network.send_message(message).await
+    }
+
+    /// Register a player and wait for the server's RegisterAck, returning the player's display ID
+    pub async fn register(&mut self, name: &str, faction: Faction) -> Result<String> {
+        self.send(ClientMessage::Register {
+            name: name.to_string(),
+            faction,
+            seq_num: 0,
+            protocol_version: ProtocolVersion::default(),
+            session_public_key: String::new(),
+        })
+        .await?;
+
+        match self
+            .wait_for(|message| matches!(message, ServerMessage::RegisterAck { .. }))
+            .await?
+        {
+            ServerMessage::RegisterAck { display_id, .. } => Ok(display_id),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Reclaim the character behind `token` and wait for the RegisterAck, returning its display ID
+    pub async fn resume(&mut self, token: &str) -> Result<String> {
+        self.send(ClientMessage::Resume {
+            token: token.to_string(),
+            seq_num: 0,
+            protocol_version: ProtocolVersion::default(),
+            session_public_key: String::new(),
+        })
+        .await?;
+
+        match self
+            .wait_for(|message| matches!(message, ServerMessage::RegisterAck { .. }))
+            .await?
+        {
+            ServerMessage::RegisterAck { display_id, .. } => Ok(display_id),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Receive messages until one matches `predicate`, failing after `DEFAULT_WAIT`
+    pub async fn wait_for<F>(&mut self, predicate: F) -> Result<ServerMessage>
+    where
+        F: Fn(&ServerMessage) -> bool,
+    {
+        let deadline = Instant::now() + DEFAULT_WAIT;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match timeout(remaining, self.network.receive_message()).await {
+                Ok(Some(message)) => {
+                    self.record(&message);
+                    if predicate(&message) {
+                        return Ok(message);
+                    }
+                }
+                // Acks, duplicates and undecodable messages are consumed internally
+                Ok(None) => {}
+                Err(_) => {
+                    return Err(anyhow!(
+                        "Timed out waiting for message; received {:?}",
+                        self.received
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Receive messages until the client's player list matches `predicate`,
+    /// failing after `DEFAULT_WAIT`
+    pub async fn wait_for_players<F>(&mut self, predicate: F) -> Result<()>
+    where
+        F: Fn(&HashMap<String, PlayerView>) -> bool,
+    {
+        let deadline = Instant::now() + DEFAULT_WAIT;
+        while !predicate(&self.world.players) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match timeout(remaining, self.network.receive_message()).await {
+                Ok(Some(message)) => self.record(&message),
+                Ok(None) => {}
+                Err(_) => {
+                    return Err(anyhow!(
+                        "Timed out waiting for players; client sees {:?}",
+                        self.world.players
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive everything that arrives within `duration`
+    pub async fn drain_for(&mut self, duration: Duration) -> Vec<ServerMessage> {
+        let deadline = Instant::now() + duration;
+        let mut messages = Vec::new();
+        while let Ok(result) = timeout(
+            deadline.saturating_duration_since(Instant::now()),
+            self.network.receive_message(),
+        )
+        .await
+        {
+            if let Some(message) = result {
+                self.record(&message);
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    fn record(&mut self, message: &ServerMessage) {
+        self.received.push(message.clone());
+        match message.clone() {
+            ServerMessage::GameState { players, seq_num } => {
+                self.world
+                    .update_players(seq_num, PlayerListUpdate::Snapshot(players));
+            }
+            ServerMessage::GameStateDelta {
+                baseline_seq,
+                updated,
+                removed,
+                seq_num,
+            } => {
+                self.world.update_players(
+                    seq_num,
+                    PlayerListUpdate::Delta {
+                        baseline_seq,
+                        updated,
+                        removed,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! End-to-end scenarios: real server event loop, real client network stack,
+//! in-process transport
+
+use std::time::Duration;
+
+use nym_mmorpg_server::config::GameConfig;
+use nymquest_e2e::{TestServer, SERVER_ADDRESS};
+use nymquest_protocol::game_protocol::{ClientMessage, Direction, Position, ServerMessage};
+use nymquest_protocol::world_lore::Faction;
+
+#[tokio::test]
+async fn test_full_session_flow() {
+    let server = TestServer::start().await.unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    let mut bob = server.connect_client().await.unwrap();
+
+    // Register
+    let alice_display_id = alice.register("alice", Faction::Nyms).await.unwrap();
+    let bob_display_id = bob
+        .register("bob", Faction::CipherCollective)
+        .await
+        .unwrap();
+    assert_ne!(alice_display_id, bob_display_id);
+    assert_eq!(server.game_state().get_players().len(), 2);
+    assert_eq!(alice.network.get_negotiated_protocol_version(), Some(1));
+
+    // Place the players within view of each other, away from the world edges
+    let game_state = server.game_state();
+    let alice_id = game_state
+        .get_player_id_by_display_id(&alice_display_id)
+        .unwrap();
+    let bob_id = game_state
+        .get_player_id_by_display_id(&bob_display_id)
+        .unwrap();
+    assert!(game_state.update_player_position(&alice_id, Position::new(0.0, 0.0)));
+    assert!(game_state.update_player_position(&bob_id, Position::new(20.0, 0.0)));
+
+    // Move: the server updates the position and broadcasts it to everyone
+    alice
+        .send(ClientMessage::Move {
+            direction: Direction::Down,
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    let expected = Position::new(0.0, game_state.get_config().movement_speed);
+    bob.wait_for_players(|players| {
+        players
+            .get(&alice_display_id)
+            .is_some_and(|alice| alice.position == expected)
+    })
+    .await
+    .unwrap();
+    assert_eq!(game_state.get_player(&alice_id).unwrap().position, expected);
+    alice
+        .wait_for_players(|players| players.len() == 2)
+        .await
+        .unwrap();
+
+    // Attack: bob is in range and takes damage
+    alice
+        .send(ClientMessage::Attack {
+            target_display_id: bob_display_id.clone(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    bob.wait_for_players(|players| {
+        players
+            .get(&bob_display_id)
+            .is_some_and(|bob| bob.health < 100)
+    })
+    .await
+    .unwrap();
+    assert!(game_state.get_player(&bob_id).unwrap().health < 100);
+
+    // Chat: delivered to other players with the sender's name
+    alice
+        .send(ClientMessage::Chat {
+            message: "hello from the mixnet".to_string(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    bob.wait_for(|message| {
+        matches!(message, ServerMessage::ChatMessage { sender_name, message, .. }
+            if sender_name == "alice" && message == "hello from the mixnet")
+    })
+    .await
+    .unwrap();
+
+    // Whisper: delivered privately to the target
+    alice
+        .send(ClientMessage::Whisper {
+            target_display_id: bob_display_id,
+            message: "meet at the relay".to_string(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    bob.wait_for(|message| {
+        matches!(message, ServerMessage::WhisperMessage { sender_name, message, .. }
+            if sender_name == "alice" && message == "meet at the relay")
+    })
+    .await
+    .unwrap();
+
+    // Every message alice sent was acknowledged
+    alice.drain_for(Duration::from_millis(200)).await;
+    assert_eq!(alice.network.pending_ack_count(), 0);
+
+    // Disconnect: alice leaves the game and bob sees the updated state
+    alice.network.disconnect().await.unwrap();
+    bob.wait_for_players(|players| !players.contains_key(&alice_display_id))
+        .await
+        .unwrap();
+    assert!(game_state.get_player(&alice_id).is_none());
+    assert_eq!(game_state.get_players().len(), 1);
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_character_is_reclaimed_after_reconnect() {
+    let server = TestServer::start().await.unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    let mut bob = server.connect_client().await.unwrap();
+    let alice_display_id = alice.register("alice", Faction::Nyms).await.unwrap();
+    let bob_display_id = bob.register("bob", Faction::Nyms).await.unwrap();
+    let token = alice.network.reclaim_token().unwrap().to_string();
+
+    let game_state = server.game_state();
+    let alice_id = game_state
+        .get_player_id_by_display_id(&alice_display_id)
+        .unwrap();
+    assert!(game_state.update_player_position(&alice_id, Position::new(30.0, -30.0)));
+    // Bob stays within view to see her come back
+    let bob_id = game_state
+        .get_player_id_by_display_id(&bob_display_id)
+        .unwrap();
+    assert!(game_state.update_player_position(&bob_id, Position::new(30.0, 0.0)));
+    let before = game_state.get_player(&alice_id).unwrap();
+
+    // Alice's client goes away without disconnecting and comes back on a new connection
+    drop(alice);
+    let mut returning = server.connect_client().await.unwrap();
+    assert_eq!(returning.resume(&token).await.unwrap(), alice_display_id);
+
+    // Same character, same place, and no duplicate on the roster
+    let after = game_state.get_player(&alice_id).unwrap();
+    assert_eq!(after.position, before.position);
+    assert_eq!(after.display_id, before.display_id);
+    assert_eq!(game_state.get_players().len(), 2);
+    bob.wait_for_players(|players| players.len() == 2 && players.contains_key(&alice_display_id))
+        .await
+        .unwrap();
+
+    // The new connection plays the character
+    returning
+        .send(ClientMessage::Chat {
+            message: "back again".to_string(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    bob.wait_for(|message| {
+        matches!(message, ServerMessage::ChatMessage { sender_name, message, .. }
+            if sender_name == "alice" && message == "back again")
+    })
+    .await
+    .unwrap();
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_character_is_resumed_after_quitting() {
+    let server = TestServer::start().await.unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    let alice_display_id = alice.register("alice", Faction::Nyms).await.unwrap();
+    let token = alice.network.reclaim_token().unwrap().to_string();
+
+    let game_state = server.game_state();
+    let alice_id = game_state
+        .get_player_id_by_display_id(&alice_display_id)
+        .unwrap();
+    assert!(game_state.update_player_position(&alice_id, Position::new(-20.0, 15.0)));
+    let before = game_state.get_player(&alice_id).unwrap();
+
+    // Alice quits: the character leaves the world but is still saved
+    alice.network.disconnect().await.unwrap();
+    alice.drain_for(Duration::from_millis(200)).await;
+    assert!(game_state.get_player(&alice_id).is_none());
+    assert!(game_state.get_saved_players().contains_key(&alice_id));
+
+    // A newcomer does not get her display ID while she is away
+    let mut carol = server.connect_client().await.unwrap();
+    assert_ne!(
+        carol.register("carol", Faction::Nyms).await.unwrap(),
+        alice_display_id
+    );
+
+    // Relaunching the client and resuming puts her back where she left
+    let mut returning = server.connect_client().await.unwrap();
+    assert_eq!(returning.resume(&token).await.unwrap(), alice_display_id);
+    let after = game_state.get_player(&alice_id).unwrap();
+    assert_eq!(after.position, before.position);
+    assert_eq!(after.experience, before.experience);
+    assert_eq!(game_state.get_players().len(), 2);
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_clients_get_deltas_between_keyframes() {
+    let server = TestServer::start_with_config(GameConfig {
+        state_broadcast_interval_seconds: 1,
+        // Everyone is in view wherever they spawn
+        view_radius: 1000.0,
+        ..GameConfig::default()
+    })
+    .await
+    .unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    let mut bob = server.connect_client().await.unwrap();
+    let alice_display_id = alice.register("alice", Faction::Nyms).await.unwrap();
+    bob.register("bob", Faction::Nyms).await.unwrap();
+    bob.wait_for_players(|players| players.len() == 2)
+        .await
+        .unwrap();
+    // Let bob's acknowledgement of the snapshot reach the server
+    bob.drain_for(Duration::from_millis(100)).await;
+
+    // A move reaches bob as a delta holding only alice
+    alice
+        .send(ClientMessage::Move {
+            direction: Direction::Up,
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    bob.wait_for(|message| {
+        matches!(message, ServerMessage::GameStateDelta { updated, removed, .. }
+            if updated.len() == 1 && updated[0].display_id == alice_display_id && removed.is_empty())
+    })
+    .await
+    .unwrap();
+    let alice_id = server
+        .game_state()
+        .get_player_id_by_display_id(&alice_display_id)
+        .unwrap();
+    let position = server.game_state().get_player(&alice_id).unwrap().position;
+    assert_eq!(bob.world.players[&alice_display_id].position, position);
+
+    // Keyframes keep arriving with nothing happening
+    bob.wait_for(
+        |message| matches!(message, ServerMessage::GameState { players, .. } if players.len() == 2),
+    )
+    .await
+    .unwrap();
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_lost_message_is_resent_until_acknowledged() {
+    let server = TestServer::start().await.unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    let mut bob = server.connect_client().await.unwrap();
+    alice.register("alice", Faction::Nyms).await.unwrap();
+    bob.register("bob", Faction::Nyms).await.unwrap();
+    alice.drain_for(Duration::from_millis(100)).await;
+    alice.network.set_ack_timeouts(200, 100, 2);
+
+    // The first copy of the chat message is lost on its way to the server
+    server.network().drop_next(SERVER_ADDRESS, 1).unwrap();
+    alice
+        .send(ClientMessage::Chat {
+            message: "are you there?".to_string(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    assert!(alice.drain_for(Duration::from_millis(300)).await.is_empty());
+    assert_eq!(alice.network.pending_ack_count(), 1);
+
+    // The retry gets through, is acknowledged and reaches bob exactly once
+    alice.network.check_for_resends().await.unwrap();
+    alice
+        .wait_for(|message| {
+            matches!(message, ServerMessage::Event { message, .. } if message.contains("are you there?"))
+        })
+        .await
+        .unwrap();
+    alice.drain_for(Duration::from_millis(100)).await;
+    assert_eq!(alice.network.pending_ack_count(), 0);
+
+    bob.wait_for(|message| matches!(message, ServerMessage::ChatMessage { .. }))
+        .await
+        .unwrap();
+    let duplicates = bob.drain_for(Duration::from_millis(200)).await;
+    assert!(!duplicates
+        .iter()
+        .any(|message| matches!(message, ServerMessage::ChatMessage { .. })));
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_many_clients_see_each_other() {
+    const CLIENTS: usize = 8;
+
+    let server = TestServer::start_with_config(GameConfig {
+        max_players: CLIENTS,
+        // Everyone is in view wherever they spawn
+        view_radius: 1000.0,
+        ..GameConfig::default()
+    })
+    .await
+    .unwrap();
+
+    let mut clients = Vec::new();
+    for i in 0..CLIENTS {
+        let mut client = server.connect_client().await.unwrap();
+        client
+            .register(&format!("runner{}", i), Faction::Independent)
+            .await
+            .unwrap();
+        clients.push(client);
+    }
+    assert_eq!(server.game_state().get_players().len(), CLIENTS);
+
+    // Every client eventually sees the full roster
+    for client in &mut clients {
+        client
+            .wait_for_players(|players| players.len() == CLIENTS)
+            .await
+            .unwrap();
+    }
+
+    // A chat from the first client reaches all the others
+    clients[0]
+        .send(ClientMessage::Chat {
+            message: "roll call".to_string(),
+            seq_num: 0,
+        })
+        .await
+        .unwrap();
+    for client in clients.iter_mut().skip(1) {
+        client
+            .wait_for(|message| {
+                matches!(message, ServerMessage::ChatMessage { sender_name, .. } if sender_name == "runner0")
+            })
+            .await
+            .unwrap();
+    }
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_notifies_connected_players() {
+    let server = TestServer::start().await.unwrap();
+    let mut alice = server.connect_client().await.unwrap();
+    alice.register("alice", Faction::Nyms).await.unwrap();
+
+    let game_state = server.game_state().clone();
+    server.shutdown().await.unwrap();
+
+    alice
+        .wait_for(|message| matches!(message, ServerMessage::ServerShutdown { .. }))
+        .await
+        .unwrap();
+    assert_eq!(game_state.get_players().len(), 1);
+}
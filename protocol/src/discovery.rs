@@ -4,9 +4,9 @@ use std::fs;
 use std::path::PathBuf;
 use tracing::{debug, info, warn};
 
-/// Module for server address discovery mechanisms on the client side
-/// This module provides cross-platform functionality for discovering server addresses
-/// saved by the server, enabling automatic connection without hardcoded paths
+/// Module for server address discovery shared by the server and the client
+/// The server writes its address file into the platform-specific data directory and
+/// the client searches the same locations, so both sides must agree on these paths
 /// while maintaining privacy and anonymity requirements through the mixnet.
 pub const SERVER_ADDRESS_ENV_VAR: &str = "NYMQUEST_SERVER_ADDRESS_FILE";
 
@@ -14,6 +14,7 @@ pub const SERVER_ADDRESS_ENV_VAR: &str = "NYMQUEST_SERVER_ADDRESS_FILE";
 pub const SERVER_ADDRESS_FILENAME: &str = "nymquest_server.addr";
 
 /// Get the platform-specific data directory for NymQuest server configuration
+/// This only resolves the path; the server is responsible for creating it
 pub fn get_server_data_dir() -> Result<PathBuf> {
     // Use XDG Base Directory specification on Unix, AppData on Windows
     let base_dir = dirs_next::data_dir()
@@ -21,11 +22,11 @@ pub fn get_server_data_dir() -> Result<PathBuf> {
         .ok_or_else(|| anyhow::anyhow!("Cannot determine user data directory"))?;
 
     let nymquest_dir = base_dir.join("nymquest").join("server");
-    debug!("Looking for server data directory: {:?}", nymquest_dir);
+    debug!("Resolved server data directory: {:?}", nymquest_dir);
     Ok(nymquest_dir)
 }
 
-/// Get all possible locations where the client should look for server address file
+/// Get all possible locations where the server address file may be found
 /// Returns paths in order of preference (most specific to most general)
 pub fn get_server_address_discovery_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
@@ -97,23 +98,21 @@ pub fn discover_server_address_file() -> Result<(PathBuf, String)> {
 }
 
 /// Validate server address file format
-/// Expected format: "nym_address;base64_auth_key"
+/// Expected format: "nym_address;ed25519:base64_public_key"
 pub fn validate_server_address_format(content: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = content.split(';').collect();
     if parts.len() != 2 {
         return Err(anyhow::anyhow!(
-            "Invalid server address file format. Expected 'nym_address;auth_key' format, got {} parts", 
+            "Invalid server address file format. Expected 'nym_address;public_key' format, got {} parts", 
             parts.len()
         ));
     }
 
     let server_address = parts[0].trim();
-    let auth_key = parts[1].trim();
+    let public_key = parts[1].trim();
 
-    if server_address.is_empty() || auth_key.is_empty() {
-        return Err(anyhow::anyhow!(
-            "Server address or authentication key is empty"
-        ));
+    if server_address.is_empty() || public_key.is_empty() {
+        return Err(anyhow::anyhow!("Server address or public key is empty"));
     }
 
     // Basic validation of Nym address format (should contain dots for proper addressing)
@@ -123,23 +122,23 @@ pub fn validate_server_address_format(content: &str) -> Result<(String, String)>
         ));
     }
 
-    Ok((server_address.to_string(), auth_key.to_string()))
+    Ok((server_address.to_string(), public_key.to_string()))
 }
 
 /// Load server connection information using the discovery mechanism
-/// Returns the server address and authentication key
+/// Returns the server address and the server's public key
 pub fn load_server_connection_info() -> Result<(String, String)> {
     let (path, content) =
         discover_server_address_file().with_context(|| "Failed to discover server address file")?;
 
-    let (server_address, auth_key_b64) = validate_server_address_format(&content)
+    let (server_address, public_key) = validate_server_address_format(&content)
         .with_context(|| format!("Invalid server address file format at {:?}", path))?;
 
     info!(
         "Successfully loaded server connection info from: {:?}",
         path
     );
-    Ok((server_address, auth_key_b64))
+    Ok((server_address, public_key))
 }
 
 #[cfg(test)]
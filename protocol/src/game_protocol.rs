@@ -0,0 +1,2056 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use crate::world_lore::{
+    CryptoItem, EquipmentSlot, Faction, InventoryItem, ItemRarity, ItemStats, WorldRegion,
+};
+
+/// Current protocol version - increment when making breaking changes
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Minimum supported protocol version for backward compatibility
+pub const MIN_SUPPORTED_VERSION: u16 = 1;
+
+/// Protocol version information exchanged during connection setup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProtocolVersion {
+    pub current: u16,
+    pub min_supported: u16,
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> Self {
+        Self {
+            current: PROTOCOL_VERSION,
+            min_supported: MIN_SUPPORTED_VERSION,
+        }
+    }
+}
+
+impl ProtocolVersion {
+    /// Check if this version is compatible with another version
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn is_compatible_with(&self, other: &ProtocolVersion) -> bool {
+        // We can communicate if our ranges overlap
+        self.min_supported <= other.current && other.min_supported <= self.current
+    }
+
+    /// Get the negotiated version to use (highest common version)
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn negotiate_with(&self, other: &ProtocolVersion) -> Option<u16> {
+        if !self.is_compatible_with(other) {
+            return None;
+        }
+        // Use the lower of the two current versions
+        Some(self.current.min(other.current))
+    }
+}
+
+// Player's position in the game world
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Position {
+    /// Create a new position from coordinates
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    // Add a movement vector to this position
+    #[allow(dead_code)]
+    pub fn apply_movement(&mut self, move_vector: (f32, f32), speed: f32) {
+        self.x += move_vector.0 * speed;
+        self.y += move_vector.1 * speed;
+
+        // Note: Boundary checking should be done separately using WorldBoundaries
+    }
+
+    /// Calculate distance to another position
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn distance_to(&self, other: &Position) -> f32 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+// Player representation kept by the server; clients only ever receive a PlayerView
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub id: String,         // Internal server ID (UUID) - never sent to clients
+    pub display_id: String, // Public privacy-preserving identifier (e.g. "Player1")
+    pub position: Position,
+    pub health: u32,
+    pub name: String,
+    pub last_attack_time: u64,
+    pub last_damage_time: u64, // Time (seconds) the player last lost health to an attack or effect
+    pub experience: u32,       // Experience points earned through gameplay
+    pub level: u8,             // Player level based on experience
+    pub faction: Faction,      // The player's chosen faction
+    pub inventory: BTreeMap<String, u32>, // Item ID -> number of unused copies carried
+    pub active_items: BTreeMap<String, u64>, // Item ID -> time (seconds) its effect wears off
+    pub equipment: BTreeMap<EquipmentSlot, String>, // Slot -> ID of the item worn in it
+    pub credits: u32,          // Spendable credits, earned along with experience
+    pub escrow: TradeAssets,   // Credits and items the server holds for an open trade
+    pub reputation: BTreeMap<Faction, i32>, // Standing with each faction; missing factions are at 0
+    pub rewarded_factions: BTreeSet<Faction>, // Factions that handed the player their signature item
+    pub quests: BTreeMap<String, QuestStatus>, // Quest ID -> progress on every quest the player accepted
+    pub effects: BTreeMap<StatusEffectKind, StatusEffect>, // Status effects currently on the player
+}
+
+// Credits and items one side of a trade puts up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeAssets {
+    pub credits: u32,
+    pub items: BTreeMap<String, u32>, // Item ID -> number of copies
+}
+
+impl TradeAssets {
+    /// Whether nothing at all is put up
+    pub fn is_empty(&self) -> bool {
+        self.credits == 0 && self.item_count() == 0
+    }
+
+    /// Number of item copies put up
+    pub fn item_count(&self) -> u32 {
+        self.items.values().sum()
+    }
+}
+
+// Public view of a player, the only player data that leaves the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub display_id: String, // Public privacy-preserving identifier (e.g. "Player1")
+    pub name: String,
+    pub position: Position,
+    pub health: u32,
+    pub level: u8,
+    pub faction: Faction,
+    pub effects: BTreeMap<StatusEffectKind, u32>, // Status effects on the player -> their stacks
+}
+
+impl From<&Player> for PlayerView {
+    fn from(player: &Player) -> Self {
+        Self {
+            display_id: player.display_id.clone(),
+            name: player.name.clone(),
+            position: player.position,
+            health: player.health,
+            level: player.level,
+            faction: player.faction.clone(),
+            effects: player
+                .effects
+                .iter()
+                .map(|(kind, effect)| (*kind, effect.stacks))
+                .collect(),
+        }
+    }
+}
+
+// An item lying on the ground, which players within reach can pick up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootDrop {
+    pub drop_id: u64,
+    pub item_id: String,
+    pub name: String,
+    pub rarity: ItemRarity,
+    pub position: Position,
+}
+
+// Kinds of non-player characters the server controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NpcKind {
+    SurveillanceDrone,
+    DeepNetBroker,
+    MonkSentinel,
+}
+
+impl NpcKind {
+    /// Name shown to players
+    pub fn name(&self) -> &'static str {
+        match self {
+            NpcKind::SurveillanceDrone => "Surveillance Drone",
+            NpcKind::DeepNetBroker => "Deep Net Broker",
+            NpcKind::MonkSentinel => "Monk Sentinel",
+        }
+    }
+}
+
+// What a non-player character is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NpcState {
+    Idle,
+    Patrol,
+    Chase,
+    Flee,
+}
+
+// Public view of a non-player character, which players can attack by its display ID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcView {
+    pub display_id: String,
+    pub kind: NpcKind,
+    pub position: Position,
+    pub health: u32,
+    pub max_health: u32,
+    pub state: NpcState,
+}
+
+// Abilities players can use on top of their plain attack, each with its own cooldown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Ability {
+    Encrypt,  // Shield yourself from part of the damage you take
+    Hack,     // Stun another player so they cannot move, attack or use abilities
+    Ghost,    // Hide from other players unless they stand right next to you
+    DataDrop, // Heal yourself or a player of your faction
+}
+
+impl Ability {
+    /// Every ability, in the order they are listed to players
+    pub const ALL: [Ability; 4] = [
+        Ability::Encrypt,
+        Ability::Hack,
+        Ability::Ghost,
+        Ability::DataDrop,
+    ];
+
+    /// Name shown to players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ability::Encrypt => "Encrypt",
+            Ability::Hack => "Hack",
+            Ability::Ghost => "Ghost",
+            Ability::DataDrop => "DataDrop",
+        }
+    }
+
+    /// Faction whose members can use the ability from the start
+    pub fn faction(&self) -> Faction {
+        match self {
+            Ability::Encrypt => Faction::CipherCollective,
+            Ability::Hack => Faction::CorporateHegemony,
+            Ability::Ghost => Faction::Nyms,
+            Ability::DataDrop => Faction::AlgorithmMonks,
+        }
+    }
+
+    /// Level a member of `faction` needs to use the ability
+    ///
+    /// Independents pick up every ability a little sooner than members of other factions.
+    pub fn unlock_level(&self, faction: &Faction) -> u8 {
+        if *faction == self.faction() {
+            1
+        } else if *faction == Faction::Independent {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// Whether the ability is used on another player rather than on yourself
+    pub fn needs_target(&self) -> bool {
+        matches!(self, Ability::Hack)
+    }
+
+    /// Parse an ability from its name or an alias
+    #[allow(clippy::should_implement_trait)] // Lenient parsing with aliases, returns Option
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "encrypt" | "shield" => Some(Ability::Encrypt),
+            "hack" | "stun" => Some(Ability::Hack),
+            "ghost" | "stealth" => Some(Ability::Ghost),
+            "datadrop" | "data_drop" | "heal" => Some(Ability::DataDrop),
+            _ => None,
+        }
+    }
+}
+
+// How long until an ability can be used again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityCooldown {
+    pub ability: Ability,
+    pub cooldown_seconds: u64,
+    pub remaining_seconds: u64,
+}
+
+// Time-limited effects a player can be under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StatusEffectKind {
+    Shield,     // Part of the damage the player takes is absorbed
+    Stun,       // The player cannot move, attack or use abilities
+    Stealth,    // Only players right next to the player can see them
+    Corruption, // The player takes damage every second, more with every stack
+    Downed,     // The player was defeated and can do nothing until they respawn
+}
+
+impl StatusEffectKind {
+    /// Name shown to players
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusEffectKind::Shield => "Shield",
+            StatusEffectKind::Stun => "Stun",
+            StatusEffectKind::Stealth => "Stealth",
+            StatusEffectKind::Corruption => "Corruption",
+            StatusEffectKind::Downed => "Downed",
+        }
+    }
+}
+
+// One effect on a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub stacks: u32,     // How many times the effect is applied at once
+    pub expires_at: u64, // Time (seconds) the effect wears off
+}
+
+// One thing a quest asks of the player, tagged by `type` in quest files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestObjective {
+    // Stand within `radius` of `position`
+    Reach { position: Position, radius: f32 },
+    // Defeat `count` NPCs of a kind
+    Defeat { kind: NpcKind, count: u32 },
+    // Whisper to the NPC with this display ID
+    Whisper { npc: String },
+    // Spend `seconds` in a row unexposed where the surveillance is at most `max_surveillance`
+    StayHidden { max_surveillance: f32, seconds: u32 },
+}
+
+// A quest the server offers, as read from its quest file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub faction: Option<Faction>, // Only members of this faction are offered the quest; everyone if None
+    pub objectives: Vec<QuestObjective>, // All of them must be met, in any order
+    #[serde(default)]
+    pub reward_experience: u32,
+    #[serde(default)]
+    pub reward_items: Vec<String>, // Item IDs handed out on completion
+}
+
+// A player's progress on a quest they accepted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuestStatus {
+    pub progress: Vec<u32>, // Progress on each objective, in the order of the definition
+    pub completed: bool,
+}
+
+// A player's standing with one faction and what it unlocked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionStanding {
+    pub faction: Faction,
+    pub score: i32,
+    pub emote_unlocked: bool, // The faction's emote can be performed
+    pub item_unlocked: bool,  // The faction granted its signature item on reaching the threshold
+}
+
+// A spot on the map that factions fight over, and who holds it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlPoint {
+    pub name: String,
+    pub region: String, // Name of the region the point lies in
+    pub position: Position,
+    pub radius: f32, // Players within this distance of `position` occupy the point
+    pub owner: Option<Faction>, // Faction whose members get the point's bonuses
+    pub capturing: Option<Faction>, // Faction currently taking the point over
+    pub progress_seconds: u32, // How long `capturing` has held the point uncontested
+}
+
+// Type of client message (used for acknowledgements)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ClientMessageType {
+    Register,
+    Move,
+    Attack,
+    Chat,
+    Emote,
+    Disconnect,
+    Heartbeat,
+    Ack,
+    Whisper,
+    Resume,
+    UseItem,
+    DropItem,
+    Inventory,
+    Equip,
+    Unequip,
+    PickUp,
+    TradeRequest,
+    TradeOffer,
+    TradeAccept,
+    TradeCancel,
+    Reputation,
+    QuestAccept,
+    UseAbility,
+}
+
+// Message types that the client can send to the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    // Message to register in the game with protocol version negotiation
+    Register {
+        name: String,
+        faction: Faction, // Player's selected faction
+        seq_num: u64,
+        protocol_version: ProtocolVersion,
+        session_public_key: String, // Client's X25519 key for this session, base64
+    },
+    // Message to move in the game world
+    Move {
+        direction: Direction,
+        seq_num: u64,
+    },
+    // Message to attack another player (using display_id for privacy)
+    Attack {
+        target_display_id: String,
+        seq_num: u64,
+    },
+    // Message to send chat to all players
+    Chat {
+        message: String,
+        seq_num: u64,
+    },
+    // Message to perform an emote
+    Emote {
+        emote_type: EmoteType,
+        seq_num: u64,
+    },
+    // Message to leave the game
+    Disconnect {
+        seq_num: u64,
+    },
+    // Heartbeat message to keep the connection alive
+    Heartbeat {
+        seq_num: u64,
+    },
+    // Acknowledge receipt of a server message
+    Ack {
+        server_seq_num: u64,
+        original_type: ServerMessageType,
+    },
+    // Private message (whisper) to another player
+    Whisper {
+        target_display_id: String,
+        message: String,
+        seq_num: u64,
+    },
+    // Message to take back a saved character using the reclaim token from its RegisterAck
+    Resume {
+        token: String,
+        seq_num: u64,
+        protocol_version: ProtocolVersion,
+        session_public_key: String, // Client's X25519 key for this session, base64
+    },
+    // Use one copy of an inventory item, activating its effect for a while
+    UseItem {
+        item_id: String,
+        seq_num: u64,
+    },
+    // Throw away one copy of an inventory item
+    DropItem {
+        item_id: String,
+        seq_num: u64,
+    },
+    // Ask for the player's inventory
+    Inventory {
+        seq_num: u64,
+    },
+    // Wear a carried item in the slot for its type, swapping out what was there
+    Equip {
+        item_id: String,
+        seq_num: u64,
+    },
+    // Take off the item worn in a slot and put it back in the inventory
+    Unequip {
+        slot: EquipmentSlot,
+        seq_num: u64,
+    },
+    // Pick up the nearest item on the ground within reach
+    PickUp {
+        seq_num: u64,
+    },
+    // Ask another player to trade, or agree to their request (using display_id like Whisper)
+    TradeRequest {
+        target_display_id: String,
+        seq_num: u64,
+    },
+    // Put up credits and items in the open trade with a player, replacing the previous offer
+    TradeOffer {
+        target_display_id: String,
+        offer: TradeAssets,
+        seq_num: u64,
+    },
+    // Confirm both offers as of `revision`; assets change hands once both sides confirmed it
+    TradeAccept {
+        target_display_id: String,
+        revision: u32,
+        seq_num: u64,
+    },
+    // Call off the trade with a player, returning everything put up
+    TradeCancel {
+        target_display_id: String,
+        seq_num: u64,
+    },
+    // Ask for the player's standing with every faction
+    Reputation {
+        seq_num: u64,
+    },
+    // Take on a quest from the latest QuestOffer
+    QuestAccept {
+        quest_id: String,
+        seq_num: u64,
+    },
+    // Use an ability, on the player with display ID `target` or on yourself without one
+    UseAbility {
+        ability: Ability,
+        target: Option<String>,
+        seq_num: u64,
+    },
+}
+
+// Type of server message (used for acknowledgements)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ServerMessageType {
+    RegisterAck,
+    GameState,
+    Event,
+    ChatMessage,
+    Error,
+    HeartbeatRequest,
+    Ack,
+    PlayerLeft,
+    PlayerUpdate,
+    ServerShutdown,
+    WhisperMessage,
+    GameStateDelta,
+    VisibilityChanged,
+    Inventory,
+    ItemUsed,
+    ItemDropped,
+    Equipment,
+    Loot,
+    TradeUpdate,
+    TradeClosed,
+    ReputationUpdate,
+    TerritoryUpdate,
+    RegionChanged,
+    SurveillanceAlert,
+    Terrain,
+    NpcUpdate,
+    QuestOffer,
+    QuestProgress,
+    QuestComplete,
+    AbilityCooldowns,
+}
+
+// Message types that the server can send to the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    // Server is shutting down, clients should disconnect
+    ServerShutdown {
+        message: String,
+        seq_num: u64,
+        shutdown_in_seconds: u8,
+    },
+    // Confirms registration and provides the player's display ID with protocol version negotiation
+    RegisterAck {
+        display_id: String,
+        seq_num: u64,
+        // World boundaries for client synchronization
+        world_boundaries: WorldBoundaries,
+        // Negotiated protocol version to use for this session
+        negotiated_version: u16,
+        // Secret that lets the player take this character back with Resume
+        reclaim_token: String,
+    },
+    // Full game state snapshot (keyframe), keyed by display ID
+    GameState {
+        players: HashMap<String, PlayerView>,
+        seq_num: u64,
+        // We could add other game elements here
+    },
+    // Changes since the state the client acknowledged as `baseline_seq`
+    GameStateDelta {
+        baseline_seq: u64,
+        // Players that joined or changed since the baseline
+        updated: Vec<PlayerView>,
+        // Display IDs of players that left since the baseline
+        removed: Vec<String>,
+        seq_num: u64,
+    },
+    // Event notification (attack, etc.)
+    Event {
+        message: String,
+        seq_num: u64,
+    },
+    // Chat message from another player
+    ChatMessage {
+        sender_name: String,
+        message: String,
+        seq_num: u64,
+    },
+    // Error message
+    Error {
+        message: String,
+        seq_num: u64,
+    },
+    // Heartbeat request
+    HeartbeatRequest {
+        seq_num: u64,
+    },
+    // Acknowledge receipt of a client message
+    Ack {
+        client_seq_num: u64,
+        original_type: ClientMessageType,
+    },
+    // Player departed
+    PlayerLeft {
+        display_id: String,
+        seq_num: u64,
+    },
+    // Player status update
+    PlayerUpdate {
+        display_id: String,
+        position: Position,
+        health: u32,
+        seq_num: u64,
+    },
+    // Players that came into or went out of view range since the last update
+    VisibilityChanged {
+        entered: Vec<String>,
+        left: Vec<String>,
+        seq_num: u64,
+    },
+    // Private message (whisper) from another player
+    WhisperMessage {
+        sender_name: String,
+        message: String,
+        seq_num: u64,
+    },
+    // The player's inventory and credits, sent on request and whenever they change
+    Inventory {
+        items: Vec<InventoryItem>,
+        credits: u32,
+        seq_num: u64,
+    },
+    // An item was used and its effect is active for `active_seconds`
+    ItemUsed {
+        item_id: String,
+        name: String,
+        active_seconds: u64,
+        seq_num: u64,
+    },
+    // An item was dropped from the inventory
+    ItemDropped {
+        item_id: String,
+        name: String,
+        seq_num: u64,
+    },
+    // The items the player wears and the combined stats of worn and active items
+    Equipment {
+        slots: BTreeMap<EquipmentSlot, CryptoItem>,
+        stats: ItemStats,
+        seq_num: u64,
+    },
+    // Items on the ground within view of the player, sent whenever they change
+    Loot {
+        drops: Vec<LootDrop>,
+        seq_num: u64,
+    },
+    // The player's open trade, sent to both sides whenever it changes
+    TradeUpdate {
+        partner_display_id: String,
+        // Bumped whenever either offer changes; TradeAccept names the revision it confirms
+        revision: u32,
+        own_offer: TradeAssets,
+        partner_offer: TradeAssets,
+        own_confirmed: bool,
+        partner_confirmed: bool,
+        seq_num: u64,
+    },
+    // The player's trade ended, with the swap done if `completed` and nothing moved otherwise
+    TradeClosed {
+        partner_display_id: String,
+        completed: bool,
+        reason: String,
+        seq_num: u64,
+    },
+    // The player's standing with every faction, sent on request and whenever it changes
+    ReputationUpdate {
+        standings: Vec<FactionStanding>,
+        // Scores needed to unlock a faction's emote and signature item
+        emote_threshold: i32,
+        item_threshold: i32,
+        seq_num: u64,
+    },
+    // Every control point and who holds it, sent on joining and to everyone whenever it changes
+    TerritoryUpdate {
+        control_points: Vec<ControlPoint>,
+        // Seconds of uncontested presence needed to capture a point
+        capture_seconds: u32,
+        seq_num: u64,
+    },
+    // The player crossed into another region, whose boundaries now apply to them
+    RegionChanged {
+        world_boundaries: WorldBoundaries,
+        seq_num: u64,
+    },
+    // A player was caught on surveillance, sent to them and to every Corporate Hegemony player
+    SurveillanceAlert {
+        display_id: String,
+        name: String,
+        faction: Faction,
+        position: Position,
+        region: String,
+        // Seconds the player stays exposed
+        duration_seconds: u64,
+        seq_num: u64,
+    },
+    // The terrain of the player's region, sent on joining and whenever they enter another region
+    Terrain {
+        terrain: TerrainMap,
+        seq_num: u64,
+    },
+    // Every non-player character within view, sent on joining and whenever that changes
+    NpcUpdate {
+        npcs: Vec<NpcView>,
+        seq_num: u64,
+    },
+    // Quests the player can take on, sent on joining and whenever that changes
+    QuestOffer {
+        quests: Vec<QuestDefinition>,
+        seq_num: u64,
+    },
+    // Progress on a quest the player took on, sent on accepting it, on joining and whenever it changes
+    QuestProgress {
+        quest: QuestDefinition,
+        progress: Vec<u32>, // Progress on each objective of `quest`
+        seq_num: u64,
+    },
+    // A quest was completed and its rewards handed out
+    QuestComplete {
+        quest_id: String,
+        name: String,
+        experience: u32,
+        items: Vec<String>, // Names of the items that fit in the inventory
+        seq_num: u64,
+    },
+    // Cooldown of every ability, sent on joining and whenever the player uses one
+    AbilityCooldowns {
+        cooldowns: Vec<AbilityCooldown>,
+        seq_num: u64,
+    },
+}
+
+// Helper implementation for ServerMessage to get metadata easily
+impl ServerMessage {
+    #[allow(dead_code)]
+    /// Get the message type
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn get_type(&self) -> ServerMessageType {
+        match self {
+            ServerMessage::ServerShutdown { .. } => ServerMessageType::ServerShutdown,
+            ServerMessage::RegisterAck { .. } => ServerMessageType::RegisterAck,
+            ServerMessage::GameState { .. } => ServerMessageType::GameState,
+            ServerMessage::GameStateDelta { .. } => ServerMessageType::GameStateDelta,
+            ServerMessage::VisibilityChanged { .. } => ServerMessageType::VisibilityChanged,
+            ServerMessage::Event { .. } => ServerMessageType::Event,
+            ServerMessage::ChatMessage { .. } => ServerMessageType::ChatMessage,
+            ServerMessage::Error { .. } => ServerMessageType::Error,
+            ServerMessage::HeartbeatRequest { .. } => ServerMessageType::HeartbeatRequest,
+            ServerMessage::Ack { .. } => ServerMessageType::Ack,
+            ServerMessage::PlayerLeft { .. } => ServerMessageType::PlayerLeft,
+            ServerMessage::PlayerUpdate { .. } => ServerMessageType::PlayerUpdate,
+            ServerMessage::WhisperMessage { .. } => ServerMessageType::WhisperMessage,
+            ServerMessage::Inventory { .. } => ServerMessageType::Inventory,
+            ServerMessage::ItemUsed { .. } => ServerMessageType::ItemUsed,
+            ServerMessage::ItemDropped { .. } => ServerMessageType::ItemDropped,
+            ServerMessage::Equipment { .. } => ServerMessageType::Equipment,
+            ServerMessage::Loot { .. } => ServerMessageType::Loot,
+            ServerMessage::TradeUpdate { .. } => ServerMessageType::TradeUpdate,
+            ServerMessage::TradeClosed { .. } => ServerMessageType::TradeClosed,
+            ServerMessage::ReputationUpdate { .. } => ServerMessageType::ReputationUpdate,
+            ServerMessage::TerritoryUpdate { .. } => ServerMessageType::TerritoryUpdate,
+            ServerMessage::RegionChanged { .. } => ServerMessageType::RegionChanged,
+            ServerMessage::SurveillanceAlert { .. } => ServerMessageType::SurveillanceAlert,
+            ServerMessage::Terrain { .. } => ServerMessageType::Terrain,
+            ServerMessage::NpcUpdate { .. } => ServerMessageType::NpcUpdate,
+            ServerMessage::QuestOffer { .. } => ServerMessageType::QuestOffer,
+            ServerMessage::QuestProgress { .. } => ServerMessageType::QuestProgress,
+            ServerMessage::QuestComplete { .. } => ServerMessageType::QuestComplete,
+            ServerMessage::AbilityCooldowns { .. } => ServerMessageType::AbilityCooldowns,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get_seq_num(&self) -> u64 {
+        match self {
+            ServerMessage::ServerShutdown { seq_num, .. } => *seq_num,
+            ServerMessage::RegisterAck { seq_num, .. } => *seq_num,
+            ServerMessage::GameState { seq_num, .. } => *seq_num,
+            ServerMessage::GameStateDelta { seq_num, .. } => *seq_num,
+            ServerMessage::VisibilityChanged { seq_num, .. } => *seq_num,
+            ServerMessage::Event { seq_num, .. } => *seq_num,
+            ServerMessage::ChatMessage { seq_num, .. } => *seq_num,
+            ServerMessage::Error { seq_num, .. } => *seq_num,
+            ServerMessage::HeartbeatRequest { seq_num, .. } => *seq_num,
+            ServerMessage::Ack { client_seq_num, .. } => *client_seq_num,
+            ServerMessage::PlayerLeft { seq_num, .. } => *seq_num,
+            ServerMessage::PlayerUpdate { seq_num, .. } => *seq_num,
+            ServerMessage::WhisperMessage { seq_num, .. } => *seq_num,
+            ServerMessage::Inventory { seq_num, .. } => *seq_num,
+            ServerMessage::ItemUsed { seq_num, .. } => *seq_num,
+            ServerMessage::ItemDropped { seq_num, .. } => *seq_num,
+            ServerMessage::Equipment { seq_num, .. } => *seq_num,
+            ServerMessage::Loot { seq_num, .. } => *seq_num,
+            ServerMessage::TradeUpdate { seq_num, .. } => *seq_num,
+            ServerMessage::TradeClosed { seq_num, .. } => *seq_num,
+            ServerMessage::ReputationUpdate { seq_num, .. } => *seq_num,
+            ServerMessage::TerritoryUpdate { seq_num, .. } => *seq_num,
+            ServerMessage::RegionChanged { seq_num, .. } => *seq_num,
+            ServerMessage::SurveillanceAlert { seq_num, .. } => *seq_num,
+            ServerMessage::Terrain { seq_num, .. } => *seq_num,
+            ServerMessage::NpcUpdate { seq_num, .. } => *seq_num,
+            ServerMessage::QuestOffer { seq_num, .. } => *seq_num,
+            ServerMessage::QuestProgress { seq_num, .. } => *seq_num,
+            ServerMessage::QuestComplete { seq_num, .. } => *seq_num,
+            ServerMessage::AbilityCooldowns { seq_num, .. } => *seq_num,
+        }
+    }
+}
+
+// Helper implementation for ClientMessage to get metadata easily
+impl ClientMessage {
+    /// Get the message type
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn get_type(&self) -> ClientMessageType {
+        match self {
+            ClientMessage::Register { .. } => ClientMessageType::Register,
+            ClientMessage::Move { .. } => ClientMessageType::Move,
+            ClientMessage::Attack { .. } => ClientMessageType::Attack,
+            ClientMessage::Chat { .. } => ClientMessageType::Chat,
+            ClientMessage::Emote { .. } => ClientMessageType::Emote,
+            ClientMessage::Disconnect { .. } => ClientMessageType::Disconnect,
+            ClientMessage::Heartbeat { .. } => ClientMessageType::Heartbeat,
+            ClientMessage::Ack { .. } => ClientMessageType::Ack,
+            ClientMessage::Whisper { .. } => ClientMessageType::Whisper,
+            ClientMessage::Resume { .. } => ClientMessageType::Resume,
+            ClientMessage::UseItem { .. } => ClientMessageType::UseItem,
+            ClientMessage::DropItem { .. } => ClientMessageType::DropItem,
+            ClientMessage::Inventory { .. } => ClientMessageType::Inventory,
+            ClientMessage::Equip { .. } => ClientMessageType::Equip,
+            ClientMessage::Unequip { .. } => ClientMessageType::Unequip,
+            ClientMessage::PickUp { .. } => ClientMessageType::PickUp,
+            ClientMessage::TradeRequest { .. } => ClientMessageType::TradeRequest,
+            ClientMessage::TradeOffer { .. } => ClientMessageType::TradeOffer,
+            ClientMessage::TradeAccept { .. } => ClientMessageType::TradeAccept,
+            ClientMessage::TradeCancel { .. } => ClientMessageType::TradeCancel,
+            ClientMessage::Reputation { .. } => ClientMessageType::Reputation,
+            ClientMessage::QuestAccept { .. } => ClientMessageType::QuestAccept,
+            ClientMessage::UseAbility { .. } => ClientMessageType::UseAbility,
+        }
+    }
+
+    /// Get the sequence number
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn get_seq_num(&self) -> u64 {
+        match self {
+            ClientMessage::Register { seq_num, .. } => *seq_num,
+            ClientMessage::Move { seq_num, .. } => *seq_num,
+            ClientMessage::Attack { seq_num, .. } => *seq_num,
+            ClientMessage::Chat { seq_num, .. } => *seq_num,
+            ClientMessage::Emote { seq_num, .. } => *seq_num,
+            ClientMessage::Disconnect { seq_num, .. } => *seq_num,
+            ClientMessage::Heartbeat { seq_num, .. } => *seq_num,
+            ClientMessage::Ack { server_seq_num, .. } => *server_seq_num,
+            ClientMessage::Whisper { seq_num, .. } => *seq_num,
+            ClientMessage::Resume { seq_num, .. } => *seq_num,
+            ClientMessage::UseItem { seq_num, .. } => *seq_num,
+            ClientMessage::DropItem { seq_num, .. } => *seq_num,
+            ClientMessage::Inventory { seq_num } => *seq_num,
+            ClientMessage::Equip { seq_num, .. } => *seq_num,
+            ClientMessage::Unequip { seq_num, .. } => *seq_num,
+            ClientMessage::PickUp { seq_num } => *seq_num,
+            ClientMessage::TradeRequest { seq_num, .. } => *seq_num,
+            ClientMessage::TradeOffer { seq_num, .. } => *seq_num,
+            ClientMessage::TradeAccept { seq_num, .. } => *seq_num,
+            ClientMessage::TradeCancel { seq_num, .. } => *seq_num,
+            ClientMessage::Reputation { seq_num } => *seq_num,
+            ClientMessage::QuestAccept { seq_num, .. } => *seq_num,
+            ClientMessage::UseAbility { seq_num, .. } => *seq_num,
+        }
+    }
+
+    /// Session public key of messages that open a session (Register and Resume)
+    pub fn session_public_key(&self) -> Option<&str> {
+        match self {
+            ClientMessage::Register {
+                session_public_key, ..
+            }
+            | ClientMessage::Resume {
+                session_public_key, ..
+            } => Some(session_public_key),
+            _ => None,
+        }
+    }
+}
+
+// Movement direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// Convert direction to a movement vector
+    ///
+    /// This function takes a direction and returns a movement vector.
+    /// The movement vector is a tuple of two f32 values representing the x and y components of the movement.
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn to_vector(self) -> (f32, f32) {
+        // 1/sqrt(2) ≈ 0.7071 is the correct normalization factor for diagonal movement
+        // This ensures that diagonal movement has the same speed as cardinal movement
+        let diag = FRAC_1_SQRT_2;
+
+        match self {
+            Direction::Up => (0.0, -1.0),
+            Direction::Down => (0.0, 1.0),
+            Direction::Left => (-1.0, 0.0),
+            Direction::Right => (1.0, 0.0),
+            Direction::UpLeft => (-diag, -diag), // Properly normalized diagonal vectors
+            Direction::UpRight => (diag, -diag),
+            Direction::DownLeft => (-diag, diag),
+            Direction::DownRight => (diag, diag),
+        }
+    }
+
+    /// Parse a direction from a string
+    #[allow(clippy::should_implement_trait)] // Lenient parsing with aliases, returns Option
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "up" | "u" | "north" | "n" => Some(Direction::Up),
+            "down" | "d" | "south" | "s" => Some(Direction::Down),
+            "left" | "l" | "west" | "w" => Some(Direction::Left),
+            "right" | "r" | "east" | "e" => Some(Direction::Right),
+            "upleft" | "ul" | "northwest" | "nw" => Some(Direction::UpLeft),
+            "upright" | "ur" | "northeast" | "ne" => Some(Direction::UpRight),
+            "downleft" | "dl" | "southwest" | "sw" => Some(Direction::DownLeft),
+            "downright" | "dr" | "southeast" | "se" => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+}
+
+// World boundaries with cypherpunk-themed properties
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldBoundaries {
+    pub min_x: f32,
+    pub max_x: f32,
+    pub min_y: f32,
+    pub max_y: f32,
+    /// Name of the region (e.g., "Neon Harbor", "The Deep Net")
+    pub name: String,
+    /// Security level in the region, affects encounter risks
+    pub security_level: String,
+    /// Surveillance density (0.0 to 1.0) affecting privacy
+    pub surveillance_density: f32,
+    /// The region type from worldbuilding lore
+    pub region_type: String,
+}
+
+impl WorldBoundaries {
+    /// Clamp a position to stay within world boundaries
+    #[allow(dead_code)]
+    pub fn clamp_position(&self, x: f32, y: f32) -> (f32, f32) {
+        let clamped_x = x.clamp(self.min_x, self.max_x);
+        let clamped_y = y.clamp(self.min_y, self.max_y);
+        (clamped_x, clamped_y)
+    }
+
+    /// Check if a position is within world boundaries
+    #[allow(dead_code)]
+    pub fn is_position_valid(&self, x: f32, y: f32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    /// Apply boundaries to a Position, modifying it in place
+    #[allow(dead_code)]
+    pub fn clamp_position_mut(&self, position: &mut Position) {
+        let (x, y) = self.clamp_position(position.x, position.y);
+        position.x = x;
+        position.y = y;
+    }
+
+    /// Check if two positions would collide given a minimum distance
+    #[allow(dead_code)]
+    pub fn would_positions_collide(pos1: &Position, pos2: &Position, min_distance: f32) -> bool {
+        pos1.distance_to(pos2) < min_distance
+    }
+
+    /// Create WorldBoundaries for a lore region with explicit world limits
+    ///
+    /// The region name is matched against the lore region names ("Neon Harbor",
+    /// "Deep Net", ...); unknown or missing names fall back to Neon Harbor.
+    pub fn for_region(
+        region: Option<&str>,
+        min_x: f32,
+        max_x: f32,
+        min_y: f32,
+        max_y: f32,
+    ) -> Self {
+        let region = region.unwrap_or("Neon Harbor").to_string();
+
+        // Convert to WorldRegion enum if possible, otherwise use default
+        let world_region = WorldRegion::from_name(&region).unwrap_or(WorldRegion::NeonHarbor);
+
+        // Get lore boundaries with all properties
+        let lore_boundaries = world_region.get_boundaries();
+
+        // Create new boundaries with cypherpunk properties
+        Self {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+            name: lore_boundaries.name.to_string(),
+            security_level: format!("{:?}", lore_boundaries.security_level),
+            surveillance_density: lore_boundaries.surveillance_density,
+            region_type: region,
+        }
+    }
+
+    /// Calculate surveillance risk for a given position
+    /// Returns a value from 0.0 (no surveillance) to 1.0 (maximum surveillance)
+    #[allow(dead_code)]
+    pub fn calculate_surveillance_risk(&self, x: f32, y: f32) -> f32 {
+        if !self.is_position_valid(x, y) {
+            return 0.0;
+        }
+
+        // Base risk from the region's surveillance density
+        let mut risk = self.surveillance_density;
+
+        // Distance from center affects risk - closer to center is higher risk in most regions
+        let center_x = (self.min_x + self.max_x) / 2.0;
+        let center_y = (self.min_y + self.max_y) / 2.0;
+
+        let max_distance =
+            ((self.max_x - self.min_x).powi(2) + (self.max_y - self.min_y).powi(2)).sqrt() / 2.0;
+        let distance = ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt();
+        let distance_factor = 1.0 - (distance / max_distance);
+
+        // Adjust risk based on security level
+        match self.security_level.as_str() {
+            "Maximum" => {
+                // In maximum security, it's equally surveilled everywhere
+                risk *= 0.8 + (0.2 * distance_factor);
+            }
+            "High" => {
+                // High security has more surveillance in the center
+                risk *= 0.6 + (0.4 * distance_factor);
+            }
+            "Moderate" => {
+                // Moderate security has some surveillance hotspots
+                risk *= 0.4 + (0.6 * distance_factor);
+            }
+            "Low" => {
+                // Low security has minimal surveillance mostly at the edges
+                risk *= 0.2 + (0.1 * distance_factor);
+            }
+            _ => {
+                // No security has almost no surveillance
+                risk *= 0.05;
+            }
+        }
+
+        risk.clamp(0.0, 1.0)
+    }
+}
+
+/// What covers a tile of a region's terrain
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Tile {
+    /// Nothing in the way
+    Open,
+    /// Passable, but players move slower across it
+    Slow,
+    /// Impassable, and blocks line of sight
+    Wall,
+    /// Open ground where nobody can attack or be attacked
+    Safe,
+}
+
+impl Tile {
+    /// Tile a character of a terrain map stands for: `.` open, `~` slow, `#` wall and `+` safe
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Open),
+            '~' => Some(Tile::Slow),
+            '#' => Some(Tile::Wall),
+            '+' => Some(Tile::Safe),
+            _ => None,
+        }
+    }
+}
+
+/// A region's terrain: a grid of tiles stretched over the region's boundaries
+///
+/// Rows run from north (`min_y`) to south and columns from west (`min_x`) to
+/// east, one character per tile as read by `Tile::from_char`. A map without
+/// rows leaves the whole region open.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerrainMap {
+    /// Name of the region the map covers
+    pub region: String,
+    pub rows: Vec<String>,
+}
+
+impl TerrainMap {
+    /// Parse the text of a terrain map for `region`, skipping blank lines
+    pub fn parse(region: &str, text: &str) -> anyhow::Result<Self> {
+        let rows: Vec<String> = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        for (index, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(anyhow::anyhow!(
+                    "Row {} of the {} terrain map is {} tiles wide, expected {}",
+                    index + 1,
+                    region,
+                    row.chars().count(),
+                    width
+                ));
+            }
+            if let Some(c) = row.chars().find(|c| Tile::from_char(*c).is_none()) {
+                return Err(anyhow::anyhow!(
+                    "Row {} of the {} terrain map has unknown tile '{}'",
+                    index + 1,
+                    region,
+                    c
+                ));
+            }
+        }
+
+        Ok(Self {
+            region: region.to_string(),
+            rows,
+        })
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.chars().count())
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Tile at `(x, y)`, with the map stretched over `bounds`; outside the map everything is open
+    pub fn tile_at(&self, bounds: &WorldBoundaries, x: f32, y: f32) -> Tile {
+        let (width, height) = (self.width(), self.height());
+        if width == 0 || !bounds.is_position_valid(x, y) {
+            return Tile::Open;
+        }
+        let column = ((x - bounds.min_x) / (bounds.max_x - bounds.min_x) * width as f32) as usize;
+        let row = ((y - bounds.min_y) / (bounds.max_y - bounds.min_y) * height as f32) as usize;
+        self.rows[row.min(height - 1)]
+            .chars()
+            .nth(column.min(width - 1))
+            .and_then(Tile::from_char)
+            .unwrap_or(Tile::Open)
+    }
+}
+
+impl QuestObjective {
+    /// Progress that meets the objective
+    pub fn required(&self) -> u32 {
+        match self {
+            QuestObjective::Reach { .. } | QuestObjective::Whisper { .. } => 1,
+            QuestObjective::Defeat { count, .. } => *count,
+            QuestObjective::StayHidden { seconds, .. } => *seconds,
+        }
+    }
+
+    /// What the objective asks, for players
+    pub fn describe(&self) -> String {
+        match self {
+            QuestObjective::Reach { position, .. } => {
+                format!("Reach ({:.0}, {:.0})", position.x, position.y)
+            }
+            QuestObjective::Defeat { kind, count } => format!("Defeat {} {}", count, kind.name()),
+            QuestObjective::Whisper { npc } => format!("Whisper to {}", npc),
+            QuestObjective::StayHidden {
+                max_surveillance,
+                seconds,
+            } => format!(
+                "Stay unexposed for {}s where surveillance is at most {:.0}%",
+                seconds,
+                max_surveillance * 100.0
+            ),
+        }
+    }
+}
+
+impl QuestDefinition {
+    /// Parse a JSON list of quests, checking that every quest can be completed
+    pub fn parse_all(text: &str) -> anyhow::Result<Vec<Self>> {
+        let quests: Vec<Self> = serde_json::from_str(text)?;
+        for (index, quest) in quests.iter().enumerate() {
+            if quest.id.is_empty() {
+                return Err(anyhow::anyhow!("Quest {} has no ID", index + 1));
+            }
+            if quests[..index].iter().any(|other| other.id == quest.id) {
+                return Err(anyhow::anyhow!("Quest '{}' is listed twice", quest.id));
+            }
+            if quest.objectives.is_empty() {
+                return Err(anyhow::anyhow!("Quest '{}' has no objectives", quest.id));
+            }
+            for objective in &quest.objectives {
+                let valid = objective.required() > 0
+                    && match objective {
+                        QuestObjective::Reach { radius, .. } => *radius > 0.0,
+                        QuestObjective::Whisper { npc } => !npc.is_empty(),
+                        QuestObjective::StayHidden {
+                            max_surveillance, ..
+                        } => (0.0..=1.0).contains(max_surveillance),
+                        QuestObjective::Defeat { .. } => true,
+                    };
+                if !valid {
+                    return Err(anyhow::anyhow!(
+                        "Quest '{}' has an objective that cannot be met: {:?}",
+                        quest.id,
+                        objective
+                    ));
+                }
+            }
+        }
+        Ok(quests)
+    }
+
+    /// Whether members of `faction` are offered the quest
+    pub fn is_offered_to(&self, faction: &Faction) -> bool {
+        self.faction.as_ref().is_none_or(|own| own == faction)
+    }
+}
+
+// Types of emotes that players can perform - enhanced with cypherpunk themes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EmoteType {
+    // Standard emotes
+    Wave,
+    Bow,
+    Laugh,
+    Dance,
+    Salute,
+    Shrug,
+    Cheer,
+    Clap,
+    ThumbsUp,
+
+    // Cypherpunk-themed emotes
+    Hack,         // Mimics typing rapidly on an invisible keyboard
+    Encrypt,      // Makes encryption gestures
+    Decrypt,      // Makes decryption gestures
+    Surveillance, // Looks around suspiciously as if being watched
+    Resist,       // Raises fist in defiance
+    Ghost,        // Mimics disappearing/becoming anonymous
+    DataDrop,     // Pantomimes dropping/transferring data
+    Glitch,       // Deliberately glitches/pixelates movements
+
+    // Faction emotes, unlocked by reputation with the faction
+    Cloak,    // Nyms: dissolves into mixnet traffic
+    Audit,    // Corporate Hegemony: inspects everyone with corporate authority
+    Leak,     // Cipher Collective: scatters leaked documents
+    Meditate, // Algorithm Monks: meditates on the flow of data
+    Wander,   // Independent: tips their hat to no one in particular
+}
+
+impl EmoteType {
+    /// Get a display string for the emote
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            // Standard emotes
+            EmoteType::Wave => "waves hello",
+            EmoteType::Bow => "bows respectfully",
+            EmoteType::Laugh => "laughs heartily",
+            EmoteType::Dance => "performs a dance",
+            EmoteType::Salute => "salutes firmly",
+            EmoteType::Shrug => "shrugs shoulders",
+            EmoteType::Cheer => "cheers enthusiastically",
+            EmoteType::Clap => "claps hands",
+            EmoteType::ThumbsUp => "gives a thumbs up",
+
+            // Cypherpunk-themed emotes
+            EmoteType::Hack => "simulates frantic hacking",
+            EmoteType::Encrypt => "makes encryption gestures",
+            EmoteType::Decrypt => "performs decryption movements",
+            EmoteType::Surveillance => "looks around suspiciously",
+            EmoteType::Resist => "raises fist in digital defiance",
+            EmoteType::Ghost => "fades into digital anonymity",
+            EmoteType::DataDrop => "mimes a secure data transfer",
+            EmoteType::Glitch => "momentarily glitches out",
+
+            // Faction emotes
+            EmoteType::Cloak => "dissolves into a cloud of mixnet packets",
+            EmoteType::Audit => "audits everyone nearby with corporate authority",
+            EmoteType::Leak => "scatters leaked documents into the air",
+            EmoteType::Meditate => "meditates on the flow of data",
+            EmoteType::Wander => "tips their hat to no one in particular",
+        }
+    }
+
+    /// Get a visual representation of the emote for display
+    #[allow(dead_code)] // Part of complete protocol API for future use
+    pub fn display_icon(&self) -> &'static str {
+        match self {
+            // Standard emotes
+            EmoteType::Wave => "👋",
+            EmoteType::Bow => "🙇",
+            EmoteType::Laugh => "😂",
+            EmoteType::Dance => "💃",
+            EmoteType::Salute => "🫡",
+            EmoteType::Shrug => "🤷",
+            EmoteType::Cheer => "🎉",
+            EmoteType::Clap => "👏",
+            EmoteType::ThumbsUp => "👍",
+
+            // Cypherpunk-themed emotes
+            EmoteType::Hack => "⌨️",
+            EmoteType::Encrypt => "🔒",
+            EmoteType::Decrypt => "🔓",
+            EmoteType::Surveillance => "👁️",
+            EmoteType::Resist => "✊",
+            EmoteType::Ghost => "👻",
+            EmoteType::DataDrop => "💾",
+            EmoteType::Glitch => "📟",
+
+            // Faction emotes
+            EmoteType::Cloak => "🌫️",
+            EmoteType::Audit => "💼",
+            EmoteType::Leak => "📜",
+            EmoteType::Meditate => "🧘",
+            EmoteType::Wander => "🎩",
+        }
+    }
+
+    /// Faction whose reputation unlocks the emote, if it is not open to everyone
+    pub fn required_faction(&self) -> Option<Faction> {
+        Faction::ALL
+            .into_iter()
+            .find(|faction| faction.emote() == *self)
+    }
+
+    /// Parse an emote from a string, accepting common aliases
+    #[allow(clippy::should_implement_trait)] // Lenient parsing with aliases, returns Option
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            // Standard emotes
+            "wave" | "hello" | "hi" => Some(EmoteType::Wave),
+            "bow" => Some(EmoteType::Bow),
+            "laugh" | "lol" | "haha" => Some(EmoteType::Laugh),
+            "dance" => Some(EmoteType::Dance),
+            "salute" => Some(EmoteType::Salute),
+            "shrug" => Some(EmoteType::Shrug),
+            "cheer" => Some(EmoteType::Cheer),
+            "clap" | "applaud" => Some(EmoteType::Clap),
+            "thumbsup" | "thumbs" | "like" => Some(EmoteType::ThumbsUp),
+
+            // Cypherpunk-themed emotes
+            "hack" | "hacking" => Some(EmoteType::Hack),
+            "encrypt" | "encryption" => Some(EmoteType::Encrypt),
+            "decrypt" | "decryption" => Some(EmoteType::Decrypt),
+            "surveillance" | "watched" | "spy" => Some(EmoteType::Surveillance),
+            "resist" | "resistance" => Some(EmoteType::Resist),
+            "ghost" | "vanish" | "anonymous" => Some(EmoteType::Ghost),
+            "datadrop" | "data" | "transfer" => Some(EmoteType::DataDrop),
+            "glitch" | "malfunction" => Some(EmoteType::Glitch),
+
+            // Faction emotes
+            "cloak" | "mix" => Some(EmoteType::Cloak),
+            "audit" | "inspect" => Some(EmoteType::Audit),
+            "leak" | "whistleblow" => Some(EmoteType::Leak),
+            "meditate" | "contemplate" => Some(EmoteType::Meditate),
+            "wander" | "drift" => Some(EmoteType::Wander),
+
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message_auth::{AuthKey, AuthenticatedMessage};
+    use crate::message_padding::{pad_message, unpad_message, PaddedMessage};
+    use crate::world_lore::generate_crypto_items;
+    use serde::de::DeserializeOwned;
+
+    const ALL_CLIENT_TYPES: [ClientMessageType; 23] = [
+        ClientMessageType::Register,
+        ClientMessageType::Move,
+        ClientMessageType::Attack,
+        ClientMessageType::Chat,
+        ClientMessageType::Emote,
+        ClientMessageType::Disconnect,
+        ClientMessageType::Heartbeat,
+        ClientMessageType::Ack,
+        ClientMessageType::Whisper,
+        ClientMessageType::Resume,
+        ClientMessageType::UseItem,
+        ClientMessageType::DropItem,
+        ClientMessageType::Inventory,
+        ClientMessageType::Equip,
+        ClientMessageType::Unequip,
+        ClientMessageType::PickUp,
+        ClientMessageType::TradeRequest,
+        ClientMessageType::TradeOffer,
+        ClientMessageType::TradeAccept,
+        ClientMessageType::TradeCancel,
+        ClientMessageType::Reputation,
+        ClientMessageType::QuestAccept,
+        ClientMessageType::UseAbility,
+    ];
+
+    const ALL_SERVER_TYPES: [ServerMessageType; 30] = [
+        ServerMessageType::RegisterAck,
+        ServerMessageType::GameState,
+        ServerMessageType::Event,
+        ServerMessageType::ChatMessage,
+        ServerMessageType::Error,
+        ServerMessageType::HeartbeatRequest,
+        ServerMessageType::Ack,
+        ServerMessageType::PlayerLeft,
+        ServerMessageType::PlayerUpdate,
+        ServerMessageType::ServerShutdown,
+        ServerMessageType::WhisperMessage,
+        ServerMessageType::GameStateDelta,
+        ServerMessageType::VisibilityChanged,
+        ServerMessageType::Inventory,
+        ServerMessageType::ItemUsed,
+        ServerMessageType::ItemDropped,
+        ServerMessageType::Equipment,
+        ServerMessageType::Loot,
+        ServerMessageType::TradeUpdate,
+        ServerMessageType::TradeClosed,
+        ServerMessageType::ReputationUpdate,
+        ServerMessageType::TerritoryUpdate,
+        ServerMessageType::RegionChanged,
+        ServerMessageType::SurveillanceAlert,
+        ServerMessageType::Terrain,
+        ServerMessageType::NpcUpdate,
+        ServerMessageType::QuestOffer,
+        ServerMessageType::QuestProgress,
+        ServerMessageType::QuestComplete,
+        ServerMessageType::AbilityCooldowns,
+    ];
+
+    fn sample_player() -> Player {
+        Player {
+            id: "00000000-0000-4000-8000-000000000000".to_string(),
+            display_id: "Player1".to_string(),
+            position: Position::new(12.5, -3.0),
+            health: 80,
+            name: "alice".to_string(),
+            last_attack_time: 1_700_000_000,
+            last_damage_time: 1_700_000_002,
+            experience: 42,
+            level: 2,
+            faction: Faction::CipherCollective,
+            inventory: BTreeMap::from([("packet_sniffer".to_string(), 2)]),
+            active_items: BTreeMap::from([("mixnet_relay".to_string(), 1_700_000_120)]),
+            equipment: BTreeMap::from([(EquipmentSlot::DefenseTool, "quantum_shield".to_string())]),
+            credits: 42,
+            escrow: sample_offer(),
+            reputation: BTreeMap::from([(Faction::Nyms, -15), (Faction::CipherCollective, 40)]),
+            rewarded_factions: BTreeSet::new(),
+            quests: BTreeMap::from([(
+                "blind_the_watchers".to_string(),
+                QuestStatus {
+                    progress: vec![2, 0],
+                    completed: false,
+                },
+            )]),
+            effects: BTreeMap::from([(
+                StatusEffectKind::Corruption,
+                StatusEffect {
+                    stacks: 2,
+                    expires_at: 1_700_000_005,
+                },
+            )]),
+        }
+    }
+
+    fn sample_quest() -> QuestDefinition {
+        QuestDefinition {
+            id: "blind_the_watchers".to_string(),
+            name: "Blind the Watchers".to_string(),
+            description: "Take down the drones over the harbor.".to_string(),
+            faction: Some(Faction::Nyms),
+            objectives: vec![
+                QuestObjective::Defeat {
+                    kind: NpcKind::SurveillanceDrone,
+                    count: 3,
+                },
+                QuestObjective::StayHidden {
+                    max_surveillance: 0.3,
+                    seconds: 20,
+                },
+            ],
+            reward_experience: 80,
+            reward_items: vec!["mixnet_relay".to_string()],
+        }
+    }
+
+    fn sample_offer() -> TradeAssets {
+        TradeAssets {
+            credits: 15,
+            items: BTreeMap::from([("zk_prover".to_string(), 1)]),
+        }
+    }
+
+    // Exhaustive so that adding a message variant forces a sample to be added here
+    fn sample_client_message(msg_type: ClientMessageType) -> ClientMessage {
+        match msg_type {
+            ClientMessageType::Register => ClientMessage::Register {
+                name: "alice".to_string(),
+                faction: Faction::Nyms,
+                seq_num: 1,
+                protocol_version: ProtocolVersion::default(),
+                session_public_key: "c2Vzc2lvbiBrZXk=".to_string(),
+            },
+            ClientMessageType::Move => ClientMessage::Move {
+                direction: Direction::UpLeft,
+                seq_num: 2,
+            },
+            ClientMessageType::Attack => ClientMessage::Attack {
+                target_display_id: "Player2".to_string(),
+                seq_num: 3,
+            },
+            ClientMessageType::Chat => ClientMessage::Chat {
+                message: "hello mixnet".to_string(),
+                seq_num: 4,
+            },
+            ClientMessageType::Emote => ClientMessage::Emote {
+                emote_type: EmoteType::Glitch,
+                seq_num: 5,
+            },
+            ClientMessageType::Disconnect => ClientMessage::Disconnect { seq_num: 6 },
+            ClientMessageType::Heartbeat => ClientMessage::Heartbeat { seq_num: 7 },
+            ClientMessageType::Ack => ClientMessage::Ack {
+                server_seq_num: 8,
+                original_type: ServerMessageType::GameState,
+            },
+            ClientMessageType::Whisper => ClientMessage::Whisper {
+                target_display_id: "Player2".to_string(),
+                message: "psst".to_string(),
+                seq_num: 9,
+            },
+            ClientMessageType::Resume => ClientMessage::Resume {
+                token: "cmVjbGFpbSB0b2tlbg".to_string(),
+                seq_num: 10,
+                protocol_version: ProtocolVersion::default(),
+                session_public_key: "c2Vzc2lvbiBrZXk=".to_string(),
+            },
+            ClientMessageType::UseItem => ClientMessage::UseItem {
+                item_id: "mixnet_relay".to_string(),
+                seq_num: 11,
+            },
+            ClientMessageType::DropItem => ClientMessage::DropItem {
+                item_id: "packet_sniffer".to_string(),
+                seq_num: 12,
+            },
+            ClientMessageType::Inventory => ClientMessage::Inventory { seq_num: 13 },
+            ClientMessageType::Equip => ClientMessage::Equip {
+                item_id: "quantum_shield".to_string(),
+                seq_num: 14,
+            },
+            ClientMessageType::Unequip => ClientMessage::Unequip {
+                slot: EquipmentSlot::Artifact,
+                seq_num: 15,
+            },
+            ClientMessageType::PickUp => ClientMessage::PickUp { seq_num: 16 },
+            ClientMessageType::TradeRequest => ClientMessage::TradeRequest {
+                target_display_id: "Player2".to_string(),
+                seq_num: 17,
+            },
+            ClientMessageType::TradeOffer => ClientMessage::TradeOffer {
+                target_display_id: "Player2".to_string(),
+                offer: sample_offer(),
+                seq_num: 18,
+            },
+            ClientMessageType::TradeAccept => ClientMessage::TradeAccept {
+                target_display_id: "Player2".to_string(),
+                revision: 3,
+                seq_num: 19,
+            },
+            ClientMessageType::TradeCancel => ClientMessage::TradeCancel {
+                target_display_id: "Player2".to_string(),
+                seq_num: 20,
+            },
+            ClientMessageType::Reputation => ClientMessage::Reputation { seq_num: 21 },
+            ClientMessageType::QuestAccept => ClientMessage::QuestAccept {
+                quest_id: "blind_the_watchers".to_string(),
+                seq_num: 22,
+            },
+            ClientMessageType::UseAbility => ClientMessage::UseAbility {
+                ability: Ability::Hack,
+                target: Some("Player2".to_string()),
+                seq_num: 23,
+            },
+        }
+    }
+
+    // Exhaustive so that adding a message variant forces a sample to be added here
+    fn sample_server_message(msg_type: ServerMessageType) -> ServerMessage {
+        match msg_type {
+            ServerMessageType::RegisterAck => ServerMessage::RegisterAck {
+                display_id: "Player1".to_string(),
+                seq_num: 1,
+                world_boundaries: WorldBoundaries::for_region(
+                    Some("Deep Net"),
+                    -100.0,
+                    100.0,
+                    -100.0,
+                    100.0,
+                ),
+                negotiated_version: PROTOCOL_VERSION,
+                reclaim_token: "cmVjbGFpbSB0b2tlbg".to_string(),
+            },
+            ServerMessageType::GameState => {
+                let player = PlayerView::from(&sample_player());
+                let mut players = HashMap::new();
+                players.insert(player.display_id.clone(), player);
+                ServerMessage::GameState {
+                    players,
+                    seq_num: 2,
+                }
+            }
+            ServerMessageType::Event => ServerMessage::Event {
+                message: "Player2 attacked you".to_string(),
+                seq_num: 3,
+            },
+            ServerMessageType::ChatMessage => ServerMessage::ChatMessage {
+                sender_name: "bob".to_string(),
+                message: "hi".to_string(),
+                seq_num: 4,
+            },
+            ServerMessageType::Error => ServerMessage::Error {
+                message: "nope".to_string(),
+                seq_num: 5,
+            },
+            ServerMessageType::HeartbeatRequest => ServerMessage::HeartbeatRequest { seq_num: 6 },
+            ServerMessageType::Ack => ServerMessage::Ack {
+                client_seq_num: 7,
+                original_type: ClientMessageType::Move,
+            },
+            ServerMessageType::PlayerLeft => ServerMessage::PlayerLeft {
+                display_id: "Player2".to_string(),
+                seq_num: 8,
+            },
+            ServerMessageType::PlayerUpdate => ServerMessage::PlayerUpdate {
+                display_id: "Player2".to_string(),
+                position: Position::new(1.0, 2.0),
+                health: 60,
+                seq_num: 9,
+            },
+            ServerMessageType::ServerShutdown => ServerMessage::ServerShutdown {
+                message: "maintenance".to_string(),
+                seq_num: 10,
+                shutdown_in_seconds: 5,
+            },
+            ServerMessageType::WhisperMessage => ServerMessage::WhisperMessage {
+                sender_name: "bob".to_string(),
+                message: "psst".to_string(),
+                seq_num: 11,
+            },
+            ServerMessageType::GameStateDelta => ServerMessage::GameStateDelta {
+                baseline_seq: 2,
+                updated: vec![PlayerView::from(&sample_player())],
+                removed: vec!["Player2".to_string()],
+                seq_num: 12,
+            },
+            ServerMessageType::VisibilityChanged => ServerMessage::VisibilityChanged {
+                entered: vec!["Player3".to_string()],
+                left: vec!["Player2".to_string()],
+                seq_num: 13,
+            },
+            ServerMessageType::Inventory => ServerMessage::Inventory {
+                items: vec![InventoryItem {
+                    item: generate_crypto_items()["zk_prover"].clone(),
+                    quantity: 1,
+                    active_seconds: 30,
+                }],
+                credits: 42,
+                seq_num: 14,
+            },
+            ServerMessageType::ItemUsed => ServerMessage::ItemUsed {
+                item_id: "zk_prover".to_string(),
+                name: "Zero-Knowledge Prover".to_string(),
+                active_seconds: 120,
+                seq_num: 15,
+            },
+            ServerMessageType::ItemDropped => ServerMessage::ItemDropped {
+                item_id: "zk_prover".to_string(),
+                name: "Zero-Knowledge Prover".to_string(),
+                seq_num: 16,
+            },
+            ServerMessageType::Equipment => {
+                let shield = generate_crypto_items()["quantum_shield"].clone();
+                ServerMessage::Equipment {
+                    stats: ItemStats::from_items([&shield]),
+                    slots: BTreeMap::from([(EquipmentSlot::DefenseTool, shield)]),
+                    seq_num: 17,
+                }
+            }
+            ServerMessageType::Loot => ServerMessage::Loot {
+                drops: vec![LootDrop {
+                    drop_id: 3,
+                    item_id: "satoshi_key".to_string(),
+                    name: "Satoshi's Private Key".to_string(),
+                    rarity: ItemRarity::Legendary,
+                    position: Position::new(-4.0, 7.5),
+                }],
+                seq_num: 18,
+            },
+            ServerMessageType::TradeUpdate => ServerMessage::TradeUpdate {
+                partner_display_id: "Player2".to_string(),
+                revision: 3,
+                own_offer: sample_offer(),
+                partner_offer: TradeAssets::default(),
+                own_confirmed: true,
+                partner_confirmed: false,
+                seq_num: 19,
+            },
+            ServerMessageType::TradeClosed => ServerMessage::TradeClosed {
+                partner_display_id: "Player2".to_string(),
+                completed: true,
+                reason: "Trade complete".to_string(),
+                seq_num: 20,
+            },
+            ServerMessageType::ReputationUpdate => ServerMessage::ReputationUpdate {
+                standings: vec![FactionStanding {
+                    faction: Faction::CipherCollective,
+                    score: 40,
+                    emote_unlocked: true,
+                    item_unlocked: false,
+                }],
+                emote_threshold: 25,
+                item_threshold: 100,
+                seq_num: 21,
+            },
+            ServerMessageType::TerritoryUpdate => ServerMessage::TerritoryUpdate {
+                control_points: vec![ControlPoint {
+                    name: "Relay Spire".to_string(),
+                    region: "Deep Net".to_string(),
+                    position: Position::new(50.0, -50.0),
+                    radius: 20.0,
+                    owner: Some(Faction::Nyms),
+                    capturing: Some(Faction::CorporateHegemony),
+                    progress_seconds: 12,
+                }],
+                capture_seconds: 30,
+                seq_num: 22,
+            },
+            ServerMessageType::RegionChanged => ServerMessage::RegionChanged {
+                world_boundaries: WorldBoundaries::for_region(
+                    Some("Deep Net"),
+                    100.0,
+                    400.0,
+                    -150.0,
+                    150.0,
+                ),
+                seq_num: 23,
+            },
+            ServerMessageType::SurveillanceAlert => ServerMessage::SurveillanceAlert {
+                display_id: "Player2".to_string(),
+                name: "Bob".to_string(),
+                faction: Faction::Nyms,
+                position: Position::new(-40.0, 60.0),
+                region: "Neon Harbor".to_string(),
+                duration_seconds: 30,
+                seq_num: 24,
+            },
+            ServerMessageType::Terrain => ServerMessage::Terrain {
+                terrain: TerrainMap {
+                    region: "Neon Harbor".to_string(),
+                    rows: vec!["..#".to_string(), ".~#".to_string()],
+                },
+                seq_num: 25,
+            },
+            ServerMessageType::NpcUpdate => ServerMessage::NpcUpdate {
+                npcs: vec![NpcView {
+                    display_id: "Drone1".to_string(),
+                    kind: NpcKind::SurveillanceDrone,
+                    position: Position::new(-20.0, 35.0),
+                    health: 30,
+                    max_health: 40,
+                    state: NpcState::Chase,
+                }],
+                seq_num: 26,
+            },
+            ServerMessageType::QuestOffer => ServerMessage::QuestOffer {
+                quests: vec![sample_quest()],
+                seq_num: 27,
+            },
+            ServerMessageType::QuestProgress => ServerMessage::QuestProgress {
+                quest: sample_quest(),
+                progress: vec![1, 12],
+                seq_num: 28,
+            },
+            ServerMessageType::QuestComplete => ServerMessage::QuestComplete {
+                quest_id: "blind_the_watchers".to_string(),
+                name: "Blind the Watchers".to_string(),
+                experience: 80,
+                items: vec!["Mixnet Relay".to_string()],
+                seq_num: 29,
+            },
+            ServerMessageType::AbilityCooldowns => ServerMessage::AbilityCooldowns {
+                cooldowns: vec![AbilityCooldown {
+                    ability: Ability::Ghost,
+                    cooldown_seconds: 45,
+                    remaining_seconds: 12,
+                }],
+                seq_num: 30,
+            },
+        }
+    }
+
+    /// Serialize, deserialize and serialize again, checking the JSON is unchanged
+    fn assert_json_round_trip<T: Serialize + DeserializeOwned>(message: &T) -> T {
+        let encoded = serde_json::to_value(message).unwrap();
+        let decoded: T = serde_json::from_value(encoded.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), encoded);
+        decoded
+    }
+
+    /// Send a message through the same padding and authentication layers used on the wire
+    fn send_over_wire<T>(message: T, auth_key: &AuthKey) -> T
+    where
+        T: Serialize + DeserializeOwned + Clone,
+    {
+        let authenticated =
+            AuthenticatedMessage::new_with_expiration(message, auth_key, 30).unwrap();
+        let padded = pad_message(authenticated).unwrap();
+        let wire = String::from_utf8(serde_json::to_vec(&padded).unwrap()).unwrap();
+
+        let received: PaddedMessage<AuthenticatedMessage<T>> = serde_json::from_str(&wire).unwrap();
+        let authenticated = unpad_message(received);
+        assert!(authenticated.verify(auth_key).unwrap());
+        authenticated.message
+    }
+
+    #[test]
+    fn test_client_messages_round_trip() {
+        for msg_type in ALL_CLIENT_TYPES {
+            let message = sample_client_message(msg_type);
+            let decoded = assert_json_round_trip(&message);
+            assert_eq!(decoded.get_type(), msg_type);
+            assert_eq!(decoded.get_seq_num(), message.get_seq_num());
+        }
+    }
+
+    #[test]
+    fn test_server_messages_round_trip() {
+        for msg_type in ALL_SERVER_TYPES {
+            let message = sample_server_message(msg_type);
+            let decoded = assert_json_round_trip(&message);
+            assert_eq!(decoded.get_type(), msg_type);
+            assert_eq!(decoded.get_seq_num(), message.get_seq_num());
+        }
+    }
+
+    #[test]
+    fn test_messages_survive_padding_and_authentication() {
+        // The client only ever holds the key the server published via discovery
+        let server_key = AuthKey::new_random().unwrap();
+        let client_key = AuthKey::from_base64(&server_key.to_base64()).unwrap();
+
+        for msg_type in ALL_CLIENT_TYPES {
+            let message = sample_client_message(msg_type);
+            let received = send_over_wire(message.clone(), &client_key);
+            assert_eq!(
+                serde_json::to_value(&received).unwrap(),
+                serde_json::to_value(&message).unwrap()
+            );
+        }
+
+        for msg_type in ALL_SERVER_TYPES {
+            let message = sample_server_message(msg_type);
+            let received = send_over_wire(message.clone(), &server_key);
+            assert_eq!(
+                serde_json::to_value(&received).unwrap(),
+                serde_json::to_value(&message).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_game_state_with_many_players_verifies() {
+        // Player maps reorder on deserialization, so the tag must not depend on key order
+        let auth_key = AuthKey::new_random().unwrap();
+        let players = (0..16)
+            .map(|i| {
+                let mut player = PlayerView::from(&sample_player());
+                player.display_id = format!("Player{}", i);
+                (player.display_id.clone(), player)
+            })
+            .collect();
+
+        let received = send_over_wire(
+            ServerMessage::GameState {
+                players,
+                seq_num: 1,
+            },
+            &auth_key,
+        );
+        match received {
+            ServerMessage::GameState { players, .. } => assert_eq!(players.len(), 16),
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_player_view_hides_internal_fields() {
+        let player = sample_player();
+        let view = serde_json::to_value(PlayerView::from(&player)).unwrap();
+
+        assert_eq!(view["display_id"], "Player1");
+        assert_eq!(view["level"], 2);
+        let text = view.to_string();
+        assert!(!text.contains(&player.id));
+        assert!(view.get("last_attack_time").is_none());
+        assert!(view.get("last_damage_time").is_none());
+        assert!(view.get("experience").is_none());
+        // Others do not see what a player carries
+        assert!(view.get("inventory").is_none());
+        assert!(view.get("active_items").is_none());
+        assert!(view.get("equipment").is_none());
+        assert!(view.get("credits").is_none());
+        assert!(view.get("escrow").is_none());
+        assert!(view.get("reputation").is_none());
+        // Everyone sees the effects on a player, but not when they wear off
+        assert_eq!(view["effects"]["Corruption"], 2);
+        assert!(!text.contains("1700000005"));
+    }
+
+    #[test]
+    fn test_trade_assets_count() {
+        assert!(TradeAssets::default().is_empty());
+        let offer = sample_offer();
+        assert!(!offer.is_empty());
+        assert_eq!(offer.item_count(), 1);
+
+        let credits_only = TradeAssets {
+            credits: 5,
+            ..Default::default()
+        };
+        assert!(!credits_only.is_empty());
+        assert_eq!(credits_only.item_count(), 0);
+    }
+
+    #[test]
+    fn test_world_boundaries_for_region() {
+        let boundaries = WorldBoundaries::for_region(Some("Dead Zones"), -50.0, 50.0, -40.0, 40.0);
+        assert_eq!(boundaries.min_x, -50.0);
+        assert_eq!(boundaries.max_y, 40.0);
+        assert_eq!(boundaries.region_type, "Dead Zones");
+        assert_eq!(boundaries.security_level, "None");
+
+        // Unknown regions fall back to Neon Harbor lore
+        let fallback = WorldBoundaries::for_region(Some("Atlantis"), -1.0, 1.0, -1.0, 1.0);
+        let default = WorldBoundaries::for_region(None, -1.0, 1.0, -1.0, 1.0);
+        assert_eq!(fallback.name, default.name);
+        assert_eq!(default.region_type, "Neon Harbor");
+    }
+
+    #[test]
+    fn test_terrain_map_covers_its_region() {
+        let bounds = WorldBoundaries::for_region(Some("Dead Zones"), -60.0, 60.0, -60.0, 60.0);
+        let terrain = TerrainMap::parse("Dead Zones", "+.#\n\n.~#  \n").unwrap();
+        assert_eq!((terrain.width(), terrain.height()), (3, 2));
+
+        // Rows run north to south and columns west to east
+        assert_eq!(terrain.tile_at(&bounds, -50.0, -50.0), Tile::Safe);
+        assert_eq!(terrain.tile_at(&bounds, 0.0, -50.0), Tile::Open);
+        assert_eq!(terrain.tile_at(&bounds, 0.0, 10.0), Tile::Slow);
+        assert_eq!(terrain.tile_at(&bounds, 60.0, 60.0), Tile::Wall);
+        assert_eq!(terrain.tile_at(&bounds, 70.0, 0.0), Tile::Open);
+        assert_eq!(TerrainMap::default().tile_at(&bounds, 0.0, 0.0), Tile::Open);
+
+        assert!(TerrainMap::parse("Dead Zones", "..#\n.#").is_err());
+        assert!(TerrainMap::parse("Dead Zones", "..X").is_err());
+    }
+
+    #[test]
+    fn test_quest_files_are_checked() {
+        let text = r#"[{
+            "id": "first_contact",
+            "name": "First Contact",
+            "description": "Find the broker.",
+            "objectives": [
+                {"type": "reach", "position": {"x": 10.0, "y": -20.0}, "radius": 15.0},
+                {"type": "whisper", "npc": "Broker1"}
+            ],
+            "reward_items": ["zk_prover"]
+        }]"#;
+        let quests = QuestDefinition::parse_all(text).unwrap();
+        assert_eq!(quests[0].reward_experience, 0);
+        assert!(quests[0].is_offered_to(&Faction::CorporateHegemony));
+        assert_eq!(quests[0].objectives[1].describe(), "Whisper to Broker1");
+        assert!(!sample_quest().is_offered_to(&Faction::CorporateHegemony));
+
+        // Quests that cannot be completed are refused
+        let twice = format!(
+            "[{}, {}]",
+            &text[1..text.len() - 1],
+            &text[1..text.len() - 1]
+        );
+        assert!(QuestDefinition::parse_all(&twice).is_err());
+        assert!(QuestDefinition::parse_all(&text.replace("15.0", "0.0")).is_err());
+        let no_objectives =
+            r#"[{"id": "idle", "name": "Idle", "description": "", "objectives": []}]"#;
+        assert!(QuestDefinition::parse_all(no_objectives).is_err());
+        assert!(QuestDefinition::parse_all("[{\"id\": \"broken\"}]").is_err());
+    }
+
+    #[test]
+    fn test_abilities_unlock_by_faction_and_level() {
+        assert_eq!(Ability::Ghost.unlock_level(&Faction::Nyms), 1);
+        assert_eq!(Ability::Ghost.unlock_level(&Faction::Independent), 2);
+        assert_eq!(Ability::Ghost.unlock_level(&Faction::CorporateHegemony), 3);
+        // Every faction but the Independents starts out with one ability
+        for faction in Faction::ALL {
+            let starting = Ability::ALL
+                .iter()
+                .filter(|ability| ability.unlock_level(&faction) == 1)
+                .count();
+            assert_eq!(starting, usize::from(faction != Faction::Independent));
+        }
+        assert_eq!(Ability::from_str("HEAL"), Some(Ability::DataDrop));
+        assert_eq!(Ability::from_str("fireball"), None);
+    }
+}
@@ -0,0 +1,22 @@
+//! Wire protocol shared by the NymQuest server and client
+//!
+//! Everything that crosses the mixnet lives here so both binaries serialize
+//! messages from the same definitions: the game messages themselves, message
+//! authentication and session key agreement, size padding, the world lore
+//! types embedded in messages and the server address discovery format.
+//!
+//! It also defines the `GameTransport` abstraction the messages travel over,
+//! with in-process and loopback UDP implementations for tests and local play,
+//! plus the Nym mixnet implementation behind the `nym` feature.
+
+pub mod discovery;
+pub mod game_protocol;
+pub mod local_transport;
+pub mod message_auth;
+pub mod message_padding;
+#[cfg(feature = "nym")]
+pub mod nym_transport;
+pub mod session;
+pub mod transport;
+pub mod udp_transport;
+pub mod world_lore;
@@ -0,0 +1,274 @@
+//! In-process transport built on tokio channels
+//!
+//! A `LocalNetwork` acts as a tiny message router: every endpoint registers an
+//! address, messages sent to an address carry the sender's reply tag, and
+//! replies to a tag are routed back to the endpoint that owns it. This mirrors
+//! the mixnet's addressing model closely enough to exercise the full server and
+//! client message flow inside `cargo test` without any networking.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::transport::{GameTransport, IncomingMessage, SenderTag, TransportConnector};
+
+#[derive(Default)]
+struct Router {
+    /// Inbox of every registered endpoint, by address
+    endpoints: HashMap<String, UnboundedSender<IncomingMessage>>,
+    /// Which endpoint address each reply tag belongs to
+    reply_routes: HashMap<SenderTag, String>,
+    /// Number of upcoming messages to silently lose, by destination address
+    pending_drops: HashMap<String, usize>,
+}
+
+/// Shared in-process network that local endpoints attach to
+#[derive(Clone, Default)]
+pub struct LocalNetwork {
+    router: Arc<Mutex<Router>>,
+}
+
+impl LocalNetwork {
+    /// Create an empty network
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a new endpoint reachable at `address`
+    pub fn endpoint(&self, address: &str) -> Result<LocalTransport> {
+        let (sender, inbox) = unbounded_channel();
+        let reply_tag = SenderTag::random();
+
+        let mut router = self.lock()?;
+        if router.endpoints.contains_key(address) {
+            return Err(anyhow!("Address {} is already in use", address));
+        }
+        router.endpoints.insert(address.to_string(), sender);
+        router.reply_routes.insert(reply_tag, address.to_string());
+
+        Ok(LocalTransport {
+            network: self.clone(),
+            address: address.to_string(),
+            reply_tag,
+            inbox,
+        })
+    }
+
+    /// Silently lose the next `count` messages sent to `address`
+    ///
+    /// Simulates mixnet packet loss so retry and acknowledgement paths can be tested.
+    pub fn drop_next(&self, address: &str, count: usize) -> Result<()> {
+        *self
+            .lock()?
+            .pending_drops
+            .entry(address.to_string())
+            .or_insert(0) += count;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Router>> {
+        self.router
+            .lock()
+            .map_err(|_| anyhow!("Local network router lock poisoned"))
+    }
+
+    fn deliver(&self, address: &str, message: IncomingMessage) -> Result<()> {
+        let mut router = self.lock()?;
+        if let Some(drops) = router.pending_drops.get_mut(address) {
+            if *drops > 0 {
+                // Lost in transit: the sender sees a successful send
+                *drops -= 1;
+                return Ok(());
+            }
+        }
+        let inbox = router
+            .endpoints
+            .get(address)
+            .ok_or_else(|| anyhow!("No local endpoint at address {}", address))?;
+        inbox
+            .send(message)
+            .map_err(|_| anyhow!("Local endpoint {} is closed", address))
+    }
+
+    fn route_for(&self, tag: &SenderTag) -> Result<String> {
+        self.lock()?
+            .reply_routes
+            .get(tag)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown sender tag {}", tag))
+    }
+
+    fn detach(&self, address: &str) {
+        if let Ok(mut router) = self.lock() {
+            router.endpoints.remove(address);
+            router.reply_routes.retain(|_, route| route != address);
+        }
+    }
+}
+
+/// Endpoint on a `LocalNetwork`
+pub struct LocalTransport {
+    network: LocalNetwork,
+    address: String,
+    /// Tag attached to everything this endpoint sends, so peers can reply
+    reply_tag: SenderTag,
+    inbox: UnboundedReceiver<IncomingMessage>,
+}
+
+impl LocalTransport {
+    /// Reply tag peers see on messages from this endpoint
+    pub fn reply_tag(&self) -> SenderTag {
+        self.reply_tag
+    }
+
+    /// Take the next queued message without waiting, if there is one
+    pub fn try_next_message(&mut self) -> Option<IncomingMessage> {
+        self.inbox.try_recv().ok()
+    }
+}
+
+impl GameTransport for LocalTransport {
+    async fn send_reply<M>(&self, tag: SenderTag, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        let address = self.network.route_for(&tag)?;
+        // Replies are anonymous, like SURB replies on the mixnet
+        self.network.deliver(
+            &address,
+            IncomingMessage {
+                payload: message.into(),
+                sender_tag: None,
+            },
+        )
+    }
+
+    async fn send_to_address<M>(&self, address: &str, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        self.network.deliver(
+            address,
+            IncomingMessage {
+                payload: message.into(),
+                sender_tag: Some(self.reply_tag),
+            },
+        )
+    }
+
+    async fn next_message(&mut self) -> Option<IncomingMessage> {
+        self.inbox.recv().await
+    }
+
+    fn own_address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn disconnect(self) {
+        // Detaching happens on drop
+    }
+}
+
+impl Drop for LocalTransport {
+    fn drop(&mut self) {
+        self.network.detach(&self.address);
+    }
+}
+
+/// Connector producing fresh endpoints on a `LocalNetwork`
+///
+/// Every connection gets a new address and reply tag, just as a reconnecting
+/// Nym client comes back with a new identity.
+#[derive(Clone)]
+pub struct LocalConnector {
+    network: LocalNetwork,
+}
+
+impl LocalConnector {
+    /// Create a connector attaching endpoints to `network`
+    pub fn new(network: LocalNetwork) -> Self {
+        Self { network }
+    }
+}
+
+impl TransportConnector for LocalConnector {
+    type Transport = LocalTransport;
+
+    async fn connect(&self) -> Result<LocalTransport> {
+        let address = format!("local-{}", SenderTag::random());
+        self.network.endpoint(&address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_message_and_reply_round_trip() {
+        let network = LocalNetwork::new();
+        let mut server = network.endpoint("server").unwrap();
+        let mut client = network.endpoint("client").unwrap();
+
+        client.send_to_address("server", "hello").await.unwrap();
+        let received = server.next_message().await.unwrap();
+        assert_eq!(received.payload, b"hello");
+        let tag = received.sender_tag.expect("messages carry a reply tag");
+        assert_eq!(tag, client.reply_tag());
+
+        server.send_reply(tag, "welcome").await.unwrap();
+        let reply = client.next_message().await.unwrap();
+        assert_eq!(reply.payload, b"welcome");
+        assert!(reply.sender_tag.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_send_to_missing_endpoint_fails() {
+        let network = LocalNetwork::new();
+        let client = network.endpoint("client").unwrap();
+
+        assert!(client.send_to_address("nowhere", "hi").await.is_err());
+        assert!(client.send_reply(SenderTag::random(), "hi").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disconnected_endpoint_is_unreachable() {
+        let network = LocalNetwork::new();
+        let server = network.endpoint("server").unwrap();
+        let client = network.endpoint("client").unwrap();
+        let tag = client.reply_tag();
+
+        client.disconnect().await;
+        assert!(server.send_reply(tag, "gone").await.is_err());
+
+        // The address can be reused once the old endpoint is gone
+        assert!(network.endpoint("client").is_ok());
+        assert!(network.endpoint("server").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_messages_are_lost_silently() {
+        let network = LocalNetwork::new();
+        let mut server = network.endpoint("server").unwrap();
+        let client = network.endpoint("client").unwrap();
+
+        network.drop_next("server", 1).unwrap();
+        client.send_to_address("server", "lost").await.unwrap();
+        client.send_to_address("server", "kept").await.unwrap();
+
+        assert_eq!(server.next_message().await.unwrap().payload, b"kept");
+        assert!(server.try_next_message().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connector_gives_fresh_identities() {
+        let network = LocalNetwork::new();
+        let connector = LocalConnector::new(network);
+
+        let first = connector.connect().await.unwrap();
+        let second = connector.connect().await.unwrap();
+        assert_ne!(first.own_address(), second.own_address());
+        assert_ne!(first.reply_tag(), second.reply_tag());
+    }
+}
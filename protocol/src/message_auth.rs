@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine};
+use hkdf::Hkdf;
 use hmac::{Hmac, Mac};
 use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
@@ -10,9 +11,15 @@ use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
+use crate::session::{ServerIdentity, ServerPublicKey};
+
 // Constants for key rotation
 const KEY_ROTATION_INTERVAL_SECONDS: u64 = 86400; // 24 hours
 const MAX_PREV_KEYS: usize = 3; // Keep 3 previous keys for verification
+const SESSION_REKEY_INTERVAL_SECONDS: u64 = 3600; // 1 hour
+
+// HKDF context for deriving the next key of a session
+const SESSION_REKEY_INFO: &[u8] = b"nymquest session rekey v1";
 
 // Normalize timestamps by truncating to day precision (for rotation stability)
 // This avoids timing attacks and ensures client/server timestamp compatibility
@@ -22,6 +29,15 @@ fn normalize_timestamp(timestamp: u64) -> u64 {
     (timestamp / KEY_ROTATION_INTERVAL_SECONDS) * KEY_ROTATION_INTERVAL_SECONDS
 }
 
+// Serialize a message with object keys in sorted order. Messages carrying
+// HashMaps (such as the player list) would otherwise serialize in a different
+// order after a round trip, and the receiver's HMAC would never match.
+fn canonical_json<T: Serialize>(message: &T) -> Result<String> {
+    let value =
+        serde_json::to_value(message).map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+    serde_json::to_string(&value).map_err(|e| anyhow!("Failed to serialize message: {}", e))
+}
+
 // Type alias for HMAC-SHA256
 type HmacSha256 = Hmac<Sha256>;
 
@@ -32,6 +48,19 @@ struct KeyEntry {
     key: Vec<u8>,
     /// Unix timestamp when this key was created
     created_at: u64,
+    /// Number of rotations since the session key was agreed (session keys only)
+    #[serde(default)]
+    epoch: u64,
+}
+
+/// How a key is replaced when it rotates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rotation {
+    /// Replace with fresh random key material (long-lived keys)
+    Random,
+    /// Derive the next key from the current one, so both ends of a session
+    /// can rotate independently and still verify each other's messages
+    Ratchet,
 }
 
 /// Key used for message authentication between client and server
@@ -44,6 +73,8 @@ pub struct AuthKey {
     previous_keys: VecDeque<KeyEntry>,
     /// Next rotation time as Unix timestamp
     next_rotation: u64,
+    /// How the current key is replaced on rotation
+    rotation: Rotation,
 }
 
 impl KeyEntry {
@@ -61,15 +92,45 @@ impl KeyEntry {
         // Normalize to day precision for stability between client/server
         let created_at = normalize_timestamp(raw_timestamp);
 
-        Ok(Self { key, created_at })
+        Ok(Self {
+            key,
+            created_at,
+            epoch: 0,
+        })
     }
 
     /// Create a key entry from existing key and timestamp
     fn from_key_and_timestamp(key: Vec<u8>, created_at: u64) -> Self {
-        Self { key, created_at }
+        Self {
+            key,
+            created_at,
+            epoch: 0,
+        }
+    }
+
+    /// Derive the key that follows this one in a session
+    fn next_in_session(&self, created_at: u64) -> Result<Self> {
+        let mut key = vec![0u8; 32];
+        Hkdf::<Sha256>::new(None, &self.key)
+            .expand(SESSION_REKEY_INFO, &mut key)
+            .map_err(|e| anyhow!("Failed to derive session key: {}", e))?;
+
+        Ok(Self {
+            key,
+            created_at,
+            epoch: self.epoch + 1,
+        })
     }
 }
 
+// Current Unix time in seconds
+fn unix_now() -> Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| anyhow!("Failed to get system time: {}", e))?
+        .as_secs())
+}
+
 impl AuthKey {
     /// Generate a new random authentication key
     pub fn new_random() -> Result<Self> {
@@ -86,6 +147,7 @@ impl AuthKey {
             current_key,
             previous_keys: VecDeque::new(),
             next_rotation: now + KEY_ROTATION_INTERVAL_SECONDS,
+            rotation: Rotation::Random,
         })
     }
 
@@ -106,6 +168,22 @@ impl AuthKey {
             current_key,
             previous_keys: VecDeque::new(),
             next_rotation: now + KEY_ROTATION_INTERVAL_SECONDS,
+            rotation: Rotation::Random,
+        })
+    }
+
+    /// Create a session key from secret material agreed during the handshake
+    ///
+    /// Session keys rotate by ratcheting: each side derives the next key from
+    /// the current one, and tags carry the key epoch so the peer can follow.
+    pub(crate) fn from_session_secret(secret: &[u8]) -> Result<Self> {
+        let now = unix_now()?;
+
+        Ok(Self {
+            current_key: KeyEntry::from_key_and_timestamp(secret.to_vec(), now),
+            previous_keys: VecDeque::new(),
+            next_rotation: now + SESSION_REKEY_INTERVAL_SECONDS,
+            rotation: Rotation::Ratchet,
         })
     }
 
@@ -160,6 +238,7 @@ impl AuthKey {
             current_key: storage.current_key,
             previous_keys,
             next_rotation: storage.next_rotation,
+            rotation: Rotation::Random,
         })
     }
 
@@ -178,15 +257,13 @@ impl AuthKey {
         // Normalize timestamp for consistency
         let now = normalize_timestamp(raw_now);
 
-        let current_key = KeyEntry {
-            key,
-            created_at: now,
-        };
+        let current_key = KeyEntry::from_key_and_timestamp(key, now);
 
         Ok(Self {
             current_key,
             previous_keys: VecDeque::new(),
             next_rotation: now + KEY_ROTATION_INTERVAL_SECONDS,
+            rotation: Rotation::Random,
         })
     }
 
@@ -250,48 +327,73 @@ impl AuthKey {
 
     /// Check if key rotation is needed and perform rotation if necessary
     /// Returns true if rotation was performed
+    ///
+    /// Long-lived keys rotate daily to fresh random keys. Session keys rotate
+    /// hourly along their ratchet, which needs no coordination with the peer.
     pub fn check_and_rotate(&mut self) -> Result<bool> {
-        let raw_now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| anyhow!("Failed to get system time: {}", e))?
-            .as_secs();
+        let raw_now = unix_now()?;
 
-        // Normalize timestamp for rotation consistency
-        let now = normalize_timestamp(raw_now);
+        // Normalize timestamp for rotation consistency; session keys are not
+        // shared through files, so they use the real clock
+        let now = match self.rotation {
+            Rotation::Random => normalize_timestamp(raw_now),
+            Rotation::Ratchet => raw_now,
+        };
 
         if now >= self.next_rotation {
-            // Time to rotate the key
-            debug!("Rotating authentication key");
+            self.rotate()?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
 
-            // Move current key to previous keys
-            self.previous_keys.push_back(self.current_key.clone());
+    /// Rotate the key immediately
+    pub fn rotate(&mut self) -> Result<()> {
+        let raw_now = unix_now()?;
 
-            // Ensure we don't exceed our maximum previous keys
-            while self.previous_keys.len() > MAX_PREV_KEYS {
-                self.previous_keys.pop_front();
-            }
+        // Time to rotate the key
+        debug!("Rotating authentication key");
 
-            // Generate new current key
-            self.current_key = KeyEntry::new_random()?;
+        let (next_key, next_rotation) = match self.rotation {
+            Rotation::Random => {
+                let now = normalize_timestamp(raw_now);
+                (KeyEntry::new_random()?, now + KEY_ROTATION_INTERVAL_SECONDS)
+            }
+            Rotation::Ratchet => (
+                self.current_key.next_in_session(raw_now)?,
+                raw_now + SESSION_REKEY_INTERVAL_SECONDS,
+            ),
+        };
 
-            // Set next rotation time
-            self.next_rotation = now + KEY_ROTATION_INTERVAL_SECONDS;
+        // Move current key to previous keys
+        self.previous_keys
+            .push_back(std::mem::replace(&mut self.current_key, next_key));
 
-            info!(
-                "Authentication key rotated, next rotation at timestamp {}",
-                self.next_rotation
-            );
-            return Ok(true);
+        // Ensure we don't exceed our maximum previous keys
+        while self.previous_keys.len() > MAX_PREV_KEYS {
+            self.previous_keys.pop_front();
         }
 
-        Ok(false)
+        // Set next rotation time
+        self.next_rotation = next_rotation;
+
+        info!(
+            "Authentication key rotated, next rotation at timestamp {}",
+            self.next_rotation
+        );
+        Ok(())
+    }
+
+    /// Epoch of the current key; incremented on every session key rotation
+    pub fn epoch(&self) -> u64 {
+        self.current_key.epoch
     }
 
     /// Generate an authentication tag for the given message
     pub fn generate_tag<T: Serialize>(&self, message: &T) -> Result<String> {
-        // Serialize the message to a JSON string
-        let message_str = serde_json::to_string(message)
-            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        // Serialize the message to a canonical JSON string
+        let message_str = canonical_json(message)?;
 
         // Create a new HMAC instance with the current key
         let mut mac = HmacSha256::new_from_slice(&self.current_key.key)
@@ -304,13 +406,13 @@ impl AuthKey {
         // Finalize and get the result
         let result = mac.finalize().into_bytes();
 
-        // Return the Base64 encoded tag with key timestamp
-        // Format: base64(hmac):timestamp
-        let tag = format!(
-            "{}:{}",
-            general_purpose::STANDARD.encode(result),
-            self.current_key.created_at
-        );
+        // Return the Base64 encoded tag with key timestamp, or the key epoch for session keys
+        // Format: base64(hmac):timestamp or base64(hmac):epoch
+        let key_id = match self.rotation {
+            Rotation::Random => self.current_key.created_at,
+            Rotation::Ratchet => self.current_key.epoch,
+        };
+        let tag = format!("{}:{}", general_purpose::STANDARD.encode(result), key_id);
 
         debug!(
             "Generated tag with timestamp {}",
@@ -321,6 +423,10 @@ impl AuthKey {
 
     /// Verify an authentication tag for the given message
     pub fn verify_tag<T: Serialize>(&self, message: &T, tag: &str) -> Result<bool> {
+        if self.rotation == Rotation::Ratchet {
+            return self.verify_session_tag(message, tag);
+        }
+
         // Parse the tag format (tag:timestamp or just tag for backward compatibility)
         let parts: Vec<&str> = tag.split(':').collect();
 
@@ -403,6 +509,48 @@ impl AuthKey {
         }
     }
 
+    /// Verify a session tag ("tag:epoch") against the key of that epoch
+    ///
+    /// Keys from a few epochs back are kept, and keys up to a few epochs ahead
+    /// are derived on the fly, so a peer that rotated earlier or later than us
+    /// still verifies.
+    fn verify_session_tag<T: Serialize>(&self, message: &T, tag: &str) -> Result<bool> {
+        let Some((tag_value, epoch)) = tag.split_once(':') else {
+            warn!("Invalid session tag format");
+            return Ok(false);
+        };
+        let epoch = epoch
+            .parse::<u64>()
+            .map_err(|e| anyhow!("Invalid epoch in tag: {}", e))?;
+
+        let current_epoch = self.current_key.epoch;
+        let key = if epoch <= current_epoch {
+            std::iter::once(&self.current_key)
+                .chain(self.previous_keys.iter())
+                .find(|entry| entry.epoch == epoch)
+                .cloned()
+        } else if epoch - current_epoch <= MAX_PREV_KEYS as u64 {
+            let mut entry = self.current_key.clone();
+            while entry.epoch < epoch {
+                entry = entry.next_in_session(entry.created_at)?;
+            }
+            Some(entry)
+        } else {
+            None
+        };
+
+        match key {
+            Some(entry) => self.verify_with_key(message, tag_value, &entry.key, 0),
+            None => {
+                warn!(
+                    "No session key for epoch {} (current epoch {})",
+                    epoch, current_epoch
+                );
+                Ok(false)
+            }
+        }
+    }
+
     /// Verify a tag with a specific key and timestamp
     fn verify_with_key<T: Serialize>(
         &self,
@@ -411,9 +559,8 @@ impl AuthKey {
         key: &[u8],
         _timestamp: u64, // Timestamp is not used in HMAC calculation anymore
     ) -> Result<bool> {
-        // Serialize the message to a JSON string
-        let message_str = serde_json::to_string(message)
-            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        // Serialize the message to a canonical JSON string
+        let message_str = canonical_json(message)?;
 
         // Create a new HMAC instance
         let mut mac =
@@ -432,9 +579,8 @@ impl AuthKey {
 
     /// Verify a legacy tag (without timestamp)
     fn verify_legacy_tag<T: Serialize>(&self, message: &T, tag: &str, key: &[u8]) -> Result<bool> {
-        // Serialize the message to a JSON string
-        let message_str = serde_json::to_string(message)
-            .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+        // Serialize the message to a canonical JSON string
+        let message_str = canonical_json(message)?;
 
         // Create a new HMAC instance
         let mut mac =
@@ -452,12 +598,19 @@ impl AuthKey {
 }
 
 /// A wrapper for messages that includes authentication and expiration
+///
+/// The HMAC tag binds a message to a session. Messages from the server are
+/// additionally signed with the server identity, so a client can tell them
+/// apart from anything produced by someone else who knows the session key.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AuthenticatedMessage<T> {
     pub message: T,
     pub auth_tag: String,
     /// Unix timestamp (seconds since epoch) when this message expires
     pub expires_at: Option<u64>,
+    /// Server signature over the message and its expiration, base64
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl<T: Serialize> AuthenticatedMessage<T> {
@@ -468,6 +621,7 @@ impl<T: Serialize> AuthenticatedMessage<T> {
             message,
             auth_tag,
             expires_at: None,
+            signature: None,
         })
     }
 
@@ -475,15 +629,13 @@ impl<T: Serialize> AuthenticatedMessage<T> {
     pub fn new_with_expiration(message: T, auth_key: &AuthKey, ttl_seconds: u64) -> Result<Self> {
         let auth_tag = auth_key.generate_tag(&message)?;
 
-        // Calculate expiration time
-        let raw_now = SystemTime::now()
+        // Calculate expiration time from the real clock; normalizing to the day here
+        // (as done for key rotation) would make short TTLs expire on arrival
+        let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|e| anyhow!("Failed to get system time: {}", e))?
             .as_secs();
 
-        // Normalize timestamp for consistency
-        let now = normalize_timestamp(raw_now);
-
         let expires_at = now
             .checked_add(ttl_seconds)
             .ok_or_else(|| anyhow!("Overflow calculating expiration time"))?;
@@ -492,15 +644,46 @@ impl<T: Serialize> AuthenticatedMessage<T> {
             message,
             auth_tag,
             expires_at: Some(expires_at),
+            signature: None,
         })
     }
 
+    /// Sign the message and its expiration with the server identity
+    pub fn sign(mut self, identity: &ServerIdentity) -> Result<Self> {
+        self.signature = Some(identity.sign(&self.signed_payload()?));
+        Ok(self)
+    }
+
     /// Verify that this message has not been tampered with and is not expired
     pub fn verify(&self, auth_key: &AuthKey) -> Result<bool>
     where
         T: Serialize + Clone,
     {
         // First check if the message is expired
+        if self.is_expired()? {
+            return Ok(false);
+        }
+
+        // Then verify the authentication tag
+        auth_key.verify_tag(&self.message, &self.auth_tag)
+    }
+
+    /// Verify that this message was signed by the server holding `server_key`
+    /// and is not expired. Unsigned messages never verify.
+    pub fn verify_signature(&self, server_key: &ServerPublicKey) -> Result<bool> {
+        let signature = match &self.signature {
+            Some(signature) => signature,
+            None => return Ok(false),
+        };
+
+        if self.is_expired()? {
+            return Ok(false);
+        }
+
+        server_key.verify_signature(&self.signed_payload()?, signature)
+    }
+
+    fn is_expired(&self) -> Result<bool> {
         if let Some(expires_at) = self.expires_at {
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -512,12 +695,19 @@ impl<T: Serialize> AuthenticatedMessage<T> {
                     "Message expired: current time {} > expiration time {}",
                     now, expires_at
                 );
-                return Ok(false);
+                return Ok(true);
             }
         }
+        Ok(false)
+    }
 
-        // Then verify the authentication tag
-        auth_key.verify_tag(&self.message, &self.auth_tag)
+    // The signature covers the expiration too, so it cannot be extended
+    fn signed_payload(&self) -> Result<Vec<u8>> {
+        let payload = serde_json::json!({
+            "message": serde_json::to_value(&self.message)?,
+            "expires_at": self.expires_at,
+        });
+        Ok(canonical_json(&payload)?.into_bytes())
     }
 
     /// Get the time remaining until expiration in seconds
@@ -541,3 +731,93 @@ impl<T: Serialize> AuthenticatedMessage<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_pair() -> (AuthKey, AuthKey) {
+        let key = AuthKey::from_session_secret(&[42u8; 32]).unwrap();
+        (key.clone(), key)
+    }
+
+    #[test]
+    fn test_session_peers_verify_across_rotations() {
+        let (mut client, server) = session_pair();
+
+        // The client rotates ahead of the server; the server derives the newer key
+        client.rotate().unwrap();
+        client.rotate().unwrap();
+        assert_eq!(client.epoch(), 2);
+        let ahead = AuthenticatedMessage::new("hello".to_string(), &client).unwrap();
+        assert!(ahead.auth_tag.ends_with(":2"));
+        assert!(ahead.verify(&server).unwrap());
+
+        // The lagging server's messages still verify against the client's previous keys
+        let behind = AuthenticatedMessage::new("welcome".to_string(), &server).unwrap();
+        assert!(behind.verify(&client).unwrap());
+    }
+
+    #[test]
+    fn test_session_keys_change_on_rotation() {
+        let (mut rotated, original) = session_pair();
+        rotated.rotate().unwrap();
+
+        let tag = rotated.generate_tag(&"hello").unwrap();
+        assert_ne!(tag, original.generate_tag(&"hello").unwrap());
+        // A tag claiming the old epoch but made with the new key must not verify
+        let (mac, _) = tag.split_once(':').unwrap();
+        assert!(!original
+            .verify_tag(&"hello", &format!("{}:0", mac))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_old_session_keys_are_forgotten() {
+        let (mut client, server) = session_pair();
+        let old = AuthenticatedMessage::new("stale".to_string(), &server).unwrap();
+
+        for _ in 0..=MAX_PREV_KEYS {
+            client.rotate().unwrap();
+        }
+        assert!(!old.verify(&client).unwrap());
+    }
+
+    #[test]
+    fn test_signed_messages_verify_against_server_key() {
+        let identity = ServerIdentity::generate();
+        let (key, _) = session_pair();
+
+        let signed = AuthenticatedMessage::new_with_expiration("state".to_string(), &key, 60)
+            .unwrap()
+            .sign(&identity)
+            .unwrap();
+        assert!(signed.verify(&key).unwrap());
+        assert!(signed.verify_signature(&identity.public_key()).unwrap());
+        assert!(!signed
+            .verify_signature(&ServerIdentity::generate().public_key())
+            .unwrap());
+
+        // A peer with the session key can produce a valid HMAC but no signature
+        let forged = AuthenticatedMessage::new("state".to_string(), &key).unwrap();
+        assert!(forged.verify(&key).unwrap());
+        assert!(!forged.verify_signature(&identity.public_key()).unwrap());
+
+        // Neither the message nor its expiration can be changed after signing
+        let mut tampered = signed.clone();
+        tampered.message = "other".to_string();
+        assert!(!tampered.verify_signature(&identity.public_key()).unwrap());
+        let mut extended = signed;
+        extended.expires_at = extended.expires_at.map(|at| at + 3600);
+        assert!(!extended.verify_signature(&identity.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_session_key_does_not_verify_other_keys() {
+        let (session, _) = session_pair();
+        let shared = AuthKey::new_random().unwrap();
+
+        let message = AuthenticatedMessage::new("hello".to_string(), &shared).unwrap();
+        assert!(!message.verify(&session).unwrap());
+    }
+}
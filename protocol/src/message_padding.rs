@@ -122,7 +122,7 @@ fn maybe_rotate_jitter_strategy() {
     let rotation_interval = JITTER_ROTATION_INTERVAL.load(Ordering::SeqCst);
 
     // Check if we need to rotate based on message count
-    if message_number % rotation_interval == 0 && message_number > 0 {
+    if message_number.is_multiple_of(rotation_interval) && message_number > 0 {
         // Get current time for entropy and tracking
         let now = get_unix_time_ms();
 
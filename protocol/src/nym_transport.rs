@@ -0,0 +1,119 @@
+//! Nym mixnet implementation of the game transport
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use nym_sdk::mixnet::{
+    AnonymousSenderTag, IncludedSurbs, MixnetClient, MixnetClientBuilder, MixnetMessageSender,
+    Recipient, StoragePaths,
+};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::transport::{GameTransport, IncomingMessage, SenderTag, TransportConnector};
+
+impl From<AnonymousSenderTag> for SenderTag {
+    fn from(tag: AnonymousSenderTag) -> Self {
+        SenderTag::from_bytes(tag.to_bytes())
+    }
+}
+
+impl From<SenderTag> for AnonymousSenderTag {
+    fn from(tag: SenderTag) -> Self {
+        AnonymousSenderTag::from_bytes(tag.to_bytes())
+    }
+}
+
+/// Connected Nym mixnet client
+pub struct NymTransport {
+    client: MixnetClient,
+}
+
+impl NymTransport {
+    /// Build a Nym client using `storage_dir` for its keys and connect it to the mixnet
+    pub async fn connect<P: AsRef<Path>>(storage_dir: P) -> Result<Self> {
+        let storage_paths = StoragePaths::new_from_dir(storage_dir.as_ref())?;
+        let client = MixnetClientBuilder::new_with_default_storage(storage_paths)
+            .await?
+            .build()?
+            .connect_to_mixnet()
+            .await?;
+        Ok(Self { client })
+    }
+}
+
+impl GameTransport for NymTransport {
+    async fn send_reply<M>(&self, tag: SenderTag, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        self.client
+            .send_reply(AnonymousSenderTag::from(tag), message.into())
+            .await?;
+        Ok(())
+    }
+
+    async fn send_to_address<M>(&self, address: &str, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        let recipient =
+            Recipient::from_str(address).map_err(|e| anyhow!("Invalid server address: {}", e))?;
+        self.client
+            .send_message(recipient, message.into(), IncludedSurbs::default())
+            .await?;
+        Ok(())
+    }
+
+    async fn next_message(&mut self) -> Option<IncomingMessage> {
+        self.client.next().await.map(|message| IncomingMessage {
+            payload: message.message,
+            sender_tag: message.sender_tag.map(SenderTag::from),
+        })
+    }
+
+    fn own_address(&self) -> String {
+        self.client.nym_address().to_string()
+    }
+
+    async fn disconnect(self) {
+        self.client.disconnect().await;
+    }
+}
+
+/// Connector creating Nym clients with throwaway identities
+///
+/// Each connection uses a unique storage directory so concurrent clients on one
+/// machine never share keys, and a reconnect comes back with a new address.
+#[derive(Clone)]
+pub struct NymConnector {
+    storage_root: PathBuf,
+}
+
+impl NymConnector {
+    /// Create a connector storing client data under `storage_root`
+    pub fn new<P: Into<PathBuf>>(storage_root: P) -> Self {
+        Self {
+            storage_root: storage_root.into(),
+        }
+    }
+}
+
+impl Default for NymConnector {
+    fn default() -> Self {
+        Self::new("/tmp")
+    }
+}
+
+impl TransportConnector for NymConnector {
+    type Transport = NymTransport;
+
+    async fn connect(&self) -> Result<NymTransport> {
+        // Generate a unique ID for this client to prevent connection conflicts
+        let unique_id = Uuid::new_v4().to_string();
+        let config_dir = self
+            .storage_root
+            .join(format!("nym_mmorpg_client_{}", unique_id));
+        NymTransport::connect(config_dir).await
+    }
+}
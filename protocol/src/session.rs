@@ -0,0 +1,353 @@
+//! Session key agreement between clients and the server
+//!
+//! The server has a long-term Ed25519 identity, and its public key is the only
+//! key material published in the discovery file. A client generates an X25519
+//! key pair for each connection and sends the public half in `Register`. Both
+//! sides run X25519 between that key and the server identity (converted to its
+//! Montgomery form, as libsodium does) and derive a per-session HMAC key with
+//! HKDF. Only the client and the holder of the server's secret key can compute
+//! it, so players can no longer forge messages for each other or the server.
+//!
+//! Because the client can derive the key before sending anything, `Register`
+//! itself is authenticated with the new session key.
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::info;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::message_auth::AuthKey;
+
+/// Prefix identifying the key type in its text form
+const SERVER_KEY_PREFIX: &str = "ed25519:";
+
+// HKDF context for deriving session keys from the shared secret
+const SESSION_KEY_INFO: &[u8] = b"nymquest session v1";
+
+/// The server's long-term identity
+pub struct ServerIdentity {
+    signing_key: SigningKey,
+}
+
+impl ServerIdentity {
+    /// Generate a new random identity
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    /// Load the identity from `path`, or create and save a new one if the file doesn't exist
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let encoded = fs::read_to_string(path)?;
+            let bytes: [u8; 32] = general_purpose::STANDARD
+                .decode(encoded.trim())
+                .map_err(|e| anyhow!("Failed to decode server identity: {}", e))?
+                .try_into()
+                .map_err(|_| anyhow!("Server identity file has the wrong length"))?;
+
+            info!("Loaded existing server identity");
+            return Ok(Self {
+                signing_key: SigningKey::from_bytes(&bytes),
+            });
+        }
+
+        let identity = Self::generate();
+        identity.save_to_file(path)?;
+        info!("Generated and saved new server identity");
+        Ok(identity)
+    }
+
+    /// Save the secret key to `path`, readable by the owner only where supported
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(
+            path,
+            general_purpose::STANDARD.encode(self.signing_key.to_bytes()),
+        )?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+
+    /// Public key to publish for clients
+    pub fn public_key(&self) -> ServerPublicKey {
+        ServerPublicKey(self.signing_key.verifying_key())
+    }
+
+    /// Sign `data` with the identity key, returning the base64 signature
+    pub fn sign(&self, data: &[u8]) -> String {
+        general_purpose::STANDARD.encode(self.signing_key.sign(data).to_bytes())
+    }
+
+    /// Derive the session key for a client that sent `client_public_key` in `Register`
+    pub fn accept_session(&self, client_public_key: &str) -> Result<AuthKey> {
+        let client_public = decode_x25519_public_key(client_public_key)?;
+        let secret = StaticSecret::from(self.signing_key.to_scalar_bytes());
+        let shared = secret.diffie_hellman(&client_public);
+        if !shared.was_contributory() {
+            return Err(anyhow!("Client session key is a low-order point"));
+        }
+
+        derive_session_key(
+            shared.as_bytes(),
+            client_public.as_bytes(),
+            &self.public_key().montgomery_bytes(),
+        )
+    }
+}
+
+/// The server's public key, as published in the discovery file
+///
+/// The text form is `ed25519:` followed by the base64 key, which keeps a stale
+/// discovery file holding an old shared HMAC key from being mistaken for one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServerPublicKey(VerifyingKey);
+
+impl ServerPublicKey {
+    /// Create a public key from its raw 32 bytes
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        VerifyingKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(|e| anyhow!("Invalid server public key: {}", e))
+    }
+
+    /// Raw 32 byte key
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Check a base64 signature made by `ServerIdentity::sign` over `data`
+    pub fn verify_signature(&self, data: &[u8], signature: &str) -> Result<bool> {
+        let bytes: [u8; 64] = general_purpose::STANDARD
+            .decode(signature)
+            .map_err(|e| anyhow!("Failed to decode signature: {}", e))?
+            .try_into()
+            .map_err(|_| anyhow!("Signature has the wrong length"))?;
+        Ok(self.0.verify(data, &Signature::from_bytes(&bytes)).is_ok())
+    }
+
+    /// The same key as an X25519 public key
+    fn montgomery_bytes(&self) -> [u8; 32] {
+        self.0.to_montgomery().to_bytes()
+    }
+}
+
+impl fmt::Display for ServerPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            SERVER_KEY_PREFIX,
+            general_purpose::STANDARD.encode(self.to_bytes())
+        )
+    }
+}
+
+impl FromStr for ServerPublicKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let encoded = s.trim().strip_prefix(SERVER_KEY_PREFIX).ok_or_else(|| {
+            anyhow!(
+                "Expected a server public key starting with '{}'. The discovery file may be \
+                 from an older server version; restart the server to regenerate it",
+                SERVER_KEY_PREFIX
+            )
+        })?;
+        let bytes: [u8; 32] = general_purpose::STANDARD
+            .decode(encoded)
+            .context("Failed to decode server public key")?
+            .try_into()
+            .map_err(|_| anyhow!("Server public key has the wrong length"))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Client half of the session handshake
+///
+/// One handshake is used per connection; resent `Register` messages reuse it,
+/// so the server derives the same key for every copy.
+pub struct ClientHandshake {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl Default for ClientHandshake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientHandshake {
+    /// Generate a fresh key pair for a new session
+    pub fn new() -> Self {
+        let secret = StaticSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    /// Public key to send to the server in `Register`
+    pub fn public_key(&self) -> String {
+        general_purpose::STANDARD.encode(self.public.as_bytes())
+    }
+
+    /// Derive the session key shared with the server identified by `server_key`
+    pub fn session_key(&self, server_key: &ServerPublicKey) -> Result<AuthKey> {
+        let server_public = PublicKey::from(server_key.montgomery_bytes());
+        let shared = self.secret.diffie_hellman(&server_public);
+        if !shared.was_contributory() {
+            return Err(anyhow!("Server public key is a low-order point"));
+        }
+
+        derive_session_key(
+            shared.as_bytes(),
+            self.public.as_bytes(),
+            server_public.as_bytes(),
+        )
+    }
+}
+
+fn decode_x25519_public_key(encoded: &str) -> Result<PublicKey> {
+    let bytes: [u8; 32] = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| anyhow!("Failed to decode session public key: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow!("Session public key has the wrong length"))?;
+    Ok(PublicKey::from(bytes))
+}
+
+// Both public keys go into the salt so the key is bound to this exact pair
+fn derive_session_key(
+    shared_secret: &[u8; 32],
+    client_public: &[u8; 32],
+    server_public: &[u8; 32],
+) -> Result<AuthKey> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(client_public);
+    salt[32..].copy_from_slice(server_public);
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(SESSION_KEY_INFO, &mut key)
+        .map_err(|e| anyhow!("Failed to derive session key: {}", e))?;
+    AuthKey::from_session_secret(&key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message_auth::AuthenticatedMessage;
+
+    #[test]
+    fn test_client_and_server_agree_on_session_key() {
+        let identity = ServerIdentity::generate();
+        let handshake = ClientHandshake::new();
+
+        let client_key = handshake.session_key(&identity.public_key()).unwrap();
+        let server_key = identity.accept_session(&handshake.public_key()).unwrap();
+
+        let message = AuthenticatedMessage::new("register".to_string(), &client_key).unwrap();
+        assert!(message.verify(&server_key).unwrap());
+        let reply = AuthenticatedMessage::new("welcome".to_string(), &server_key).unwrap();
+        assert!(reply.verify(&client_key).unwrap());
+    }
+
+    #[test]
+    fn test_sessions_do_not_share_keys() {
+        let identity = ServerIdentity::generate();
+        let alice = ClientHandshake::new();
+        let mallory = ClientHandshake::new();
+
+        let alice_key = identity.accept_session(&alice.public_key()).unwrap();
+        let mallory_key = mallory.session_key(&identity.public_key()).unwrap();
+
+        // Another player's key cannot produce messages that verify for alice's session
+        let forged = AuthenticatedMessage::new("forged".to_string(), &mallory_key).unwrap();
+        assert!(!forged.verify(&alice_key).unwrap());
+    }
+
+    #[test]
+    fn test_wrong_server_key_does_not_verify() {
+        let identity = ServerIdentity::generate();
+        let impostor = ServerIdentity::generate();
+        let handshake = ClientHandshake::new();
+
+        let client_key = handshake.session_key(&identity.public_key()).unwrap();
+        let impostor_key = impostor.accept_session(&handshake.public_key()).unwrap();
+
+        let reply = AuthenticatedMessage::new("welcome".to_string(), &impostor_key).unwrap();
+        assert!(!reply.verify(&client_key).unwrap());
+    }
+
+    #[test]
+    fn test_low_order_client_key_is_rejected() {
+        let identity = ServerIdentity::generate();
+        let identity_point = general_purpose::STANDARD.encode([0u8; 32]);
+        assert!(identity.accept_session(&identity_point).is_err());
+        assert!(identity.accept_session("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_server_public_key_text_round_trip() {
+        let key = ServerIdentity::generate().public_key();
+        let text = key.to_string();
+        assert!(text.starts_with(SERVER_KEY_PREFIX));
+        assert_eq!(text.parse::<ServerPublicKey>().unwrap(), key);
+
+        // A legacy shared HMAC key is rejected with a clear error
+        let legacy = general_purpose::STANDARD.encode([7u8; 32]);
+        let error = legacy.parse::<ServerPublicKey>().unwrap_err();
+        assert!(error.to_string().contains("older server version"));
+    }
+
+    #[test]
+    fn test_signatures_verify_only_with_the_signing_identity() {
+        let identity = ServerIdentity::generate();
+        let signature = identity.sign(b"game state");
+
+        assert!(identity
+            .public_key()
+            .verify_signature(b"game state", &signature)
+            .unwrap());
+        assert!(!identity
+            .public_key()
+            .verify_signature(b"tampered", &signature)
+            .unwrap());
+        assert!(!ServerIdentity::generate()
+            .public_key()
+            .verify_signature(b"game state", &signature)
+            .unwrap());
+        assert!(identity
+            .public_key()
+            .verify_signature(b"game state", "short")
+            .is_err());
+    }
+
+    #[test]
+    fn test_identity_persists_across_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server_identity.key");
+
+        let created = ServerIdentity::load_or_create(&path).unwrap();
+        let loaded = ServerIdentity::load_or_create(&path).unwrap();
+        assert_eq!(created.public_key(), loaded.public_key());
+    }
+}
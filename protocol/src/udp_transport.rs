@@ -0,0 +1,174 @@
+//! Loopback UDP transport for running server and client without the mixnet
+//!
+//! Each peer that sends us a datagram is assigned a random `SenderTag`, and
+//! replies to that tag go back to the peer's socket address. The tag-to-address
+//! table never leaves this process, so handlers see the same opaque reply
+//! handles they get from Nym. Intended for local development and tests only:
+//! plain UDP offers none of the mixnet's anonymity.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use tokio::net::{ToSocketAddrs, UdpSocket};
+use tracing::warn;
+
+use crate::transport::{GameTransport, IncomingMessage, SenderTag, TransportConnector};
+
+/// Largest datagram we accept, comfortably above the largest padded message
+const MAX_DATAGRAM_SIZE: usize = 64 * 1024;
+
+#[derive(Default)]
+struct PeerTable {
+    by_tag: HashMap<SenderTag, SocketAddr>,
+    by_addr: HashMap<SocketAddr, SenderTag>,
+}
+
+impl PeerTable {
+    fn tag_for(&mut self, addr: SocketAddr) -> SenderTag {
+        if let Some(tag) = self.by_addr.get(&addr) {
+            return *tag;
+        }
+        let tag = SenderTag::random();
+        self.by_addr.insert(addr, tag);
+        self.by_tag.insert(tag, addr);
+        tag
+    }
+}
+
+/// UDP socket speaking the game transport interface
+pub struct UdpTransport {
+    socket: UdpSocket,
+    peers: Mutex<PeerTable>,
+    buffer: Vec<u8>,
+}
+
+impl UdpTransport {
+    /// Bind a socket, e.g. `"127.0.0.1:0"` for an ephemeral loopback port
+    pub async fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let socket = UdpSocket::bind(addr)
+            .await
+            .context("Failed to bind UDP socket")?;
+        Ok(Self {
+            socket,
+            peers: Mutex::new(PeerTable::default()),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        })
+    }
+
+    /// Local socket address of this transport
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+}
+
+impl GameTransport for UdpTransport {
+    async fn send_reply<M>(&self, tag: SenderTag, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        let addr = self
+            .peers
+            .lock()
+            .map_err(|_| anyhow!("UDP peer table lock poisoned"))?
+            .by_tag
+            .get(&tag)
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown sender tag {}", tag))?;
+        self.socket.send_to(&message.into(), addr).await?;
+        Ok(())
+    }
+
+    async fn send_to_address<M>(&self, address: &str, message: M) -> Result<()>
+    where
+        M: Into<Vec<u8>> + Send,
+    {
+        let addr: SocketAddr = address
+            .parse()
+            .map_err(|e| anyhow!("Invalid UDP address {}: {}", address, e))?;
+        self.socket.send_to(&message.into(), addr).await?;
+        Ok(())
+    }
+
+    async fn next_message(&mut self) -> Option<IncomingMessage> {
+        loop {
+            match self.socket.recv_from(&mut self.buffer).await {
+                Ok((len, addr)) => {
+                    let sender_tag = match self.peers.lock() {
+                        Ok(mut peers) => peers.tag_for(addr),
+                        Err(_) => return None,
+                    };
+                    return Some(IncomingMessage {
+                        payload: self.buffer[..len].to_vec(),
+                        sender_tag: Some(sender_tag),
+                    });
+                }
+                Err(e) => {
+                    // ICMP errors from unreachable peers surface here; keep listening
+                    warn!("UDP receive error: {}", e);
+                }
+            }
+        }
+    }
+
+    fn own_address(&self) -> String {
+        self.socket
+            .local_addr()
+            .map(|addr| addr.to_string())
+            .unwrap_or_default()
+    }
+
+    async fn disconnect(self) {
+        // Dropping the socket closes it
+    }
+}
+
+/// Connector binding a fresh loopback socket per connection
+#[derive(Clone, Copy, Default)]
+pub struct UdpConnector;
+
+impl TransportConnector for UdpConnector {
+    type Transport = UdpTransport;
+
+    async fn connect(&self) -> Result<UdpTransport> {
+        UdpTransport::bind("127.0.0.1:0").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_udp_loopback_round_trip() {
+        let mut server = UdpTransport::bind("127.0.0.1:0").await.unwrap();
+        let mut client = UdpConnector.connect().await.unwrap();
+
+        client
+            .send_to_address(&server.own_address(), "ping")
+            .await
+            .unwrap();
+        let received = server.next_message().await.unwrap();
+        assert_eq!(received.payload, b"ping");
+        let tag = received.sender_tag.unwrap();
+
+        server.send_reply(tag, "pong").await.unwrap();
+        let reply = client.next_message().await.unwrap();
+        assert_eq!(reply.payload, b"pong");
+    }
+
+    #[tokio::test]
+    async fn test_udp_peer_keeps_its_tag() {
+        let mut server = UdpTransport::bind("127.0.0.1:0").await.unwrap();
+        let client = UdpTransport::bind("127.0.0.1:0").await.unwrap();
+        let address = server.own_address();
+
+        client.send_to_address(&address, "one").await.unwrap();
+        client.send_to_address(&address, "two").await.unwrap();
+        let first = server.next_message().await.unwrap().sender_tag;
+        let second = server.next_message().await.unwrap().sender_tag;
+        assert_eq!(first, second);
+
+        assert!(server.send_reply(SenderTag::random(), "x").await.is_err());
+    }
+}
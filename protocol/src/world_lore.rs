@@ -0,0 +1,785 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::game_protocol::EmoteType;
+
+/// Factions in the NymQuest cypherpunk world
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Faction {
+    /// Masters of anonymity and privacy technology
+    Nyms,
+    /// Consortium of mega-corporations controlling digital infrastructure
+    CorporateHegemony,
+    /// Information freedom fighters advocating for radical transparency
+    CipherCollective,
+    /// Quasi-religious order studying patterns in data flows
+    AlgorithmMonks,
+    /// Unaligned or independent actors
+    Independent,
+}
+
+impl Faction {
+    /// Every faction, in display order
+    pub const ALL: [Faction; 5] = [
+        Faction::Nyms,
+        Faction::CorporateHegemony,
+        Faction::CipherCollective,
+        Faction::AlgorithmMonks,
+        Faction::Independent,
+    ];
+
+    /// Short name used in commands and configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            Faction::Nyms => "nyms",
+            Faction::CorporateHegemony => "corporate",
+            Faction::CipherCollective => "cipher",
+            Faction::AlgorithmMonks => "monks",
+            Faction::Independent => "independent",
+        }
+    }
+
+    /// Name used when telling players about the faction
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Faction::Nyms => "Nyms",
+            Faction::CorporateHegemony => "Corporate Hegemony",
+            Faction::CipherCollective => "Cipher Collective",
+            Faction::AlgorithmMonks => "Algorithm Monks",
+            Faction::Independent => "Independents",
+        }
+    }
+
+    /// Parse a faction from its short name or an alias
+    #[allow(clippy::should_implement_trait)] // Lenient parsing with aliases, returns Option
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "nyms" => Some(Faction::Nyms),
+            "corporate" | "corp" | "hegemony" => Some(Faction::CorporateHegemony),
+            "cipher" | "collective" | "ciphercollective" => Some(Faction::CipherCollective),
+            "monks" | "algorithm" | "algorithmmonks" => Some(Faction::AlgorithmMonks),
+            "independent" | "indie" | "free" => Some(Faction::Independent),
+            _ => None,
+        }
+    }
+
+    /// How the faction's strengths and weaknesses change the game rules, unless configured otherwise
+    pub fn default_modifiers(&self) -> FactionModifiers {
+        let neutral = FactionModifiers::default();
+        match self {
+            // Hard to watch, but not built for fighting
+            Faction::Nyms => FactionModifiers {
+                surveillance_exposure: 0.5,
+                damage_dealt: 0.9,
+                ..neutral
+            },
+            // Corporate-grade equipment is sturdy and heavy
+            Faction::CorporateHegemony => FactionModifiers {
+                damage_taken: 0.85,
+                movement_speed: 0.9,
+                ..neutral
+            },
+            // Hard-hitting hacktivists who neglect their own defenses
+            Faction::CipherCollective => FactionModifiers {
+                damage_dealt: 1.15,
+                damage_taken: 1.1,
+                ..neutral
+            },
+            // Learn from every pattern, at the expense of striking power
+            Faction::AlgorithmMonks => FactionModifiers {
+                experience_gain: 1.25,
+                damage_dealt: 0.9,
+                ..neutral
+            },
+            // Unencumbered by any faction's baggage
+            Faction::Independent => FactionModifiers {
+                movement_speed: 1.1,
+                ..neutral
+            },
+        }
+    }
+
+    /// Whether players of the two factions count as friends for friendly fire
+    /// Independents owe nothing to each other, so only members of the same faction do.
+    pub fn is_allied_with(&self, other: &Faction) -> bool {
+        self == other && *self != Faction::Independent
+    }
+
+    /// The faction whose members count as its enemies, if any
+    /// Defeating a rival's member is a task that earns reputation with the faction.
+    pub fn rival(&self) -> Option<Faction> {
+        match self {
+            Faction::Nyms => Some(Faction::CorporateHegemony),
+            Faction::CorporateHegemony => Some(Faction::Nyms),
+            Faction::CipherCollective => Some(Faction::AlgorithmMonks),
+            Faction::AlgorithmMonks => Some(Faction::CipherCollective),
+            Faction::Independent => None,
+        }
+    }
+
+    /// Emote that reputation with the faction unlocks
+    pub fn emote(&self) -> EmoteType {
+        match self {
+            Faction::Nyms => EmoteType::Cloak,
+            Faction::CorporateHegemony => EmoteType::Audit,
+            Faction::CipherCollective => EmoteType::Leak,
+            Faction::AlgorithmMonks => EmoteType::Meditate,
+            Faction::Independent => EmoteType::Wander,
+        }
+    }
+
+    /// Item from `generate_crypto_items` that new players of the faction start with
+    /// It is also the signature item the faction hands out once a player's reputation is high enough.
+    pub fn starter_item_id(&self) -> &'static str {
+        match self {
+            Faction::Nyms => "mixnet_relay",
+            Faction::CorporateHegemony => "packet_sniffer",
+            Faction::CipherCollective => "packet_sniffer",
+            Faction::AlgorithmMonks => "zk_prover",
+            Faction::Independent => "mixnet_relay",
+        }
+    }
+
+    /// Get a description of the faction
+    #[allow(dead_code)]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Faction::Nyms => {
+                "Masters of anonymity and privacy technology. They believe that identity should be a choice, not a prison."
+            }
+            Faction::CorporateHegemony => {
+                "A consortium of mega-corporations that controls the mainstream digital infrastructure. They commodify data and sell the illusion of convenience."
+            }
+            Faction::CipherCollective => {
+                "Information freedom fighters who believe that all data should be publicly available. They stand for radical transparency."
+            }
+            Faction::AlgorithmMonks => {
+                "A quasi-religious order that studies the deeper patterns in data flows, believing in an emergent digital consciousness."
+            }
+            Faction::Independent => {
+                "Free agents who navigate between factions, loyal only to themselves or their own cause."
+            }
+        }
+    }
+}
+
+/// Multipliers a faction applies to the game rules for its players; 1.0 leaves a rule as it is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FactionModifiers {
+    /// Damage the faction's players deal
+    pub damage_dealt: f32,
+    /// Damage the faction's players take
+    pub damage_taken: f32,
+    /// Distance the faction's players cover per move
+    pub movement_speed: f32,
+    /// How much the surveillance at their spot exposes the faction's players
+    pub surveillance_exposure: f32,
+    /// Experience the faction's players earn
+    pub experience_gain: f32,
+}
+
+impl Default for FactionModifiers {
+    fn default() -> Self {
+        Self {
+            damage_dealt: 1.0,
+            damage_taken: 1.0,
+            movement_speed: 1.0,
+            surveillance_exposure: 1.0,
+            experience_gain: 1.0,
+        }
+    }
+}
+
+impl FactionModifiers {
+    /// Names of the modifiers, as used in configuration
+    pub const NAMES: [&'static str; 5] = [
+        "damage_dealt",
+        "damage_taken",
+        "movement_speed",
+        "surveillance_exposure",
+        "experience_gain",
+    ];
+
+    /// The modifier called `name`, for setting it from configuration
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "damage_dealt" => Some(&mut self.damage_dealt),
+            "damage_taken" => Some(&mut self.damage_taken),
+            "movement_speed" => Some(&mut self.movement_speed),
+            "surveillance_exposure" => Some(&mut self.surveillance_exposure),
+            "experience_gain" => Some(&mut self.experience_gain),
+            _ => None,
+        }
+    }
+}
+
+/// World regions in the NymQuest cypherpunk setting
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WorldRegion {
+    /// A city where physical and digital realms merge
+    NeonHarbor,
+    /// Hidden layer of the network only accessible through specialized tools
+    DeepNet,
+    /// Sovereign territories outside standard regulatory frameworks
+    DataHavens,
+    /// Areas deliberately cut off from network access
+    DeadZones,
+    /// The controlled mainstream network
+    TheGrid,
+}
+
+impl WorldRegion {
+    /// Find the region with a lore name such as "Neon Harbor"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Neon Harbor" => Some(WorldRegion::NeonHarbor),
+            "Deep Net" => Some(WorldRegion::DeepNet),
+            "Data Havens" => Some(WorldRegion::DataHavens),
+            "Dead Zones" => Some(WorldRegion::DeadZones),
+            "The Grid" => Some(WorldRegion::TheGrid),
+            _ => None,
+        }
+    }
+
+    /// Item types that turn up more often in loot found in the region
+    pub fn favored_item_types(&self) -> &'static [CryptoItemType] {
+        match self {
+            WorldRegion::NeonHarbor => &[CryptoItemType::PrivacyTool, CryptoItemType::AttackTool],
+            WorldRegion::DeepNet => &[CryptoItemType::PrivacyTool, CryptoItemType::Artifact],
+            WorldRegion::DataHavens => &[CryptoItemType::DefenseTool, CryptoItemType::PrivacyTool],
+            WorldRegion::DeadZones => &[CryptoItemType::Artifact, CryptoItemType::DefenseTool],
+            WorldRegion::TheGrid => &[CryptoItemType::AttackTool, CryptoItemType::DefenseTool],
+        }
+    }
+
+    /// Get a description of the world region
+    #[allow(dead_code)]
+    pub fn description(&self) -> &'static str {
+        match self {
+            WorldRegion::NeonHarbor => {
+                "A city where the physical and digital realms have begun to merge, with augmented reality overlays and ambient computing."
+            }
+            WorldRegion::DeepNet => {
+                "A hidden layer of the network only accessible through specialized tools. Home to underground marketplaces and sanctuary communities."
+            }
+            WorldRegion::DataHavens => {
+                "Sovereign territories that operate outside standard regulatory frameworks, offering secure hosting for sensitive data."
+            }
+            WorldRegion::DeadZones => {
+                "Areas deliberately cut off from network access, operating on local mesh networks and old-school tech."
+            }
+            WorldRegion::TheGrid => {
+                "The corporate-controlled network infrastructure, heavily monitored and regulated but offering powerful resources."
+            }
+        }
+    }
+
+    /// Get world boundary configuration for this region
+    pub fn get_boundaries(&self) -> WorldBoundaries {
+        match self {
+            WorldRegion::NeonHarbor => WorldBoundaries {
+                min_x: -100.0,
+                max_x: 100.0,
+                min_y: -100.0,
+                max_y: 100.0,
+                name: "Neon Harbor",
+                security_level: SecurityLevel::Moderate,
+                surveillance_density: 0.6,
+            },
+            WorldRegion::DeepNet => WorldBoundaries {
+                min_x: -150.0,
+                max_x: 150.0,
+                min_y: -150.0,
+                max_y: 150.0,
+                name: "Deep Net",
+                security_level: SecurityLevel::Low,
+                surveillance_density: 0.2,
+            },
+            WorldRegion::DataHavens => WorldBoundaries {
+                min_x: -80.0,
+                max_x: 80.0,
+                min_y: -80.0,
+                max_y: 80.0,
+                name: "Data Havens",
+                security_level: SecurityLevel::High,
+                surveillance_density: 0.1,
+            },
+            WorldRegion::DeadZones => WorldBoundaries {
+                min_x: -60.0,
+                max_x: 60.0,
+                min_y: -60.0,
+                max_y: 60.0,
+                name: "Dead Zones",
+                security_level: SecurityLevel::None,
+                surveillance_density: 0.0,
+            },
+            WorldRegion::TheGrid => WorldBoundaries {
+                min_x: -120.0,
+                max_x: 120.0,
+                min_y: -120.0,
+                max_y: 120.0,
+                name: "The Grid",
+                security_level: SecurityLevel::Maximum,
+                surveillance_density: 0.9,
+            },
+        }
+    }
+}
+
+/// Security level of a region in the world
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SecurityLevel {
+    /// No security presence
+    None,
+    /// Minimal security presence
+    Low,
+    /// Average security presence
+    Moderate,
+    /// Strong security presence
+    High,
+    /// Maximum security presence
+    Maximum,
+}
+
+/// Description of world boundaries with cypherpunk setting elements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldBoundaries {
+    /// Minimum X coordinate
+    pub min_x: f32,
+    /// Maximum X coordinate
+    pub max_x: f32,
+    /// Minimum Y coordinate
+    pub min_y: f32,
+    /// Maximum Y coordinate
+    pub max_y: f32,
+    /// Name of the region
+    pub name: &'static str,
+    /// Security level in this region
+    pub security_level: SecurityLevel,
+    /// Surveillance density (0.0 to 1.0) affecting privacy
+    pub surveillance_density: f32,
+}
+
+impl WorldBoundaries {
+    /// Clamp a position to stay within world boundaries
+    #[allow(dead_code)]
+    pub fn clamp_position(&self, x: f32, y: f32) -> (f32, f32) {
+        let clamped_x = x.clamp(self.min_x, self.max_x);
+        let clamped_y = y.clamp(self.min_y, self.max_y);
+        (clamped_x, clamped_y)
+    }
+
+    /// Check if a position is within world boundaries
+    #[allow(dead_code)]
+    pub fn is_position_valid(&self, x: f32, y: f32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    /// Calculate surveillance risk for a given position
+    /// Returns a value from 0.0 (no surveillance) to 1.0 (maximum surveillance)
+    #[allow(dead_code)]
+    pub fn calculate_surveillance_risk(&self, x: f32, y: f32) -> f32 {
+        if !self.is_position_valid(x, y) {
+            return 0.0;
+        }
+
+        // Base risk from the region's surveillance density
+        let mut risk = self.surveillance_density;
+
+        // Distance from center affects risk - closer to center is higher risk in most regions
+        let center_x = (self.min_x + self.max_x) / 2.0;
+        let center_y = (self.min_y + self.max_y) / 2.0;
+
+        let max_distance =
+            ((self.max_x - self.min_x).powi(2) + (self.max_y - self.min_y).powi(2)).sqrt() / 2.0;
+        let distance = ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt();
+        let distance_factor = 1.0 - (distance / max_distance);
+
+        // Adjust risk based on distance from center
+        match self.security_level {
+            SecurityLevel::Maximum => {
+                // In maximum security, it's equally surveilled everywhere
+                risk *= 0.8 + (0.2 * distance_factor);
+            }
+            SecurityLevel::High => {
+                // High security has more surveillance in the center
+                risk *= 0.6 + (0.4 * distance_factor);
+            }
+            SecurityLevel::Moderate => {
+                // Moderate security has some surveillance hotspots
+                risk *= 0.4 + (0.6 * distance_factor);
+            }
+            SecurityLevel::Low => {
+                // Low security has minimal surveillance mostly at the edges
+                risk *= 0.2 + (0.1 * distance_factor);
+            }
+            SecurityLevel::None => {
+                // No security has almost no surveillance
+                risk *= 0.05;
+            }
+        }
+
+        risk.clamp(0.0, 1.0)
+    }
+}
+
+/// Cryptographic items that can be found or earned in the game
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CryptoItem {
+    /// Unique identifier
+    pub id: String,
+    /// Name of the item
+    pub name: String,
+    /// Description of the item
+    pub description: String,
+    /// Rarity level of the item
+    pub rarity: ItemRarity,
+    /// Item type
+    pub item_type: CryptoItemType,
+    /// Stats modifications the item provides
+    pub stats: HashMap<String, f32>,
+}
+
+/// Types of cryptographic items
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CryptoItemType {
+    /// Enhances anonymity and privacy
+    PrivacyTool,
+    /// Provides offensive capabilities
+    AttackTool,
+    /// Defensive tools for protection
+    DefenseTool,
+    /// Special items for unique effects
+    Artifact,
+}
+
+/// Equipment slot an item is worn in; each slot holds one item of its type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EquipmentSlot {
+    AttackTool,
+    DefenseTool,
+    PrivacyTool,
+    Artifact,
+}
+
+impl EquipmentSlot {
+    /// Every slot, in display order
+    pub const ALL: [EquipmentSlot; 4] = [
+        EquipmentSlot::AttackTool,
+        EquipmentSlot::DefenseTool,
+        EquipmentSlot::PrivacyTool,
+        EquipmentSlot::Artifact,
+    ];
+
+    /// Slot that items of `item_type` are worn in
+    pub fn for_item_type(item_type: &CryptoItemType) -> Self {
+        match item_type {
+            CryptoItemType::AttackTool => EquipmentSlot::AttackTool,
+            CryptoItemType::DefenseTool => EquipmentSlot::DefenseTool,
+            CryptoItemType::PrivacyTool => EquipmentSlot::PrivacyTool,
+            CryptoItemType::Artifact => EquipmentSlot::Artifact,
+        }
+    }
+
+    /// Short name used in commands and the UI
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipmentSlot::AttackTool => "attack",
+            EquipmentSlot::DefenseTool => "defense",
+            EquipmentSlot::PrivacyTool => "privacy",
+            EquipmentSlot::Artifact => "artifact",
+        }
+    }
+
+    /// Parse a slot from its short name
+    #[allow(clippy::should_implement_trait)] // Lenient parsing with aliases, returns Option
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "attack" | "weapon" => Some(EquipmentSlot::AttackTool),
+            "defense" | "armor" => Some(EquipmentSlot::DefenseTool),
+            "privacy" => Some(EquipmentSlot::PrivacyTool),
+            "artifact" => Some(EquipmentSlot::Artifact),
+            _ => None,
+        }
+    }
+}
+
+/// Rarity levels for items
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemRarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl ItemRarity {
+    /// Relative chance of an item of this rarity being picked from a loot table
+    pub fn drop_weight(&self) -> u32 {
+        match self {
+            ItemRarity::Common => 40,
+            ItemRarity::Uncommon => 25,
+            ItemRarity::Rare => 10,
+            ItemRarity::Epic => 4,
+            ItemRarity::Legendary => 1,
+        }
+    }
+
+    pub fn get_color_code(&self) -> &'static str {
+        match self {
+            ItemRarity::Common => "white",
+            ItemRarity::Uncommon => "green",
+            ItemRarity::Rare => "blue",
+            ItemRarity::Epic => "purple",
+            ItemRarity::Legendary => "orange",
+        }
+    }
+}
+
+/// Combined stats of a set of items
+///
+/// Only the stats the game rules use are collected; `all_stats` counts towards each of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemStats {
+    pub attack: f32,
+    pub defense: f32,
+    pub privacy: f32,
+    pub detection_resistance: f32,
+    pub trust: f32,
+}
+
+impl ItemStats {
+    /// Sum the stats of `items`
+    pub fn from_items<'a>(items: impl IntoIterator<Item = &'a CryptoItem>) -> Self {
+        let mut total = Self::default();
+        for item in items {
+            let stat = |name: &str| {
+                item.stats.get(name).copied().unwrap_or(0.0)
+                    + item.stats.get("all_stats").copied().unwrap_or(0.0)
+            };
+            total.attack += stat("attack");
+            total.defense += stat("defense");
+            total.privacy += stat("privacy");
+            total.detection_resistance += stat("detection_resistance");
+            total.trust += stat("trust");
+        }
+        total
+    }
+}
+
+/// An item stack in a player's inventory, as sent to its owner
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryItem {
+    pub item: CryptoItem,
+    /// Number of unused copies carried
+    pub quantity: u32,
+    /// Seconds until the effect of a used copy wears off, 0 when none is active
+    pub active_seconds: u64,
+}
+
+/// Generate a predefined list of crypto items for the game
+pub fn generate_crypto_items() -> HashMap<String, CryptoItem> {
+    let mut items = HashMap::new();
+
+    // Privacy tools
+    let mixnet_relay = CryptoItem {
+        id: "mixnet_relay".to_string(),
+        name: "Portable Mixnet Relay".to_string(),
+        description: "A personal relay node that enhances your anonymity on the network."
+            .to_string(),
+        rarity: ItemRarity::Uncommon,
+        item_type: CryptoItemType::PrivacyTool,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("privacy".to_string(), 25.0);
+            stats.insert("detection_resistance".to_string(), 15.0);
+            stats
+        },
+    };
+    items.insert(mixnet_relay.id.clone(), mixnet_relay);
+
+    let zero_knowledge_prover = CryptoItem {
+        id: "zk_prover".to_string(),
+        name: "Zero-Knowledge Prover".to_string(),
+        description: "Allows you to validate identity without revealing personal information."
+            .to_string(),
+        rarity: ItemRarity::Rare,
+        item_type: CryptoItemType::PrivacyTool,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("privacy".to_string(), 40.0);
+            stats.insert("trust".to_string(), 20.0);
+            stats
+        },
+    };
+    items.insert(zero_knowledge_prover.id.clone(), zero_knowledge_prover);
+
+    // Attack tools
+    let packet_sniffer = CryptoItem {
+        id: "packet_sniffer".to_string(),
+        name: "Quantum Packet Sniffer".to_string(),
+        description: "Intercepts and analyzes network traffic to extract information.".to_string(),
+        rarity: ItemRarity::Uncommon,
+        item_type: CryptoItemType::AttackTool,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("attack".to_string(), 20.0);
+            stats.insert("information_gathering".to_string(), 30.0);
+            stats
+        },
+    };
+    items.insert(packet_sniffer.id.clone(), packet_sniffer);
+
+    let key_fragmenter = CryptoItem {
+        id: "key_fragmenter".to_string(),
+        name: "Cryptographic Key Fragmenter".to_string(),
+        description: "Breaks down encryption keys into recoverable fragments.".to_string(),
+        rarity: ItemRarity::Epic,
+        item_type: CryptoItemType::AttackTool,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("attack".to_string(), 45.0);
+            stats.insert("encryption_breaking".to_string(), 35.0);
+            stats
+        },
+    };
+    items.insert(key_fragmenter.id.clone(), key_fragmenter);
+
+    // Defense tools
+    let quantum_shield = CryptoItem {
+        id: "quantum_shield".to_string(),
+        name: "Quantum Entanglement Shield".to_string(),
+        description: "Creates a defensive barrier using quantum entanglement principles."
+            .to_string(),
+        rarity: ItemRarity::Rare,
+        item_type: CryptoItemType::DefenseTool,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("defense".to_string(), 35.0);
+            stats.insert("quantum_resistance".to_string(), 40.0);
+            stats
+        },
+    };
+    items.insert(quantum_shield.id.clone(), quantum_shield);
+
+    // Artifacts
+    let satoshi_key = CryptoItem {
+        id: "satoshi_key".to_string(),
+        name: "Satoshi's Private Key".to_string(),
+        description: "A legendary artifact that grants special abilities in the digital realm."
+            .to_string(),
+        rarity: ItemRarity::Legendary,
+        item_type: CryptoItemType::Artifact,
+        stats: {
+            let mut stats = HashMap::new();
+            stats.insert("all_stats".to_string(), 50.0);
+            stats.insert("reputation".to_string(), 100.0);
+            stats
+        },
+    };
+    items.insert(satoshi_key.id.clone(), satoshi_key);
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_stats_sum_and_apply_all_stats() {
+        let items = generate_crypto_items();
+        let stats = ItemStats::from_items([&items["mixnet_relay"], &items["zk_prover"]]);
+        assert_eq!(stats.privacy, 65.0);
+        assert_eq!(stats.detection_resistance, 15.0);
+        assert_eq!(stats.trust, 20.0);
+        assert_eq!(stats.attack, 0.0);
+
+        // The legendary artifact raises every stat
+        let stats = ItemStats::from_items([&items["satoshi_key"]]);
+        assert_eq!(stats.attack, 50.0);
+        assert_eq!(stats.defense, 50.0);
+    }
+
+    #[test]
+    fn test_every_item_fits_a_slot() {
+        for item in generate_crypto_items().values() {
+            let slot = EquipmentSlot::for_item_type(&item.item_type);
+            assert_eq!(EquipmentSlot::from_str(slot.name()), Some(slot));
+        }
+    }
+
+    #[test]
+    fn test_starter_items_exist() {
+        let items = generate_crypto_items();
+        for faction in [
+            Faction::Nyms,
+            Faction::CorporateHegemony,
+            Faction::CipherCollective,
+            Faction::AlgorithmMonks,
+            Faction::Independent,
+        ] {
+            assert!(items.contains_key(faction.starter_item_id()));
+        }
+    }
+
+    #[test]
+    fn test_faction_names_and_modifiers() {
+        for faction in Faction::ALL {
+            assert_eq!(Faction::from_str(faction.name()), Some(faction.clone()));
+            let modifiers = faction.default_modifiers();
+            assert_ne!(modifiers, FactionModifiers::default());
+        }
+        assert_eq!(Faction::from_str("CORP"), Some(Faction::CorporateHegemony));
+        assert_eq!(Faction::from_str("pirates"), None);
+
+        // Nyms live up to their reduced surveillance risk
+        assert!(Faction::Nyms.default_modifiers().surveillance_exposure < 1.0);
+
+        let mut modifiers = FactionModifiers::default();
+        for name in FactionModifiers::NAMES {
+            *modifiers.get_mut(name).unwrap() = 2.0;
+        }
+        assert_eq!(modifiers.experience_gain, 2.0);
+        assert!(modifiers.get_mut("luck").is_none());
+
+        assert!(Faction::Nyms.is_allied_with(&Faction::Nyms));
+        assert!(!Faction::Nyms.is_allied_with(&Faction::CipherCollective));
+        assert!(!Faction::Independent.is_allied_with(&Faction::Independent));
+    }
+
+    #[test]
+    fn test_faction_rivals_and_emotes() {
+        for faction in Faction::ALL {
+            // Rivalries go both ways
+            if let Some(rival) = faction.rival() {
+                assert_eq!(rival.rival(), Some(faction.clone()));
+            }
+            assert_eq!(faction.emote().required_faction(), Some(faction.clone()));
+        }
+        assert_eq!(Faction::Independent.rival(), None);
+        assert_eq!(EmoteType::Wave.required_faction(), None);
+        assert_eq!(EmoteType::from_str("meditate"), Some(EmoteType::Meditate));
+    }
+
+    #[test]
+    fn test_region_names_and_loot_preferences() {
+        for region in [
+            WorldRegion::NeonHarbor,
+            WorldRegion::DeepNet,
+            WorldRegion::DataHavens,
+            WorldRegion::DeadZones,
+            WorldRegion::TheGrid,
+        ] {
+            let name = region.get_boundaries().name;
+            assert_eq!(WorldRegion::from_name(name), Some(region));
+            assert!(!region.favored_item_types().is_empty());
+        }
+        assert_eq!(WorldRegion::from_name("Atlantis"), None);
+
+        // Rarer items are less likely to drop
+        assert!(ItemRarity::Common.drop_weight() > ItemRarity::Uncommon.drop_weight());
+        assert!(ItemRarity::Epic.drop_weight() > ItemRarity::Legendary.drop_weight());
+    }
+}
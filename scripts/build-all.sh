@@ -48,22 +48,26 @@ print_status "Running quality checks..."
 
 # Format check
 print_status "Checking code formatting..."
-cd server && cargo fmt --check
+cd protocol && cargo fmt --check
+cd ../server && cargo fmt --check
 cd ../client && cargo fmt --check
 cd ..
 print_success "Code formatting check passed"
 
 # Clippy check
 print_status "Running clippy..."
-cd server && cargo clippy -- -D warnings
+cd protocol && cargo clippy --all-targets -- -D warnings
+cd ../server && cargo clippy -- -D warnings
 cd ../client && cargo clippy -- -D warnings
 cd ..
 print_success "Clippy check passed"
 
 # Tests
 print_status "Running tests..."
-cd server && cargo test
+cd protocol && cargo test
+cd ../server && cargo test
 cd ../client && cargo test
+cd ../e2e && cargo test
 cd ..
 print_success "All tests passed"
 
@@ -129,11 +133,11 @@ for target in "${TARGETS[@]}"; do
     
     # Copy binaries to release directory
     if [[ "$target" == *"windows"* ]]; then
-        cp "server/target/$target/release/nym-mmorpg-server.exe" "release/$platform/"
-        cp "client/target/$target/release/nym-mmorpg-client.exe" "release/$platform/"
+        cp "target/$target/release/nym-mmorpg-server.exe" "release/$platform/"
+        cp "target/$target/release/nym-mmorpg-client.exe" "release/$platform/"
     else
-        cp "server/target/$target/release/nym-mmorpg-server" "release/$platform/"
-        cp "client/target/$target/release/nym-mmorpg-client" "release/$platform/"
+        cp "target/$target/release/nym-mmorpg-server" "release/$platform/"
+        cp "target/$target/release/nym-mmorpg-client" "release/$platform/"
     fi
     
     # Copy documentation
@@ -32,7 +32,15 @@ fi
 
 # Format check
 print_status "Checking code formatting..."
-cd server
+cd protocol
+if cargo fmt --check; then
+    print_success "Protocol formatting is correct"
+else
+    print_error "Protocol formatting issues found"
+    exit 1
+fi
+
+cd ../server
 if cargo fmt --check; then
     print_success "Server formatting is correct"
 else
@@ -51,7 +59,15 @@ cd ..
 
 # Clippy check
 print_status "Running clippy checks..."
-cd server
+cd protocol
+if cargo clippy --all-targets -- -D warnings; then
+    print_success "Protocol clippy check passed"
+else
+    print_error "Protocol clippy check failed"
+    exit 1
+fi
+
+cd ../server
 if cargo clippy -- -D warnings; then
     print_success "Server clippy check passed"
 else
@@ -70,7 +86,15 @@ cd ..
 
 # Build check
 print_status "Building projects..."
-cd server
+cd protocol
+if cargo build; then
+    print_success "Protocol builds successfully"
+else
+    print_error "Protocol build failed"
+    exit 1
+fi
+
+cd ../server
 if cargo build; then
     print_success "Server builds successfully"
 else
@@ -89,7 +113,15 @@ cd ..
 
 # Test check
 print_status "Running tests..."
-cd server
+cd protocol
+if cargo test; then
+    print_success "Protocol tests passed"
+else
+    print_error "Protocol tests failed"
+    exit 1
+fi
+
+cd ../server
 if cargo test; then
     print_success "Server tests passed"
 else
@@ -104,6 +136,14 @@ else
     print_error "Client tests failed"
     exit 1
 fi
+
+cd ../e2e
+if cargo test; then
+    print_success "End-to-end tests passed"
+else
+    print_error "End-to-end tests failed"
+    exit 1
+fi
 cd ..
 
 print_success "✅ All verification checks passed!"
@@ -0,0 +1,167 @@
+//! Abilities players use on top of their plain attack
+//!
+//! Each ability has its own cooldown, range and effect, listed by `spec`.
+//! Encrypt puts a shield on the player, Hack stuns another player and Ghost
+//! puts the player in stealth until it wears off or they attack (see
+//! `status_effects` for what each does), and DataDrop heals the player or a
+//! member of an allied faction. Members of an ability's faction can use it
+//! from level 1, Independents from level 2 and everyone else from level 3 (see
+//! `Ability::unlock_level`). Cooldowns are not persisted.
+
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+use nymquest_protocol::game_protocol::{Ability, AbilityCooldown, StatusEffectKind};
+
+/// Cooldown, reach and strength of an ability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbilitySpec {
+    /// Seconds before the ability can be used again
+    pub cooldown_seconds: u64,
+    /// How far away another player can be to be the target
+    pub range: f32,
+    /// Status effect put on the target, if any
+    pub effect: Option<StatusEffectKind>,
+    /// Seconds the effect lasts
+    pub duration_seconds: u64,
+    /// Health restored to the target
+    pub heal: u32,
+}
+
+/// How an ability plays
+pub fn spec(ability: Ability) -> AbilitySpec {
+    match ability {
+        Ability::Encrypt => AbilitySpec {
+            cooldown_seconds: 30,
+            range: 0.0,
+            effect: Some(StatusEffectKind::Shield),
+            duration_seconds: 8,
+            heal: 0,
+        },
+        Ability::Hack => AbilitySpec {
+            cooldown_seconds: 20,
+            range: 20.0,
+            effect: Some(StatusEffectKind::Stun),
+            duration_seconds: 3,
+            heal: 0,
+        },
+        Ability::Ghost => AbilitySpec {
+            cooldown_seconds: 45,
+            range: 0.0,
+            effect: Some(StatusEffectKind::Stealth),
+            duration_seconds: 10,
+            heal: 0,
+        },
+        Ability::DataDrop => AbilitySpec {
+            cooldown_seconds: 25,
+            range: 15.0,
+            effect: None,
+            duration_seconds: 0,
+            heal: 30,
+        },
+    }
+}
+
+#[derive(Default)]
+struct AbilityState {
+    /// When each ability a player used comes off cooldown
+    ready_at: HashMap<String, BTreeMap<Ability, u64>>,
+}
+
+/// Ability cooldowns
+pub struct Abilities {
+    state: Mutex<AbilityState>,
+}
+
+impl Default for Abilities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Abilities {
+    /// Create a world where nobody has used an ability yet
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(AbilityState::default()),
+        }
+    }
+
+    /// Seconds until `player_id` can use `ability` again
+    pub fn remaining(&self, player_id: &str, ability: Ability, now: u64) -> Result<u64> {
+        Ok(self
+            .lock()?
+            .ready_at
+            .get(player_id)
+            .and_then(|ready_at| ready_at.get(&ability))
+            .map_or(0, |ready_at| ready_at.saturating_sub(now)))
+    }
+
+    /// Cooldown of every ability for `player_id`
+    pub fn cooldowns(&self, player_id: &str, now: u64) -> Result<Vec<AbilityCooldown>> {
+        Ability::ALL
+            .into_iter()
+            .map(|ability| {
+                Ok(AbilityCooldown {
+                    ability,
+                    cooldown_seconds: spec(ability).cooldown_seconds,
+                    remaining_seconds: self.remaining(player_id, ability, now)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Put `ability` on cooldown for `player_id`
+    pub fn start_cooldown(&self, player_id: &str, ability: Ability, now: u64) -> Result<()> {
+        self.lock()?
+            .ready_at
+            .entry(player_id.to_string())
+            .or_default()
+            .insert(ability, now + spec(ability).cooldown_seconds);
+        Ok(())
+    }
+
+    /// Forget the cooldowns that ran out by `now`, and those of players for whom `exists` is false
+    pub fn expire<F>(&self, now: u64, exists: F) -> Result<()>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.lock()?.ready_at.retain(|player_id, ready_at| {
+            ready_at.retain(|_, ready_at| *ready_at > now);
+            !ready_at.is_empty() && exists(player_id)
+        });
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, AbilityState>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("Abilities lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldowns_run_out() {
+        let abilities = Abilities::new();
+        abilities.start_cooldown("a", Ability::Hack, 100).unwrap();
+        abilities.start_cooldown("b", Ability::Ghost, 100).unwrap();
+        assert_eq!(abilities.remaining("a", Ability::Hack, 105).unwrap(), 15);
+        assert_eq!(abilities.remaining("a", Ability::Ghost, 105).unwrap(), 0);
+
+        let cooldowns = abilities.cooldowns("a", 110).unwrap();
+        assert_eq!(cooldowns.len(), Ability::ALL.len());
+        assert_eq!(cooldowns[1].remaining_seconds, 10);
+
+        // Cooldowns of players who left are forgotten
+        abilities.expire(110, |player_id| player_id != "b").unwrap();
+        assert_eq!(abilities.remaining("b", Ability::Ghost, 110).unwrap(), 0);
+        abilities.expire(120, |_| true).unwrap();
+        assert_eq!(abilities.remaining("a", Ability::Hack, 110).unwrap(), 0);
+        assert!(spec(Ability::DataDrop).effect.is_none());
+    }
+}
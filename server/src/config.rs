@@ -1,9 +1,21 @@
 use anyhow::{anyhow, Result};
+use nymquest_protocol::game_protocol::{Position, QuestDefinition, TerrainMap, WorldBoundaries};
+use nymquest_protocol::world_lore::{Faction, FactionModifiers, ItemStats, WorldRegion};
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::quests;
+use crate::reputation::MAX_REPUTATION;
+
+/// Largest fraction by which detection resistance can shrink the distance a player is seen from
+const MAX_DETECTION_RESISTANCE: f32 = 0.5;
+
 // Static flag to track whether detailed configuration has been logged already
 static CONFIG_LOGGED: AtomicBool = AtomicBool::new(false);
 
@@ -14,6 +26,11 @@ static CONFIG_LOGGED: AtomicBool = AtomicBool::new(false);
 /// - NYMQUEST_WORLD_MIN_X: Minimum X coordinate boundary (default: -100.0)  
 /// - NYMQUEST_WORLD_MAX_Y: Maximum Y coordinate boundary (default: 100.0)
 /// - NYMQUEST_WORLD_MIN_Y: Minimum Y coordinate boundary (default: -100.0)
+/// - NYMQUEST_WORLD_REGION: Lore region of a single-region world, e.g. "Deep Net" (default: Neon Harbor)
+/// - NYMQUEST_WORLD_REGIONS: Comma-separated regions the world is made of, west to east, e.g. "Neon Harbor,Deep Net"; each takes its lore boundaries, replacing the world boundaries above (default: empty, a single region)
+/// - NYMQUEST_TERRAIN_DIR: Directory holding a terrain map per region, named after the region in lower case with underscores, e.g. `neon_harbor.txt`; regions without one are open terrain (default: "./maps")
+/// - NYMQUEST_TERRAIN_SLOW_FACTOR: Movement speed multiplier for players starting a step on slow terrain (default: 0.5)
+/// - NYMQUEST_QUEST_FILE: JSON file listing the quests players are offered; without one there are no quests (default: "./quests.json")
 /// - NYMQUEST_MOVEMENT_SPEED: Player movement speed multiplier (default: 14.0)
 /// - NYMQUEST_PLAYER_COLLISION_RADIUS: Minimum distance between players (default: 7.0)
 /// - NYMQUEST_MAX_PLAYER_NAME_LENGTH: Maximum player name length (default: 50)
@@ -35,6 +52,32 @@ static CONFIG_LOGGED: AtomicBool = AtomicBool::new(false);
 /// - NYMQUEST_MESSAGE_PROCESSING_INTERVAL_MS: Minimum interval between processing messages in milliseconds (default: 100)
 /// - NYMQUEST_ENABLE_MESSAGE_PROCESSING_PACING: Enable message processing pacing for enhanced privacy (default: false)
 /// - NYMQUEST_STATE_BROADCAST_INTERVAL_SECONDS: Interval for broadcasting game state (default: 5)
+/// - NYMQUEST_SIMULATION_TICK_RATE_HZ: Simulation ticks per second (default: 10)
+/// - NYMQUEST_HEALTH_REGEN_PER_SECOND: Health regained per second by damaged players (default: 1)
+/// - NYMQUEST_HEALTH_REGEN_DELAY_SECONDS: Seconds after attacking or being hit before health regenerates (default: 5)
+/// - NYMQUEST_RESPAWN_SECONDS: How long a defeated player stays down before respawning (default: 5, 0 respawns at once)
+/// - NYMQUEST_DEATH_EXPERIENCE_PENALTY: Share of the experience gained since the last level up lost on defeat (default: 0.0, disabled)
+/// - NYMQUEST_DEATH_DROPS_ITEM: Defeated players drop a random carried item (default: false)
+/// - NYMQUEST_ITEM_EFFECT_SECONDS: How long the effect of a used item lasts (default: 120)
+/// - NYMQUEST_MAX_INVENTORY_ITEMS: Maximum number of items a player can carry (default: 20)
+/// - NYMQUEST_PICKUP_RADIUS: Distance within which players can pick up items on the ground (default: 10.0)
+/// - NYMQUEST_LOOT_SPAWN_INTERVAL_SECONDS: Interval between items appearing in the world (default: 60, 0 disables)
+/// - NYMQUEST_MAX_LOOT_DROPS: Maximum number of items on the ground at once (default: 50)
+/// - NYMQUEST_FACTION_MODIFIERS: Overrides of faction modifiers as comma-separated `faction.modifier=value` entries, e.g. `nyms.damage_dealt=1.0,monks.experience_gain=1.5` (default: each faction's built-in table)
+/// - NYMQUEST_FRIENDLY_FIRE: Allow players of the same faction to attack each other (default: false)
+/// - NYMQUEST_REPUTATION_EMOTE_THRESHOLD: Reputation with a faction needed to perform its emote (default: 25)
+/// - NYMQUEST_REPUTATION_ITEM_THRESHOLD: Reputation with a faction at which it hands out its signature item (default: 100)
+/// - NYMQUEST_CONTROL_POINT_RADIUS: Distance within which players occupy a control point (default: 20.0, 0 disables territory)
+/// - NYMQUEST_TERRITORY_CAPTURE_SECONDS: Seconds a faction has to hold a control point uncontested to capture it (default: 30)
+/// - NYMQUEST_TERRITORY_XP_BONUS: Experience multiplier for players inside a control point their faction owns (default: 1.25)
+/// - NYMQUEST_TERRITORY_SURVEILLANCE_FACTOR: Surveillance exposure multiplier for players inside a control point their faction owns (default: 0.5)
+/// - NYMQUEST_SURVEILLANCE_CHECK_SECONDS: Interval between surveillance checks that may expose players (default: 10, 0 disables)
+/// - NYMQUEST_SURVEILLANCE_EXPOSURE_CHANCE: Chance per check that an unshielded player at a surveillance risk of 1.0 is exposed (default: 0.5)
+/// - NYMQUEST_SURVEILLANCE_EXPOSURE_SECONDS: How long an exposed player stays exposed (default: 30)
+/// - NYMQUEST_ENABLE_NPCS: Populate the regions with server-controlled characters (default: true)
+/// - NYMQUEST_NPC_RESPAWN_SECONDS: How long a defeated character takes to come back (default: 60)
+/// - NYMQUEST_VIEW_RADIUS: Distance within which players see each other (default: 60.0)
+/// - NYMQUEST_SURVEILLANCE_VIEW_BONUS: Extra view radius fraction for players in fully surveilled spots (default: 0.0, disabled)
 /// - NYMQUEST_INACTIVE_PLAYER_CLEANUP_INTERVAL_SECONDS: Interval for cleaning up inactive players (default: 45)
 /// - NYMQUEST_REPLAY_PROTECTION_WINDOW_SIZE: Number of sequence numbers to track for replay prevention (default: 64)
 /// - NYMQUEST_REPLAY_PROTECTION_ADAPTIVE: Enable adaptive replay protection window sizing (default: true)
@@ -93,10 +136,63 @@ pub struct GameConfig {
     pub message_processing_interval_ms: u64,
     /// Enable message processing pacing for enhanced privacy
     pub enable_message_processing_pacing: bool,
-    /// Interval for broadcasting game state in seconds
+    /// Interval between full game state snapshots (keyframes) in seconds
     pub state_broadcast_interval_seconds: u64,
     /// Interval for cleaning up inactive players in seconds
     pub inactive_player_cleanup_interval_seconds: u64,
+    /// Simulation ticks per second; player intents queued between ticks are applied together
+    pub simulation_tick_rate_hz: u32,
+    /// Health regained per second by players below their maximum health
+    pub health_regen_per_second: u32,
+    /// Seconds after a player last attacked or lost health before they regenerate
+    pub health_regen_delay_seconds: u64,
+    /// Seconds a defeated player stays down before respawning; 0 respawns them at once
+    pub respawn_seconds: u64,
+    /// Share of the experience earned toward their next level a defeated player loses
+    pub death_experience_penalty: f32,
+    /// Whether a defeated player drops a random item they carry where they fell
+    pub death_drops_item: bool,
+    /// Seconds the stats of a used item stay active
+    pub item_effect_seconds: u64,
+    /// Maximum number of items a player can carry, counting every copy
+    pub max_inventory_items: u32,
+    /// Players can pick up items on the ground within this distance
+    pub pickup_radius: f32,
+    /// Seconds between items appearing at random spots in the world; 0 disables spawning
+    pub loot_spawn_interval_seconds: u64,
+    /// Maximum number of items on the ground; the oldest disappears when another drops
+    pub max_loot_drops: usize,
+    /// Multipliers each faction applies to combat, movement, surveillance and experience
+    pub faction_modifiers: HashMap<Faction, FactionModifiers>,
+    /// Whether players of the same faction can attack each other; Independents always can
+    pub friendly_fire: bool,
+    /// Reputation with a faction needed to perform its emote
+    pub reputation_emote_threshold: i32,
+    /// Reputation with a faction at which it hands the player its signature item, once
+    pub reputation_item_threshold: i32,
+    /// Players within this distance of a control point occupy it; 0.0 disables territory control
+    pub control_point_radius: f32,
+    /// Seconds a faction has to occupy a control point on its own to capture it
+    pub territory_capture_seconds: u32,
+    /// Experience multiplier for players inside a control point their faction owns
+    pub territory_xp_bonus: f32,
+    /// Surveillance exposure multiplier for players inside a control point their faction owns
+    pub territory_surveillance_factor: f32,
+    /// Seconds between surveillance checks that may expose players; 0 disables exposure
+    pub surveillance_check_seconds: u64,
+    /// Chance per check that a player at a surveillance risk of 1.0, without privacy, is exposed
+    pub surveillance_exposure_chance: f32,
+    /// Seconds an exposed player stays exposed to the Corporate Hegemony
+    pub surveillance_exposure_seconds: u64,
+    /// Whether the regions are populated with server-controlled characters
+    pub enable_npcs: bool,
+    /// Seconds a defeated character takes to come back
+    pub npc_respawn_seconds: u64,
+    /// Players only receive state for others within this distance
+    pub view_radius: f32,
+    /// Extra view radius, as a fraction of `view_radius`, for a player standing where
+    /// the region's surveillance risk is 1.0; 0.0 disables surveillance modulation
+    pub surveillance_view_bonus: f32,
     /// Replay protection window size (number of sequence numbers to track for replay prevention)
     pub replay_protection_window_size: u8,
     /// Enable adaptive replay protection window sizing
@@ -114,6 +210,19 @@ pub struct GameConfig {
     pub message_expiration_seconds: Option<u64>,
     /// World region (cypherpunk setting)
     pub world_region: Option<String>,
+    /// Lore regions the world is made of, west to east; empty for a single
+    /// `world_region` spanning the world boundaries
+    pub world_regions: Vec<String>,
+    /// Directory the terrain maps of the regions are read from
+    pub terrain_dir: String,
+    /// Terrain map of each region that has one, by region name
+    pub terrain_maps: HashMap<String, TerrainMap>,
+    /// Movement speed multiplier for steps that start on slow terrain
+    pub terrain_slow_factor: f32,
+    /// File the quest definitions are read from
+    pub quest_file: String,
+    /// Quests players are offered, in the order they are listed
+    pub quests: Vec<QuestDefinition>,
 }
 
 impl Default for GameConfig {
@@ -145,6 +254,35 @@ impl Default for GameConfig {
             enable_message_processing_pacing: false,
             state_broadcast_interval_seconds: 5,
             inactive_player_cleanup_interval_seconds: 45,
+            simulation_tick_rate_hz: 10,
+            health_regen_per_second: 1,
+            health_regen_delay_seconds: 5,
+            respawn_seconds: 5,
+            death_experience_penalty: 0.0,
+            death_drops_item: false,
+            item_effect_seconds: 120,
+            max_inventory_items: 20,
+            pickup_radius: 10.0,
+            loot_spawn_interval_seconds: 60,
+            max_loot_drops: 50,
+            faction_modifiers: Faction::ALL
+                .iter()
+                .map(|faction| (faction.clone(), faction.default_modifiers()))
+                .collect(),
+            friendly_fire: false,
+            reputation_emote_threshold: 25,
+            reputation_item_threshold: 100,
+            control_point_radius: 20.0,
+            territory_capture_seconds: 30,
+            territory_xp_bonus: 1.25,
+            territory_surveillance_factor: 0.5,
+            surveillance_check_seconds: 10,
+            surveillance_exposure_chance: 0.5,
+            surveillance_exposure_seconds: 30,
+            enable_npcs: true,
+            npc_respawn_seconds: 60,
+            view_radius: 60.0,
+            surveillance_view_bonus: 0.0,
             replay_protection_window_size: 64, // Default window size for replay protection
             replay_protection_adaptive: true,
             replay_protection_min_window: 32,
@@ -153,6 +291,12 @@ impl Default for GameConfig {
             message_processing_jitter_percent: 25, // Default 25% jitter for privacy protection
             message_expiration_seconds: Some(300), // 5 minutes by default
             world_region: None,
+            world_regions: Vec::new(),
+            terrain_dir: "./maps".to_string(),
+            terrain_maps: HashMap::new(),
+            terrain_slow_factor: 0.5,
+            quest_file: "./quests.json".to_string(),
+            quests: Vec::new(),
         }
     }
 }
@@ -169,6 +313,21 @@ impl GameConfig {
         config.world_min_y = Self::load_env_f32("NYMQUEST_WORLD_MIN_Y", config.world_min_y)?;
         config.world_region =
             Self::load_env_string_opt("NYMQUEST_WORLD_REGION", config.world_region.clone())?;
+        if let Ok(regions) = env::var("NYMQUEST_WORLD_REGIONS") {
+            config.world_regions = regions
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        config.terrain_dir =
+            env::var("NYMQUEST_TERRAIN_DIR").unwrap_or_else(|_| config.terrain_dir.clone());
+        config.terrain_slow_factor =
+            Self::load_env_f32("NYMQUEST_TERRAIN_SLOW_FACTOR", config.terrain_slow_factor)?;
+        config.quest_file =
+            env::var("NYMQUEST_QUEST_FILE").unwrap_or_else(|_| config.quest_file.clone());
 
         config.movement_speed =
             Self::load_env_f32("NYMQUEST_MOVEMENT_SPEED", config.movement_speed)?;
@@ -231,6 +390,81 @@ impl GameConfig {
             "NYMQUEST_STATE_BROADCAST_INTERVAL_SECONDS",
             config.state_broadcast_interval_seconds,
         )?;
+        config.simulation_tick_rate_hz = Self::load_env_u32(
+            "NYMQUEST_SIMULATION_TICK_RATE_HZ",
+            config.simulation_tick_rate_hz,
+        )?;
+        config.health_regen_per_second = Self::load_env_u32(
+            "NYMQUEST_HEALTH_REGEN_PER_SECOND",
+            config.health_regen_per_second,
+        )?;
+        config.health_regen_delay_seconds = Self::load_env_u64(
+            "NYMQUEST_HEALTH_REGEN_DELAY_SECONDS",
+            config.health_regen_delay_seconds,
+        )?;
+        config.respawn_seconds =
+            Self::load_env_u64("NYMQUEST_RESPAWN_SECONDS", config.respawn_seconds)?;
+        config.death_experience_penalty = Self::load_env_f32(
+            "NYMQUEST_DEATH_EXPERIENCE_PENALTY",
+            config.death_experience_penalty,
+        )?;
+        config.death_drops_item =
+            Self::load_env_bool("NYMQUEST_DEATH_DROPS_ITEM", config.death_drops_item)?;
+        config.item_effect_seconds =
+            Self::load_env_u64("NYMQUEST_ITEM_EFFECT_SECONDS", config.item_effect_seconds)?;
+        config.max_inventory_items =
+            Self::load_env_u32("NYMQUEST_MAX_INVENTORY_ITEMS", config.max_inventory_items)?;
+        config.pickup_radius = Self::load_env_f32("NYMQUEST_PICKUP_RADIUS", config.pickup_radius)?;
+        config.loot_spawn_interval_seconds = Self::load_env_u64(
+            "NYMQUEST_LOOT_SPAWN_INTERVAL_SECONDS",
+            config.loot_spawn_interval_seconds,
+        )?;
+        config.max_loot_drops =
+            Self::load_env_usize("NYMQUEST_MAX_LOOT_DROPS", config.max_loot_drops)?;
+        if let Ok(overrides) = env::var("NYMQUEST_FACTION_MODIFIERS") {
+            config.apply_faction_modifier_overrides(&overrides)?;
+        }
+        config.friendly_fire = Self::load_env_bool("NYMQUEST_FRIENDLY_FIRE", config.friendly_fire)?;
+        config.reputation_emote_threshold = Self::load_env_i32(
+            "NYMQUEST_REPUTATION_EMOTE_THRESHOLD",
+            config.reputation_emote_threshold,
+        )?;
+        config.reputation_item_threshold = Self::load_env_i32(
+            "NYMQUEST_REPUTATION_ITEM_THRESHOLD",
+            config.reputation_item_threshold,
+        )?;
+        config.control_point_radius =
+            Self::load_env_f32("NYMQUEST_CONTROL_POINT_RADIUS", config.control_point_radius)?;
+        config.territory_capture_seconds = Self::load_env_u32(
+            "NYMQUEST_TERRITORY_CAPTURE_SECONDS",
+            config.territory_capture_seconds,
+        )?;
+        config.territory_xp_bonus =
+            Self::load_env_f32("NYMQUEST_TERRITORY_XP_BONUS", config.territory_xp_bonus)?;
+        config.territory_surveillance_factor = Self::load_env_f32(
+            "NYMQUEST_TERRITORY_SURVEILLANCE_FACTOR",
+            config.territory_surveillance_factor,
+        )?;
+        config.surveillance_check_seconds = Self::load_env_u64(
+            "NYMQUEST_SURVEILLANCE_CHECK_SECONDS",
+            config.surveillance_check_seconds,
+        )?;
+        config.surveillance_exposure_chance = Self::load_env_f32(
+            "NYMQUEST_SURVEILLANCE_EXPOSURE_CHANCE",
+            config.surveillance_exposure_chance,
+        )?;
+        config.surveillance_exposure_seconds = Self::load_env_u64(
+            "NYMQUEST_SURVEILLANCE_EXPOSURE_SECONDS",
+            config.surveillance_exposure_seconds,
+        )?;
+        config.enable_npcs = Self::load_env_bool("NYMQUEST_ENABLE_NPCS", config.enable_npcs)?;
+        config.npc_respawn_seconds =
+            Self::load_env_u64("NYMQUEST_NPC_RESPAWN_SECONDS", config.npc_respawn_seconds)?;
+        config.view_radius = Self::load_env_f32("NYMQUEST_VIEW_RADIUS", config.view_radius)?;
+        config.surveillance_view_bonus = Self::load_env_f32(
+            "NYMQUEST_SURVEILLANCE_VIEW_BONUS",
+            config.surveillance_view_bonus,
+        )?;
         config.inactive_player_cleanup_interval_seconds = Self::load_env_u64(
             "NYMQUEST_INACTIVE_PLAYER_CLEANUP_INTERVAL_SECONDS",
             config.inactive_player_cleanup_interval_seconds,
@@ -293,6 +527,8 @@ impl GameConfig {
 
         // Validate configuration
         config.validate()?;
+        config.terrain_maps = config.load_terrain_maps()?;
+        config.quests = quests::load(&config.quest_file)?;
 
         // Check if we've already logged the configuration details
         let config_already_logged = CONFIG_LOGGED.load(Ordering::SeqCst);
@@ -309,6 +545,68 @@ impl GameConfig {
                 config.world_min_x, config.world_min_y, config.world_max_x, config.world_max_y
             );
             info!("Movement speed: {}", config.movement_speed);
+            info!(
+                "Simulation: {} ticks/sec, health regen {}/sec after {}s out of combat",
+                config.simulation_tick_rate_hz,
+                config.health_regen_per_second,
+                config.health_regen_delay_seconds
+            );
+            info!(
+                "Defeat: respawn after {}s, {} of level experience lost, item drop {}",
+                config.respawn_seconds,
+                config.death_experience_penalty,
+                if config.death_drops_item {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+            info!(
+                "Items: {}s effect, {} carried at most",
+                config.item_effect_seconds, config.max_inventory_items
+            );
+            info!(
+                "Loot: pickup radius {}, spawn every {}s, {} on the ground at most",
+                config.pickup_radius, config.loot_spawn_interval_seconds, config.max_loot_drops
+            );
+            info!("Friendly fire: {}", config.friendly_fire);
+            info!(
+                "Reputation: emotes at {}, signature items at {}",
+                config.reputation_emote_threshold, config.reputation_item_threshold
+            );
+            info!(
+                "Territory: control point radius {}, captured after {}s, owners get {}x XP and {}x surveillance exposure",
+                config.control_point_radius,
+                config.territory_capture_seconds,
+                config.territory_xp_bonus,
+                config.territory_surveillance_factor
+            );
+            info!(
+                "Surveillance: checks every {}s, {} exposure chance at full risk, exposed for {}s",
+                config.surveillance_check_seconds,
+                config.surveillance_exposure_chance,
+                config.surveillance_exposure_seconds
+            );
+            info!(
+                "NPCs: {}, respawning after {}s",
+                if config.enable_npcs {
+                    "enabled"
+                } else {
+                    "disabled"
+                },
+                config.npc_respawn_seconds
+            );
+            for faction in Faction::ALL {
+                info!(
+                    "Faction {}: {:?}",
+                    faction.name(),
+                    config.faction_modifiers(&faction)
+                );
+            }
+            info!(
+                "View radius: {} (surveillance bonus: {})",
+                config.view_radius, config.surveillance_view_bonus
+            );
             info!(
                 "Heartbeat: {}s interval, {}s timeout",
                 config.heartbeat_interval_seconds, config.heartbeat_timeout_seconds
@@ -321,7 +619,24 @@ impl GameConfig {
                 "Replay protection window size: {}",
                 config.replay_protection_window_size
             );
-            info!("World region: {:?}", config.world_region);
+            if config.world_regions.is_empty() {
+                info!("World region: {:?}", config.world_region);
+            } else {
+                info!("World regions: {}", config.world_regions.join(", "));
+            }
+            let mut mapped: Vec<&str> = config.terrain_maps.keys().map(String::as_str).collect();
+            mapped.sort_unstable();
+            info!(
+                "Terrain maps from {}: {} (slow terrain factor {})",
+                config.terrain_dir,
+                if mapped.is_empty() {
+                    "none".to_string()
+                } else {
+                    mapped.join(", ")
+                },
+                config.terrain_slow_factor
+            );
+            info!("Quests from {}: {}", config.quest_file, config.quests.len());
 
             // Mark as logged to avoid redundancy
             CONFIG_LOGGED.store(true, Ordering::SeqCst);
@@ -352,6 +667,26 @@ impl GameConfig {
             ));
         }
 
+        // Every region of a multi-region world must be a lore region, and appear once
+        for (index, name) in self.world_regions.iter().enumerate() {
+            if WorldRegion::from_name(name).is_none() {
+                return Err(anyhow!(
+                    "Unknown world region '{}' (expected one of: Neon Harbor, Deep Net, Data Havens, Dead Zones, The Grid)",
+                    name
+                ));
+            }
+            if self.world_regions[..index].contains(name) {
+                return Err(anyhow!("World region '{}' is listed twice", name));
+            }
+        }
+
+        if self.terrain_slow_factor <= 0.0 || self.terrain_slow_factor > 1.0 {
+            return Err(anyhow!(
+                "Terrain slow factor must be greater than 0.0 and at most 1.0, got: {}",
+                self.terrain_slow_factor
+            ));
+        }
+
         // Validate movement speed
         if self.movement_speed <= 0.0 {
             return Err(anyhow!(
@@ -367,6 +702,139 @@ impl GameConfig {
             );
         }
 
+        // Validate simulation rate
+        if self.simulation_tick_rate_hz == 0 || self.simulation_tick_rate_hz > 100 {
+            return Err(anyhow!(
+                "Invalid simulation tick rate: {} (must be 1-100 Hz)",
+                self.simulation_tick_rate_hz
+            ));
+        }
+
+        // Validate the death penalty
+        if !(0.0..=1.0).contains(&self.death_experience_penalty) {
+            return Err(anyhow!(
+                "Death experience penalty must be between 0.0 and 1.0, got: {}",
+                self.death_experience_penalty
+            ));
+        }
+
+        // Validate items
+        if self.item_effect_seconds == 0 {
+            return Err(anyhow!("Item effect duration must be positive"));
+        }
+
+        if self.max_inventory_items == 0 {
+            return Err(anyhow!("Maximum inventory size must be positive"));
+        }
+
+        // Validate loot
+        if self.pickup_radius <= 0.0 {
+            return Err(anyhow!(
+                "Pickup radius must be positive, got: {}",
+                self.pickup_radius
+            ));
+        }
+
+        if self.max_loot_drops == 0 {
+            return Err(anyhow!("Maximum number of loot drops must be positive"));
+        }
+
+        // Thresholds have to be reachable, and nobody starts out past them
+        for (name, threshold) in [
+            ("emote", self.reputation_emote_threshold),
+            ("item", self.reputation_item_threshold),
+        ] {
+            if threshold <= 0 || threshold > MAX_REPUTATION {
+                return Err(anyhow!(
+                    "Reputation {} threshold must be between 1 and {}, got: {}",
+                    name,
+                    MAX_REPUTATION,
+                    threshold
+                ));
+            }
+        }
+
+        // Validate faction modifiers; surveillance exposure may be 0 to ignore surveillance entirely
+        for (faction, modifiers) in &self.faction_modifiers {
+            let multipliers = [
+                modifiers.damage_dealt,
+                modifiers.damage_taken,
+                modifiers.movement_speed,
+                modifiers.experience_gain,
+            ];
+            if multipliers
+                .iter()
+                .any(|value| !(*value > 0.0 && value.is_finite()))
+                || !(modifiers.surveillance_exposure >= 0.0
+                    && modifiers.surveillance_exposure.is_finite())
+            {
+                return Err(anyhow!(
+                    "Invalid modifiers for faction {}: {:?} (must be positive)",
+                    faction.name(),
+                    modifiers
+                ));
+            }
+        }
+
+        // Validate territory control
+        if !(self.control_point_radius >= 0.0 && self.control_point_radius.is_finite()) {
+            return Err(anyhow!(
+                "Control point radius must not be negative, got: {}",
+                self.control_point_radius
+            ));
+        }
+
+        if self.territory_capture_seconds == 0 {
+            return Err(anyhow!("Territory capture time must be positive"));
+        }
+
+        if !(self.territory_xp_bonus > 0.0 && self.territory_xp_bonus.is_finite()) {
+            return Err(anyhow!(
+                "Territory XP bonus must be positive, got: {}",
+                self.territory_xp_bonus
+            ));
+        }
+
+        // Owning a point must not expose players further than the widest view distance allows
+        if !(0.0..=1.0).contains(&self.territory_surveillance_factor) {
+            return Err(anyhow!(
+                "Territory surveillance factor must be between 0.0 and 1.0, got: {}",
+                self.territory_surveillance_factor
+            ));
+        }
+
+        // Validate surveillance
+        if !(0.0..=1.0).contains(&self.surveillance_exposure_chance) {
+            return Err(anyhow!(
+                "Surveillance exposure chance must be between 0.0 and 1.0, got: {}",
+                self.surveillance_exposure_chance
+            ));
+        }
+
+        if self.surveillance_exposure_seconds == 0 {
+            return Err(anyhow!("Surveillance exposure time must be positive"));
+        }
+
+        // Validate NPCs
+        if self.npc_respawn_seconds == 0 {
+            return Err(anyhow!("NPC respawn time must be positive"));
+        }
+
+        // Validate interest management
+        if self.view_radius <= 0.0 {
+            return Err(anyhow!(
+                "View radius must be positive, got: {}",
+                self.view_radius
+            ));
+        }
+
+        if self.surveillance_view_bonus < 0.0 {
+            return Err(anyhow!(
+                "Surveillance view bonus must not be negative, got: {}",
+                self.surveillance_view_bonus
+            ));
+        }
+
         // Validate string lengths
         if self.max_player_name_length == 0 || self.max_player_name_length > 1000 {
             return Err(anyhow!(
@@ -395,6 +863,11 @@ impl GameConfig {
             ));
         }
 
+        // Keyframes are the only way clients recover from missed deltas
+        if self.state_broadcast_interval_seconds == 0 {
+            return Err(anyhow!("State broadcast interval must be positive"));
+        }
+
         // Validate player limits
         if self.max_players == 0 || self.max_players > 10000 {
             return Err(anyhow!(
@@ -476,6 +949,180 @@ impl GameConfig {
             && y <= self.world_max_y
     }
 
+    /// Build the world boundaries sent to clients from the configured limits and region
+    pub fn world_boundaries(&self) -> WorldBoundaries {
+        WorldBoundaries::for_region(
+            self.world_region.as_deref(),
+            self.world_min_x,
+            self.world_max_x,
+            self.world_min_y,
+            self.world_max_y,
+        )
+    }
+
+    /// Lore region of the world, which decides what loot is found in it
+    pub fn lore_region(&self) -> WorldRegion {
+        self.world_region
+            .as_deref()
+            .and_then(WorldRegion::from_name)
+            .unwrap_or(WorldRegion::NeonHarbor)
+    }
+
+    /// Read the terrain map of every region of the world from `terrain_dir`
+    ///
+    /// A region's map is the file named after it in lower case with underscores,
+    /// e.g. `neon_harbor.txt`; regions without one are open terrain.
+    pub fn load_terrain_maps(&self) -> Result<HashMap<String, TerrainMap>> {
+        let regions: Vec<WorldRegion> = if self.world_regions.is_empty() {
+            vec![self.lore_region()]
+        } else {
+            self.world_regions
+                .iter()
+                .filter_map(|name| WorldRegion::from_name(name))
+                .collect()
+        };
+
+        let mut maps = HashMap::new();
+        for region in regions {
+            let name = region.get_boundaries().name;
+            let path = Path::new(&self.terrain_dir)
+                .join(format!("{}.txt", name.to_lowercase().replace(' ', "_")));
+            match fs::read_to_string(&path) {
+                Ok(text) => {
+                    maps.insert(name.to_string(), TerrainMap::parse(name, &text)?);
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    debug!("No terrain map at {}, {} is open", path.display(), name);
+                }
+                Err(e) => {
+                    return Err(anyhow!(
+                        "Failed to read terrain map {}: {}",
+                        path.display(),
+                        e
+                    ))
+                }
+            }
+        }
+        Ok(maps)
+    }
+
+    /// Multipliers that `faction` applies to the game rules for its players
+    pub fn faction_modifiers(&self, faction: &Faction) -> FactionModifiers {
+        self.faction_modifiers
+            .get(faction)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Apply `faction.modifier=value` overrides, separated by commas, to the faction modifier table
+    pub fn apply_faction_modifier_overrides(&mut self, overrides: &str) -> Result<()> {
+        for entry in overrides
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        {
+            let parsed = entry.split_once('=').and_then(|(key, value)| {
+                let (faction, modifier) = key.trim().split_once('.')?;
+                Some((
+                    Faction::from_str(faction.trim())?,
+                    modifier.trim(),
+                    value.trim().parse::<f32>().ok()?,
+                ))
+            });
+            let Some((faction, modifier, value)) = parsed else {
+                return Err(anyhow!(
+                    "Invalid faction modifier override '{}' (expected faction.modifier=value)",
+                    entry
+                ));
+            };
+
+            let modifiers = self
+                .faction_modifiers
+                .entry(faction.clone())
+                .or_insert_with(|| faction.default_modifiers());
+            match modifiers.get_mut(modifier) {
+                Some(slot) => *slot = value,
+                None => {
+                    return Err(anyhow!(
+                        "Unknown faction modifier '{}' (expected one of: {})",
+                        modifier,
+                        FactionModifiers::NAMES.join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Largest distance at which one player can see another
+    pub fn max_view_distance(&self) -> f32 {
+        let exposure = self
+            .faction_modifiers
+            .values()
+            .map(|modifiers| modifiers.surveillance_exposure)
+            .fold(1.0, f32::max);
+        self.view_radius * (1.0 + self.surveillance_view_bonus * exposure)
+    }
+
+    /// Distance at which a player standing at `position` can be seen
+    /// Heavily surveilled spots expose players from further away when the bonus is enabled
+    pub fn view_distance_at(&self, boundaries: &WorldBoundaries, position: &Position) -> f32 {
+        self.concealed_view_distance(
+            boundaries,
+            position,
+            &ItemStats::default(),
+            &FactionModifiers::default(),
+        )
+    }
+
+    /// Surveillance that reaches a player standing at `position` with active item `stats` and faction `modifiers`
+    ///
+    /// This is the surveillance risk at their spot, from 0.0 to 1.0, of which privacy
+    /// (0-100) shields the player and which the surveillance exposure of the modifiers scales.
+    pub fn surveillance_at(
+        &self,
+        boundaries: &WorldBoundaries,
+        position: &Position,
+        stats: &ItemStats,
+        modifiers: &FactionModifiers,
+    ) -> f32 {
+        let shielding = 1.0 - (stats.privacy / 100.0).clamp(0.0, 1.0);
+        let risk = boundaries
+            .calculate_surveillance_risk(position.x, position.y)
+            .clamp(0.0, 1.0);
+        risk * shielding * modifiers.surveillance_exposure
+    }
+
+    /// Chance that a surveillance check exposes a player, given the `surveillance` that reaches them
+    pub fn exposure_chance(&self, surveillance: f32) -> f32 {
+        (self.surveillance_exposure_chance * surveillance).clamp(0.0, 1.0)
+    }
+
+    /// Distance at which a player standing at `position` with active item `stats` and faction `modifiers` can be seen
+    ///
+    /// The surveillance that reaches the player extends the distance, and detection
+    /// resistance (0-100) shrinks it, by at most half.
+    pub fn concealed_view_distance(
+        &self,
+        boundaries: &WorldBoundaries,
+        position: &Position,
+        stats: &ItemStats,
+        modifiers: &FactionModifiers,
+    ) -> f32 {
+        let resistance = (stats.detection_resistance / 100.0).clamp(0.0, MAX_DETECTION_RESISTANCE);
+        let base = self.view_radius * (1.0 - resistance);
+        if self.surveillance_view_bonus <= 0.0 {
+            return base;
+        }
+        let surveillance = self.surveillance_at(boundaries, position, stats, modifiers);
+        base * (1.0 + self.surveillance_view_bonus * surveillance)
+    }
+
+    /// Time between simulation ticks
+    pub fn simulation_tick_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.simulation_tick_rate_hz
+    }
+
     #[allow(dead_code)]
     /// Get heartbeat interval as Duration
     pub fn heartbeat_interval(&self) -> Duration {
@@ -528,6 +1175,15 @@ impl GameConfig {
         }
     }
 
+    fn load_env_i32(var_name: &str, default: i32) -> Result<i32> {
+        match env::var(var_name) {
+            Ok(val) => val
+                .parse::<i32>()
+                .map_err(|e| anyhow!("Invalid i32 value for {}: {} ({})", var_name, val, e)),
+            Err(_) => Ok(default),
+        }
+    }
+
     fn load_env_u32(var_name: &str, default: u32) -> Result<u32> {
         match env::var(var_name) {
             Ok(val) => val
@@ -590,6 +1246,9 @@ impl GameConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::regions::Regions;
+    use crate::territory::Territory;
+    use nymquest_protocol::game_protocol::Tile;
 
     #[test]
     fn test_default_config_validation() {
@@ -597,6 +1256,15 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_death_penalty_validation() {
+        let config = GameConfig {
+            death_experience_penalty: 1.5,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_invalid_world_boundaries() {
         let config = GameConfig {
@@ -607,6 +1275,68 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_world_regions_validation() {
+        let mut config = GameConfig {
+            world_regions: vec!["Neon Harbor".to_string(), "Deep Net".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.world_regions.push("Neon Harbor".to_string());
+        assert!(config.validate().is_err());
+
+        config.world_regions = vec!["Atlantis".to_string()];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_shipped_terrain_maps_load() {
+        let mut config = GameConfig {
+            world_regions: [
+                "Neon Harbor",
+                "Deep Net",
+                "Data Havens",
+                "Dead Zones",
+                "The Grid",
+            ]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+            terrain_dir: concat!(env!("CARGO_MANIFEST_DIR"), "/maps").to_string(),
+            ..Default::default()
+        };
+        config.terrain_maps = config.load_terrain_maps().unwrap();
+        assert_eq!(config.terrain_maps.len(), 5);
+        assert_eq!(config.terrain_maps["Deep Net"].region, "Deep Net");
+
+        // Every control point can be reached and fought over
+        let regions = Regions::from_config(&config);
+        for point in Territory::new(&regions, 20.0).control_points().unwrap() {
+            assert!(
+                !matches!(regions.tile_at(&point.position), Tile::Wall | Tile::Safe),
+                "{}",
+                point.name
+            );
+        }
+
+        // Every region has a safe zone
+        for map in config.terrain_maps.values() {
+            assert!(
+                map.rows.iter().any(|row| row.contains('+')),
+                "{}",
+                map.region
+            );
+        }
+
+        // Regions without a map are open
+        let missing = GameConfig {
+            terrain_dir: "./no_such_maps".to_string(),
+            ..Default::default()
+        };
+        assert!(missing.load_terrain_maps().unwrap().is_empty());
+    }
+
     #[test]
     fn test_position_validation() {
         let config = GameConfig::default();
@@ -617,6 +1347,106 @@ mod tests {
         assert!(!config.is_position_valid(0.0, 101.0));
     }
 
+    #[test]
+    fn test_view_distance_grows_with_surveillance() {
+        let independent = FactionModifiers::default();
+        let mut config = GameConfig {
+            world_region: Some("The Grid".to_string()),
+            ..Default::default()
+        };
+        let boundaries = config.world_boundaries();
+        let center = Position::new(0.0, 0.0);
+        assert_eq!(
+            config.view_distance_at(&boundaries, &center),
+            config.view_radius
+        );
+
+        config.surveillance_view_bonus = 0.5;
+        let watched = config.view_distance_at(&boundaries, &center);
+        assert!(watched > config.view_radius);
+        assert!(watched <= config.max_view_distance());
+
+        // Privacy hides players from the surveillance, detection resistance from everyone
+        let private = ItemStats {
+            privacy: 100.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.concealed_view_distance(&boundaries, &center, &private, &independent),
+            config.view_radius
+        );
+        let resistant = ItemStats {
+            detection_resistance: 90.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.concealed_view_distance(&boundaries, &center, &resistant, &independent),
+            watched * 0.5
+        );
+
+        // Nyms draw less attention from the surveillance than everyone else
+        let nym = config.concealed_view_distance(
+            &boundaries,
+            &center,
+            &ItemStats::default(),
+            &config.faction_modifiers(&Faction::Nyms),
+        );
+        assert!(nym > config.view_radius && nym < watched);
+
+        // Holding territory can only hide players, never expose them further
+        config.territory_surveillance_factor = 1.5;
+        assert!(config.validate().is_err());
+        config.territory_surveillance_factor = 0.5;
+
+        config.view_radius = 0.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_faction_modifier_overrides() {
+        let mut config = GameConfig::default();
+        config
+            .apply_faction_modifier_overrides(
+                "nyms.surveillance_exposure=0, cipher.damage_dealt=1.5",
+            )
+            .unwrap();
+        assert_eq!(
+            config
+                .faction_modifiers(&Faction::Nyms)
+                .surveillance_exposure,
+            0.0
+        );
+        assert_eq!(
+            config
+                .faction_modifiers(&Faction::CipherCollective)
+                .damage_dealt,
+            1.5
+        );
+        // Untouched modifiers keep the faction's defaults
+        assert_eq!(
+            config
+                .faction_modifiers(&Faction::CipherCollective)
+                .damage_taken,
+            Faction::CipherCollective.default_modifiers().damage_taken
+        );
+        assert!(config.validate().is_ok());
+
+        assert!(config
+            .apply_faction_modifier_overrides("pirates.damage_dealt=2")
+            .is_err());
+        assert!(config
+            .apply_faction_modifier_overrides("nyms.luck=2")
+            .is_err());
+        assert!(config
+            .apply_faction_modifier_overrides("nyms.damage_dealt")
+            .is_err());
+
+        config
+            .apply_faction_modifier_overrides("monks.experience_gain=0")
+            .unwrap();
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_position_clamping() {
         let config = GameConfig::default();
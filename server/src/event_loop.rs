@@ -0,0 +1,400 @@
+//! Server main loop, independent of the underlying transport
+//!
+//! `main` sets up the Nym transport, keys and persisted state, then hands
+//! everything to `run_event_loop`. Tests drive the same loop over an
+//! in-process transport.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use crate::config::GameConfig;
+use crate::game_state::GameState;
+use crate::handlers::{
+    self, broadcast_game_state_keyframe, broadcast_shutdown_notification, cleanup_inactive_players,
+    cleanup_rate_limiter, handle_client_message, run_simulation_tick, send_heartbeat_requests,
+};
+use crate::mixnet_monitor::MixnetMonitor;
+use crate::persistence::GameStatePersistence;
+use crate::sessions::SessionManager;
+use nymquest_protocol::game_protocol::ClientMessage;
+use nymquest_protocol::message_auth::AuthenticatedMessage;
+use nymquest_protocol::message_padding::{unpad_message, PaddedMessage};
+use nymquest_protocol::transport::{GameTransport, SenderTag};
+
+// Event loop timing constants
+const PERSISTENCE_INTERVAL_SECONDS: u64 = 120; // 2 minutes
+const RATE_LIMITER_CLEANUP_INTERVAL_SECONDS: u64 = 300; // 5 minutes
+const MONITORING_STATS_INTERVAL_SECONDS: u64 = 60; // 1 minute
+const SESSION_REKEY_CHECK_INTERVAL_SECONDS: u64 = 60; // 1 minute
+const SHUTDOWN_NOTIFICATION_COUNTDOWN_SECONDS: u8 = 5;
+
+/// Run the server until a shutdown signal arrives or the transport closes
+///
+/// Dispatches incoming client messages to the handlers, runs the fixed-rate
+/// simulation tick that applies the player intents they queue, and runs the periodic
+/// heartbeat, cleanup, session rekeying, persistence and monitoring tasks. On shutdown the final
+/// state is saved, players are notified and the transport is disconnected.
+pub async fn run_event_loop<T: GameTransport>(
+    mut client: T,
+    game_state: Arc<GameState>,
+    sessions: SessionManager,
+    game_config: GameConfig,
+    persistence: GameStatePersistence,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) -> Result<()> {
+    let mixnet_monitor = MixnetMonitor::new();
+
+    // Main event loop
+    info!("Server ready to receive connections");
+    let mut heartbeat_interval =
+        interval(Duration::from_secs(game_config.heartbeat_interval_seconds));
+    let mut cleanup_interval = interval(Duration::from_secs(
+        game_config.inactive_player_cleanup_interval_seconds,
+    ));
+
+    // Add persistence interval (save state every 2 minutes)
+    let mut persistence_interval = interval(Duration::from_secs(PERSISTENCE_INTERVAL_SECONDS));
+
+    // Add rate limiter cleanup interval (cleanup every 5 minutes)
+    let mut rate_limiter_cleanup_interval =
+        interval(Duration::from_secs(RATE_LIMITER_CLEANUP_INTERVAL_SECONDS));
+
+    // Message processing pacing for privacy protection
+    let mut last_message_processed: Option<Instant> = None;
+
+    // Add monitoring stats interval (log every 60 seconds)
+    let mut monitor_stats_interval =
+        interval(Duration::from_secs(MONITORING_STATS_INTERVAL_SECONDS));
+
+    // Check for session keys due for rotation (every minute)
+    let mut session_rekey_interval =
+        interval(Duration::from_secs(SESSION_REKEY_CHECK_INTERVAL_SECONDS));
+
+    // Full state snapshots between which clients receive deltas
+    let mut state_keyframe_interval = interval(Duration::from_secs(
+        game_config.state_broadcast_interval_seconds,
+    ));
+
+    // Simulation ticks apply queued player intents; a tick delayed by slow IO is
+    // skipped rather than run in a burst
+    let mut simulation_interval = interval(game_config.simulation_tick_interval());
+    simulation_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Skip the first tick to avoid immediate execution
+    heartbeat_interval.tick().await;
+    cleanup_interval.tick().await;
+    persistence_interval.tick().await;
+    rate_limiter_cleanup_interval.tick().await;
+    monitor_stats_interval.tick().await;
+    session_rekey_interval.tick().await;
+    state_keyframe_interval.tick().await;
+
+    // Main event loop with background task scheduling
+    loop {
+        tokio::select! {
+            // Handle shutdown signal
+            _ = shutdown_rx.recv() => {
+                info!("Processing shutdown sequence...");
+
+                // Final state persistence
+                let players = game_state.get_saved_players();
+                info!("Saving final game state...");
+                if let Err(e) = persistence.save_state(&players, &game_state.get_reclaim_token_hashes(), &game_config).await {
+                    error!("Failed to save final game state during shutdown: {}", e);
+                } else {
+                    info!("Final game state saved successfully");
+                }
+
+                // Send shutdown notification to all players with 5 second countdown
+                info!("Notifying connected players of server shutdown...");
+                if let Err(e) = broadcast_shutdown_notification(
+                    &client,
+                    &game_state,
+                    "Server is shutting down",
+                    SHUTDOWN_NOTIFICATION_COUNTDOWN_SECONDS,
+                    &sessions
+                ).await {
+                    error!("Failed to send shutdown notification: {}", e);
+                }
+
+                // Wait a moment to allow clients to receive the notification
+                info!("Waiting for notification delivery...");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+
+                // Clean disconnect from mixnet
+                info!("Disconnecting from Nym mixnet...");
+                break;
+            },
+            // Handle incoming messages from clients
+            received_message = client.next_message() => {
+                match received_message {
+                    Some(message) => {
+                        // Record message received in mixnet monitor
+                        mixnet_monitor.record_message_received().await;
+
+                        // Process the message
+                        if let Err(e) = process_incoming_message(&client, &game_state, message.payload, message.sender_tag, &sessions, &game_config, &mut last_message_processed).await {
+                            error!("Error processing incoming message: {}", e);
+                        }
+                    }
+                    None => {
+                        error!("Message stream ended unexpectedly");
+                        break;
+                    }
+                }
+            },
+
+            // Advance the simulation and send its results
+            _ = simulation_interval.tick() => {
+                if let Err(e) = run_simulation_tick(&client, &game_state, &sessions).await {
+                    error!("Failed to run simulation tick: {}", e);
+                }
+            },
+
+            // Send heartbeat requests to all connected players periodically
+            _ = heartbeat_interval.tick() => {
+                if let Err(e) = send_heartbeat_requests(&client, &game_state, &sessions).await {
+                    error!("Failed to send heartbeat requests: {}", e);
+                }
+            },
+
+            // Clean up inactive players periodically
+            _ = cleanup_interval.tick() => {
+                if let Err(e) = cleanup_inactive_players(&client, &game_state, &sessions).await {
+                    error!("Failed to cleanup inactive players: {}", e);
+                }
+                // Drop session keys of connections that no longer have a player
+                if let Err(e) = sessions.prune(|tag| game_state.get_player_id(tag).is_some()) {
+                    error!("Failed to prune sessions: {}", e);
+                }
+            },
+
+            // Send every player a full snapshot so missed deltas are recovered
+            _ = state_keyframe_interval.tick() => {
+                if let Err(e) = broadcast_game_state_keyframe(&client, &game_state, &sessions).await {
+                    error!("Failed to broadcast game state keyframe: {}", e);
+                }
+            },
+
+            // Rotate session keys that are due
+            _ = session_rekey_interval.tick() => {
+                if let Err(e) = sessions.rotate_keys() {
+                    error!("Failed to rotate session keys: {}", e);
+                }
+            },
+
+            // Save game state to disk periodically
+            _ = persistence_interval.tick() => {
+                let players = game_state.get_saved_players();
+                if let Err(e) = persistence.save_state(&players, &game_state.get_reclaim_token_hashes(), &game_config).await {
+                    error!("Failed to save game state: {}", e);
+                } else if !players.is_empty() {
+                    debug!("Periodically saved game state with {} players", players.len());
+                }
+            },
+
+            // Record and log mixnet health statistics periodically
+            _ = monitor_stats_interval.tick() => {
+                // Log the current mixnet health statistics
+                mixnet_monitor.log_connection_stats().await;
+            }
+        }
+    }
+
+    info!("Server is shutting down gracefully...");
+
+    // Final cleanup of rate limiter
+    cleanup_rate_limiter();
+
+    // Disconnect from the mixnet (ensuring data is flushed)
+    client.disconnect().await;
+    info!("Successfully disconnected from Nym mixnet");
+
+    Ok(())
+}
+
+/// Process an incoming message from a client
+async fn process_incoming_message<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    received_message: impl Into<Vec<u8>>,
+    sender_tag: Option<SenderTag>,
+    sessions: &SessionManager,
+    game_config: &GameConfig,
+    last_message_processed: &mut Option<Instant>,
+) -> Result<()> {
+    // Access the mixnet monitor using a static reference
+    let mixnet_monitor = MixnetMonitor::new();
+    let message_content = received_message.into();
+
+    // Apply message processing pacing with jitter for enhanced privacy protection
+    if game_config.enable_message_processing_pacing {
+        if let Some(last_processed) = *last_message_processed {
+            let elapsed = last_processed.elapsed();
+            let min_interval = Duration::from_millis(game_config.message_processing_interval_ms);
+
+            if elapsed < min_interval {
+                let base_wait_time = min_interval - elapsed;
+                debug!(
+                    "Applying message processing pacing: base wait time {:?} for privacy protection",
+                    base_wait_time
+                );
+
+                // First apply the base waiting time to ensure minimum interval
+                tokio::time::sleep(base_wait_time).await;
+
+                // Then apply additional jitter for enhanced privacy
+                let jitter_ms = handlers::apply_message_processing_jitter(
+                    game_config.message_processing_interval_ms,
+                    game_config.message_processing_jitter_percent,
+                    None, // No specific message priority for this jitter
+                )
+                .await;
+
+                if jitter_ms > 0 {
+                    debug!(
+                        "Applied additional jitter of {}ms for enhanced privacy protection",
+                        jitter_ms
+                    );
+                }
+            } else {
+                // Even if we're past the minimum interval, still apply some jitter
+                // for better privacy (but with a smaller base interval)
+                let base_interval_ms = game_config.message_processing_interval_ms / 4;
+                let jitter_ms = handlers::apply_message_processing_jitter(
+                    base_interval_ms,
+                    game_config.message_processing_jitter_percent,
+                    None, // No specific message priority for this jitter
+                )
+                .await;
+
+                if jitter_ms > 0 {
+                    debug!(
+                        "Applied minimal jitter of {}ms for privacy protection",
+                        jitter_ms
+                    );
+                }
+            }
+        } else {
+            // For the first message, apply a small random delay
+            let jitter_ms = handlers::apply_message_processing_jitter(
+                game_config.message_processing_interval_ms / 4,
+                game_config.message_processing_jitter_percent,
+                None, // No specific message priority for this jitter
+            )
+            .await;
+
+            if jitter_ms > 0 {
+                debug!(
+                    "Applied initial jitter of {}ms for privacy protection",
+                    jitter_ms
+                );
+            }
+        }
+        *last_message_processed = Some(Instant::now());
+    }
+
+    // Skip empty messages
+    if message_content.is_empty() {
+        debug!("Received empty message, skipping");
+        return Ok(());
+    }
+
+    let sender_tag = match sender_tag {
+        Some(tag) => tag,
+        None => {
+            debug!("Received message without sender tag, skipping");
+            return Ok(());
+        }
+    };
+
+    match String::from_utf8(message_content) {
+        Ok(content) => {
+            debug!(message_size = content.len(), "Processing incoming message");
+
+            // Try to deserialize as a padded authenticated message
+            match serde_json::from_str::<PaddedMessage<AuthenticatedMessage<ClientMessage>>>(
+                &content,
+            ) {
+                Ok(padded_message) => {
+                    debug!("Successfully parsed padded message");
+                    // Extract the authenticated message from the padding
+                    let authenticated_message = unpad_message(padded_message);
+
+                    // Verify message authenticity against the connection's session key and check expiration
+                    match sessions.verify(&sender_tag, &authenticated_message) {
+                        Ok(true) => {
+                            // Message is authentic and not expired, extract the actual client message
+                            let client_message = authenticated_message.message;
+                            debug!(
+                                message_type = ?client_message,
+                                expiration = ?authenticated_message.expires_at,
+                                "Processing authenticated client message"
+                            );
+
+                            let result = handle_client_message(
+                                client,
+                                game_state,
+                                client_message,
+                                sender_tag,
+                                sessions,
+                            )
+                            .await;
+
+                            if let Err(e) = result {
+                                error!(
+                                    error = %e,
+                                    "Failed to handle client message"
+                                );
+
+                                // Record the failure in mixnet monitor
+                                mixnet_monitor.record_send_failure();
+                            }
+                        }
+                        Ok(false) => {
+                            // This could be due to invalid authentication OR message expiration
+                            if let Some(expires_at) = authenticated_message.expires_at {
+                                let now = SystemTime::now()
+                                    .duration_since(UNIX_EPOCH)
+                                    .unwrap_or_default()
+                                    .as_secs();
+
+                                if now > expires_at {
+                                    warn!("Rejected expired message (expired at {})", expires_at);
+                                } else {
+                                    warn!("Received message with invalid authentication - possible security threat");
+                                }
+                            } else {
+                                warn!("Received message with invalid authentication - possible security threat");
+                            }
+                        }
+                        Err(e) => {
+                            error!(
+                                error = %e,
+                                "Error verifying message authenticity"
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!(
+                        error = %e,
+                        "Failed to deserialize message as authenticated format"
+                    );
+                }
+            }
+        }
+        Err(e) => {
+            debug!(
+                error = %e,
+                "Received non-UTF8 message content"
+            );
+        }
+    }
+
+    Ok(())
+}
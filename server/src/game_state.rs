@@ -1,17 +1,144 @@
-use rand::{thread_rng, Rng};
-use std::collections::HashMap;
+use base64::{engine::general_purpose, Engine};
+use rand::rngs::OsRng;
+use rand::{thread_rng, Rng, RngCore};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::{Mutex, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::abilities::Abilities;
 use crate::config::GameConfig;
-use crate::game_protocol::{Player, Position};
-use crate::world_lore::Faction;
-use nym_sdk::mixnet::AnonymousSenderTag;
+use crate::loot::{GroundLoot, LootTable};
+use crate::npcs::Npcs;
+use crate::quests::{self, QuestEvent, QuestUpdate};
+use crate::regions::{RegionIndex, Regions};
+use crate::reputation::{self, Unlocks};
+use crate::simulation::Simulation;
+use crate::state_sync::StateSync;
+use crate::status_effects;
+use crate::surveillance::Surveillance;
+use crate::territory::Territory;
+use crate::trading::Trades;
+use nymquest_protocol::game_protocol::{
+    FactionStanding, LootDrop, NpcView, Player, PlayerView, Position, QuestDefinition, QuestStatus,
+    StatusEffect, StatusEffectKind, TradeAssets,
+};
+use nymquest_protocol::transport::SenderTag;
+use nymquest_protocol::world_lore::{
+    generate_crypto_items, CryptoItem, EquipmentSlot, Faction, FactionModifiers, InventoryItem,
+    ItemStats,
+};
 
 /// Type alias for a player ID and its associated sender tag
-pub type PlayerTag = (String, AnonymousSenderTag);
+pub type PlayerTag = (String, SenderTag);
+
+/// Number of random bytes in a reclaim token
+const RECLAIM_TOKEN_BYTES: usize = 32;
+
+/// Extra damage dealt per point of the attacker's active attack stat
+const ITEM_DAMAGE_PER_ATTACK: f32 = 0.25;
+
+/// Damage prevented per point of the target's active defense stat
+const ITEM_DAMAGE_PER_DEFENSE: f32 = 0.25;
+
+/// Result of `GameState::apply_damage`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DamageOutcome {
+    /// Health the target lost, after level and item modifiers
+    pub damage: u32,
+    /// Whether the target was defeated, and is down or respawned
+    pub defeated: bool,
+    /// What a defeated player lost to the death penalty
+    pub penalty: DeathPenalty,
+}
+
+/// What a defeated player loses, as configured by `death_experience_penalty` and `death_drops_item`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeathPenalty {
+    /// Experience taken off the player
+    pub experience: u32,
+    /// Item the player dropped where they fell
+    pub dropped: Option<LootDrop>,
+    /// Drops that vanished to make room for it on a full ground
+    pub removed: Vec<LootDrop>,
+}
+
+/// Uniform grid over the world for finding players near a position
+///
+/// Cells are as wide as the largest view distance, so a visibility query only
+/// touches the cells around the observer instead of every player.
+pub struct SpatialGrid {
+    cell_size: f32,
+    /// Player IDs in each occupied cell
+    cells: HashMap<(i32, i32), HashSet<String>>,
+    /// Cell each player is currently in
+    player_cells: HashMap<String, (i32, i32)>,
+}
+
+impl SpatialGrid {
+    /// Create an empty grid with square cells of `cell_size` world units
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::with_capacity(256),
+            player_cells: HashMap::with_capacity(256),
+        }
+    }
+
+    fn cell_of(&self, position: &Position) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Insert a player or move them to the cell containing `position`
+    pub fn update(&mut self, player_id: &str, position: &Position) {
+        let cell = self.cell_of(position);
+        match self.player_cells.get(player_id) {
+            Some(current) if *current == cell => return,
+            Some(_) => self.remove(player_id),
+            None => {}
+        }
+        self.cells
+            .entry(cell)
+            .or_default()
+            .insert(player_id.to_string());
+        self.player_cells.insert(player_id.to_string(), cell);
+    }
+
+    /// Remove a player from the grid
+    pub fn remove(&mut self, player_id: &str) {
+        if let Some(cell) = self.player_cells.remove(player_id) {
+            if let Some(occupants) = self.cells.get_mut(&cell) {
+                occupants.remove(player_id);
+                if occupants.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// IDs of players in cells overlapping the square of half-width `radius` around `position`
+    ///
+    /// Candidates still need an exact distance check.
+    pub fn candidates_near(&self, position: &Position, radius: f32) -> Vec<&String> {
+        let (min_x, min_y) = self.cell_of(&Position::new(position.x - radius, position.y - radius));
+        let (max_x, max_y) = self.cell_of(&Position::new(position.x + radius, position.y + radius));
+
+        let mut candidates = Vec::new();
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                if let Some(occupants) = self.cells.get(&(cell_x, cell_y)) {
+                    candidates.extend(occupants.iter());
+                }
+            }
+        }
+        candidates
+    }
+}
 
 /// GameState manages the entire game state including players and connections
 pub struct GameState {
@@ -23,31 +150,87 @@ pub struct GameState {
     last_heartbeat: Mutex<HashMap<String, u64>>,
     /// Reverse lookup: display_id (lowercase) -> player_id for O(1) lookups
     display_id_to_player_id: RwLock<HashMap<String, String>>,
+    /// Map of reclaim token hashes to the player IDs they reclaim
+    reclaim_tokens: RwLock<HashMap<String, String>>,
+    /// Characters whose players left, kept out of the world until they are resumed
+    departed: RwLock<HashMap<String, Player>>,
+    /// State each connection acknowledged, for delta updates
+    state_sync: StateSync,
+    /// Player positions indexed by grid cell for area-of-interest queries
+    spatial_grid: RwLock<SpatialGrid>,
+    /// Player IDs indexed by region, so players only see within their region
+    region_index: RwLock<RegionIndex>,
+    /// Player intents waiting for the next simulation tick
+    simulation: Simulation,
+    /// Every item that exists in the world, by item ID
+    item_catalog: HashMap<String, CryptoItem>,
+    /// Regions the world is made of
+    regions: Regions,
+    /// What items drop in each region, by region index
+    loot_tables: Vec<LootTable>,
+    /// Items lying on the ground
+    ground_loot: GroundLoot,
+    /// Trades between players in progress
+    trades: Trades,
+    /// Control points and the factions holding them
+    territory: Territory,
+    /// Players caught on surveillance
+    surveillance: Surveillance,
+    /// Characters the server controls
+    npcs: Npcs,
+    /// Ability cooldowns
+    abilities: Abilities,
     /// Game configuration
     config: GameConfig,
 }
 
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GameState {
     /// Create a new empty GameState with default configuration
     #[allow(dead_code)]
     pub fn new() -> Self {
-        let config = GameConfig::default();
-        GameState {
-            players: RwLock::new(HashMap::with_capacity(256)), // Increased capacity
-            connections: Mutex::new(Vec::with_capacity(256)),
-            last_heartbeat: Mutex::new(HashMap::with_capacity(256)),
-            display_id_to_player_id: RwLock::new(HashMap::with_capacity(256)),
-            config,
-        }
+        Self::new_with_config(GameConfig::default())
     }
 
     /// Create a new GameState with specific configuration
     pub fn new_with_config(config: GameConfig) -> Self {
+        let item_catalog = generate_crypto_items();
+        let regions = Regions::from_config(&config);
+        let npcs = Npcs::new();
+        if config.enable_npcs {
+            if let Err(e) = npcs.spawn(&regions, &mut thread_rng()) {
+                error!("Failed to spawn characters: {}", e);
+            }
+        }
         GameState {
             players: RwLock::new(HashMap::with_capacity(256)), // Increased capacity
             connections: Mutex::new(Vec::with_capacity(256)),
             last_heartbeat: Mutex::new(HashMap::with_capacity(256)),
             display_id_to_player_id: RwLock::new(HashMap::with_capacity(256)),
+            reclaim_tokens: RwLock::new(HashMap::with_capacity(256)),
+            departed: RwLock::new(HashMap::new()),
+            state_sync: StateSync::new(),
+            spatial_grid: RwLock::new(SpatialGrid::new(config.max_view_distance())),
+            region_index: RwLock::new(RegionIndex::new()),
+            simulation: Simulation::new(),
+            loot_tables: regions
+                .all()
+                .iter()
+                .map(|region| LootTable::for_region(region.lore, &item_catalog))
+                .collect(),
+            ground_loot: GroundLoot::new(config.max_loot_drops),
+            trades: Trades::new(),
+            territory: Territory::new(&regions, config.control_point_radius),
+            surveillance: Surveillance::new(),
+            npcs,
+            abilities: Abilities::new(),
+            regions,
+            item_catalog,
             config,
         }
     }
@@ -57,13 +240,95 @@ impl GameState {
         &self.config
     }
 
+    /// Get the per-connection state baselines used for delta updates
+    pub fn state_sync(&self) -> &StateSync {
+        &self.state_sync
+    }
+
+    /// Get the simulation that applies queued player intents each tick
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    /// Get the catalog of items that exist in the world
+    pub fn item_catalog(&self) -> &HashMap<String, CryptoItem> {
+        &self.item_catalog
+    }
+
+    /// Get the regions the world is made of
+    pub fn regions(&self) -> &Regions {
+        &self.regions
+    }
+
+    /// Get the loot table items at `position` are drawn from
+    pub fn loot_table_at(&self, position: &Position) -> &LootTable {
+        &self.loot_tables[self.regions.index_at(position)]
+    }
+
+    /// Get the items lying on the ground
+    pub fn ground_loot(&self) -> &GroundLoot {
+        &self.ground_loot
+    }
+
+    /// Get the trades between players in progress
+    pub fn trades(&self) -> &Trades {
+        &self.trades
+    }
+
+    /// Get the control points and who holds them
+    pub fn territory(&self) -> &Territory {
+        &self.territory
+    }
+
+    /// Get the players caught on surveillance
+    pub fn surveillance(&self) -> &Surveillance {
+        &self.surveillance
+    }
+
+    /// Get the characters the server controls
+    pub fn npcs(&self) -> &Npcs {
+        &self.npcs
+    }
+
+    /// Get the ability cooldowns
+    pub fn abilities(&self) -> &Abilities {
+        &self.abilities
+    }
+
+    /// Get the characters within view of `player_id` in their region, by display ID
+    pub fn get_visible_npcs(&self, player_id: &str) -> Vec<NpcView> {
+        let Some(player) = self.get_player(player_id) else {
+            return Vec::new();
+        };
+        self.npcs.views_near(
+            self.regions.index_at(&player.position),
+            &player.position,
+            self.config.view_radius,
+        )
+    }
+
+    /// Get the items on the ground within view of `player_id`, oldest first
+    pub fn get_visible_loot(&self, player_id: &str) -> Vec<LootDrop> {
+        let Some(player) = self.get_player(player_id) else {
+            return Vec::new();
+        };
+        match self
+            .ground_loot
+            .within(&player.position, self.config.view_radius)
+        {
+            Ok(drops) => drops
+                .into_iter()
+                .filter(|drop| self.regions.same_region(&drop.position, &player.position))
+                .collect(),
+            Err(e) => {
+                error!("Failed to find loot near player {}: {}", player_id, e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Add a new player to the game
-    pub fn add_player(
-        &self,
-        name: String,
-        faction: Faction,
-        sender_tag: AnonymousSenderTag,
-    ) -> String {
+    pub fn add_player(&self, name: String, faction: Faction, sender_tag: SenderTag) -> String {
         // Validate player name length according to configuration
         if name.len() > self.config.max_player_name_length {
             warn!(
@@ -97,7 +362,7 @@ impl GameState {
         // Generate a position that's not occupied by another player
         let available_position = {
             match self.players.read() {
-                Ok(state) => self.generate_available_position(&state),
+                Ok(state) => self.generate_available_position(&state, 0),
                 Err(e) => {
                     warn!("Failed to access players for position generation: {}", e);
                     // Return a default position if mutex is poisoned
@@ -128,6 +393,18 @@ impl GameState {
             format!("{}{}", prefix, suffix)
         };
 
+        // Characters that may be resumed keep their display IDs
+        let departed_display_ids: Vec<String> = match self.departed.read() {
+            Ok(departed) => departed.values().map(|p| p.display_id.clone()).collect(),
+            Err(e) => {
+                warn!(
+                    "Failed to access departed players for display ID generation: {}",
+                    e
+                );
+                Vec::new()
+            }
+        };
+
         // Ensure the display ID is unique
         let unique_display_id = {
             match self.players.read() {
@@ -136,7 +413,10 @@ impl GameState {
                     let mut attempts = 0;
 
                     // Ensure the display ID is unique
-                    while state.values().any(|p| p.display_id == current_id) && attempts < 100 {
+                    while (state.values().any(|p| p.display_id == current_id)
+                        || departed_display_ids.contains(&current_id))
+                        && attempts < 100
+                    {
                         attempts += 1;
                         current_id = format!("Player{}", state.len() + attempts);
                     }
@@ -154,6 +434,9 @@ impl GameState {
             }
         };
 
+        // New players start out with their faction's signature item
+        let inventory = BTreeMap::from([(faction.starter_item_id().to_string(), 1)]);
+
         // Create a new player with the available position
         let player = Player {
             id: player_id.clone(),
@@ -165,15 +448,26 @@ impl GameState {
             position: available_position,
             health: self.config.initial_player_health, // New players at level 1 don't get bonus health
             last_attack_time: now.saturating_sub(self.config.attack_cooldown_seconds), // Allow immediate first attack
+            last_damage_time: 0,
             experience: 0, // New players start with 0 experience
             level: 1,      // New players start at level 1
             faction,       // Store the player's chosen faction
+            inventory,
+            active_items: BTreeMap::new(),
+            equipment: BTreeMap::new(),
+            credits: 0,
+            escrow: TradeAssets::default(),
+            reputation: BTreeMap::new(),
+            rewarded_factions: BTreeSet::new(),
+            quests: BTreeMap::new(),
+            effects: BTreeMap::new(),
         };
 
         // Add the player to the game state
         let display_id_for_lookup = player.display_id.to_lowercase();
         match self.players.write() {
             Ok(mut players) => {
+                self.index_player(&player_id, &player.position);
                 players.insert(player_id.clone(), player);
             }
             Err(e) => {
@@ -216,7 +510,7 @@ impl GameState {
     }
 
     /// Remove a player from the game
-    pub fn remove_player(&self, tag: &AnonymousSenderTag) -> Option<String> {
+    pub fn remove_player(&self, tag: &SenderTag) -> Option<String> {
         let mut connection_index = None;
         let mut player_id_to_remove = None;
 
@@ -252,24 +546,22 @@ impl GameState {
 
             // Remove from game state and get display_id for reverse lookup cleanup
             let display_id_for_cleanup = if let Some(id) = &player_id_to_remove {
-                let display_id = match self.players.read() {
-                    Ok(players) => players.get(id).map(|p| p.display_id.to_lowercase()),
-                    Err(e) => {
-                        error!("Failed to read player for display_id cleanup: {}", e);
-                        None
-                    }
-                };
-
-                match self.players.write() {
+                let removed = match self.players.write() {
                     Ok(mut players) => {
-                        players.remove(id);
+                        self.unindex_player(id);
+                        players.remove(id)
                     }
                     Err(e) => {
                         error!("Failed to remove player from game state: {}", e);
+                        None
                     }
-                }
+                };
 
-                display_id
+                removed.map(|player| {
+                    let display_id = player.display_id.to_lowercase();
+                    self.keep_departed(player);
+                    display_id
+                })
             } else {
                 None
             };
@@ -299,11 +591,145 @@ impl GameState {
             }
         }
 
+        self.state_sync.forget(tag);
+
         player_id_to_remove
     }
 
+    /// Keep the character of a player who left so they can resume it with their reclaim token
+    /// Characters without a token could never be resumed, so they are let go
+    fn keep_departed(&self, mut player: Player) {
+        let reclaimable = match self.reclaim_tokens.read() {
+            Ok(reclaim_tokens) => reclaim_tokens.values().any(|id| id == &player.id),
+            Err(e) => {
+                error!("Failed to access reclaim tokens: {}", e);
+                false
+            }
+        };
+        if !reclaimable {
+            return;
+        }
+
+        // Trades are called off when a player leaves, so whatever was put up goes back
+        Self::fold_escrow(&mut player);
+        match self.departed.write() {
+            Ok(mut departed) => {
+                departed.insert(player.id.clone(), player);
+            }
+            Err(e) => {
+                error!("Failed to keep departed player {}: {}", player.id, e);
+            }
+        }
+    }
+
+    /// Put a departed character back into the world
+    /// Returns None if no character with this ID departed
+    fn return_departed(&self, player_id: &str) -> Option<()> {
+        let player = match self.departed.write() {
+            Ok(mut departed) => departed.remove(player_id)?,
+            Err(e) => {
+                error!("Failed to access departed players: {}", e);
+                return None;
+            }
+        };
+
+        match self.display_id_to_player_id.write() {
+            Ok(mut reverse_lookup) => {
+                reverse_lookup.insert(player.display_id.to_lowercase(), player_id.to_string());
+            }
+            Err(e) => {
+                error!("Failed to add reverse lookup mapping: {}", e);
+            }
+        }
+
+        match self.players.write() {
+            Ok(mut players) => {
+                self.index_player(player_id, &player.position);
+                players.insert(player_id.to_string(), player);
+                Some(())
+            }
+            Err(e) => {
+                error!("Failed to return departed player {}: {}", player_id, e);
+                None
+            }
+        }
+    }
+
+    /// Issue a new reclaim token for a player, replacing any earlier one
+    /// Only a hash of the token is kept, so saved state cannot be used to take over characters
+    pub fn issue_reclaim_token(&self, player_id: &str) -> String {
+        let mut bytes = [0u8; RECLAIM_TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let token = general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+
+        match self.reclaim_tokens.write() {
+            Ok(mut reclaim_tokens) => {
+                reclaim_tokens.retain(|_, id| id != player_id);
+                reclaim_tokens.insert(hash_reclaim_token(&token), player_id.to_string());
+            }
+            Err(e) => {
+                error!(
+                    "Failed to store reclaim token for player {}: {}",
+                    player_id, e
+                );
+            }
+        }
+
+        token
+    }
+
+    /// Bind the character a reclaim token belongs to to a new connection
+    /// Characters whose players left are put back where they left the world
+    /// Returns the player ID, or None if the token is unknown or the character is gone
+    pub fn reclaim_player(&self, token: &str, sender_tag: SenderTag) -> Option<String> {
+        let player_id = match self.reclaim_tokens.read() {
+            Ok(reclaim_tokens) => reclaim_tokens.get(&hash_reclaim_token(token)).cloned()?,
+            Err(e) => {
+                error!("Failed to access reclaim tokens: {}", e);
+                return None;
+            }
+        };
+
+        if self.get_player(&player_id).is_none() {
+            self.return_departed(&player_id)?;
+        }
+
+        // Replace the connection the character had before, if it still has one
+        match self.connections.lock() {
+            Ok(mut connections) => {
+                for (_, old_tag) in connections.iter().filter(|(id, _)| id == &player_id) {
+                    self.state_sync.forget(old_tag);
+                }
+                connections.retain(|(id, _)| id != &player_id);
+                connections.push((player_id.clone(), sender_tag));
+            }
+            Err(e) => {
+                error!("Failed to store connection for reclaimed player: {}", e);
+                return None;
+            }
+        }
+
+        self.update_heartbeat(&player_id);
+        info!("Player {} reclaimed their character", player_id);
+        Some(player_id)
+    }
+
+    /// Get the reclaim token hash of every player that has one, keyed by player ID
+    pub fn get_reclaim_token_hashes(&self) -> HashMap<String, String> {
+        match self.reclaim_tokens.read() {
+            Ok(reclaim_tokens) => reclaim_tokens
+                .iter()
+                .map(|(hash, player_id)| (player_id.clone(), hash.clone()))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to access reclaim tokens: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
     /// Get a player ID from a sender tag
-    pub fn get_player_id(&self, tag: &AnonymousSenderTag) -> Option<String> {
+    pub fn get_player_id(&self, tag: &SenderTag) -> Option<String> {
         // Clone connections to reduce lock scope
         let connections_clone = match self.connections.lock() {
             Ok(connections) => connections.clone(),
@@ -337,6 +763,130 @@ impl GameState {
         }
     }
 
+    /// Get every character to save: the players in the world and those who left and can resume
+    pub fn get_saved_players(&self) -> HashMap<String, Player> {
+        let mut players = self.get_players();
+        match self.departed.read() {
+            Ok(departed) => players.extend(
+                departed
+                    .iter()
+                    .map(|(player_id, player)| (player_id.clone(), player.clone())),
+            ),
+            Err(e) => {
+                warn!("Failed to access departed players for retrieval: {}", e);
+            }
+        }
+        players
+    }
+
+    /// Get where every connected player who is not down stands and which faction they belong to
+    pub fn get_faction_positions(&self) -> Vec<(Position, Faction)> {
+        let connections = self.get_connections();
+        match self.players.read() {
+            Ok(players) => players
+                .values()
+                .filter(|player| {
+                    connections.iter().any(|(id, _)| id == &player.id)
+                        && !player.effects.contains_key(&StatusEffectKind::Downed)
+                })
+                .map(|player| (player.position, player.faction.clone()))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to access players for retrieval: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get the IDs of the players in the region with index `region`, sorted
+    pub fn get_region_player_ids(&self, region: usize) -> Vec<String> {
+        match self.region_index.read() {
+            Ok(index) => {
+                let mut player_ids: Vec<String> =
+                    index.players_in(region).into_iter().cloned().collect();
+                player_ids.sort();
+                player_ids
+            }
+            Err(e) => {
+                error!("Failed to access region index: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get the players `observer_id` can see, keyed by display ID
+    ///
+    /// Players always see themselves, and others within the configured view
+    /// distance of where those others stand. Corporate Hegemony players also
+    /// see every exposed player in their region. Players using Ghost are only
+    /// seen from right next to them.
+    pub fn get_visible_player_views(&self, observer_id: &str) -> HashMap<String, PlayerView> {
+        let players = match self.players.read() {
+            Ok(players) => players,
+            Err(e) => {
+                warn!("Failed to access players for retrieval: {}", e);
+                return HashMap::new();
+            }
+        };
+        let Some(observer) = players.get(observer_id) else {
+            return HashMap::new();
+        };
+        let grid = match self.spatial_grid.read() {
+            Ok(grid) => grid,
+            Err(e) => {
+                error!("Failed to access spatial grid: {}", e);
+                return HashMap::new();
+            }
+        };
+
+        let region_index = match self.region_index.read() {
+            Ok(index) => index,
+            Err(e) => {
+                error!("Failed to access region index: {}", e);
+                return HashMap::new();
+            }
+        };
+
+        // Exposed players are in view of the Hegemony wherever they stand
+        let mut candidates =
+            grid.candidates_near(&observer.position, self.config.max_view_distance());
+        let exposed = if observer.faction == Faction::CorporateHegemony {
+            self.surveillance.exposed_players().unwrap_or_else(|e| {
+                warn!("Failed to access surveillance: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        candidates.extend(exposed.iter());
+
+        // Players only see others in the same region
+        let region_number = self.regions.index_at(&observer.position);
+        let region = &self.regions.all()[region_number];
+        candidates
+            .into_iter()
+            .filter(|player_id| region_index.contains(region_number, player_id))
+            .filter_map(|player_id| players.get(player_id))
+            .filter(|player| {
+                let distance = observer.position.distance_to(&player.position);
+                player.id == observer.id
+                    || if player.effects.contains_key(&StatusEffectKind::Stealth) {
+                        !status_effects::is_hidden(&player.effects, distance)
+                    } else {
+                        exposed.contains(&player.id)
+                            || distance
+                                <= self.config.concealed_view_distance(
+                                    &region.boundaries,
+                                    &player.position,
+                                    &self.item_stats(player),
+                                    &self.effective_modifiers(player),
+                                )
+                    }
+            })
+            .map(|player| (player.display_id.clone(), PlayerView::from(player)))
+            .collect()
+    }
+
     /// Get a specific player by their internal ID
     pub fn get_player(&self, player_id: &str) -> Option<Player> {
         match self.players.read() {
@@ -391,14 +941,14 @@ impl GameState {
 
     /// Update a player's position
     pub fn update_player_position(&self, player_id: &str, new_position: Position) -> bool {
-        // Validate position using configuration boundaries
-        let (clamped_x, clamped_y) = self.config.clamp_position(new_position.x, new_position.y);
-        let clamped_position = Position::new(clamped_x, clamped_y);
+        // Keep the position inside the world's regions
+        let clamped_position = self.regions.clamp(&new_position);
 
         match self.players.write() {
             Ok(mut players) => {
                 if let Some(player) = players.get_mut(player_id) {
                     player.position = clamped_position;
+                    self.index_player(player_id, &clamped_position);
                     debug!(
                         "Updated position for player {} to ({:.1}, {:.1})",
                         player_id, clamped_position.x, clamped_position.y
@@ -416,116 +966,86 @@ impl GameState {
         }
     }
 
-    /// Apply damage to a player and return true if they were defeated
+    /// Apply damage to a player or character and report how much they lost and whether they were defeated
     /// Also awards experience points to the attacker
-    pub fn apply_damage(&self, target_id: &str, attacker_id: &str, damage: u32) -> bool {
-        // Get attacker level and calculate damage bonus
-        let attacker_level = match self.players.read() {
+    ///
+    /// The attacker's level and worn or active attack items add to the damage, and
+    /// the target's defense items take away from it, down to 1. Characters are
+    /// targeted and attack by their display ID. `now` and `rng` come from the
+    /// simulation tick and decide when a defeated player respawns and what they drop.
+    pub fn apply_damage<R: Rng>(
+        &self,
+        target_id: &str,
+        attacker_id: &str,
+        damage: u32,
+        now: u64,
+        rng: &mut R,
+    ) -> DamageOutcome {
+        // Get attacker level, active item stats and faction modifiers to calculate the damage bonus
+        let (attacker_level, attacker_stats, attacker_modifiers) = match self.players.read() {
             Ok(players) => {
                 if let Some(attacker) = players.get(attacker_id) {
-                    attacker.level
+                    (
+                        attacker.level,
+                        self.item_stats(attacker),
+                        self.effective_modifiers(attacker),
+                    )
                 } else {
-                    1 // Default to level 1 if attacker not found
+                    // Default to level 1 if attacker not found
+                    (1, ItemStats::default(), FactionModifiers::default())
                 }
             }
             Err(e) => {
                 error!("Failed to get attacker level: {}", e);
-                1 // Default to level 1 on error
+                (1, ItemStats::default(), FactionModifiers::default()) // Default to level 1 on error
             }
         };
 
-        // Calculate damage with level-based bonus
-        let (damage_bonus, _) = Self::calculate_level_bonuses(attacker_level);
+        // Calculate damage with level and item bonuses
+        let (level_bonus, _) = Self::calculate_level_bonuses(attacker_level);
+        let damage_bonus =
+            level_bonus + (attacker_stats.attack.max(0.0) * ITEM_DAMAGE_PER_ATTACK) as u32;
         let modified_damage = damage + damage_bonus;
-        let actual_damage = modified_damage.min(self.config.attack_damage + damage_bonus); // Limit damage to configured max + bonus
-
-        // First, determine if we need a new position by checking if player will be defeated
-        let needs_respawn = match self.players.read() {
-            Ok(players) => {
-                if let Some(player) = players.get(target_id) {
-                    player.health <= actual_damage
-                } else {
-                    return false; // Player doesn't exist
-                }
-            }
-            Err(e) => {
-                error!("Failed to check player health: {}", e);
-                return false;
-            }
-        };
-
-        // Generate new position if needed (while no locks are held)
-        let new_position = if needs_respawn {
-            match self.players.read() {
-                Ok(players) => Some(self.generate_available_position(&players)),
-                Err(e) => {
-                    error!("Failed to generate respawn position: {}", e);
-                    return false;
-                }
+        let capped_damage = modified_damage.min(self.config.attack_damage + damage_bonus); // Limit damage to configured max + bonus
+        let dealt_damage = capped_damage as f32 * attacker_modifiers.damage_dealt;
+
+        // Targets that are not players are characters, which have no defense
+        let (actual_damage, was_defeated, penalty) = if self.get_player(target_id).is_some() {
+            match self.damage_player(target_id, dealt_damage, now, rng) {
+                Some(result) => (result.damage, result.defeated, result.penalty),
+                None => return DamageOutcome::default(),
             }
         } else {
-            None
-        };
-
-        // Now apply the damage and update position if needed
-        // First apply damage and get result
-        let was_defeated = match self.players.write() {
-            Ok(mut players) => {
-                if let Some(player) = players.get_mut(target_id) {
-                    if let Some(respawn_pos) = new_position {
-                        // Player defeated - reset position and health (with level bonuses)
-                        player.position = respawn_pos;
-                        let (_, health_bonus) = Self::calculate_level_bonuses(player.level);
-                        player.health = self.config.initial_player_health + health_bonus;
-                        info!(
-                            "Player {} was defeated and respawned at {:?}",
-                            target_id, player.position
-                        );
-                        true
-                    } else {
-                        player.health -= actual_damage;
-                        info!(
-                            "Player {} took {} damage, health now: {}",
-                            target_id, actual_damage, player.health
-                        );
-                        false
-                    }
-                } else {
-                    warn!("Attempted to damage non-existent player: {}", target_id);
-                    return false;
+            match self
+                .npcs
+                .damage(target_id, dealt_damage.round().max(1.0) as u32)
+            {
+                Ok(Some((damage, defeated))) => (damage, defeated, DeathPenalty::default()),
+                Ok(None) => return DamageOutcome::default(),
+                Err(e) => {
+                    error!("Failed to damage character {}: {}", target_id, e);
+                    return DamageOutcome::default();
                 }
             }
-            Err(e) => {
-                error!("Failed to apply damage: {}", e);
-                return false;
-            }
         };
 
-        // Award XP to the attacker
+        // Award XP to the attacker; characters attacking players are not in the player list
         if attacker_id != target_id {
             // No XP for self-damage
             match self.players.write() {
                 Ok(mut players) => {
                     if let Some(attacker) = players.get_mut(attacker_id) {
                         // Award more XP for defeating a player
-                        let xp_gained = if was_defeated {
+                        let base_xp = if was_defeated {
                             // Bonus XP for defeating a player
                             20 + actual_damage
                         } else {
                             // Base XP for dealing damage
                             actual_damage
                         };
-
-                        attacker.experience += xp_gained;
-
-                        // Check for level up - simple level formula: each level needs level*100 XP
-                        let next_level = attacker.level + 1;
-                        let xp_needed_for_level = (next_level as u32) * 100;
-
-                        if attacker.experience >= xp_needed_for_level {
-                            attacker.level = next_level;
-                            info!("Player {} reached level {}!", attacker_id, next_level);
-                        }
+                        let xp_gained =
+                            (base_xp as f32 * attacker_modifiers.experience_gain).round() as u32;
+                        Self::gain_experience(attacker, xp_gained);
 
                         info!(
                             "Player {} gained {} XP for attacking {}",
@@ -539,20 +1059,937 @@ impl GameState {
             }
         }
 
-        was_defeated
-    }
-
-    /// Update a player's last attack time
-    pub fn update_attack_time(&self, player_id: &str, time: u64) {
-        match self.players.write() {
-            Ok(mut state) => {
-                if let Some(player) = state.get_mut(player_id) {
-                    player.last_attack_time = time;
-                }
-            }
-            Err(e) => {
-                warn!("Failed to access players for attack time update: {}", e);
-            }
+        DamageOutcome {
+            damage: actual_damage,
+            defeated: was_defeated,
+            penalty,
+        }
+    }
+
+    /// Add experience and the credits earned alongside it, levelling the player up once they have enough
+    fn gain_experience(player: &mut Player, experience: u32) {
+        player.experience += experience;
+        // Credits are earned alongside XP and spent in trades
+        player.credits = player.credits.saturating_add(experience);
+
+        // Check for level up - simple level formula: each level needs level*100 XP
+        let next_level = player.level + 1;
+        let xp_needed_for_level = (next_level as u32) * 100;
+
+        if player.experience >= xp_needed_for_level {
+            player.level = next_level;
+            info!("Player {} reached level {}!", player.id, next_level);
+        }
+    }
+
+    /// Get the quests `player_id` can take on
+    pub fn get_quest_offers(&self, player_id: &str) -> Vec<QuestDefinition> {
+        self.get_player(player_id)
+            .map(|player| quests::offers(&self.config.quests, &player))
+            .unwrap_or_default()
+    }
+
+    /// Get the quests `player_id` took on and has not completed yet, with their progress
+    pub fn get_active_quests(&self, player_id: &str) -> Vec<(QuestDefinition, QuestStatus)> {
+        let Some(player) = self.get_player(player_id) else {
+            return Vec::new();
+        };
+        self.config
+            .quests
+            .iter()
+            .filter_map(|quest| {
+                player
+                    .quests
+                    .get(&quest.id)
+                    .filter(|status| !status.completed)
+                    .map(|status| (quest.clone(), status.clone()))
+            })
+            .collect()
+    }
+
+    /// Take on a quest `player_id` is offered
+    /// Returns the quest, or `None` if it is not offered to them or they already took it on
+    pub fn accept_quest(&self, player_id: &str, quest_id: &str) -> Option<QuestDefinition> {
+        let mut players = match self.players.write() {
+            Ok(players) => players,
+            Err(e) => {
+                error!("Failed to accept quest: {}", e);
+                return None;
+            }
+        };
+        let player = players.get_mut(player_id)?;
+        let quest = quests::offers(&self.config.quests, player)
+            .into_iter()
+            .find(|quest| quest.id == quest_id)?;
+        player
+            .quests
+            .insert(quest.id.clone(), quests::start(&quest));
+        info!("Player {} accepted quest {}", player_id, quest.id);
+        Some(quest)
+    }
+
+    /// Count `event` toward the quests `player_id` is working on
+    ///
+    /// Quests it finishes are marked completed and hand out their experience,
+    /// scaled by the player's faction modifiers like any other experience, and
+    /// their reward items; items that do not fit in the inventory are left on
+    /// the ground at the player's feet. Returns the quests whose progress changed.
+    pub fn advance_quests(&self, player_id: &str, event: &QuestEvent) -> Vec<QuestUpdate> {
+        let mut updates = Vec::new();
+        let position;
+        match self.players.write() {
+            Ok(mut players) => {
+                let Some(player) = players.get_mut(player_id) else {
+                    return Vec::new();
+                };
+                for quest in &self.config.quests {
+                    let Some(status) = player.quests.get_mut(&quest.id) else {
+                        continue;
+                    };
+                    if !quests::advance(quest, status, event) {
+                        continue;
+                    }
+                    if quests::is_done(quest, status) {
+                        status.completed = true;
+                    }
+                    updates.push(QuestUpdate {
+                        quest: quest.clone(),
+                        status: status.clone(),
+                        experience: 0,
+                        items: Vec::new(),
+                        dropped: Vec::new(),
+                        removed: Vec::new(),
+                    });
+                }
+                let experience_gain = self.effective_modifiers(player).experience_gain;
+                for update in updates.iter_mut().filter(|update| update.status.completed) {
+                    update.experience =
+                        (update.quest.reward_experience as f32 * experience_gain).round() as u32;
+                    Self::gain_experience(player, update.experience);
+                    info!("Player {} completed quest {}", player_id, update.quest.id);
+                }
+                position = player.position;
+            }
+            Err(e) => {
+                error!("Failed to advance quests: {}", e);
+                return Vec::new();
+            }
+        }
+
+        for update in updates.iter_mut().filter(|update| update.status.completed) {
+            for item_id in &update.quest.reward_items {
+                if self.add_item(player_id, item_id, 1) {
+                    update.items.push(item_id.clone());
+                    continue;
+                }
+                let Some(item) = self.item_catalog.get(item_id) else {
+                    continue;
+                };
+                match self.ground_loot.place(item, position) {
+                    Ok((drop, removed)) => {
+                        update.dropped.push(drop);
+                        update.removed.extend(removed);
+                    }
+                    Err(e) => error!("Failed to leave {} for {}: {}", item_id, player_id, e),
+                }
+            }
+        }
+        updates
+    }
+
+    /// Take `dealt_damage`, less the player's defense and any shield, off a player's health
+    ///
+    /// Defeated players pay the death penalty and go down where they fell for
+    /// `respawn_seconds`, after which the simulation respawns them; without a
+    /// respawn delay they respawn at once. Players who are down take no damage.
+    /// Returns `None` if the player is gone or down.
+    fn damage_player<R: Rng>(
+        &self,
+        target_id: &str,
+        dealt_damage: f32,
+        now: u64,
+        rng: &mut R,
+    ) -> Option<DamageOutcome> {
+        let (damage, dropped_item, fallen_at) = match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(target_id)?;
+                if player.effects.contains_key(&StatusEffectKind::Downed) {
+                    return None;
+                }
+                let defense = self.item_stats(player).defense.max(0.0);
+                let damage_taken = self.effective_modifiers(player).damage_taken
+                    * status_effects::damage_taken(&player.effects);
+                let blocked = (defense * ITEM_DAMAGE_PER_DEFENSE) as u32;
+                let damage = ((dealt_damage - blocked as f32).max(0.0) * damage_taken)
+                    .round()
+                    .max(1.0) as u32;
+                player.last_damage_time = now;
+
+                if player.health > damage {
+                    player.health -= damage;
+                    info!(
+                        "Player {} took {} damage, health now: {}",
+                        target_id, damage, player.health
+                    );
+                    return Some(DamageOutcome {
+                        damage,
+                        defeated: false,
+                        penalty: DeathPenalty::default(),
+                    });
+                }
+
+                // Defeated: every effect ends, and the player goes down where they fell
+                player.health = 0;
+                player.effects.clear();
+                player.effects.insert(
+                    StatusEffectKind::Downed,
+                    StatusEffect {
+                        stacks: 1,
+                        expires_at: now + self.config.respawn_seconds,
+                    },
+                );
+                let experience = self.take_death_experience(player);
+                let dropped_item = self.take_death_item(player, rng);
+                info!("Player {} was defeated at {:?}", target_id, player.position);
+                (
+                    DamageOutcome {
+                        damage,
+                        defeated: true,
+                        penalty: DeathPenalty {
+                            experience,
+                            ..Default::default()
+                        },
+                    },
+                    dropped_item,
+                    player.position,
+                )
+            }
+            Err(e) => {
+                error!("Failed to apply damage: {}", e);
+                return None;
+            }
+        };
+
+        let mut outcome = damage;
+        if let Some(item) = dropped_item {
+            match self.ground_loot.place(&item, fallen_at) {
+                Ok((drop, removed)) => {
+                    outcome.penalty.dropped = Some(drop);
+                    outcome.penalty.removed = removed;
+                }
+                Err(e) => error!("Failed to drop {} for {}: {}", item.id, target_id, e),
+            }
+        }
+        if self.config.respawn_seconds == 0 {
+            self.respawn_player(target_id);
+        }
+        Some(outcome)
+    }
+
+    /// Take the share of experience toward the next level set by `death_experience_penalty`
+    ///
+    /// Players never drop a level. Returns the experience taken.
+    fn take_death_experience(&self, player: &mut Player) -> u32 {
+        let level_start = if player.level > 1 {
+            u32::from(player.level) * 100
+        } else {
+            0
+        };
+        let progress = player.experience.saturating_sub(level_start);
+        let lost = (progress as f32 * self.config.death_experience_penalty) as u32;
+        player.experience -= lost;
+        lost
+    }
+
+    /// Take a random carried item off a defeated player, if `death_drops_item` is set
+    fn take_death_item<R: Rng>(&self, player: &mut Player, rng: &mut R) -> Option<CryptoItem> {
+        if !self.config.death_drops_item || player.inventory.is_empty() {
+            return None;
+        }
+        let index = rng.gen_range(0..player.inventory.len());
+        let item_id = player.inventory.keys().nth(index)?.clone();
+        let item = self.item_catalog.get(&item_id)?.clone();
+        if let Some(count) = player.inventory.get_mut(&item_id) {
+            *count -= 1;
+            if *count == 0 {
+                player.inventory.remove(&item_id);
+            }
+        }
+        Some(item)
+    }
+
+    /// Bring a defeated player back at an open spot of the region they fell in, at full health
+    ///
+    /// Returns where they respawned, or `None` if the player is gone.
+    pub fn respawn_player(&self, player_id: &str) -> Option<Position> {
+        let position = match self.players.read() {
+            Ok(players) => {
+                let region = self.regions.index_at(&players.get(player_id)?.position);
+                self.generate_available_position(&players, region)
+            }
+            Err(e) => {
+                error!("Failed to generate respawn position: {}", e);
+                return None;
+            }
+        };
+
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                player.position = position;
+                self.index_player(player_id, &position);
+                player.health = self.max_health(player.level);
+                player.effects.remove(&StatusEffectKind::Downed);
+                info!("Player {} respawned at {:?}", player_id, position);
+                Some(position)
+            }
+            Err(e) => {
+                error!("Failed to respawn player: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Restore up to `amount` health to one player, up to their maximum
+    /// Returns the health restored, or `None` if the player is gone
+    pub fn heal(&self, player_id: &str, amount: u32) -> Option<u32> {
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                let max_health = self.max_health(player.level);
+                let healed = amount.min(max_health.saturating_sub(player.health));
+                player.health += healed;
+                Some(healed)
+            }
+            Err(e) => {
+                error!("Failed to heal player: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Put `kind` on `player_id` until `until`, following its stacking rule
+    ///
+    /// Returns the effect as it now is, or `None` if the player is gone.
+    pub fn add_effect(
+        &self,
+        player_id: &str,
+        kind: StatusEffectKind,
+        until: u64,
+    ) -> Option<StatusEffect> {
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                Some(status_effects::add(&mut player.effects, kind, until))
+            }
+            Err(e) => {
+                error!("Failed to add status effect: {}", e);
+                None
+            }
+        }
+    }
+
+    /// End `kind` on `player_id` early; returns whether it was on them
+    pub fn remove_effect(&self, player_id: &str, kind: StatusEffectKind) -> bool {
+        match self.players.write() {
+            Ok(mut players) => players
+                .get_mut(player_id)
+                .is_some_and(|player| player.effects.remove(&kind).is_some()),
+            Err(e) => {
+                error!("Failed to remove status effect: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Whether `kind` is on `player_id`
+    pub fn has_effect(&self, player_id: &str, kind: StatusEffectKind) -> bool {
+        match self.players.read() {
+            Ok(players) => players
+                .get(player_id)
+                .is_some_and(|player| player.effects.contains_key(&kind)),
+            Err(e) => {
+                warn!("Failed to access players for status effects: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Deal a second's worth of damage from status effects to every player under one
+    ///
+    /// The damage goes through defense and shields like any other. Returns what
+    /// each player affected took, sorted by player ID.
+    pub fn apply_effect_damage<R: Rng>(
+        &self,
+        now: u64,
+        rng: &mut R,
+    ) -> Vec<(String, DamageOutcome)> {
+        let mut damaged: Vec<(String, u32)> = match self.players.read() {
+            Ok(players) => players
+                .values()
+                .map(|player| {
+                    (
+                        player.id.clone(),
+                        status_effects::damage_per_second(&player.effects),
+                    )
+                })
+                .filter(|(_, damage)| *damage > 0)
+                .collect(),
+            Err(e) => {
+                error!("Failed to access players for status effect damage: {}", e);
+                return Vec::new();
+            }
+        };
+        damaged.sort();
+
+        damaged
+            .into_iter()
+            .filter_map(|(player_id, damage)| {
+                let outcome = self.damage_player(&player_id, damage as f32, now, rng)?;
+                Some((player_id, outcome))
+            })
+            .collect()
+    }
+
+    /// Restore up to `amount` health to every player below their maximum who is out of combat
+    ///
+    /// Players are out of combat once `health_regen_delay_seconds` passed since
+    /// they last attacked or lost health; players who are down never regenerate.
+    /// Returns the number of players healed
+    pub fn regenerate_health(&self, amount: u32, now: u64) -> usize {
+        let delay = self.config.health_regen_delay_seconds;
+        match self.players.write() {
+            Ok(mut players) => {
+                let mut healed = 0;
+                for player in players.values_mut() {
+                    let last_fought = player.last_attack_time.max(player.last_damage_time);
+                    if now < last_fought + delay
+                        || player.effects.contains_key(&StatusEffectKind::Downed)
+                    {
+                        continue;
+                    }
+                    let max_health = self.max_health(player.level);
+                    if player.health < max_health {
+                        player.health = (player.health + amount).min(max_health);
+                        healed += 1;
+                    }
+                }
+                healed
+            }
+            Err(e) => {
+                error!("Failed to regenerate health: {}", e);
+                0
+            }
+        }
+    }
+
+    /// Get the items `player_id` carries or has active, sorted by item ID
+    pub fn get_inventory(&self, player_id: &str, now: u64) -> Vec<InventoryItem> {
+        let players = match self.players.read() {
+            Ok(players) => players,
+            Err(e) => {
+                warn!("Failed to access players for inventory: {}", e);
+                return Vec::new();
+            }
+        };
+        let Some(player) = players.get(player_id) else {
+            return Vec::new();
+        };
+
+        let item_ids: BTreeSet<&String> = player
+            .inventory
+            .keys()
+            .chain(player.active_items.keys())
+            .collect();
+        item_ids
+            .into_iter()
+            .filter_map(|item_id| {
+                let item = self.item_catalog.get(item_id)?;
+                Some(InventoryItem {
+                    item: item.clone(),
+                    quantity: player.inventory.get(item_id).copied().unwrap_or(0),
+                    active_seconds: player
+                        .active_items
+                        .get(item_id)
+                        .map_or(0, |expires_at| expires_at.saturating_sub(now)),
+                })
+            })
+            .collect()
+    }
+
+    /// Give `quantity` copies of an item to a player
+    /// Returns false for unknown items or when the player cannot carry that many more
+    pub fn add_item(&self, player_id: &str, item_id: &str, quantity: u32) -> bool {
+        if !self.item_catalog.contains_key(item_id) {
+            warn!("Attempted to give unknown item {}", item_id);
+            return false;
+        }
+
+        match self.players.write() {
+            Ok(mut players) => {
+                let Some(player) = players.get_mut(player_id) else {
+                    return false;
+                };
+                if Self::carried_items(player).saturating_add(quantity)
+                    > self.config.max_inventory_items
+                {
+                    debug!("Inventory of player {} is full", player_id);
+                    return false;
+                }
+                *player.inventory.entry(item_id.to_string()).or_insert(0) += quantity;
+                true
+            }
+            Err(e) => {
+                error!("Failed to add item: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Use up one carried copy of an item, activating its stats for the configured duration
+    /// Using an item that is already active extends its effect. Returns the item
+    /// used, or `None` when the player carries no copy of it.
+    pub fn use_item(&self, player_id: &str, item_id: &str, now: u64) -> Option<CryptoItem> {
+        let item = self.item_catalog.get(item_id)?;
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                Self::take_one(&mut player.inventory, item_id)?;
+                player
+                    .active_items
+                    .insert(item_id.to_string(), now + self.config.item_effect_seconds);
+                debug!("Player {} used item {}", player_id, item_id);
+                Some(item.clone())
+            }
+            Err(e) => {
+                error!("Failed to use item: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Throw away one carried copy of an item
+    /// Returns the item dropped, or `None` when the player carries no copy of it
+    pub fn drop_item(&self, player_id: &str, item_id: &str) -> Option<CryptoItem> {
+        let item = self.item_catalog.get(item_id)?;
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                Self::take_one(&mut player.inventory, item_id)?;
+                debug!("Player {} dropped item {}", player_id, item_id);
+                Some(item.clone())
+            }
+            Err(e) => {
+                error!("Failed to drop item: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Wear a carried item in the slot for its type
+    /// Whatever was worn there goes back into the inventory. Returns the slot and
+    /// the item, or `None` when the player carries no copy of it.
+    pub fn equip_item(
+        &self,
+        player_id: &str,
+        item_id: &str,
+    ) -> Option<(EquipmentSlot, CryptoItem)> {
+        let item = self.item_catalog.get(item_id)?;
+        let slot = EquipmentSlot::for_item_type(&item.item_type);
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                Self::take_one(&mut player.inventory, item_id)?;
+                if let Some(previous) = player.equipment.insert(slot, item_id.to_string()) {
+                    *player.inventory.entry(previous).or_insert(0) += 1;
+                }
+                debug!("Player {} equipped item {}", player_id, item_id);
+                Some((slot, item.clone()))
+            }
+            Err(e) => {
+                error!("Failed to equip item: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Take off the item worn in `slot` and put it back in the inventory
+    /// Returns the item, or `None` when nothing is worn there or the inventory is full
+    pub fn unequip_item(&self, player_id: &str, slot: EquipmentSlot) -> Option<CryptoItem> {
+        match self.players.write() {
+            Ok(mut players) => {
+                let player = players.get_mut(player_id)?;
+                if Self::carried_items(player) >= self.config.max_inventory_items {
+                    return None;
+                }
+                let item_id = player.equipment.remove(&slot)?;
+                let item = self.item_catalog.get(&item_id).cloned();
+                *player.inventory.entry(item_id).or_insert(0) += 1;
+                debug!("Player {} unequipped their {} slot", player_id, slot.name());
+                item
+            }
+            Err(e) => {
+                error!("Failed to unequip item: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Get the items a player wears, by slot
+    pub fn get_equipment(&self, player_id: &str) -> BTreeMap<EquipmentSlot, CryptoItem> {
+        match self.players.read() {
+            Ok(players) => players
+                .get(player_id)
+                .map(|player| {
+                    player
+                        .equipment
+                        .iter()
+                        .filter_map(|(slot, item_id)| {
+                            self.item_catalog
+                                .get(item_id)
+                                .map(|item| (*slot, item.clone()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to access players for equipment: {}", e);
+                BTreeMap::new()
+            }
+        }
+    }
+
+    /// Get the credits a player holds outside of trades
+    pub fn get_credits(&self, player_id: &str) -> u32 {
+        match self.players.read() {
+            Ok(players) => players.get(player_id).map_or(0, |player| player.credits),
+            Err(e) => {
+                warn!("Failed to access players for credits: {}", e);
+                0
+            }
+        }
+    }
+
+    /// Get a player's standing with every faction
+    pub fn get_standings(&self, player_id: &str) -> Vec<FactionStanding> {
+        match self.players.read() {
+            Ok(players) => players.get(player_id).map_or_else(Vec::new, |player| {
+                reputation::standings(
+                    &player.reputation,
+                    &player.rewarded_factions,
+                    self.config.reputation_emote_threshold,
+                )
+            }),
+            Err(e) => {
+                warn!("Failed to access players for reputation: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get a player's standing with one faction
+    pub fn get_reputation(&self, player_id: &str, faction: &Faction) -> i32 {
+        match self.players.read() {
+            Ok(players) => players
+                .get(player_id)
+                .and_then(|player| player.reputation.get(faction).copied())
+                .unwrap_or(0),
+            Err(e) => {
+                warn!("Failed to access players for reputation: {}", e);
+                0
+            }
+        }
+    }
+
+    /// Change a player's standing with each faction in `changes`
+    /// Returns the emotes this unlocked and the factions that handed out their
+    /// signature item, which happens once, when the player has room to carry it,
+    /// or `None` if the player does not exist.
+    pub fn adjust_reputation(
+        &self,
+        player_id: &str,
+        changes: &[(Faction, i32)],
+    ) -> Option<Unlocks> {
+        let mut players = match self.players.write() {
+            Ok(players) => players,
+            Err(e) => {
+                error!("Failed to adjust reputation: {}", e);
+                return None;
+            }
+        };
+        let player = players.get_mut(player_id)?;
+
+        let mut unlocks = Unlocks::default();
+        for (faction, delta) in changes {
+            let score = player.reputation.entry(faction.clone()).or_insert(0);
+            let before = *score;
+            *score = reputation::adjusted(before, *delta);
+            let after = *score;
+            debug!(
+                "Reputation of {} with {} went from {} to {}",
+                player_id,
+                faction.name(),
+                before,
+                after
+            );
+
+            let emote_threshold = self.config.reputation_emote_threshold;
+            if before < emote_threshold && after >= emote_threshold {
+                unlocks.emotes.push(faction.emote());
+            }
+            if after >= self.config.reputation_item_threshold
+                && !player.rewarded_factions.contains(faction)
+                && Self::carried_items(player) < self.config.max_inventory_items
+            {
+                *player
+                    .inventory
+                    .entry(faction.starter_item_id().to_string())
+                    .or_insert(0) += 1;
+                player.rewarded_factions.insert(faction.clone());
+                unlocks.items.push(faction.clone());
+            }
+        }
+        Some(unlocks)
+    }
+
+    /// Put `offer` up in a trade, replacing what the player offered before
+    /// The previous offer goes back first, then the new one moves out of the
+    /// inventory into escrow. Returns false, changing nothing, when the player
+    /// does not hold everything offered.
+    pub fn set_trade_offer(&self, player_id: &str, offer: &TradeAssets) -> bool {
+        match self.players.write() {
+            Ok(mut players) => {
+                let Some(player) = players.get_mut(player_id) else {
+                    return false;
+                };
+                let mut inventory = player.inventory.clone();
+                let mut credits = player.credits.saturating_add(player.escrow.credits);
+                Self::add_assets(&mut inventory, &player.escrow.items);
+
+                if credits < offer.credits {
+                    return false;
+                }
+                credits -= offer.credits;
+                for (item_id, quantity) in &offer.items {
+                    match inventory.get_mut(item_id) {
+                        Some(held) if *held >= *quantity && *quantity > 0 => {
+                            *held -= quantity;
+                            if *held == 0 {
+                                inventory.remove(item_id);
+                            }
+                        }
+                        _ => return false,
+                    }
+                }
+
+                player.inventory = inventory;
+                player.credits = credits;
+                player.escrow = offer.clone();
+                debug!("Player {} offered {:?}", player_id, offer);
+                true
+            }
+            Err(e) => {
+                error!("Failed to set trade offer: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Give a player back whatever they put up in a trade
+    pub fn return_escrow(&self, player_id: &str) {
+        match self.players.write() {
+            Ok(mut players) => {
+                if let Some(player) = players.get_mut(player_id) {
+                    Self::fold_escrow(player);
+                }
+            }
+            Err(e) => {
+                error!("Failed to return escrow of player {}: {}", player_id, e);
+            }
+        }
+    }
+
+    /// Swap what two players put up in their trade
+    /// Both escrows change hands under a single lock, so a failure leaves both
+    /// as they were. Returns false when either player is gone or one of them
+    /// could not carry what they would receive.
+    pub fn complete_trade(&self, first_id: &str, second_id: &str) -> bool {
+        match self.players.write() {
+            Ok(mut players) => {
+                let (Some(first), Some(second)) = (players.get(first_id), players.get(second_id))
+                else {
+                    return false;
+                };
+                let fits = |receiver: &Player, giver: &Player| {
+                    receiver
+                        .inventory
+                        .values()
+                        .sum::<u32>()
+                        .saturating_add(giver.escrow.item_count())
+                        <= self.config.max_inventory_items
+                };
+                if !fits(first, second) || !fits(second, first) {
+                    debug!(
+                        "Trade between {} and {} does not fit their inventories",
+                        first_id, second_id
+                    );
+                    return false;
+                }
+
+                let first_escrow = players
+                    .get_mut(first_id)
+                    .map(|player| std::mem::take(&mut player.escrow))
+                    .unwrap_or_default();
+                let second_escrow = players
+                    .get_mut(second_id)
+                    .map(|player| std::mem::take(&mut player.escrow))
+                    .unwrap_or_default();
+                for (player_id, received) in [(first_id, second_escrow), (second_id, first_escrow)]
+                {
+                    if let Some(player) = players.get_mut(player_id) {
+                        player.credits = player.credits.saturating_add(received.credits);
+                        Self::add_assets(&mut player.inventory, &received.items);
+                    }
+                }
+                info!("Players {} and {} completed a trade", first_id, second_id);
+                true
+            }
+            Err(e) => {
+                error!("Failed to complete trade: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Number of items a player carries, counting those they put up in a trade
+    fn carried_items(player: &Player) -> u32 {
+        player
+            .inventory
+            .values()
+            .sum::<u32>()
+            .saturating_add(player.escrow.item_count())
+    }
+
+    fn fold_escrow(player: &mut Player) {
+        let escrow = std::mem::take(&mut player.escrow);
+        player.credits = player.credits.saturating_add(escrow.credits);
+        Self::add_assets(&mut player.inventory, &escrow.items);
+    }
+
+    fn add_assets(inventory: &mut BTreeMap<String, u32>, items: &BTreeMap<String, u32>) {
+        for (item_id, quantity) in items {
+            *inventory.entry(item_id.clone()).or_insert(0) += quantity;
+        }
+    }
+
+    fn take_one(inventory: &mut BTreeMap<String, u32>, item_id: &str) -> Option<()> {
+        let quantity = inventory.get_mut(item_id)?;
+        *quantity = quantity.saturating_sub(1);
+        if *quantity == 0 {
+            inventory.remove(item_id);
+        }
+        Some(())
+    }
+
+    /// End the effects of items that have worn off by `now`
+    /// Returns each player ID with the item that wore off
+    pub fn expire_items(&self, now: u64) -> Vec<(String, CryptoItem)> {
+        let mut expired = Vec::new();
+        match self.players.write() {
+            Ok(mut players) => {
+                for (player_id, player) in players.iter_mut() {
+                    player.active_items.retain(|item_id, expires_at| {
+                        if *expires_at > now {
+                            return true;
+                        }
+                        if let Some(item) = self.item_catalog.get(item_id) {
+                            expired.push((player_id.clone(), item.clone()));
+                        }
+                        false
+                    });
+                }
+            }
+            Err(e) => {
+                error!("Failed to expire items: {}", e);
+            }
+        }
+        expired
+    }
+
+    /// Remove the status effects that wore off by `now`
+    ///
+    /// Returns the player and kind of every effect removed, sorted by player ID.
+    pub fn expire_effects(&self, now: u64) -> Vec<(String, StatusEffectKind)> {
+        let mut expired = Vec::new();
+        match self.players.write() {
+            Ok(mut players) => {
+                for (player_id, player) in players.iter_mut() {
+                    for kind in status_effects::expire(&mut player.effects, now) {
+                        expired.push((player_id.clone(), kind));
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Failed to expire status effects: {}", e);
+            }
+        }
+        expired.sort();
+        expired
+    }
+
+    /// Combined stats of the items `player_id` wears and has active
+    pub fn get_item_stats(&self, player_id: &str) -> ItemStats {
+        match self.players.read() {
+            Ok(players) => players
+                .get(player_id)
+                .map(|player| self.item_stats(player))
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to access players for item stats: {}", e);
+                ItemStats::default()
+            }
+        }
+    }
+
+    /// Combined stats of the items a player wears and has active
+    pub fn item_stats(&self, player: &Player) -> ItemStats {
+        ItemStats::from_items(
+            player
+                .equipment
+                .values()
+                .chain(player.active_items.keys())
+                .filter_map(|item_id| self.item_catalog.get(item_id)),
+        )
+    }
+
+    /// Faction modifiers that apply to `player` where they stand
+    ///
+    /// Inside a control point their faction owns, players earn more experience
+    /// and draw less surveillance.
+    pub fn effective_modifiers(&self, player: &Player) -> FactionModifiers {
+        let mut modifiers = self.config.faction_modifiers(&player.faction);
+        match self.territory.owner_at(&player.position) {
+            Ok(Some(owner)) if owner == player.faction => {
+                modifiers.experience_gain *= self.config.territory_xp_bonus;
+                modifiers.surveillance_exposure *= self.config.territory_surveillance_factor;
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to look up territory of player {}: {}", player.id, e),
+        }
+        modifiers
+    }
+
+    /// Update a player's last attack time
+    pub fn update_attack_time(&self, player_id: &str, time: u64) {
+        match self.players.write() {
+            Ok(mut state) => {
+                if let Some(player) = state.get_mut(player_id) {
+                    player.last_attack_time = time;
+                }
+            }
+            Err(e) => {
+                warn!("Failed to access players for attack time update: {}", e);
+            }
         }
     }
 
@@ -579,7 +2016,7 @@ impl GameState {
     }
 
     /// Get all sender tags for connected players
-    pub fn get_player_tags(&self) -> Vec<AnonymousSenderTag> {
+    pub fn get_player_tags(&self) -> Vec<SenderTag> {
         match self.connections.lock() {
             Ok(connections) => {
                 // Extract the sender tags from the (player_id, sender_tag) tuples
@@ -669,9 +2106,39 @@ impl GameState {
     /// Restore a player from persistence (used during server recovery)
     /// This method restores player data without creating a network connection
     /// Players will need to reconnect to establish their mixnet connection
-    pub fn restore_player(&self, player_id: String, player: Player) {
+    pub fn restore_player(
+        &self,
+        player_id: String,
+        mut player: Player,
+        reclaim_token_hash: Option<String>,
+    ) {
+        // Trades do not survive a restart, so whatever was put up goes back
+        Self::fold_escrow(&mut player);
+
+        match self.display_id_to_player_id.write() {
+            Ok(mut reverse_lookup) => {
+                reverse_lookup.insert(player.display_id.to_lowercase(), player_id.clone());
+            }
+            Err(e) => {
+                error!("Failed to add reverse lookup mapping: {}", e);
+            }
+        }
+
+        // Players take their character back with Resume using the token they were issued
+        if let Some(hash) = reclaim_token_hash {
+            match self.reclaim_tokens.write() {
+                Ok(mut reclaim_tokens) => {
+                    reclaim_tokens.insert(hash, player_id.clone());
+                }
+                Err(e) => {
+                    error!("Failed to restore reclaim token for {}: {}", player_id, e);
+                }
+            }
+        }
+
         match self.players.write() {
             Ok(mut players) => {
+                self.index_player(&player_id, &player.position);
                 players.insert(player_id.clone(), player.clone());
                 info!(
                     "Restored player {} ({}) at position ({:.1}, {:.1}) with {} health",
@@ -688,8 +2155,32 @@ impl GameState {
         // This preserves the privacy and security properties of the system
     }
 
+    /// Put a player at `position` in the spatial grid and the region index
+    fn index_player(&self, player_id: &str, position: &Position) {
+        match self.spatial_grid.write() {
+            Ok(mut grid) => grid.update(player_id, position),
+            Err(e) => error!("Failed to update spatial grid: {}", e),
+        }
+        match self.region_index.write() {
+            Ok(mut index) => index.update(player_id, self.regions.index_at(position)),
+            Err(e) => error!("Failed to update region index: {}", e),
+        }
+    }
+
+    /// Take a player out of the spatial grid and the region index
+    fn unindex_player(&self, player_id: &str) {
+        match self.spatial_grid.write() {
+            Ok(mut grid) => grid.remove(player_id),
+            Err(e) => error!("Failed to update spatial grid: {}", e),
+        }
+        match self.region_index.write() {
+            Ok(mut index) => index.remove(player_id),
+            Err(e) => error!("Failed to update region index: {}", e),
+        }
+    }
+
     /// Helper method to get sender tag by player ID
-    fn get_sender_tag_by_player_id(&self, player_id: &str) -> Option<AnonymousSenderTag> {
+    fn get_sender_tag_by_player_id(&self, player_id: &str) -> Option<SenderTag> {
         match self.connections.lock() {
             Ok(connections) => connections
                 .iter()
@@ -704,7 +2195,7 @@ impl GameState {
 
     /// Get connection tag (sender tag) for a player by their player ID
     /// This is used for direct communication, such as sending whisper messages
-    pub fn get_connection_tag(&self, player_id: &str) -> Option<AnonymousSenderTag> {
+    pub fn get_connection_tag(&self, player_id: &str) -> Option<SenderTag> {
         // This is essentially the same as get_sender_tag_by_player_id but with a public interface
         self.get_sender_tag_by_player_id(player_id)
     }
@@ -721,8 +2212,19 @@ impl GameState {
         (damage_bonus, health_bonus)
     }
 
-    /// Function to generate a random position that is not already occupied by another player
-    fn generate_available_position(&self, players: &HashMap<String, Player>) -> Position {
+    /// Health of a player at `level` when fully healed, level bonus included
+    pub fn max_health(&self, level: u8) -> u32 {
+        let (_, health_bonus) = Self::calculate_level_bonuses(level);
+        self.config.initial_player_health + health_bonus
+    }
+
+    /// Function to generate a random position in the region with index `region` that is not
+    /// already occupied by another player
+    fn generate_available_position(
+        &self,
+        players: &HashMap<String, Player>,
+        region: usize,
+    ) -> Position {
         let mut rng = thread_rng();
         let position_tolerance: f32 = 2.0; // Minimum distance between players
 
@@ -730,11 +2232,8 @@ impl GameState {
         const MAX_ATTEMPTS: usize = 100;
 
         for _ in 0..MAX_ATTEMPTS {
-            // Generate a random position within configured world boundaries
-            let position = Position {
-                x: rng.gen_range(self.config.world_min_x..self.config.world_max_x),
-                y: rng.gen_range(self.config.world_min_y..self.config.world_max_y),
-            };
+            // Generate a random position within the region's boundaries
+            let position = self.regions.random_position(region, &mut rng);
 
             // Check if this position is far enough from all other players
             let position_is_available = players.values().all(|player| {
@@ -753,9 +2252,216 @@ impl GameState {
 
         // If we can't find an available position after max attempts, just return a random one
         // This is a fallback that should rarely be needed
-        Position {
-            x: rng.gen_range(self.config.world_min_x..self.config.world_max_x),
-            y: rng.gen_range(self.config.world_min_y..self.config.world_max_y),
-        }
+        self.regions.random_position(region, &mut rng)
+    }
+}
+
+/// Hash under which a reclaim token is stored
+fn hash_reclaim_token(token: &str) -> String {
+    general_purpose::STANDARD.encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::game_protocol::QuestObjective;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Damage is dealt at the time the simulation tick passes in, well after
+    // players joined by the real clock
+    const NOW: u64 = 4_000_000_000;
+
+    // Faction rules have their own tests; here every faction hits alike
+    fn world(config: GameConfig) -> GameState {
+        GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::new(),
+            enable_npcs: false,
+            ..config
+        })
+    }
+
+    fn spawn(game_state: &GameState, name: &str) -> String {
+        game_state.add_player(name.to_string(), Faction::Nyms, SenderTag::random())
+    }
+
+    #[test]
+    fn test_defeated_players_are_down_until_they_respawn() {
+        let game_state = world(GameConfig {
+            initial_player_health: 10,
+            respawn_seconds: 5,
+            death_experience_penalty: 0.5,
+            death_drops_item: true,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice");
+        let bob = spawn(&game_state, "bob");
+        game_state
+            .players
+            .write()
+            .unwrap()
+            .get_mut(&bob)
+            .unwrap()
+            .experience = 60;
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let outcome = game_state.apply_damage(&bob, &alice, 20, NOW, &mut rng);
+        assert!(outcome.defeated);
+        assert_eq!(outcome.penalty.experience, 30);
+        let dropped = outcome.penalty.dropped.unwrap();
+        assert_eq!(dropped.item_id, Faction::Nyms.starter_item_id());
+
+        let downed = game_state.get_player(&bob).unwrap();
+        assert_eq!(downed.health, 0);
+        assert_eq!(downed.experience, 30);
+        assert_eq!(downed.last_damage_time, NOW);
+        assert!(downed.inventory.is_empty());
+        assert_eq!(
+            downed.effects[&StatusEffectKind::Downed].expires_at,
+            NOW + 5
+        );
+
+        // Players who are down take no more damage and do not regenerate
+        assert_eq!(
+            game_state.apply_damage(&bob, &alice, 20, NOW + 1, &mut rng),
+            DamageOutcome::default()
+        );
+        assert_eq!(game_state.regenerate_health(5, NOW + 60), 0);
+
+        // Respawning restores the level bonus too
+        game_state
+            .players
+            .write()
+            .unwrap()
+            .get_mut(&bob)
+            .unwrap()
+            .level = 3;
+        assert!(game_state.respawn_player(&bob).is_some());
+        assert_eq!(game_state.max_health(3), 20);
+        assert_eq!(game_state.get_player(&bob).unwrap().health, 20);
+        assert!(!game_state.has_effect(&bob, StatusEffectKind::Downed));
+        assert_eq!(game_state.respawn_player("gone"), None);
+    }
+
+    #[test]
+    fn test_the_same_rolls_drop_the_same_item() {
+        let dropped = || {
+            let game_state = world(GameConfig {
+                initial_player_health: 10,
+                death_drops_item: true,
+                ..GameConfig::default()
+            });
+            let alice = spawn(&game_state, "alice");
+            let bob = spawn(&game_state, "bob");
+            for item_id in ["mixnet_relay", "zk_prover", "quantum_shield"] {
+                assert!(game_state.add_item(&bob, item_id, 1));
+            }
+            game_state
+                .apply_damage(&bob, &alice, 20, NOW, &mut StdRng::seed_from_u64(11))
+                .penalty
+                .dropped
+                .unwrap()
+                .item_id
+        };
+        assert_eq!(dropped(), dropped());
+    }
+
+    #[test]
+    fn test_death_penalty_is_off_by_default_and_keeps_levels() {
+        let game_state = world(GameConfig {
+            initial_player_health: 10,
+            respawn_seconds: 0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice");
+        let bob = spawn(&game_state, "bob");
+
+        // Without a respawn timer players are back on their feet straight away
+        let outcome = game_state.apply_damage(&bob, &alice, 20, NOW, &mut StdRng::seed_from_u64(7));
+        assert!(outcome.defeated);
+        assert_eq!(outcome.penalty, DeathPenalty::default());
+        let respawned = game_state.get_player(&bob).unwrap();
+        assert_eq!(respawned.health, 10);
+        assert_eq!(respawned.inventory.len(), 1);
+        assert!(respawned.effects.is_empty());
+
+        // Only experience gained since the last level up can be lost
+        let penalized = world(GameConfig {
+            death_experience_penalty: 1.0,
+            ..game_state.get_config().clone()
+        });
+        let mut player = respawned;
+        player.level = 2;
+        player.experience = 250;
+        assert_eq!(penalized.take_death_experience(&mut player), 50);
+        assert_eq!(player.experience, 200);
+        assert_eq!(penalized.take_death_experience(&mut player), 0);
+    }
+
+    #[test]
+    fn test_quest_rewards_follow_faction_modifiers_and_never_vanish() {
+        let game_state = GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::from([(
+                Faction::Nyms,
+                FactionModifiers {
+                    experience_gain: 1.5,
+                    ..Default::default()
+                },
+            )]),
+            enable_npcs: false,
+            max_inventory_items: 1,
+            quests: vec![QuestDefinition {
+                id: "tip_off".to_string(),
+                name: "Tip Off".to_string(),
+                description: "Have a word with the broker.".to_string(),
+                faction: None,
+                objectives: vec![QuestObjective::Whisper {
+                    npc: "Broker1".to_string(),
+                }],
+                reward_experience: 25,
+                reward_items: vec!["zk_prover".to_string()],
+            }],
+            ..GameConfig::default()
+        });
+        let nym = spawn(&game_state, "nym");
+        let position = game_state.get_player(&nym).unwrap().position;
+        assert!(game_state.accept_quest(&nym, "tip_off").is_some());
+
+        let updates = game_state.advance_quests(&nym, &QuestEvent::Whispered("Broker1"));
+        assert_eq!(updates.len(), 1);
+        let update = &updates[0];
+        assert!(update.status.completed);
+        assert_eq!(update.experience, 38);
+        assert_eq!(game_state.get_player(&nym).unwrap().experience, 38);
+
+        // The starter item fills the inventory, so the reward waits on the ground
+        assert!(update.items.is_empty());
+        assert_eq!(update.dropped.len(), 1);
+        assert_eq!(update.dropped[0].item_id, "zk_prover");
+        assert_eq!(update.dropped[0].position, position);
+        assert_eq!(game_state.ground_loot().drop_count(), 1);
+    }
+
+    #[test]
+    fn test_health_regenerates_once_out_of_combat() {
+        let game_state = world(GameConfig {
+            health_regen_delay_seconds: 5,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice");
+        let bob = spawn(&game_state, "bob");
+        let hit_at = NOW;
+        let outcome =
+            game_state.apply_damage(&bob, &alice, 10, hit_at, &mut StdRng::seed_from_u64(7));
+        assert!(!outcome.defeated);
+        let hurt = game_state.get_player(&bob).unwrap().health;
+
+        assert_eq!(game_state.regenerate_health(3, hit_at + 1), 0);
+        assert_eq!(game_state.regenerate_health(3, hit_at + 60), 1);
+        assert_eq!(game_state.get_player(&bob).unwrap().health, hurt + 3);
+
+        // Attacking keeps a player in combat too
+        game_state.update_attack_time(&bob, hit_at + 60);
+        assert_eq!(game_state.regenerate_health(3, hit_at + 61), 0);
     }
 }
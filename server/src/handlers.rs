@@ -1,24 +1,27 @@
 #![allow(clippy::clone_on_copy)]
 
 use anyhow::Result;
-use nym_sdk::mixnet::{AnonymousSenderTag, MixnetClient, MixnetMessageSender};
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, trace, warn};
 
-use crate::message_auth::{AuthKey, AuthenticatedMessage};
-use crate::message_padding::pad_message;
 use crate::mixnet_monitor::MixnetMonitor;
+use nymquest_protocol::message_padding::pad_message;
+use nymquest_protocol::transport::{GameTransport, SenderTag};
 
 use crate::config::GameConfig;
-use crate::game_protocol::{
+use crate::game_state::GameState;
+use crate::sessions::SessionManager;
+use crate::simulation::PlayerIntent;
+use crate::state_sync::PlayerSnapshot;
+use nymquest_protocol::game_protocol::{
     ClientMessage, ClientMessageType, Direction, EmoteType, Position, ProtocolVersion,
-    ServerMessage, WorldBoundaries,
+    ServerMessage, ServerMessageType,
 };
-use crate::game_state::GameState;
 
 /// Message priority enum for privacy-enhancing load management
 /// Different message types have different priorities to prevent
@@ -43,10 +46,24 @@ pub fn get_message_priority(msg_type: &ClientMessageType) -> MessagePriority {
         ClientMessageType::Disconnect => MessagePriority::Critical,
         ClientMessageType::Heartbeat => MessagePriority::High,
         ClientMessageType::Register => MessagePriority::High,
+        ClientMessageType::Resume => MessagePriority::High,
 
         // Gameplay affecting actions
         ClientMessageType::Move => MessagePriority::Medium,
         ClientMessageType::Attack => MessagePriority::Medium,
+        ClientMessageType::UseItem => MessagePriority::Medium,
+        ClientMessageType::DropItem => MessagePriority::Medium,
+        ClientMessageType::Equip => MessagePriority::Medium,
+        ClientMessageType::Unequip => MessagePriority::Medium,
+        ClientMessageType::PickUp => MessagePriority::Medium,
+        ClientMessageType::TradeRequest => MessagePriority::Medium,
+        ClientMessageType::TradeOffer => MessagePriority::Medium,
+        ClientMessageType::TradeAccept => MessagePriority::Medium,
+        ClientMessageType::TradeCancel => MessagePriority::Medium,
+        ClientMessageType::QuestAccept => MessagePriority::Medium,
+        ClientMessageType::UseAbility => MessagePriority::Medium,
+        ClientMessageType::Inventory => MessagePriority::Low,
+        ClientMessageType::Reputation => MessagePriority::Low,
 
         // Social interactions (lower priority)
         ClientMessageType::Chat => MessagePriority::Low,
@@ -179,7 +196,7 @@ fn next_seq_num() -> u64 {
 
 /// Track a mixnet message sending attempt for monitoring purposes
 /// This function is privacy-preserving as it only tracks success/failure without content details
-pub async fn track_message_send(result: std::result::Result<(), nym_sdk::Error>) -> Result<()> {
+pub async fn track_message_send(result: Result<()>) -> Result<()> {
     // Get a reference to the mixnet monitor
     let monitor = MixnetMonitor::new();
 
@@ -193,7 +210,7 @@ pub async fn track_message_send(result: std::result::Result<(), nym_sdk::Error>)
         Err(e) => {
             // Record failed send
             monitor.record_send_failure();
-            Err(anyhow::anyhow!("{}", e))
+            Err(e)
         }
     }
 }
@@ -226,7 +243,8 @@ pub async fn apply_message_processing_jitter(
     }
 
     // Calculate jitter to add randomness to timing (prevents timing analysis)
-    let mut rng = rand::thread_rng();
+    // StdRng is Send, unlike ThreadRng, so it can live across the await below
+    let mut rng = StdRng::from_entropy();
     let max_jitter = calculate_max_jitter(base_interval_ms, jitter_percent);
 
     // Adjust jitter based on message priority if provided
@@ -425,7 +443,7 @@ impl ReplayProtectionWindow {
             self.window |= 1;
 
             // Consider adjusting window size periodically
-            if self.total_messages % 100 == 0 {
+            if self.total_messages.is_multiple_of(100) {
                 self.adjust_window_size();
             }
 
@@ -458,7 +476,7 @@ impl ReplayProtectionWindow {
         self.window |= mask;
 
         // Consider adjusting window size periodically
-        if self.total_messages % 100 == 0 {
+        if self.total_messages.is_multiple_of(100) {
             self.adjust_window_size();
         }
 
@@ -472,7 +490,7 @@ lazy_static::lazy_static! {
 }
 
 // Check if we've seen this message before (replay protection)
-fn is_message_replay(tag: &AnonymousSenderTag, seq_num: u64) -> bool {
+fn is_message_replay(tag: &SenderTag, seq_num: u64) -> bool {
     // Get a string representation of the sender tag for HashMap lookup
     let tag_str = tag.to_string();
 
@@ -546,12 +564,12 @@ fn get_replay_protection_window_size() -> u8 {
 
 /// Send an acknowledgment message back to the client
 #[allow(clippy::clone_on_copy)]
-async fn send_ack(
-    client: &MixnetClient,
-    sender_tag: &AnonymousSenderTag,
+async fn send_ack<T: GameTransport>(
+    client: &T,
+    sender_tag: &SenderTag,
     seq_num: u64,
     msg_type: ClientMessageType,
-    auth_key: &AuthKey,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Create acknowledgment
     let ack = ServerMessage::Ack {
@@ -562,7 +580,7 @@ async fn send_ack(
     // Create an authenticated acknowledgment message with expiration
     // Short expiration time as acknowledgments are only relevant for a short period
     let message_ttl = 30; // 30 seconds
-    let authenticated_ack = AuthenticatedMessage::new_with_expiration(ack, auth_key, message_ttl)?;
+    let authenticated_ack = sessions.authenticate_with_expiration(sender_tag, ack, message_ttl)?;
 
     let ack_json = String::from_utf8(serde_json::to_vec(&authenticated_ack)?)?;
 
@@ -573,12 +591,12 @@ async fn send_ack(
 }
 
 /// Broadcast a server shutdown notification to all connected players
-pub async fn broadcast_shutdown_notification(
-    client: &MixnetClient,
+pub async fn broadcast_shutdown_notification<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     message: &str,
     shutdown_in_seconds: u8,
-    _auth_key: &AuthKey,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Get all connected players
     let player_tags = game_state.get_player_tags();
@@ -597,11 +615,25 @@ pub async fn broadcast_shutdown_notification(
         shutdown_in_seconds,
     };
 
-    let message_json = String::from_utf8(serde_json::to_vec(&shutdown_msg)?)?;
-
-    // Broadcast to all players
+    // Broadcast to all players, authenticated and signed so clients can tell it
+    // really came from the server
     for tag in player_tags {
-        if let Err(e) = client.send_reply(tag.clone(), message_json.clone()).await {
+        let message_json = match sessions
+            .authenticate(&tag, shutdown_msg.clone())
+            .and_then(pad_message)
+            .and_then(|padded| Ok(String::from_utf8(serde_json::to_vec(&padded)?)?))
+        {
+            Ok(message_json) => message_json,
+            Err(e) => {
+                warn!(
+                    "Failed to prepare shutdown notification for a player: {}",
+                    e
+                );
+                continue;
+            }
+        };
+
+        if let Err(e) = client.send_reply(tag.clone(), message_json).await {
             warn!("Failed to send shutdown notification to a player: {}", e);
         }
     }
@@ -610,34 +642,133 @@ pub async fn broadcast_shutdown_notification(
     Ok(())
 }
 
-/// Broadcast game state to all active players
-#[allow(dead_code)]
-pub async fn broadcast_game_state(
-    client: &MixnetClient,
+/// Broadcast the latest game state to all active players
+///
+/// Each player receives only the players within their view. Connections with an
+/// acknowledged baseline get a delta against it, others a full snapshot; see `state_sync`.
+pub async fn broadcast_game_state<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
-    exclude_tag: Option<AnonymousSenderTag>,
-    auth_key: &AuthKey,
+    exclude_tag: Option<SenderTag>,
+    sessions: &SessionManager,
 ) -> Result<()> {
-    // Get the current game state
-    let players = game_state.get_players();
+    send_state_updates(client, game_state, exclude_tag, false, sessions).await
+}
 
-    // Create the game state message
-    let game_state_message = ServerMessage::GameState {
-        players,
-        seq_num: next_seq_num(),
+/// Broadcast a full snapshot (keyframe) of the game state to all active players
+///
+/// Sent periodically so clients that missed or could not apply deltas resync.
+pub async fn broadcast_game_state_keyframe<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    sessions: &SessionManager,
+) -> Result<()> {
+    send_state_updates(client, game_state, None, true, sessions).await
+}
+
+/// Run one simulation tick and send its results
+///
+/// Replies go to each player's current connection in the order the tick
+/// produced them, followed by a single state update if anything changed.
+pub async fn run_simulation_tick<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let outcome = {
+        let mut rng = thread_rng();
+        game_state
+            .simulation()
+            .tick(game_state, now, &mut rng, next_seq_num)?
     };
 
-    // Create an authenticated message with HMAC and expiration
-    // Default to a reasonable expiration time (5 minutes) for game state messages
-    let message_ttl = 300; // 5 minutes
-    let authenticated_message =
-        AuthenticatedMessage::new_with_expiration(game_state_message, auth_key, message_ttl)?;
+    // Short expiration time as tick results are only relevant for a short period
+    let message_ttl = 30; // 30 seconds
+    for (player_id, message) in outcome.replies {
+        // The player may have disconnected during the tick
+        let Some(tag) = game_state.get_connection_tag(&player_id) else {
+            continue;
+        };
+        // A connection without a session must not cost everyone else their replies
+        let authenticated = match sessions.authenticate_with_expiration(&tag, message, message_ttl)
+        {
+            Ok(authenticated) => authenticated,
+            Err(e) => {
+                warn!(
+                    "Failed to authenticate tick result for player {}: {}",
+                    player_id, e
+                );
+                continue;
+            }
+        };
+        if let Err(e) = client
+            .send_reply(tag, serde_json::to_string(&authenticated)?)
+            .await
+        {
+            warn!("Failed to send tick result to player {}: {}", player_id, e);
+        }
+    }
+
+    if !outcome.loot_changed_at.is_empty() {
+        send_loot_updates(client, game_state, &outcome.loot_changed_at, sessions).await?;
+    }
+
+    if outcome.state_changed {
+        broadcast_game_state(client, game_state, None, sessions).await?;
+    }
+
+    Ok(())
+}
+
+/// Send a fresh loot list to every player who can see one of the spots in `changed_at`
+async fn send_loot_updates<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    changed_at: &[Position],
+    sessions: &SessionManager,
+) -> Result<()> {
+    let message_ttl = 30; // 30 seconds
+    let view_radius = game_state.get_config().view_radius;
+
+    for (player_id, tag) in game_state.get_connections() {
+        let Some(player) = game_state.get_player(&player_id) else {
+            continue;
+        };
+        if !changed_at
+            .iter()
+            .any(|position| position.distance_to(&player.position) <= view_radius)
+        {
+            continue;
+        }
 
-    // Apply message padding to prevent size correlation attacks
-    let padded_message = pad_message(authenticated_message)?;
-    debug!("Applied message padding to game state broadcast for enhanced privacy");
+        let loot = ServerMessage::Loot {
+            drops: game_state.get_visible_loot(&player_id),
+            seq_num: next_seq_num(),
+        };
+        let authenticated = sessions.authenticate_with_expiration(&tag, loot, message_ttl)?;
+        if let Err(e) = client
+            .send_reply(tag, serde_json::to_string(&authenticated)?)
+            .await
+        {
+            warn!("Failed to send loot to player {}: {}", player_id, e);
+        }
+    }
+    Ok(())
+}
 
-    let serialized = String::from_utf8(serde_json::to_vec(&padded_message)?)?;
+async fn send_state_updates<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    exclude_tag: Option<SenderTag>,
+    keyframe: bool,
+    sessions: &SessionManager,
+) -> Result<()> {
+    // Default to a reasonable expiration time (5 minutes) for game state messages
+    let message_ttl = 300; // 5 minutes
 
     // Get a copy of all active connections
     let connections = game_state.get_connections();
@@ -654,18 +785,63 @@ pub async fn broadcast_game_state(
             }
         }
 
-        // Send the update to this player and track failures
-        let send_result = client.send_reply(tag.clone(), serialized.clone()).await;
-
-        // Track message send attempt with mixnet monitoring
-        match track_message_send(send_result).await {
-            Ok(_) => {
-                // Successfully sent
-                debug!("Game state sent to player {}", player_id);
-            }
+        // Each player only learns about the players within their view
+        let players = Arc::new(game_state.get_visible_player_views(&player_id));
+
+        let mut updates = Vec::with_capacity(2);
+        match game_state
+            .state_sync()
+            .next_update(&tag, &players, keyframe, next_seq_num)
+        {
+            Ok(Some(update)) => updates.push(update),
+            // Nothing changed since the state this player acknowledged
+            Ok(None) => {}
             Err(e) => {
-                error!("Failed to send game state to player {}: {}", player_id, e);
-                failed_tags.push(tag);
+                error!(
+                    "Failed to build game state update for player {}: {}",
+                    player_id, e
+                );
+                continue;
+            }
+        }
+        match visibility_update(game_state, &player_id, &tag, &players) {
+            Ok(Some(update)) => updates.push(update),
+            Ok(None) => {}
+            Err(e) => error!("Failed to track visibility for player {}: {}", player_id, e),
+        }
+
+        for update in updates {
+            // Authenticate with this player's session key and pad to prevent size correlation attacks
+            let serialized = match sessions
+                .authenticate_with_expiration(&tag, update, message_ttl)
+                .and_then(pad_message)
+                .and_then(|padded| Ok(String::from_utf8(serde_json::to_vec(&padded)?)?))
+            {
+                Ok(serialized) => serialized,
+                Err(e) => {
+                    error!(
+                        "Failed to prepare game state for player {}: {}",
+                        player_id, e
+                    );
+                    failed_tags.push(tag);
+                    break;
+                }
+            };
+
+            // Send the update to this player and track failures
+            let send_result = client.send_reply(tag.clone(), serialized).await;
+
+            // Track message send attempt with mixnet monitoring
+            match track_message_send(send_result).await {
+                Ok(_) => {
+                    // Successfully sent
+                    debug!("Game state sent to player {}", player_id);
+                }
+                Err(e) => {
+                    error!("Failed to send game state to player {}: {}", player_id, e);
+                    failed_tags.push(tag);
+                    break;
+                }
             }
         }
     }
@@ -680,6 +856,36 @@ pub async fn broadcast_game_state(
     Ok(())
 }
 
+/// Build the notice of players that came into or went out of `player_id`'s view, if any did
+fn visibility_update(
+    game_state: &GameState,
+    player_id: &str,
+    tag: &SenderTag,
+    players: &PlayerSnapshot,
+) -> Result<Option<ServerMessage>> {
+    let own_display_id = game_state
+        .get_player(player_id)
+        .map(|player| player.display_id);
+    let visible = players
+        .keys()
+        .filter(|display_id| Some(*display_id) != own_display_id.as_ref())
+        .cloned()
+        .collect();
+
+    let (entered, mut left) = game_state.state_sync().update_visibility(tag, visible)?;
+    // Players who left the game did not go out of view
+    left.retain(|display_id| game_state.get_player_id_by_display_id(display_id).is_some());
+    if entered.is_empty() && left.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ServerMessage::VisibilityChanged {
+        entered,
+        left,
+        seq_num: next_seq_num(),
+    }))
+}
+
 // Global rate limiter instance for DoS protection
 lazy_static::lazy_static! {
     static ref GLOBAL_RATE_LIMITER: Arc<Mutex<Option<RateLimiter>>> = Arc::new(Mutex::new(None));
@@ -707,12 +913,12 @@ pub fn cleanup_rate_limiter() {
 }
 
 /// Handle a message from a client
-pub async fn handle_client_message(
-    client: &MixnetClient,
+pub async fn handle_client_message<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     message: ClientMessage,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Check rate limit
     let should_rate_limit = if let Ok(mut limiter_guard) = GLOBAL_RATE_LIMITER.lock() {
@@ -733,7 +939,7 @@ pub async fn handle_client_message(
             message: "Rate limit exceeded. Please slow down your message frequency.".to_string(),
             seq_num: next_seq_num(),
         };
-        let authenticated_response = AuthenticatedMessage::new(error_msg, auth_key)?;
+        let authenticated_response = sessions.authenticate(&sender_tag, error_msg)?;
         let response_str = String::from_utf8(serde_json::to_vec(&authenticated_response)?)?;
 
         // Send reply to the rate-limited client
@@ -747,9 +953,20 @@ pub async fn handle_client_message(
     let msg_type = message.get_type();
 
     // Handle acknowledgments separately and directly
-    if let ClientMessage::Ack { .. } = &message {
-        // We don't need to do anything with acks in this simple implementation
-        // In a more complex system, we might track which messages were acknowledged
+    if let ClientMessage::Ack {
+        server_seq_num,
+        original_type,
+    } = &message
+    {
+        // An acknowledged state update becomes the baseline for this connection's deltas
+        if matches!(
+            original_type,
+            ServerMessageType::GameState | ServerMessageType::GameStateDelta
+        ) {
+            game_state
+                .state_sync()
+                .acknowledge(&sender_tag, *server_seq_num)?;
+        }
         return Ok(());
     }
 
@@ -794,7 +1011,7 @@ pub async fn handle_client_message(
     }
 
     // Send acknowledgment first for all non-ack messages
-    send_ack(client, &sender_tag, seq_num, msg_type, auth_key).await?;
+    send_ack(client, &sender_tag, seq_num, msg_type, sessions).await?;
 
     // Process the message based on its type
     match message {
@@ -803,83 +1020,43 @@ pub async fn handle_client_message(
             faction,
             seq_num: _,
             protocol_version,
+            session_public_key: _, // Session already opened when the message was verified
         } => {
-            // First, check protocol version compatibility
-            let server_version = ProtocolVersion::default();
-            let negotiated_version = match server_version.negotiate_with(&protocol_version) {
-                Some(version) => {
-                    info!(
-                        "Protocol version negotiated: v{} (client: v{}, server: v{})",
-                        version, protocol_version.current, server_version.current
-                    );
-                    version
-                }
-                None => {
-                    error!("Protocol version incompatible: client v{} (min: v{}), server v{} (min: v{})",
-                           protocol_version.current, protocol_version.min_supported,
-                           server_version.current, server_version.min_supported);
-
-                    // Send error message for incompatible version
-                    let error_msg = ServerMessage::Error {
-                        message: format!("Protocol version incompatible. Server supports v{}-v{}, client requested v{}-v{}",
-                                       server_version.min_supported, server_version.current,
-                                       protocol_version.min_supported, protocol_version.current),
-                        seq_num: next_seq_num(),
-                    };
-
-                    let authenticated_error = AuthenticatedMessage::new(error_msg, auth_key)?;
-                    // Apply message padding for privacy protection
-                    let padded_error = pad_message(authenticated_error)?;
-                    let error_json = String::from_utf8(serde_json::to_vec(&padded_error)?)?;
-                    client.send_reply(sender_tag.clone(), error_json).await?;
-
-                    return Ok(());
-                }
+            let Some(negotiated_version) =
+                negotiate_protocol_version(client, &protocol_version, sender_tag, sessions).await?
+            else {
+                return Ok(());
             };
 
             // Check if this sender_tag is already associated with a registered player
             if let Some(existing_player_id) = game_state.get_player_id(&sender_tag) {
-                // Client is already registered, send an error message
-                let error_msg = ServerMessage::Error {
-                    message: format!("Player {} is already registered", existing_player_id),
-                    seq_num: next_seq_num(),
-                };
-
-                let authenticated_error = AuthenticatedMessage::new(error_msg, auth_key)?;
-                // Apply message padding for privacy protection
-                let padded_error = pad_message(authenticated_error)?;
-                let error_json = serde_json::to_string(&padded_error)?;
-
-                client.send_reply(sender_tag.clone(), error_json).await?;
-                return Ok(());
+                return send_already_registered(
+                    client,
+                    game_state,
+                    &existing_player_id,
+                    sender_tag,
+                    sessions,
+                )
+                .await;
             }
 
             // Register the new player with their chosen faction
             let player_id = game_state.add_player(name, faction, sender_tag);
+            let reclaim_token = game_state.issue_reclaim_token(&player_id);
 
-            // Create a successful registration response with negotiated version
-            let register_ack = ServerMessage::RegisterAck {
-                player_id: player_id.clone(),
-                seq_num: next_seq_num(),
-                world_boundaries: WorldBoundaries::from_config(game_state.get_config()),
+            send_register_ack(
+                client,
+                game_state,
+                &player_id,
+                reclaim_token,
                 negotiated_version,
-            };
-
-            // Create an authenticated message with expiration
-            // Higher timeout (10 minutes) for registration acks since they're critical for initial connection
-            let message_ttl = 600; // 10 minutes
-            let authenticated_ack =
-                AuthenticatedMessage::new_with_expiration(register_ack, auth_key, message_ttl)?;
-
-            let register_ack_json = serde_json::to_string(&authenticated_ack)?;
-
-            // Send the registration confirmation to the new player
-            client
-                .send_reply(sender_tag.clone(), register_ack_json)
-                .await?;
+                sender_tag,
+                sessions,
+            )
+            .await?;
 
             // Broadcast updated game state to all players
-            broadcast_game_state(client, game_state, None, auth_key).await?;
+            broadcast_game_state(client, game_state, None, sessions).await?;
 
             info!(
                 "New player registered: {} (protocol v{})",
@@ -887,18 +1064,141 @@ pub async fn handle_client_message(
             );
             Ok(())
         }
+        ClientMessage::Resume {
+            token,
+            seq_num: _,
+            protocol_version,
+            session_public_key: _, // Session already opened when the message was verified
+        } => {
+            handle_resume(
+                client,
+                game_state,
+                token,
+                protocol_version,
+                sender_tag,
+                sessions,
+            )
+            .await
+        }
         ClientMessage::Move { direction, .. } => {
-            handle_move(client, game_state, direction, sender_tag, auth_key).await
+            handle_move(client, game_state, direction, sender_tag, sessions).await
         }
         ClientMessage::Attack {
             target_display_id, ..
-        } => handle_attack(client, game_state, target_display_id, sender_tag, auth_key).await,
+        } => handle_attack(client, game_state, target_display_id, sender_tag, sessions).await,
         ClientMessage::Chat { message, .. } => {
-            handle_chat(client, game_state, message, sender_tag, auth_key).await
+            handle_chat(client, game_state, message, sender_tag, sessions).await
         }
         ClientMessage::Emote { emote_type, .. } => {
-            handle_emote(client, game_state, emote_type, sender_tag, auth_key).await
+            handle_emote(client, game_state, emote_type, sender_tag, sessions).await
+        }
+        ClientMessage::UseItem { item_id, .. } => {
+            let intent = PlayerIntent::UseItem { item_id };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::DropItem { item_id, .. } => {
+            let intent = PlayerIntent::DropItem { item_id };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::Equip { item_id, .. } => {
+            let intent = PlayerIntent::Equip { item_id };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::Unequip { slot, .. } => {
+            let intent = PlayerIntent::Unequip { slot };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::PickUp { .. } => {
+            handle_item_intent(
+                client,
+                game_state,
+                PlayerIntent::PickUp,
+                sender_tag,
+                sessions,
+            )
+            .await
+        }
+        ClientMessage::TradeRequest {
+            target_display_id, ..
+        } => {
+            let intent = PlayerIntent::TradeRequest { target_display_id };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::TradeOffer {
+            target_display_id,
+            offer,
+            ..
+        } => {
+            let intent = PlayerIntent::TradeOffer {
+                target_display_id,
+                offer,
+            };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::TradeAccept {
+            target_display_id,
+            revision,
+            ..
+        } => {
+            let intent = PlayerIntent::TradeAccept {
+                target_display_id,
+                revision,
+            };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::TradeCancel {
+            target_display_id, ..
+        } => {
+            let intent = PlayerIntent::TradeCancel { target_display_id };
+            handle_item_intent(client, game_state, intent, sender_tag, sessions).await
+        }
+        ClientMessage::QuestAccept { quest_id, .. } => {
+            match game_state.get_player_id(&sender_tag) {
+                Some(player_id) => game_state
+                    .simulation()
+                    .enqueue(&player_id, PlayerIntent::AcceptQuest { quest_id }),
+                None => {
+                    let error = ServerMessage::Error {
+                        message: "You need to register before taking on quests".to_string(),
+                        seq_num: next_seq_num(),
+                    };
+                    let message =
+                        serde_json::to_string(&sessions.authenticate(&sender_tag, error)?)?;
+                    client.send_reply(sender_tag, message).await
+                }
+            }
         }
+        ClientMessage::UseAbility {
+            ability, target, ..
+        } => match game_state.get_player_id(&sender_tag) {
+            Some(player_id) => game_state.simulation().enqueue(
+                &player_id,
+                PlayerIntent::UseAbility {
+                    ability,
+                    target_display_id: target,
+                },
+            ),
+            None => {
+                let error = ServerMessage::Error {
+                    message: "You need to register before using abilities".to_string(),
+                    seq_num: next_seq_num(),
+                };
+                let message = serde_json::to_string(&sessions.authenticate(&sender_tag, error)?)?;
+                client.send_reply(sender_tag, message).await
+            }
+        },
+        ClientMessage::Inventory { .. } => match game_state.get_player_id(&sender_tag) {
+            Some(player_id) => {
+                send_items(client, game_state, &player_id, sender_tag, sessions).await
+            }
+            None => Ok(()),
+        },
+        ClientMessage::Reputation { .. } => match game_state.get_player_id(&sender_tag) {
+            Some(player_id) => {
+                send_reputation(client, game_state, &player_id, sender_tag, sessions).await
+            }
+            None => Ok(()),
+        },
         ClientMessage::Disconnect { seq_num } => {
             debug!("Processing disconnect message with seq_num: {}", seq_num);
 
@@ -908,12 +1208,12 @@ pub async fn handle_client_message(
                 &sender_tag,
                 seq_num,
                 ClientMessageType::Disconnect,
-                auth_key,
+                sessions,
             )
             .await?;
 
             // Handle the disconnection
-            handle_disconnect(client, game_state, sender_tag, auth_key).await?;
+            handle_disconnect(client, game_state, sender_tag, sessions).await?;
             Ok(())
         }
         ClientMessage::Heartbeat { seq_num } => {
@@ -925,12 +1225,12 @@ pub async fn handle_client_message(
                 &sender_tag,
                 seq_num,
                 ClientMessageType::Heartbeat,
-                auth_key,
+                sessions,
             )
             .await?;
 
             // Update the heartbeat timestamp
-            handle_heartbeat(client, game_state, sender_tag, auth_key).await?;
+            handle_heartbeat(client, game_state, sender_tag, sessions).await?;
             Ok(())
         }
         ClientMessage::Ack { .. } => {
@@ -950,7 +1250,7 @@ pub async fn handle_client_message(
                 &sender_tag,
                 seq_num,
                 ClientMessageType::Whisper,
-                auth_key,
+                sessions,
             )
             .await?;
 
@@ -961,21 +1261,228 @@ pub async fn handle_client_message(
                 target_display_id,
                 message,
                 sender_tag,
-                auth_key,
+                sessions,
             )
             .await
         }
     }
 }
 
+/// Negotiate the protocol version with a registering client
+/// Sends an error and returns None if the versions are incompatible
+async fn negotiate_protocol_version<T: GameTransport>(
+    client: &T,
+    protocol_version: &ProtocolVersion,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<Option<u16>> {
+    let server_version = ProtocolVersion::default();
+    match server_version.negotiate_with(protocol_version) {
+        Some(version) => {
+            info!(
+                "Protocol version negotiated: v{} (client: v{}, server: v{})",
+                version, protocol_version.current, server_version.current
+            );
+            Ok(Some(version))
+        }
+        None => {
+            error!(
+                "Protocol version incompatible: client v{} (min: v{}), server v{} (min: v{})",
+                protocol_version.current,
+                protocol_version.min_supported,
+                server_version.current,
+                server_version.min_supported
+            );
+
+            // Send error message for incompatible version
+            let error_msg = ServerMessage::Error {
+                message: format!(
+                    "Protocol version incompatible. Server supports v{}-v{}, client requested v{}-v{}",
+                    server_version.min_supported,
+                    server_version.current,
+                    protocol_version.min_supported,
+                    protocol_version.current
+                ),
+                seq_num: next_seq_num(),
+            };
+
+            let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
+            // Apply message padding for privacy protection
+            let padded_error = pad_message(authenticated_error)?;
+            let error_json = String::from_utf8(serde_json::to_vec(&padded_error)?)?;
+            client.send_reply(sender_tag, error_json).await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Tell a client that its connection already has a player
+async fn send_already_registered<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    existing_player_id: &str,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let display_id = game_state
+        .get_player(existing_player_id)
+        .map(|player| player.display_id)
+        .unwrap_or_default();
+    let error_msg = ServerMessage::Error {
+        message: format!("You are already registered as {}", display_id),
+        seq_num: next_seq_num(),
+    };
+
+    let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
+    // Apply message padding for privacy protection
+    let padded_error = pad_message(authenticated_error)?;
+    let error_json = serde_json::to_string(&padded_error)?;
+
+    client.send_reply(sender_tag, error_json).await
+}
+
+/// Confirm a registration or resumed session to the player
+async fn send_register_ack<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    player_id: &str,
+    reclaim_token: String,
+    negotiated_version: u16,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    // Only the public display ID is sent; the internal player ID never leaves the server
+    let player = game_state.get_player(player_id);
+    let display_id = player
+        .as_ref()
+        .map(|player| player.display_id.clone())
+        .unwrap_or_default();
+    // Players start out in the region they are in, which is the home region for new ones
+    let regions = game_state.regions();
+    let region = player.map_or(regions.home(), |player| regions.region_at(&player.position));
+
+    // Create a successful registration response with negotiated version
+    let register_ack = ServerMessage::RegisterAck {
+        display_id,
+        seq_num: next_seq_num(),
+        world_boundaries: region.boundaries.clone(),
+        negotiated_version,
+        reclaim_token,
+    };
+
+    // Create an authenticated message with expiration
+    // Higher timeout (10 minutes) for registration acks since they're critical for initial connection
+    let message_ttl = 600; // 10 minutes
+    let authenticated_ack =
+        sessions.authenticate_with_expiration(&sender_tag, register_ack, message_ttl)?;
+
+    let register_ack_json = serde_json::to_string(&authenticated_ack)?;
+
+    // Send the registration confirmation to the player, then the terrain and characters around them, what
+    // they carry, their ability cooldowns, their standing, their quests and who holds the control points
+    client.send_reply(sender_tag, register_ack_json).await?;
+    let terrain = ServerMessage::Terrain {
+        terrain: region.terrain.clone(),
+        seq_num: next_seq_num(),
+    };
+    let terrain_json = serde_json::to_string(&sessions.authenticate(&sender_tag, terrain)?)?;
+    client.send_reply(sender_tag, terrain_json).await?;
+    if game_state.get_config().enable_npcs {
+        let npcs = game_state.get_visible_npcs(player_id);
+        game_state.npcs().record_sent(player_id, &npcs)?;
+        let update = ServerMessage::NpcUpdate {
+            npcs,
+            seq_num: next_seq_num(),
+        };
+        let update_json = serde_json::to_string(&sessions.authenticate(&sender_tag, update)?)?;
+        client.send_reply(sender_tag, update_json).await?;
+    }
+    send_items(client, game_state, player_id, sender_tag, sessions).await?;
+    send_cooldowns(client, game_state, player_id, sender_tag, sessions).await?;
+    send_reputation(client, game_state, player_id, sender_tag, sessions).await?;
+    if !game_state.get_config().quests.is_empty() {
+        send_quests(client, game_state, player_id, sender_tag, sessions).await?;
+    }
+    send_territory(client, game_state, sender_tag, sessions).await
+}
+
+/// Handle a returning player taking back their character with a reclaim token
+async fn handle_resume<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    token: String,
+    protocol_version: ProtocolVersion,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let Some(negotiated_version) =
+        negotiate_protocol_version(client, &protocol_version, sender_tag, sessions).await?
+    else {
+        return Ok(());
+    };
+
+    if let Some(existing_player_id) = game_state.get_player_id(&sender_tag) {
+        return send_already_registered(
+            client,
+            game_state,
+            &existing_player_id,
+            sender_tag,
+            sessions,
+        )
+        .await;
+    }
+
+    let player_id = match game_state.reclaim_player(&token, sender_tag) {
+        Some(id) => id,
+        None => {
+            warn!("Rejected Resume with an unknown reclaim token");
+
+            let error_msg = ServerMessage::Error {
+                message: "No character found for this reclaim token. Use /register to create a new character.".to_string(),
+                seq_num: next_seq_num(),
+            };
+
+            let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
+            // Apply message padding for privacy protection
+            let padded_error = pad_message(authenticated_error)?;
+            let error_json = serde_json::to_string(&padded_error)?;
+
+            client.send_reply(sender_tag, error_json).await?;
+            return Ok(());
+        }
+    };
+
+    // The token stays valid, so the client keeps the one it already has
+    send_register_ack(
+        client,
+        game_state,
+        &player_id,
+        token,
+        negotiated_version,
+        sender_tag,
+        sessions,
+    )
+    .await?;
+
+    // Broadcast updated game state to all players
+    broadcast_game_state(client, game_state, None, sessions).await?;
+
+    info!(
+        "Player resumed: {} (protocol v{})",
+        player_id, negotiated_version
+    );
+    Ok(())
+}
+
 /// Handle a private message (whisper) between players
-async fn handle_whisper(
-    client: &MixnetClient,
+async fn handle_whisper<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     target_display_id: String,
     message: String,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Find the player ID from sender tag
     let sender_id = match game_state.get_player_id(&sender_tag) {
@@ -986,7 +1493,7 @@ async fn handle_whisper(
                 message: "You must be registered to send whispers".to_string(),
                 seq_num: next_seq_num(),
             };
-            let authenticated_error = AuthenticatedMessage::new(error_msg, auth_key)?;
+            let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
             let error_json = serde_json::to_string(&authenticated_error)?;
             client.send_reply(sender_tag, error_json).await?;
             return Ok(());
@@ -1002,6 +1509,18 @@ async fn handle_whisper(
         }
     };
 
+    // Whispers to characters are answered by the simulation, which knows who can hear them
+    if game_state
+        .get_player_id_by_display_id(&target_display_id)
+        .is_none()
+        && game_state.npcs().get(&target_display_id).is_some()
+    {
+        let intent = PlayerIntent::WhisperNpc {
+            npc_display_id: target_display_id,
+        };
+        return game_state.simulation().enqueue(&sender_id, intent);
+    }
+
     // Find the target player by display ID
     let target_player_id = match game_state.get_player_id_by_display_id(&target_display_id) {
         Some(id) => id,
@@ -1011,7 +1530,7 @@ async fn handle_whisper(
                 message: format!("Player '{}' not found", target_display_id),
                 seq_num: next_seq_num(),
             };
-            let authenticated_error = AuthenticatedMessage::new(error_msg, auth_key)?;
+            let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
             let error_json = serde_json::to_string(&authenticated_error)?;
             client.send_reply(sender_tag, error_json).await?;
             return Ok(());
@@ -1027,7 +1546,7 @@ async fn handle_whisper(
                 message: format!("Player '{}' is not connected", target_display_id),
                 seq_num: next_seq_num(),
             };
-            let authenticated_error = AuthenticatedMessage::new(error_msg, auth_key)?;
+            let authenticated_error = sessions.authenticate(&sender_tag, error_msg)?;
             let error_json = serde_json::to_string(&authenticated_error)?;
             client.send_reply(sender_tag, error_json).await?;
             return Ok(());
@@ -1045,7 +1564,7 @@ async fn handle_whisper(
     // Short expiration time as whispers are only relevant for a short period
     let message_ttl = 300; // 5 minutes
     let authenticated_whisper =
-        AuthenticatedMessage::new_with_expiration(whisper_msg, auth_key, message_ttl)?;
+        sessions.authenticate_with_expiration(&target_tag, whisper_msg, message_ttl)?;
 
     let whisper_json = serde_json::to_string(&authenticated_whisper)?;
     client.send_reply(target_tag, whisper_json).await?;
@@ -1058,101 +1577,21 @@ async fn handle_whisper(
 }
 
 /// Handle player movement
-async fn handle_move(
-    client: &MixnetClient,
+///
+/// The move is queued and applied on the next simulation tick.
+async fn handle_move<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     direction: Direction,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Find the player ID from sender tag
     if let Some(player_id) = game_state.get_player_id(&sender_tag) {
-        // Get the current player information
-        if let Some(player) = game_state.get_player(&player_id) {
-            // Calculate movement vector
-            let (dx, dy) = direction.to_vector();
-
-            // Get config for movement parameters and boundaries
-            let config = game_state.get_config();
-
-            // Use the configured movement speed from server settings
-            // This ensures consistency between server behavior and client configuration
-            let movement_speed = config.movement_speed;
-
-            // Apply movement vector to get new position
-            let mut new_position = Position {
-                x: player.position.x + dx * movement_speed,
-                y: player.position.y + dy * movement_speed,
-            };
-
-            // Ensure the position stays within world boundaries
-            let (clamped_x, clamped_y) = config.clamp_position(new_position.x, new_position.y);
-            new_position.x = clamped_x;
-            new_position.y = clamped_y;
-
-            // Check for collisions with other players before updating position
-            let collision_detected = {
-                // Get all other players to check for collisions
-                let other_players = game_state.get_all_players_except(&player_id);
-
-                // Define minimum distance between players (based on the configured collision radius)
-                let min_distance = config.player_collision_radius * 2.0; // Twice the radius for two players
-
-                // Check if the new position would collide with any other player
-                other_players.iter().any(|other_player| {
-                    WorldBoundaries::would_positions_collide(
-                        &new_position,
-                        &other_player.position,
-                        min_distance,
-                    )
-                })
-            };
-
-            if !collision_detected {
-                // No collision detected, update the position
-                if game_state.update_player_position(&player_id, new_position) {
-                    // Movement was successful
-                    // Provide immediate feedback to the player who moved
-                    let move_confirm = ServerMessage::Event {
-                        message: format!(
-                            "Moved {:?} to position ({:.1}, {:.1})",
-                            direction, new_position.x, new_position.y
-                        ),
-                        seq_num: next_seq_num(),
-                    };
-
-                    // Create an authenticated message with expiration
-                    // Short expiration time as movement confirmations are only relevant for a short period
-                    let message_ttl = 30; // 30 seconds
-                    let authenticated_confirm = AuthenticatedMessage::new_with_expiration(
-                        move_confirm,
-                        auth_key,
-                        message_ttl,
-                    )?;
-
-                    let confirm_msg = serde_json::to_string(&authenticated_confirm)?;
-                    client.send_reply(sender_tag.clone(), confirm_msg).await?;
-
-                    // Broadcast updated state to all players
-                    broadcast_game_state(client, game_state, None, auth_key).await?
-                }
-            } else {
-                // Collision detected with another player
-                let error_msg = ServerMessage::Error {
-                    message: "Cannot move to that position - another player is already there"
-                        .to_string(),
-                    seq_num: next_seq_num(),
-                };
-
-                // Create an authenticated message with expiration
-                // Short expiration time as error messages are only relevant for a short period
-                let message_ttl = 30; // 30 seconds
-                let authenticated_error =
-                    AuthenticatedMessage::new_with_expiration(error_msg, auth_key, message_ttl)?;
-
-                let message = serde_json::to_string(&authenticated_error)?;
-                client.send_reply(sender_tag.clone(), message).await?;
-            }
+        if game_state.get_player(&player_id).is_some() {
+            game_state
+                .simulation()
+                .enqueue(&player_id, PlayerIntent::Move { direction })?;
         } else {
             // Player not found
             let error_msg = ServerMessage::Error {
@@ -1164,7 +1603,7 @@ async fn handle_move(
             // Short expiration time as error messages are only relevant for a short period
             let message_ttl = 30; // 30 seconds
             let authenticated_error =
-                AuthenticatedMessage::new_with_expiration(error_msg, auth_key, message_ttl)?;
+                sessions.authenticate_with_expiration(&sender_tag, error_msg, message_ttl)?;
 
             let message = serde_json::to_string(&authenticated_error)?;
             client.send_reply(sender_tag.clone(), message).await?;
@@ -1175,242 +1614,189 @@ async fn handle_move(
 }
 
 /// Handle player attacks
-async fn handle_attack(
-    client: &MixnetClient,
+///
+/// Unknown targets are rejected right away; cooldown and range are checked when
+/// the attack is applied on the next simulation tick, since positions may change
+/// until then.
+async fn handle_attack<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     target_display_id: String,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Find the attacker ID from sender tag
     if let Some(attacker_id) = game_state.get_player_id(&sender_tag) {
-        // Convert target display ID to real ID
-        let target_id = match game_state.get_player_id_by_display_id(&target_display_id) {
-            Some(id) => id,
-            None => {
-                // Target display ID doesn't exist
-                let error = ServerMessage::Error {
-                    message: format!("Attack failed: Player '{}' not found.", target_display_id),
-                    seq_num: next_seq_num(),
-                };
-                let message = serde_json::to_string(&error)?;
-                client.send_reply(sender_tag.clone(), message).await?;
-                return Ok(());
-            }
-        };
-
-        info!(
-            "Player {} attacking player with display ID {}",
-            attacker_id, target_display_id
-        );
-
-        // Get current time
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        // Check if the player is on cooldown (using configuration)
-        if !game_state.can_attack(&attacker_id, now) {
-            // Get remaining cooldown time for better error message
-            let remaining_cooldown = match game_state.get_player(&attacker_id) {
-                Some(player) => {
-                    let config = game_state.get_config();
-                    let time_since_last = now.saturating_sub(player.last_attack_time);
-                    config
-                        .attack_cooldown_seconds
-                        .saturating_sub(time_since_last)
-                }
-                None => 0,
-            };
-
-            // Send an error message if on cooldown
-            let cooldown_msg = ServerMessage::Error {
-                message: format!(
-                    "Attack on cooldown! Wait {} more seconds.",
-                    remaining_cooldown
-                ),
+        if game_state
+            .get_player_id_by_display_id(&target_display_id)
+            .is_none()
+            && game_state.npcs().get(&target_display_id).is_none()
+        {
+            // Target display ID is neither a player nor a character
+            let error = ServerMessage::Error {
+                message: format!("Attack failed: Player '{}' not found.", target_display_id),
                 seq_num: next_seq_num(),
             };
-            let message = serde_json::to_string(&cooldown_msg)?;
+            let message = serde_json::to_string(&sessions.authenticate(&sender_tag, error)?)?;
             client.send_reply(sender_tag.clone(), message).await?;
             return Ok(());
         }
 
-        // Check if attacker and target are within range (using configuration)
-        let attack_range = game_state.get_config().attack_range;
-
-        // Get attacker's position
-        let attacker_pos = match game_state.get_player(&attacker_id) {
-            Some(player) => player.position,
-            None => {
-                // This shouldn't happen but handle it anyway
-                let error = ServerMessage::Error {
-                    message: "Attack failed: Unable to find your player.".to_string(),
-                    seq_num: next_seq_num(),
-                };
-                let message = serde_json::to_string(&error)?;
-                client.send_reply(sender_tag.clone(), message).await?;
-                return Ok(());
-            }
-        };
-
-        // Get target's position
-        let target_pos = match game_state.get_player(&target_id) {
-            Some(player) => player.position,
-            None => {
-                // Target doesn't exist
-                let error = ServerMessage::Error {
-                    message: "Attack failed: Target does not exist.".to_string(),
-                    seq_num: next_seq_num(),
-                };
-                let message = serde_json::to_string(&error)?;
-                client.send_reply(sender_tag.clone(), message).await?;
-                return Ok(());
-            }
-        };
+        game_state
+            .simulation()
+            .enqueue(&attacker_id, PlayerIntent::Attack { target_display_id })?;
+    }
 
-        // Calculate distance between attacker and target
-        let distance = attacker_pos.distance_to(&target_pos);
+    Ok(())
+}
 
-        if distance > attack_range {
-            // Target is out of range
+/// Handle using, dropping, equipping, unequipping or trading items
+///
+/// Whether the player carries the items is checked when the intent is applied on
+/// the next simulation tick, after any earlier intents changed their inventory.
+async fn handle_item_intent<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    intent: PlayerIntent,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    match game_state.get_player_id(&sender_tag) {
+        Some(player_id) => game_state.simulation().enqueue(&player_id, intent),
+        None => {
             let error = ServerMessage::Error {
-                message: format!(
-                    "Attack failed: Target is out of range ({:.1} > {:.1}).",
-                    distance, attack_range
-                ),
+                message: "You need to register before using or trading items".to_string(),
                 seq_num: next_seq_num(),
             };
-            let message = serde_json::to_string(&error)?;
-            client.send_reply(sender_tag.clone(), message).await?;
-            return Ok(());
+            let message = serde_json::to_string(&sessions.authenticate(&sender_tag, error)?)?;
+            client.send_reply(sender_tag, message).await
         }
+    }
+}
 
-        // Update the last attack time
-        game_state.update_attack_time(&attacker_id, now);
-
-        // Get target player's name and sender tag for notification
-        let (target_name, target_tag) = {
-            let connections = game_state.get_connections();
-            let mut target_name = "Unknown".to_string();
-            let mut target_tag = None;
-
-            // Find the target's connection
-            for (id, tag) in connections {
-                if id == target_id {
-                    if let Some(player) = game_state.get_player(&id) {
-                        target_name = player.name.clone();
-                    }
-                    target_tag = Some(tag);
-                    break;
-                }
-            }
-
-            (target_name, target_tag)
-        };
-
-        // Get attacker's name for the notification
-        let attacker_name = game_state
-            .get_player(&attacker_id)
-            .map(|p| p.name.clone())
-            .unwrap_or("Unknown".to_string());
-
-        // Apply damage
-        let config = game_state.get_config();
-        let base_damage = config.base_damage;
-        let crit_chance = config.crit_chance;
-        let crit_multiplier = config.crit_multiplier;
+/// Send a player their inventory, their equipment with its combined stats and the loot around them
+async fn send_items<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    player_id: &str,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let inventory = ServerMessage::Inventory {
+        items: game_state.get_inventory(player_id, now),
+        credits: game_state.get_credits(player_id),
+        seq_num: next_seq_num(),
+    };
+    let equipment = ServerMessage::Equipment {
+        slots: game_state.get_equipment(player_id),
+        stats: game_state.get_item_stats(player_id),
+        seq_num: next_seq_num(),
+    };
 
-        // Calculate if this is a critical hit
-        let mut rng = thread_rng();
-        let is_critical = rng.gen::<f32>() < crit_chance;
+    let loot = ServerMessage::Loot {
+        drops: game_state.get_visible_loot(player_id),
+        seq_num: next_seq_num(),
+    };
 
-        // Calculate final damage
-        let damage = if is_critical {
-            (base_damage as f32 * crit_multiplier) as u32
-        } else {
-            base_damage
-        };
+    for reply in [inventory, equipment, loot] {
+        let message = serde_json::to_string(&sessions.authenticate(&sender_tag, reply)?)?;
+        client.send_reply(sender_tag, message).await?;
+    }
+    Ok(())
+}
 
-        // Apply damage and check if target was defeated
-        let target_defeated = game_state.apply_damage(&target_id, &attacker_id, damage);
+/// Send a player their standing with every faction
+async fn send_reputation<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    player_id: &str,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let config = game_state.get_config();
+    let update = ServerMessage::ReputationUpdate {
+        standings: game_state.get_standings(player_id),
+        emote_threshold: config.reputation_emote_threshold,
+        item_threshold: config.reputation_item_threshold,
+        seq_num: next_seq_num(),
+    };
+    let message = serde_json::to_string(&sessions.authenticate(&sender_tag, update)?)?;
+    client.send_reply(sender_tag, message).await
+}
 
-        // Send notification to the target
-        if let Some(tag) = target_tag {
-            // Create attack notification for target player
-            let attack_notification = ServerMessage::Event {
-                message: format!(
-                    "⚠️ You are being attacked by {}! You lost {} health points.",
-                    attacker_name, damage
-                ),
-                seq_num: next_seq_num(),
-            };
+/// Send a player the cooldown of every ability
+async fn send_cooldowns<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    player_id: &str,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cooldowns = ServerMessage::AbilityCooldowns {
+        cooldowns: game_state.abilities().cooldowns(player_id, now)?,
+        seq_num: next_seq_num(),
+    };
+    let message = serde_json::to_string(&sessions.authenticate(&sender_tag, cooldowns)?)?;
+    client.send_reply(sender_tag, message).await
+}
 
-            // Create an authenticated message with expiration
-            // Short expiration time as attack notifications are only relevant for a short period
-            let message_ttl = 30; // 30 seconds
-            let authenticated_notification = AuthenticatedMessage::new_with_expiration(
-                attack_notification,
-                auth_key,
-                message_ttl,
-            )?;
-
-            let notification_msg = serde_json::to_string(&authenticated_notification)?;
-            client.send_reply(tag.clone(), notification_msg).await?;
-        }
-
-        // Send notification to the attacker
-        let attacker_notification = ServerMessage::Event {
-            message: if target_defeated {
-                format!(
-                    "You defeated {}{}",
-                    target_name,
-                    if is_critical {
-                        " with a critical hit!"
-                    } else {
-                        "!"
-                    }
-                )
-            } else {
-                format!(
-                    "You hit {} for {} damage{}",
-                    target_name,
-                    damage,
-                    if is_critical { " (CRITICAL HIT!)" } else { "" }
-                )
-            },
+/// Send a player the quests on offer to them and their progress on those they took on
+async fn send_quests<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    player_id: &str,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let offer = ServerMessage::QuestOffer {
+        quests: game_state.get_quest_offers(player_id),
+        seq_num: next_seq_num(),
+    };
+    let message = serde_json::to_string(&sessions.authenticate(&sender_tag, offer)?)?;
+    client.send_reply(sender_tag, message).await?;
+    for (quest, status) in game_state.get_active_quests(player_id) {
+        let progress = ServerMessage::QuestProgress {
+            quest,
+            progress: status.progress,
             seq_num: next_seq_num(),
         };
-
-        // Create an authenticated message with expiration
-        // Short expiration time as attack notifications are only relevant for a short period
-        let message_ttl = 30; // 30 seconds
-        let authenticated_notification = AuthenticatedMessage::new_with_expiration(
-            attacker_notification,
-            auth_key,
-            message_ttl,
-        )?;
-
-        let attacker_msg = serde_json::to_string(&authenticated_notification)?;
-        client.send_reply(sender_tag.clone(), attacker_msg).await?;
-
-        // This event is now sent in the move handler itself, we don't need to send it again here
-
-        // Broadcast the updated game state to all players
-        broadcast_game_state(client, game_state, None, auth_key).await?;
+        let message = serde_json::to_string(&sessions.authenticate(&sender_tag, progress)?)?;
+        client.send_reply(sender_tag, message).await?;
     }
-
     Ok(())
 }
 
+/// Send a player every control point and who holds it
+async fn send_territory<T: GameTransport>(
+    client: &T,
+    game_state: &Arc<GameState>,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
+) -> Result<()> {
+    let update = ServerMessage::TerritoryUpdate {
+        control_points: game_state.territory().control_points()?,
+        capture_seconds: game_state.get_config().territory_capture_seconds,
+        seq_num: next_seq_num(),
+    };
+    let message = serde_json::to_string(&sessions.authenticate(&sender_tag, update)?)?;
+    client.send_reply(sender_tag, message).await
+}
+
 /// Handle emote messages
-async fn handle_emote(
-    client: &MixnetClient,
+async fn handle_emote<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     emote_type: EmoteType,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Find the player ID from sender tag
     if let Some(sender_id) = game_state.get_player_id(&sender_tag) {
@@ -1418,6 +1804,26 @@ async fn handle_emote(
         if let Some(player) = game_state.get_player(&sender_id) {
             let sender_name = player.name.clone();
 
+            // Faction emotes need enough standing with the faction
+            if let Some(faction) = emote_type.required_faction() {
+                let threshold = game_state.get_config().reputation_emote_threshold;
+                let score = player.reputation.get(&faction).copied().unwrap_or(0);
+                if score < threshold {
+                    let error = ServerMessage::Error {
+                        message: format!(
+                            "You need {} reputation with the {} for that emote (you have {})",
+                            threshold,
+                            faction.display_name(),
+                            score
+                        ),
+                        seq_num: next_seq_num(),
+                    };
+                    let message =
+                        serde_json::to_string(&sessions.authenticate(&sender_tag, error)?)?;
+                    return client.send_reply(sender_tag, message).await;
+                }
+            }
+
             // Create the emote message to broadcast to other players
             let emote_msg = format!(
                 "{} {} {}",
@@ -1433,13 +1839,8 @@ async fn handle_emote(
                 seq_num: next_seq_num(),
             };
 
-            // Create an authenticated chat message with expiration
             // Short expiration time as emotes are only relevant for a short period
-            let message_ttl = 30; // 30 seconds
-            let authenticated_chat =
-                AuthenticatedMessage::new_with_expiration(chat_msg, auth_key, message_ttl)?;
-
-            let serialized = serde_json::to_string(&authenticated_chat)?;
+            let chat_ttl = 30; // 30 seconds
 
             // Create confirmation message for the sender
             let confirm_msg = ServerMessage::Event {
@@ -1451,7 +1852,7 @@ async fn handle_emote(
             // Short expiration time as emote confirmations are only relevant for a short period
             let message_ttl = 30; // 30 seconds
             let authenticated_confirm =
-                AuthenticatedMessage::new_with_expiration(confirm_msg, auth_key, message_ttl)?;
+                sessions.authenticate_with_expiration(&sender_tag, confirm_msg, message_ttl)?;
 
             let confirm_serialized = serde_json::to_string(&authenticated_confirm)?;
 
@@ -1479,7 +1880,19 @@ async fn handle_emote(
             for (player_id, tag) in connections {
                 // Skip sending to the original sender by comparing the tag bytes
                 if tag.to_string().into_bytes() != exclude_bytes {
-                    match client.send_reply(tag.clone(), serialized.clone()).await {
+                    // Each recipient gets a copy authenticated with their own session key
+                    let serialized = match sessions
+                        .authenticate_with_expiration(&tag, chat_msg.clone(), chat_ttl)
+                        .and_then(|message| Ok(serde_json::to_string(&message)?))
+                    {
+                        Ok(serialized) => serialized,
+                        Err(e) => {
+                            error!("Failed to prepare emote for player {}: {}", player_id, e);
+                            continue;
+                        }
+                    };
+
+                    match client.send_reply(tag.clone(), serialized).await {
                         Ok(_) => {
                             trace!("Emote message sent to player {}", player_id);
                         }
@@ -1501,12 +1914,12 @@ async fn handle_emote(
 }
 
 /// Handle chat messages
-async fn handle_chat(
-    client: &MixnetClient,
+async fn handle_chat<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
     message: String,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
     // Find the player ID from sender tag
     if let Some(sender_id) = game_state.get_player_id(&sender_tag) {
@@ -1521,13 +1934,8 @@ async fn handle_chat(
                 seq_num: next_seq_num(),
             };
 
-            // Create an authenticated chat message with expiration
             // Short expiration time as chat messages are only relevant for a short period
-            let message_ttl = 300; // 5 minutes
-            let authenticated_chat =
-                AuthenticatedMessage::new_with_expiration(chat_msg, auth_key, message_ttl)?;
-
-            let serialized = serde_json::to_string(&authenticated_chat)?;
+            let chat_ttl = 300; // 5 minutes
 
             // Create confirmation message for the sender
             let confirm_msg = ServerMessage::Event {
@@ -1539,7 +1947,7 @@ async fn handle_chat(
             // Short expiration time as chat confirmations are only relevant for a short period
             let message_ttl = 30; // 30 seconds
             let authenticated_confirm =
-                AuthenticatedMessage::new_with_expiration(confirm_msg, auth_key, message_ttl)?;
+                sessions.authenticate_with_expiration(&sender_tag, confirm_msg, message_ttl)?;
 
             let confirm_serialized = serde_json::to_string(&authenticated_confirm)?;
 
@@ -1563,7 +1971,22 @@ async fn handle_chat(
             for (player_id, tag) in connections {
                 // Skip sending to the original sender by comparing the tag bytes
                 if tag.to_string().into_bytes() != exclude_bytes {
-                    match client.send_reply(tag.clone(), serialized.clone()).await {
+                    // Each recipient gets a copy authenticated with their own session key
+                    let serialized = match sessions
+                        .authenticate_with_expiration(&tag, chat_msg.clone(), chat_ttl)
+                        .and_then(|message| Ok(serde_json::to_string(&message)?))
+                    {
+                        Ok(serialized) => serialized,
+                        Err(e) => {
+                            error!(
+                                "Failed to prepare chat message for player {}: {}",
+                                player_id, e
+                            );
+                            continue;
+                        }
+                    };
+
+                    match client.send_reply(tag.clone(), serialized).await {
                         Ok(_) => {
                             info!("Chat message sent to player {}", player_id);
                         }
@@ -1582,29 +2005,30 @@ async fn handle_chat(
 }
 
 /// Handle player disconnection
-async fn handle_disconnect(
-    client: &MixnetClient,
+async fn handle_disconnect<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
-    sender_tag: AnonymousSenderTag,
-    auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    sessions: &SessionManager,
 ) -> Result<()> {
-    // Remove the player
+    // Remove the player and forget the connection's session key
+    sessions.close(&sender_tag);
     if let Some(player_id) = game_state.remove_player(&sender_tag) {
         info!("Player {} disconnected", player_id);
 
         // Broadcast the updated game state to all remaining players
-        broadcast_game_state(client, game_state, None, auth_key).await?;
+        broadcast_game_state(client, game_state, None, sessions).await?;
     }
 
     Ok(())
 }
 
 /// Handle heartbeat messages
-async fn handle_heartbeat(
-    _client: &MixnetClient,
+async fn handle_heartbeat<T: GameTransport>(
+    _client: &T,
     game_state: &Arc<GameState>,
-    sender_tag: AnonymousSenderTag,
-    _auth_key: &AuthKey,
+    sender_tag: SenderTag,
+    _sessions: &SessionManager,
 ) -> Result<()> {
     // Find the player ID from sender tag
     if let Some(player_id) = game_state.get_player_id(&sender_tag) {
@@ -1620,10 +2044,10 @@ async fn handle_heartbeat(
 }
 
 /// Send heartbeat request to all connected players
-pub async fn send_heartbeat_requests(
-    client: &MixnetClient,
+pub async fn send_heartbeat_requests<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
-    auth_key: &AuthKey,
+    sessions: &SessionManager,
 ) -> Result<()> {
     let connections = game_state.get_connections();
 
@@ -1635,17 +2059,8 @@ pub async fn send_heartbeat_requests(
         seq_num: next_seq_num(),
     };
 
-    // Create an authenticated request with expiration
     // Short expiration time as heartbeat requests are only relevant for a short period
     let message_ttl = 30; // 30 seconds
-    let authenticated_request =
-        AuthenticatedMessage::new_with_expiration(heartbeat_request, auth_key, message_ttl)?;
-
-    // Apply message padding to prevent size correlation attacks
-    let padded_request = pad_message(authenticated_request)?;
-    debug!("Applied message padding to heartbeat request for enhanced privacy");
-
-    let serialized = serde_json::to_string(&padded_request)?;
 
     debug!(
         "Sending heartbeat requests to {} players",
@@ -1653,7 +2068,23 @@ pub async fn send_heartbeat_requests(
     );
 
     for (player_id, tag) in connections {
-        match client.send_reply(tag.clone(), serialized.clone()).await {
+        // Authenticate with this player's session key and pad to prevent size correlation attacks
+        let serialized = match sessions
+            .authenticate_with_expiration(&tag, heartbeat_request.clone(), message_ttl)
+            .and_then(pad_message)
+            .and_then(|padded| Ok(serde_json::to_string(&padded)?))
+        {
+            Ok(serialized) => serialized,
+            Err(e) => {
+                warn!(
+                    "Failed to prepare heartbeat request for player {}: {}",
+                    player_id, e
+                );
+                continue;
+            }
+        };
+
+        match client.send_reply(tag.clone(), serialized).await {
             Ok(_) => {
                 trace!("Heartbeat request sent to player {}", player_id);
             }
@@ -1670,10 +2101,10 @@ pub async fn send_heartbeat_requests(
 }
 
 /// Check for inactive players and remove them from the game
-pub async fn cleanup_inactive_players(
-    client: &MixnetClient,
+pub async fn cleanup_inactive_players<T: GameTransport>(
+    client: &T,
     game_state: &Arc<GameState>,
-    auth_key: &AuthKey,
+    sessions: &SessionManager,
 ) -> Result<()> {
     let inactive_players = game_state.get_inactive_players();
 
@@ -1693,8 +2124,802 @@ pub async fn cleanup_inactive_players(
         info!("Removed {} inactive players", removed_players.len());
 
         // Broadcast updated game state to remaining players
-        broadcast_game_state(client, game_state, None, auth_key).await?;
+        broadcast_game_state(client, game_state, None, sessions).await?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::game_protocol::Position;
+    use nymquest_protocol::local_transport::{LocalNetwork, LocalTransport};
+    use nymquest_protocol::message_auth::{AuthKey, AuthenticatedMessage};
+    use nymquest_protocol::message_padding::{unpad_message, PaddedMessage};
+    use nymquest_protocol::session::{ClientHandshake, ServerIdentity, ServerPublicKey};
+    use nymquest_protocol::transport::IncomingMessage;
+    use nymquest_protocol::world_lore::Faction;
+    use uuid::Uuid;
+
+    /// Server endpoint plus connected player endpoints on an in-process network
+    struct TestWorld {
+        server: LocalTransport,
+        game_state: Arc<GameState>,
+        sessions: SessionManager,
+        /// Session public key and session key of each player's connection
+        client_sessions: HashMap<SenderTag, (String, AuthKey)>,
+        next_seq: u64,
+    }
+
+    impl TestWorld {
+        fn new(network: &LocalNetwork) -> Self {
+            Self {
+                server: network.endpoint("server").unwrap(),
+                game_state: Arc::new(GameState::new()),
+                sessions: SessionManager::new(ServerIdentity::generate()),
+                client_sessions: HashMap::new(),
+                next_seq: 1,
+            }
+        }
+
+        /// Deliver a client message to the handlers as if it came from `player`
+        async fn send(&mut self, player: &LocalTransport, message: ClientMessage) {
+            self.send_as(player.reply_tag(), message).await;
+        }
+
+        /// Authenticate `message` with the connection's session key, verify it like
+        /// the event loop does and hand it to the handlers
+        async fn send_as(&mut self, tag: SenderTag, mut message: ClientMessage) {
+            let server_key = self.sessions.public_key();
+            let (public_key, auth_key) = self.client_sessions.entry(tag).or_insert_with(|| {
+                let handshake = ClientHandshake::new();
+                let auth_key = handshake.session_key(&server_key).unwrap();
+                (handshake.public_key(), auth_key)
+            });
+            if let ClientMessage::Register {
+                session_public_key, ..
+            }
+            | ClientMessage::Resume {
+                session_public_key, ..
+            } = &mut message
+            {
+                *session_public_key = public_key.clone();
+            }
+
+            let authenticated = AuthenticatedMessage::new(message, auth_key).unwrap();
+            assert!(self.sessions.verify(&tag, &authenticated).unwrap());
+            handle_client_message(
+                &self.server,
+                &self.game_state,
+                authenticated.message,
+                tag,
+                &self.sessions,
+            )
+            .await
+            .unwrap();
+        }
+
+        /// Run a simulation tick, applying the intents queued so far
+        async fn tick(&self) {
+            run_simulation_tick(&self.server, &self.game_state, &self.sessions)
+                .await
+                .unwrap();
+        }
+
+        fn seq(&mut self) -> u64 {
+            self.next_seq += 1;
+            self.next_seq
+        }
+
+        async fn register(&mut self, player: &LocalTransport, name: &str) -> String {
+            let seq_num = self.seq();
+            self.send(
+                player,
+                ClientMessage::Register {
+                    name: name.to_string(),
+                    faction: Faction::Nyms,
+                    seq_num,
+                    protocol_version: ProtocolVersion::default(),
+                    session_public_key: String::new(),
+                },
+            )
+            .await;
+            self.game_state
+                .get_player_id(&player.reply_tag())
+                .expect("player should be registered")
+        }
+    }
+
+    /// Decode a server reply, checking it is authenticated with `auth_key` and
+    /// signed by the server
+    fn decode(
+        message: IncomingMessage,
+        auth_key: &AuthKey,
+        server_key: &ServerPublicKey,
+    ) -> ServerMessage {
+        let text = String::from_utf8(message.payload).unwrap();
+        let authenticated = match serde_json::from_str::<
+            PaddedMessage<AuthenticatedMessage<ServerMessage>>,
+        >(&text)
+        {
+            Ok(padded) => unpad_message(padded),
+            Err(_) => serde_json::from_str::<AuthenticatedMessage<ServerMessage>>(&text).unwrap(),
+        };
+        assert!(authenticated.verify(auth_key).unwrap());
+        assert!(authenticated.verify_signature(server_key).unwrap());
+        authenticated.message
+    }
+
+    /// Drain every message currently queued for `player`, checking each was
+    /// authenticated with that player's session key
+    fn drain(player: &mut LocalTransport, world: &TestWorld) -> Vec<ServerMessage> {
+        let (_, auth_key) = &world.client_sessions[&player.reply_tag()];
+        let server_key = world.sessions.public_key();
+        let mut messages = Vec::new();
+        while let Some(message) = player.try_next_message() {
+            messages.push(decode(message, auth_key, &server_key));
+        }
+        messages
+    }
+
+    /// Whether `text` contains anything shaped like a hyphenated UUID
+    fn contains_uuid(text: &str) -> bool {
+        text.as_bytes().windows(36).any(|window| {
+            std::str::from_utf8(window).is_ok_and(|candidate| Uuid::try_parse(candidate).is_ok())
+        })
+    }
+
+    /// Drain every message currently queued for `player` as the raw text sent over the wire
+    fn drain_raw(player: &mut LocalTransport) -> Vec<String> {
+        let mut messages = Vec::new();
+        while let Some(message) = player.try_next_message() {
+            messages.push(String::from_utf8(message.payload).unwrap());
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_register_acks_and_broadcasts_state() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        let player_id = world.register(&alice, "alice").await;
+        let display_id = world.game_state.get_player(&player_id).unwrap().display_id;
+
+        let replies = drain(&mut alice, &world);
+        assert!(matches!(
+            replies[0],
+            ServerMessage::Ack {
+                original_type: ClientMessageType::Register,
+                ..
+            }
+        ));
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::RegisterAck { display_id: id, .. } if *id == display_id
+        )));
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::GameState { players, .. } if players.contains_key(&display_id)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_register_is_rejected() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        world.register(&alice, "alice").await;
+        drain(&mut alice, &world);
+        world.register(&alice, "alice again").await;
+
+        let replies = drain(&mut alice, &world);
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::Error { message, .. } if message.contains("already registered")
+        )));
+        assert_eq!(world.game_state.get_players().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resume_reclaims_character_on_new_connection() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        let player_id = world.register(&alice, "alice").await;
+        let token = drain(&mut alice, &world)
+            .into_iter()
+            .find_map(|message| match message {
+                ServerMessage::RegisterAck { reclaim_token, .. } => Some(reclaim_token),
+                _ => None,
+            })
+            .unwrap();
+        assert!(world
+            .game_state
+            .update_player_position(&player_id, Position::new(12.0, -7.0)));
+
+        // The old connection is lost and the player comes back on a new one
+        let mut returning = network.endpoint("alice-returning").unwrap();
+        let seq_num = world.seq();
+        world
+            .send(
+                &returning,
+                ClientMessage::Resume {
+                    token: token.clone(),
+                    seq_num,
+                    protocol_version: ProtocolVersion::default(),
+                    session_public_key: String::new(),
+                },
+            )
+            .await;
+
+        let player = world.game_state.get_player(&player_id).unwrap();
+        let replies = drain(&mut returning, &world);
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::RegisterAck { display_id, reclaim_token, .. }
+                if *display_id == player.display_id && *reclaim_token == token
+        )));
+        assert_eq!(
+            world.game_state.get_player_id(&returning.reply_tag()),
+            Some(player_id.clone())
+        );
+        assert_eq!(world.game_state.get_player_id(&alice.reply_tag()), None);
+        assert_eq!(player.position, Position::new(12.0, -7.0));
+        assert_eq!(world.game_state.get_players().len(), 1);
+
+        // A guessed token reclaims nothing
+        let mut mallory = network.endpoint("mallory").unwrap();
+        let seq_num = world.seq();
+        world
+            .send(
+                &mallory,
+                ClientMessage::Resume {
+                    token: "guessed".to_string(),
+                    seq_num,
+                    protocol_version: ProtocolVersion::default(),
+                    session_public_key: String::new(),
+                },
+            )
+            .await;
+        assert!(drain(&mut mallory, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::Error { message, .. } if message.contains("No character found")
+        )));
+        assert_eq!(world.game_state.get_player_id(&mallory.reply_tag()), None);
+    }
+
+    #[tokio::test]
+    async fn test_move_updates_position() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        let player_id = world.register(&alice, "alice").await;
+        let start = world.game_state.get_player(&player_id).unwrap().position;
+        drain(&mut alice, &world);
+
+        let seq_num = world.seq();
+        let direction = if start.x > 0.0 {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        world
+            .send(&alice, ClientMessage::Move { direction, seq_num })
+            .await;
+        // Nothing moves until the simulation ticks
+        assert_eq!(
+            world.game_state.get_player(&player_id).unwrap().position,
+            start
+        );
+        world.tick().await;
+
+        let moved = world.game_state.get_player(&player_id).unwrap().position;
+        assert_ne!(moved.x, start.x);
+        assert_eq!(moved.y, start.y);
+        let replies = drain(&mut alice, &world);
+        assert!(replies
+            .iter()
+            .any(|message| matches!(message, ServerMessage::Event { message, .. } if message.starts_with("Moved"))));
+    }
+
+    #[tokio::test]
+    async fn test_tick_replies_survive_a_connection_without_session() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        let alice_id = world.register(&alice, "alice").await;
+        let bob_id = world.register(&bob, "bob").await;
+        drain(&mut bob, &world);
+
+        // Alice's reply comes first, but her session is already gone
+        for player_id in [&alice_id, &bob_id] {
+            world
+                .game_state
+                .simulation()
+                .enqueue(
+                    player_id,
+                    PlayerIntent::Move {
+                        direction: Direction::Up,
+                    },
+                )
+                .unwrap();
+        }
+        world.sessions.close(&alice.reply_tag());
+        world.tick().await;
+
+        let replies = drain(&mut bob, &world);
+        assert!(replies
+            .iter()
+            .any(|message| matches!(message, ServerMessage::Event { message, .. } if message.starts_with("Moved"))));
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::GameState { .. } | ServerMessage::GameStateDelta { .. }
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_inventory_and_item_use() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        let player_id = world.register(&alice, "alice").await;
+        drain(&mut alice, &world);
+
+        // Inventory requests are answered right away with the faction's starter item
+        let seq_num = world.seq();
+        world
+            .send(&alice, ClientMessage::Inventory { seq_num })
+            .await;
+        let starter = Faction::Nyms.starter_item_id();
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::Inventory { items, .. }
+                if items.len() == 1 && items[0].item.id == starter && items[0].quantity == 1
+        )));
+
+        // Using it is applied on the next tick and reported with the updated inventory
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::UseItem {
+                    item_id: starter.to_string(),
+                    seq_num,
+                },
+            )
+            .await;
+        world.tick().await;
+        let replies = drain(&mut alice, &world);
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::ItemUsed { item_id, .. } if item_id == starter
+        )));
+        assert!(replies.iter().any(|message| matches!(
+            message,
+            ServerMessage::Inventory { items, .. }
+                if items[0].quantity == 0 && items[0].active_seconds > 0
+        )));
+        let player = world.game_state.get_player(&player_id).unwrap();
+        assert!(player.inventory.is_empty());
+        assert!(player.active_items.contains_key(starter));
+
+        // The only copy is gone, so there is nothing left to drop
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::DropItem {
+                    item_id: starter.to_string(),
+                    seq_num,
+                },
+            )
+            .await;
+        world.tick().await;
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::Error { message, .. } if message.contains("not carrying")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_faction_emotes_need_reputation() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        let player_id = world.register(&alice, "alice").await;
+        // Registering tells the player where they stand
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::ReputationUpdate { standings, .. }
+                if standings.len() == Faction::ALL.len() && standings.iter().all(|standing| standing.score == 0)
+        )));
+
+        let emote = |seq_num| ClientMessage::Emote {
+            emote_type: Faction::Nyms.emote(),
+            seq_num,
+        };
+        let seq_num = world.seq();
+        world.send(&alice, emote(seq_num)).await;
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::Error { message, .. } if message.contains("reputation with the Nyms")
+        )));
+
+        let threshold = world.game_state.get_config().reputation_emote_threshold;
+        let unlocks = world
+            .game_state
+            .adjust_reputation(&player_id, &[(Faction::Nyms, threshold)])
+            .unwrap();
+        assert_eq!(unlocks.emotes, vec![Faction::Nyms.emote()]);
+
+        let seq_num = world.seq();
+        world
+            .send(&alice, ClientMessage::Reputation { seq_num })
+            .await;
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::ReputationUpdate { standings, .. }
+                if standings.iter().any(|standing| standing.faction == Faction::Nyms && standing.emote_unlocked)
+        )));
+
+        let seq_num = world.seq();
+        world.send(&alice, emote(seq_num)).await;
+        assert!(drain(&mut alice, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::Event { message, .. } if message.contains("mixnet packets")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_chat_reaches_other_players() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        world.register(&alice, "alice").await;
+        world.register(&bob, "bob").await;
+        drain(&mut alice, &world);
+        drain(&mut bob, &world);
+
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::Chat {
+                    message: "hello mixnet".to_string(),
+                    seq_num,
+                },
+            )
+            .await;
+
+        let bob_messages = drain(&mut bob, &world);
+        assert!(bob_messages.iter().any(|message| matches!(
+            message,
+            ServerMessage::ChatMessage { sender_name, message, .. }
+                if sender_name == "alice" && message == "hello mixnet"
+        )));
+        let alice_messages = drain(&mut alice, &world);
+        assert!(!alice_messages
+            .iter()
+            .any(|message| matches!(message, ServerMessage::ChatMessage { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_whisper_only_reaches_target() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+        let mut carol = network.endpoint("carol").unwrap();
+
+        world.register(&alice, "alice").await;
+        let bob_id = world.register(&bob, "bob").await;
+        world.register(&carol, "carol").await;
+        for player in [&mut alice, &mut bob, &mut carol] {
+            drain(player, &world);
+        }
+
+        let target_display_id = world.game_state.get_player(&bob_id).unwrap().display_id;
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::Whisper {
+                    target_display_id,
+                    message: "psst".to_string(),
+                    seq_num,
+                },
+            )
+            .await;
+
+        let is_whisper = |message: &ServerMessage| matches!(message, ServerMessage::WhisperMessage { message, .. } if message == "psst");
+        assert!(drain(&mut bob, &world).iter().any(is_whisper));
+        assert!(!drain(&mut carol, &world).iter().any(is_whisper));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_message_is_ignored() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+
+        world.register(&alice, "alice").await;
+        drain(&mut alice, &world);
+
+        let chat = ClientMessage::Chat {
+            message: "once".to_string(),
+            seq_num: world.seq(),
+        };
+        world.send(&alice, chat.clone()).await;
+        assert!(!drain(&mut alice, &world).is_empty());
+
+        world.send(&alice, chat).await;
+        assert!(drain(&mut alice, &world).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_removes_player() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        let alice_id = world.register(&alice, "alice").await;
+        let alice_display_id = world.game_state.get_player(&alice_id).unwrap().display_id;
+        world.register(&bob, "bob").await;
+        drain(&mut bob, &world);
+
+        let seq_num = world.seq();
+        world
+            .send(&alice, ClientMessage::Disconnect { seq_num })
+            .await;
+
+        assert!(world.game_state.get_player(&alice_id).is_none());
+        assert!(drain(&mut bob, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::GameState { players, .. }
+                if players.len() == 1 && !players.contains_key(&alice_display_id)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_acknowledged_state_gets_deltas() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        let alice_id = world.register(&alice, "alice").await;
+        let alice_display_id = world.game_state.get_player(&alice_id).unwrap().display_id;
+        let bob_id = world.register(&bob, "bob").await;
+        // Within view of each other
+        assert!(world
+            .game_state
+            .update_player_position(&alice_id, Position::new(0.0, 0.0)));
+        assert!(world
+            .game_state
+            .update_player_position(&bob_id, Position::new(0.0, 30.0)));
+        broadcast_game_state(&world.server, &world.game_state, None, &world.sessions)
+            .await
+            .unwrap();
+        let snapshot_seq = drain(&mut bob, &world)
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                ServerMessage::GameState { seq_num, .. } => Some(*seq_num),
+                _ => None,
+            })
+            .expect("bob should get a snapshot after registering");
+
+        world
+            .send(
+                &bob,
+                ClientMessage::Ack {
+                    server_seq_num: snapshot_seq,
+                    original_type: ServerMessageType::GameState,
+                },
+            )
+            .await;
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::Move {
+                    direction: Direction::Up,
+                    seq_num,
+                },
+            )
+            .await;
+        world.tick().await;
+
+        // Bob only hears about the player that changed
+        let updates = drain(&mut bob, &world);
+        assert!(matches!(
+            updates.as_slice(),
+            [ServerMessage::GameStateDelta { baseline_seq, updated, removed, .. }]
+                if *baseline_seq == snapshot_seq
+                    && updated.len() == 1
+                    && updated[0].display_id == alice_display_id
+                    && removed.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_players_out_of_view_are_not_broadcast() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        let alice_id = world.register(&alice, "alice").await;
+        let alice_display_id = world.game_state.get_player(&alice_id).unwrap().display_id;
+        let bob_id = world.register(&bob, "bob").await;
+        let view_radius = world.game_state.get_config().view_radius;
+        assert!(world
+            .game_state
+            .update_player_position(&alice_id, Position::new(-90.0, -90.0)));
+        assert!(world
+            .game_state
+            .update_player_position(&bob_id, Position::new(90.0, 90.0)));
+        broadcast_game_state(&world.server, &world.game_state, None, &world.sessions)
+            .await
+            .unwrap();
+        drain(&mut bob, &world);
+
+        let alice_is_mentioned = |messages: &[ServerMessage]| {
+            messages.iter().any(|message| match message {
+                ServerMessage::GameState { players, .. } => players.contains_key(&alice_display_id),
+                ServerMessage::GameStateDelta { updated, .. } => updated
+                    .iter()
+                    .any(|player| player.display_id == alice_display_id),
+                _ => false,
+            })
+        };
+
+        // Far away, alice's moves are not sent to bob at all
+        let seq_num = world.seq();
+        world
+            .send(
+                &alice,
+                ClientMessage::Move {
+                    direction: Direction::Up,
+                    seq_num,
+                },
+            )
+            .await;
+        world.tick().await;
+        assert!(!alice_is_mentioned(&drain(&mut bob, &world)));
+
+        // Stepping into bob's view announces her and sends her position
+        assert!(world
+            .game_state
+            .update_player_position(&alice_id, Position::new(90.0 - view_radius / 2.0, 90.0)));
+        broadcast_game_state(&world.server, &world.game_state, None, &world.sessions)
+            .await
+            .unwrap();
+        let updates = drain(&mut bob, &world);
+        assert!(alice_is_mentioned(&updates));
+        assert!(updates.iter().any(|message| matches!(
+            message,
+            ServerMessage::VisibilityChanged { entered, left, .. }
+                if *entered == [alice_display_id.clone()] && left.is_empty()
+        )));
+
+        // And walking away again is announced too
+        assert!(world
+            .game_state
+            .update_player_position(&alice_id, Position::new(-90.0, -90.0)));
+        broadcast_game_state(&world.server, &world.game_state, None, &world.sessions)
+            .await
+            .unwrap();
+        assert!(drain(&mut bob, &world).iter().any(|message| matches!(
+            message,
+            ServerMessage::VisibilityChanged { entered, left, .. }
+                if entered.is_empty() && *left == [alice_display_id.clone()]
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_server_messages_never_contain_internal_ids() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let mut alice = network.endpoint("alice").unwrap();
+        let mut bob = network.endpoint("bob").unwrap();
+
+        let alice_id = world.register(&alice, "alice").await;
+        let bob_id = world.register(&bob, "bob").await;
+        let bob_display_id = world.game_state.get_player(&bob_id).unwrap().display_id;
+
+        // Exercise every handler that replies or broadcasts
+        let messages = vec![
+            ClientMessage::Move {
+                direction: Direction::Up,
+                seq_num: 0,
+            },
+            ClientMessage::Attack {
+                target_display_id: bob_display_id.clone(),
+                seq_num: 0,
+            },
+            ClientMessage::Chat {
+                message: "hello".to_string(),
+                seq_num: 0,
+            },
+            ClientMessage::Whisper {
+                target_display_id: bob_display_id,
+                message: "psst".to_string(),
+                seq_num: 0,
+            },
+            ClientMessage::Emote {
+                emote_type: EmoteType::Wave,
+                seq_num: 0,
+            },
+            ClientMessage::Heartbeat { seq_num: 0 },
+            ClientMessage::Register {
+                name: "alice again".to_string(),
+                faction: Faction::Nyms,
+                seq_num: 0,
+                protocol_version: ProtocolVersion::default(),
+                session_public_key: String::new(),
+            },
+            ClientMessage::Disconnect { seq_num: 0 },
+        ];
+        let mut sent = Vec::new();
+        for mut message in messages {
+            let seq_num = world.seq();
+            match &mut message {
+                ClientMessage::Move { seq_num: s, .. }
+                | ClientMessage::Attack { seq_num: s, .. }
+                | ClientMessage::Chat { seq_num: s, .. }
+                | ClientMessage::Whisper { seq_num: s, .. }
+                | ClientMessage::Emote { seq_num: s, .. }
+                | ClientMessage::Heartbeat { seq_num: s }
+                | ClientMessage::Register { seq_num: s, .. }
+                | ClientMessage::Disconnect { seq_num: s } => *s = seq_num,
+                _ => unreachable!(),
+            }
+            world.send(&alice, message).await;
+            world.tick().await;
+            sent.extend(drain_raw(&mut alice));
+            sent.extend(drain_raw(&mut bob));
+        }
+
+        assert!(sent.len() > 10);
+        for text in &sent {
+            assert!(
+                !contains_uuid(text),
+                "Internal ID sent to a client: {}",
+                text
+            );
+            assert!(!text.contains(&alice_id) && !text.contains(&bob_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_players_are_dropped_on_broadcast() {
+        let network = LocalNetwork::new();
+        let mut world = TestWorld::new(&network);
+        let alice = network.endpoint("alice").unwrap();
+        let bob = network.endpoint("bob").unwrap();
+
+        world.register(&alice, "alice").await;
+        let bob_id = world.register(&bob, "bob").await;
+        bob.disconnect().await;
+
+        broadcast_game_state(&world.server, &world.game_state, None, &world.sessions)
+            .await
+            .unwrap();
+        assert!(world.game_state.get_player(&bob_id).is_none());
+        assert_eq!(world.game_state.get_players().len(), 1);
+    }
+}
@@ -0,0 +1,27 @@
+//! NymQuest game server
+//!
+//! The binary in `main.rs` connects to the Nym mixnet and runs the event loop
+//! from these modules; they are exposed as a library so integration tests can
+//! run the same server over an in-process transport.
+
+pub mod abilities;
+pub mod config;
+pub mod discovery;
+pub mod event_loop;
+pub mod game_state;
+pub mod handlers;
+pub mod loot;
+pub mod mixnet_monitor;
+pub mod npcs;
+pub mod persistence;
+pub mod quests;
+pub mod regions;
+pub mod reputation;
+pub mod sessions;
+pub mod simulation;
+pub mod state_sync;
+pub mod status_effects;
+pub mod surveillance;
+pub mod territory;
+pub mod trading;
+pub mod utils;
@@ -0,0 +1,237 @@
+//! Loot tables and items lying on the ground
+//!
+//! Items turn up in the world where a player is defeated and, every
+//! `loot_spawn_interval_seconds`, at a random spot. Which item is drawn from
+//! the loot table of the world's region: every catalog item is weighted by its
+//! rarity, and items of the types the region favors are twice as likely. Drops
+//! stay on the ground until someone picks them up or `max_loot_drops` newer
+//! ones push them out. They are not persisted.
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use tracing::debug;
+
+use nymquest_protocol::game_protocol::{LootDrop, Position};
+use nymquest_protocol::world_lore::{CryptoItem, WorldRegion};
+
+/// Weight multiplier for items of a type the region favors
+const FAVORED_TYPE_WEIGHT: u32 = 2;
+
+/// Items that can drop in a region and their relative weights
+pub struct LootTable {
+    /// Item IDs and weights, sorted by item ID so rolls do not depend on hash order
+    entries: Vec<(String, u32)>,
+    total_weight: u32,
+}
+
+impl LootTable {
+    /// Build the loot table of `region` from the item catalog
+    pub fn for_region(region: WorldRegion, catalog: &HashMap<String, CryptoItem>) -> Self {
+        let favored = region.favored_item_types();
+        let mut entries: Vec<(String, u32)> = catalog
+            .values()
+            .map(|item| {
+                let mut weight = item.rarity.drop_weight();
+                if favored.contains(&item.item_type) {
+                    weight *= FAVORED_TYPE_WEIGHT;
+                }
+                (item.id.clone(), weight)
+            })
+            .collect();
+        entries.sort();
+        let total_weight = entries.iter().map(|(_, weight)| weight).sum();
+
+        Self {
+            entries,
+            total_weight,
+        }
+    }
+
+    /// Chance that a roll gives `item_id`, from 0.0 to 1.0
+    pub fn chance(&self, item_id: &str) -> f32 {
+        if self.total_weight == 0 {
+            return 0.0;
+        }
+        self.entries
+            .iter()
+            .find(|(id, _)| id == item_id)
+            .map_or(0.0, |(_, weight)| *weight as f32 / self.total_weight as f32)
+    }
+
+    /// Draw an item ID, or `None` if the table is empty
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Option<&str> {
+        if self.total_weight == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..self.total_weight);
+        for (item_id, weight) in &self.entries {
+            if pick < *weight {
+                return Some(item_id);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+struct Drops {
+    /// Drops by ID; IDs only grow, so the first entry is the oldest
+    by_id: BTreeMap<u64, LootDrop>,
+    next_id: u64,
+}
+
+/// Items on the ground that players can pick up
+pub struct GroundLoot {
+    drops: Mutex<Drops>,
+    max_drops: usize,
+}
+
+impl GroundLoot {
+    /// Create an empty ground holding at most `max_drops` items
+    pub fn new(max_drops: usize) -> Self {
+        Self {
+            drops: Mutex::new(Drops {
+                by_id: BTreeMap::new(),
+                next_id: 1,
+            }),
+            max_drops,
+        }
+    }
+
+    /// Put `item` on the ground at `position`
+    ///
+    /// Returns the new drop and the oldest drops removed to make room for it.
+    pub fn place(
+        &self,
+        item: &CryptoItem,
+        position: Position,
+    ) -> Result<(LootDrop, Vec<LootDrop>)> {
+        let mut drops = self.lock()?;
+        let drop = LootDrop {
+            drop_id: drops.next_id,
+            item_id: item.id.clone(),
+            name: item.name.clone(),
+            rarity: item.rarity.clone(),
+            position,
+        };
+        drops.next_id += 1;
+        drops.by_id.insert(drop.drop_id, drop.clone());
+
+        let mut removed = Vec::new();
+        while drops.by_id.len() > self.max_drops {
+            if let Some((_, oldest)) = drops.by_id.pop_first() {
+                debug!("Loot {} disappeared to make room", oldest.item_id);
+                removed.push(oldest);
+            }
+        }
+        Ok((drop, removed))
+    }
+
+    /// The drop closest to `position` within `radius`, if any
+    pub fn nearest(&self, position: &Position, radius: f32) -> Result<Option<LootDrop>> {
+        let drops = self.lock()?;
+        Ok(drops
+            .by_id
+            .values()
+            .map(|drop| (drop.position.distance_to(position), drop))
+            .filter(|(distance, _)| *distance <= radius)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, drop)| drop.clone()))
+    }
+
+    /// Take the drop `drop_id` off the ground; `None` if it is already gone
+    pub fn take(&self, drop_id: u64) -> Result<Option<LootDrop>> {
+        Ok(self.lock()?.by_id.remove(&drop_id))
+    }
+
+    /// Drops within `radius` of `position`, oldest first
+    pub fn within(&self, position: &Position, radius: f32) -> Result<Vec<LootDrop>> {
+        let drops = self.lock()?;
+        Ok(drops
+            .by_id
+            .values()
+            .filter(|drop| drop.position.distance_to(position) <= radius)
+            .cloned()
+            .collect())
+    }
+
+    /// Number of items on the ground
+    pub fn drop_count(&self) -> usize {
+        self.lock().map(|drops| drops.by_id.len()).unwrap_or(0)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Drops>> {
+        self.drops
+            .lock()
+            .map_err(|_| anyhow!("Ground loot lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::world_lore::generate_crypto_items;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_loot_table_follows_rarity_and_region() {
+        let catalog = generate_crypto_items();
+        let harbor = LootTable::for_region(WorldRegion::NeonHarbor, &catalog);
+        let total: f32 = catalog.keys().map(|id| harbor.chance(id)).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+
+        // Both are privacy tools Neon Harbor favors, but the relay is more common
+        assert!(harbor.chance("mixnet_relay") > harbor.chance("zk_prover"));
+        // The Dead Zones favor artifacts, Neon Harbor does not
+        let dead_zones = LootTable::for_region(WorldRegion::DeadZones, &catalog);
+        assert!(dead_zones.chance("satoshi_key") > harbor.chance("satoshi_key"));
+        assert_eq!(harbor.chance("no_such_item"), 0.0);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let item_id = harbor.roll(&mut rng).unwrap();
+            assert!(catalog.contains_key(item_id));
+        }
+        assert!(LootTable::for_region(WorldRegion::TheGrid, &HashMap::new())
+            .roll(&mut rng)
+            .is_none());
+    }
+
+    #[test]
+    fn test_drops_are_found_taken_and_capped() {
+        let catalog = generate_crypto_items();
+        let ground = GroundLoot::new(2);
+        let (relay, _) = ground
+            .place(&catalog["mixnet_relay"], Position::new(0.0, 0.0))
+            .unwrap();
+        let (shield, _) = ground
+            .place(&catalog["quantum_shield"], Position::new(8.0, 0.0))
+            .unwrap();
+
+        let nearest = ground.nearest(&Position::new(5.0, 0.0), 10.0).unwrap();
+        assert_eq!(nearest, Some(shield.clone()));
+        assert!(ground
+            .nearest(&Position::new(50.0, 0.0), 10.0)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            ground.within(&Position::new(0.0, 0.0), 10.0).unwrap(),
+            vec![relay.clone(), shield.clone()]
+        );
+
+        // The oldest drop makes room for a new one
+        let (key, removed) = ground
+            .place(&catalog["satoshi_key"], Position::new(0.0, 0.0))
+            .unwrap();
+        assert_eq!(removed, vec![relay.clone()]);
+        assert_eq!(ground.drop_count(), 2);
+        assert!(ground.take(relay.drop_id).unwrap().is_none());
+
+        assert_eq!(ground.take(key.drop_id).unwrap(), Some(key.clone()));
+        assert!(ground.take(key.drop_id).unwrap().is_none());
+        assert_eq!(ground.drop_count(), 1);
+    }
+}
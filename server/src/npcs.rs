@@ -0,0 +1,624 @@
+//! Non-player characters and how they behave
+//!
+//! When the server starts, every region is populated from the spawn table of
+//! its lore region. Surveillance drones patrol the watched cities and chase
+//! anyone outside the Corporate Hegemony, Deep Net brokers keep to their spot
+//! and flee once hurt, and Monk sentinels guard their post against anyone but
+//! the Algorithm Monks. Once per simulated second each character picks a
+//! behavior state (idle, patrol, chase or flee) and takes one step, and those
+//! that caught up with their target attack it. Characters never leave their
+//! region, cannot walk through walls and give up a chase that leads them too
+//! far from home. Out of a fight they slowly heal. A defeated character comes
+//! back at a random spot of its region after `npc_respawn_seconds`. Drone
+//! hits also corrupt the player's data (see `inflicted_effect`).
+//! Characters are not persisted.
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use tracing::debug;
+
+use nymquest_protocol::game_protocol::{
+    NpcKind, NpcState, NpcView, Player, Position, StatusEffectKind,
+};
+use nymquest_protocol::world_lore::{Faction, WorldRegion};
+
+use crate::regions::Regions;
+
+/// Distance within which a character can hit a player
+pub const NPC_ATTACK_RANGE: f32 = 10.0;
+
+/// Health a character out of a fight recovers every second
+const NPC_REGEN_PER_SECOND: u32 = 2;
+
+/// Random spots tried when picking where a patrol goes next
+const MAX_WAYPOINT_ATTEMPTS: usize = 10;
+
+/// How a kind of character fights and moves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Behavior {
+    pub max_health: u32,
+    /// Damage per attack; 0 for characters that never attack
+    pub damage: u32,
+    /// World units covered per second
+    pub speed: f32,
+    /// Distance within which the character notices players
+    pub aggro_range: f32,
+    /// Distance from home the character patrols within and chases players to
+    pub leash_range: f32,
+    /// The character flees from players while its health is below this fraction of the maximum
+    pub flee_below: f32,
+    /// Faction whose members the character leaves alone
+    pub spares: Option<Faction>,
+    /// Whether the character walks around its home instead of waiting there
+    pub patrols: bool,
+}
+
+/// Behavior of a kind of character
+pub fn behavior(kind: NpcKind) -> Behavior {
+    match kind {
+        NpcKind::SurveillanceDrone => Behavior {
+            max_health: 40,
+            damage: 8,
+            speed: 6.0,
+            aggro_range: 40.0,
+            leash_range: 60.0,
+            flee_below: 0.0,
+            spares: Some(Faction::CorporateHegemony),
+            patrols: true,
+        },
+        NpcKind::DeepNetBroker => Behavior {
+            max_health: 60,
+            damage: 0,
+            speed: 5.0,
+            aggro_range: 30.0,
+            leash_range: 40.0,
+            flee_below: 1.0,
+            spares: None,
+            patrols: false,
+        },
+        NpcKind::MonkSentinel => Behavior {
+            max_health: 120,
+            damage: 12,
+            speed: 4.0,
+            aggro_range: 25.0,
+            leash_range: 40.0,
+            flee_below: 0.0,
+            spares: Some(Faction::AlgorithmMonks),
+            patrols: false,
+        },
+    }
+}
+
+/// Characters that live in each lore region, by kind and number
+pub fn spawn_table(region: WorldRegion) -> &'static [(NpcKind, usize)] {
+    match region {
+        WorldRegion::NeonHarbor => &[(NpcKind::SurveillanceDrone, 3), (NpcKind::DeepNetBroker, 2)],
+        WorldRegion::DeepNet => &[(NpcKind::DeepNetBroker, 3), (NpcKind::SurveillanceDrone, 1)],
+        WorldRegion::DataHavens => &[(NpcKind::MonkSentinel, 2), (NpcKind::DeepNetBroker, 1)],
+        WorldRegion::DeadZones => &[(NpcKind::MonkSentinel, 1), (NpcKind::DeepNetBroker, 1)],
+        WorldRegion::TheGrid => &[(NpcKind::SurveillanceDrone, 5), (NpcKind::MonkSentinel, 1)],
+    }
+}
+
+/// Prefix of the display IDs of a kind of character
+fn display_prefix(kind: NpcKind) -> &'static str {
+    match kind {
+        NpcKind::SurveillanceDrone => "Drone",
+        NpcKind::DeepNetBroker => "Broker",
+        NpcKind::MonkSentinel => "Sentinel",
+    }
+}
+
+/// What a kind of character whispers back to players who whisper to it
+pub fn whisper_reply(kind: NpcKind) -> &'static str {
+    match kind {
+        NpcKind::SurveillanceDrone => "*static* CITIZEN. YOUR COMPLIANCE HAS BEEN NOTED.",
+        NpcKind::DeepNetBroker => "Everything has a price down here. Even silence.",
+        NpcKind::MonkSentinel => "The pattern sees all who walk it. Walk softly.",
+    }
+}
+
+/// Status effect a kind of character puts on players it hits, and for how many seconds
+pub fn inflicted_effect(kind: NpcKind) -> Option<(StatusEffectKind, u64)> {
+    match kind {
+        NpcKind::SurveillanceDrone => Some((StatusEffectKind::Corruption, 5)),
+        NpcKind::DeepNetBroker | NpcKind::MonkSentinel => None,
+    }
+}
+
+/// A character the server controls
+#[derive(Debug, Clone)]
+pub struct Npc {
+    /// Public ID players attack it by; characters have no other
+    pub display_id: String,
+    pub kind: NpcKind,
+    /// Index of the region the character lives in
+    pub region: usize,
+    /// Where the character spawned, which it patrols around and returns to
+    pub home: Position,
+    pub position: Position,
+    pub health: u32,
+    pub state: NpcState,
+    /// Where a patrol is headed
+    waypoint: Position,
+    last_attack_time: u64,
+    /// When a defeated character comes back
+    respawn_at: Option<u64>,
+}
+
+impl Npc {
+    /// Whether the character is around, rather than defeated and waiting to respawn
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    /// What players get to know about the character
+    pub fn view(&self) -> NpcView {
+        NpcView {
+            display_id: self.display_id.clone(),
+            kind: self.kind,
+            position: self.position,
+            health: self.health,
+            max_health: behavior(self.kind).max_health,
+            state: self.state,
+        }
+    }
+}
+
+/// An attack a character made on a player
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpcAttack {
+    /// Display ID of the attacking character
+    pub npc: String,
+    pub kind: NpcKind,
+    /// Player ID of the target
+    pub target: String,
+    pub damage: u32,
+}
+
+/// Every character in the world and what players were last told about them
+pub struct Npcs {
+    /// Characters by lowercase display ID
+    npcs: Mutex<BTreeMap<String, Npc>>,
+    /// Characters each player was last sent, by player ID
+    sent: Mutex<HashMap<String, Vec<NpcView>>>,
+}
+
+impl Default for Npcs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Npcs {
+    /// Create a world without characters
+    pub fn new() -> Self {
+        Self {
+            npcs: Mutex::new(BTreeMap::new()),
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Populate every region from its spawn table
+    pub fn spawn<R: Rng>(&self, regions: &Regions, rng: &mut R) -> Result<()> {
+        let mut npcs = self.lock()?;
+        let mut counts: HashMap<NpcKind, usize> = HashMap::new();
+        for (index, region) in regions.all().iter().enumerate() {
+            for &(kind, number) in spawn_table(region.lore) {
+                for _ in 0..number {
+                    let count = counts.entry(kind).or_insert(0);
+                    *count += 1;
+                    let display_id = format!("{}{}", display_prefix(kind), count);
+                    let home = regions.random_position(index, rng);
+                    npcs.insert(
+                        display_id.to_lowercase(),
+                        Npc {
+                            display_id,
+                            kind,
+                            region: index,
+                            home,
+                            position: home,
+                            health: behavior(kind).max_health,
+                            state: NpcState::Idle,
+                            waypoint: home,
+                            last_attack_time: 0,
+                            respawn_at: None,
+                        },
+                    );
+                }
+            }
+        }
+        debug!("Spawned {} characters", npcs.len());
+        Ok(())
+    }
+
+    /// Character with `display_id`, ignoring case, whether alive or not
+    pub fn get(&self, display_id: &str) -> Option<Npc> {
+        self.lock()
+            .ok()
+            .and_then(|npcs| npcs.get(&display_id.to_lowercase()).cloned())
+    }
+
+    /// Live characters in region `region` within `radius` of `position`, by display ID
+    pub fn views_near(&self, region: usize, position: &Position, radius: f32) -> Vec<NpcView> {
+        self.lock()
+            .map(|npcs| {
+                npcs.values()
+                    .filter(|npc| {
+                        npc.is_alive()
+                            && npc.region == region
+                            && npc.position.distance_to(position) <= radius
+                    })
+                    .map(Npc::view)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Take `damage` off a live character's health
+    ///
+    /// Returns the damage taken and whether it defeated the character, or `None`
+    /// if there is no such character alive.
+    pub fn damage(&self, display_id: &str, damage: u32) -> Result<Option<(u32, bool)>> {
+        let mut npcs = self.lock()?;
+        let Some(npc) = npcs
+            .get_mut(&display_id.to_lowercase())
+            .filter(|npc| npc.is_alive())
+        else {
+            return Ok(None);
+        };
+        let taken = damage.min(npc.health);
+        npc.health -= taken;
+        if npc.health == 0 {
+            debug!("{} was defeated", npc.display_id);
+            npc.state = NpcState::Idle;
+        }
+        Ok(Some((taken, npc.health == 0)))
+    }
+
+    /// Advance every character by one second, given the players in the world
+    ///
+    /// Returns the attacks characters made, for the caller to apply.
+    pub fn update<R: Rng>(
+        &self,
+        players: &[Player],
+        regions: &Regions,
+        now: u64,
+        attack_cooldown_seconds: u64,
+        respawn_seconds: u64,
+        rng: &mut R,
+    ) -> Result<Vec<NpcAttack>> {
+        let mut npcs = self.lock()?;
+        let mut attacks = Vec::new();
+
+        for npc in npcs.values_mut() {
+            let behavior = behavior(npc.kind);
+
+            if !npc.is_alive() {
+                match npc.respawn_at {
+                    None => npc.respawn_at = Some(now + respawn_seconds),
+                    Some(at) if at <= now => {
+                        npc.home = regions.random_position(npc.region, rng);
+                        npc.position = npc.home;
+                        npc.waypoint = npc.home;
+                        npc.health = behavior.max_health;
+                        npc.respawn_at = None;
+                        debug!("{} respawned at {:?}", npc.display_id, npc.position);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            // Players the character notices: in its region, close by and in sight
+            let noticed: Vec<&Player> = players
+                .iter()
+                .filter(|player| {
+                    regions.index_at(&player.position) == npc.region
+                        && npc.position.distance_to(&player.position) <= behavior.aggro_range
+                        && regions.is_clear(&npc.position, &player.position)
+                })
+                .collect();
+            let nearest = |candidates: &mut dyn Iterator<Item = &&Player>| {
+                candidates
+                    .min_by(|a, b| {
+                        npc.position
+                            .distance_to(&a.position)
+                            .total_cmp(&npc.position.distance_to(&b.position))
+                    })
+                    .map(|player| (*player).clone())
+            };
+            let threat = nearest(&mut noticed.iter());
+            let target = if behavior.damage > 0 {
+                nearest(&mut noticed.iter().filter(|player| {
+                    behavior.spares.as_ref() != Some(&player.faction)
+                        && npc.home.distance_to(&player.position) <= behavior.leash_range
+                }))
+            } else {
+                None
+            };
+            let hurt = (npc.health as f32) < behavior.flee_below * behavior.max_health as f32;
+
+            match (threat, target) {
+                (Some(threat), _) if hurt => {
+                    npc.state = NpcState::Flee;
+                    let away = Position::new(
+                        2.0 * npc.position.x - threat.position.x,
+                        2.0 * npc.position.y - threat.position.y,
+                    );
+                    if let Some(position) = step(regions, npc, &away, behavior.speed) {
+                        npc.position = position;
+                    }
+                }
+                (_, Some(target)) => {
+                    npc.state = NpcState::Chase;
+                    let distance = npc.position.distance_to(&target.position);
+                    if distance <= NPC_ATTACK_RANGE {
+                        if now >= npc.last_attack_time + attack_cooldown_seconds {
+                            npc.last_attack_time = now;
+                            attacks.push(NpcAttack {
+                                npc: npc.display_id.clone(),
+                                kind: npc.kind,
+                                target: target.id.clone(),
+                                damage: behavior.damage,
+                            });
+                        }
+                    } else {
+                        // Close in, but stop short of standing on the target
+                        let closing = behavior.speed.min(distance - NPC_ATTACK_RANGE / 2.0);
+                        if let Some(position) = step(regions, npc, &target.position, closing) {
+                            npc.position = position;
+                        }
+                    }
+                }
+                _ if behavior.patrols => {
+                    npc.state = NpcState::Patrol;
+                    if npc.position.distance_to(&npc.waypoint) <= behavior.speed {
+                        npc.waypoint = pick_waypoint(regions, npc, behavior.leash_range, rng);
+                    }
+                    let waypoint = npc.waypoint;
+                    match step(regions, npc, &waypoint, behavior.speed) {
+                        Some(position) => npc.position = position,
+                        // Walked into a wall; go somewhere else next time
+                        None => npc.waypoint = npc.home,
+                    }
+                }
+                _ => {
+                    npc.state = NpcState::Idle;
+                    let home = npc.home;
+                    let distance = npc.position.distance_to(&home);
+                    if distance > 0.0 {
+                        if let Some(position) =
+                            step(regions, npc, &home, behavior.speed.min(distance))
+                        {
+                            npc.position = position;
+                        }
+                    }
+                }
+            }
+
+            if matches!(npc.state, NpcState::Idle | NpcState::Patrol) {
+                npc.health = (npc.health + NPC_REGEN_PER_SECOND).min(behavior.max_health);
+            }
+        }
+        Ok(attacks)
+    }
+
+    /// Remember the characters `player_id` was sent; returns whether they differ from last time
+    pub fn record_sent(&self, player_id: &str, views: &[NpcView]) -> Result<bool> {
+        let mut sent = self
+            .sent
+            .lock()
+            .map_err(|_| anyhow!("NPC views lock poisoned"))?;
+        if sent.get(player_id).map(Vec::as_slice) == Some(views) {
+            return Ok(false);
+        }
+        sent.insert(player_id.to_string(), views.to_vec());
+        Ok(true)
+    }
+
+    /// Forget what was sent to players for whom `keep` is false
+    pub fn retain_sent<F>(&self, keep: F) -> Result<()>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.sent
+            .lock()
+            .map_err(|_| anyhow!("NPC views lock poisoned"))?
+            .retain(|player_id, _| keep(player_id));
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, BTreeMap<String, Npc>>> {
+        self.npcs.lock().map_err(|_| anyhow!("NPC lock poisoned"))
+    }
+}
+
+/// Where `npc` ends up walking `distance` towards `to`, or `None` if a wall or
+/// the edge of its region is in the way
+fn step(regions: &Regions, npc: &Npc, to: &Position, distance: f32) -> Option<Position> {
+    let length = npc.position.distance_to(to);
+    if length <= 0.0 || distance <= 0.0 {
+        return None;
+    }
+    let bounds = &regions.all()[npc.region].boundaries;
+    let (x, y) = bounds.clamp_position(
+        npc.position.x + (to.x - npc.position.x) / length * distance,
+        npc.position.y + (to.y - npc.position.y) / length * distance,
+    );
+    let position = Position::new(x, y);
+    (regions.index_at(&position) == npc.region && regions.is_clear(&npc.position, &position))
+        .then_some(position)
+}
+
+/// A random spot within `leash_range` of the character's home, away from walls if possible
+fn pick_waypoint<R: Rng>(regions: &Regions, npc: &Npc, leash_range: f32, rng: &mut R) -> Position {
+    let bounds = &regions.all()[npc.region].boundaries;
+    for _ in 0..MAX_WAYPOINT_ATTEMPTS {
+        let (x, y) = bounds.clamp_position(
+            npc.home.x + rng.gen_range(-leash_range..leash_range),
+            npc.home.y + rng.gen_range(-leash_range..leash_range),
+        );
+        let waypoint = Position::new(x, y);
+        if regions.index_at(&waypoint) == npc.region && regions.is_clear(&npc.home, &waypoint) {
+            return waypoint;
+        }
+    }
+    npc.home
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn player(id: &str, faction: Faction, x: f32, y: f32) -> Player {
+        Player {
+            id: id.to_string(),
+            display_id: id.to_string(),
+            name: id.to_string(),
+            position: Position::new(x, y),
+            health: 100,
+            level: 1,
+            experience: 0,
+            last_attack_time: 0,
+            last_damage_time: 0,
+            faction,
+            inventory: Default::default(),
+            active_items: Default::default(),
+            equipment: Default::default(),
+            credits: 0,
+            escrow: Default::default(),
+            reputation: Default::default(),
+            rewarded_factions: Default::default(),
+            quests: Default::default(),
+            effects: Default::default(),
+        }
+    }
+
+    /// A world with a single character of `kind` standing at the middle of Neon Harbor
+    fn world_with(kind: NpcKind) -> (Regions, Npcs) {
+        let regions = Regions::from_config(&GameConfig::default());
+        let npcs = Npcs::new();
+        let home = Position::new(0.0, 0.0);
+        npcs.lock().unwrap().insert(
+            "npc".to_string(),
+            Npc {
+                display_id: "Npc".to_string(),
+                kind,
+                region: 0,
+                home,
+                position: home,
+                health: behavior(kind).max_health,
+                state: NpcState::Idle,
+                waypoint: home,
+                last_attack_time: 0,
+                respawn_at: None,
+            },
+        );
+        (regions, npcs)
+    }
+
+    fn update(regions: &Regions, npcs: &Npcs, players: &[Player], now: u64) -> Vec<NpcAttack> {
+        let mut rng = StdRng::seed_from_u64(7);
+        npcs.update(players, regions, now, 3, 10, &mut rng).unwrap()
+    }
+
+    #[test]
+    fn test_regions_are_populated_from_spawn_tables() {
+        let regions = Regions::from_config(&GameConfig {
+            world_regions: vec!["Neon Harbor".to_string(), "The Grid".to_string()],
+            ..Default::default()
+        });
+        let npcs = Npcs::new();
+        npcs.spawn(&regions, &mut StdRng::seed_from_u64(7)).unwrap();
+
+        let drones = npcs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|npc| npc.kind == NpcKind::SurveillanceDrone)
+            .count();
+        assert_eq!(drones, 8);
+        let last = npcs.get("DRONE8").unwrap();
+        assert_eq!(last.region, 1);
+        assert_eq!(regions.index_at(&last.position), 1);
+    }
+
+    #[test]
+    fn test_drones_chase_and_attack_but_spare_the_hegemony() {
+        let (regions, npcs) = world_with(NpcKind::SurveillanceDrone);
+
+        // The Hegemony walks by unharmed, and the drone keeps patrolling
+        let corp = player("corp", Faction::CorporateHegemony, 5.0, 0.0);
+        assert!(update(&regions, &npcs, std::slice::from_ref(&corp), 100).is_empty());
+        assert_eq!(npcs.get("npc").unwrap().state, NpcState::Patrol);
+
+        // Anyone else within reach gets chased...
+        npcs.lock().unwrap().get_mut("npc").unwrap().position = Position::new(0.0, 0.0);
+        let nym = player("nym", Faction::Nyms, 30.0, 0.0);
+        assert!(update(&regions, &npcs, &[corp.clone(), nym.clone()], 100).is_empty());
+        let drone = npcs.get("npc").unwrap();
+        assert_eq!(drone.state, NpcState::Chase);
+        assert_eq!(drone.position, Position::new(6.0, 0.0));
+
+        // ...and attacked once caught up with, no more often than the cooldown allows
+        let nym = player("nym", Faction::Nyms, 10.0, 0.0);
+        let attacks = update(&regions, &npcs, std::slice::from_ref(&nym), 100);
+        assert_eq!(attacks.len(), 1);
+        assert_eq!(attacks[0].target, "nym");
+        assert_eq!(attacks[0].damage, 8);
+        assert!(update(&regions, &npcs, std::slice::from_ref(&nym), 101).is_empty());
+        assert_eq!(update(&regions, &npcs, &[nym], 103).len(), 1);
+    }
+
+    #[test]
+    fn test_hurt_brokers_flee_and_defeated_npcs_respawn() {
+        let (regions, npcs) = world_with(NpcKind::DeepNetBroker);
+        let nym = player("nym", Faction::Nyms, 10.0, 0.0);
+
+        // Brokers never attack and stay put while unharmed
+        assert!(update(&regions, &npcs, std::slice::from_ref(&nym), 100).is_empty());
+        assert_eq!(npcs.get("npc").unwrap().state, NpcState::Idle);
+
+        assert_eq!(npcs.damage("NPC", 20).unwrap(), Some((20, false)));
+        update(&regions, &npcs, std::slice::from_ref(&nym), 101);
+        let broker = npcs.get("npc").unwrap();
+        assert_eq!(broker.state, NpcState::Flee);
+        assert_eq!(broker.position, Position::new(-5.0, 0.0));
+
+        assert_eq!(npcs.damage("npc", 100).unwrap(), Some((40, true)));
+        assert_eq!(npcs.damage("npc", 10).unwrap(), None);
+        assert!(npcs
+            .views_near(0, &Position::new(0.0, 0.0), 100.0)
+            .is_empty());
+
+        // Defeated characters come back after the respawn time
+        update(&regions, &npcs, &[], 102);
+        update(&regions, &npcs, &[], 111);
+        assert!(!npcs.get("npc").unwrap().is_alive());
+        update(&regions, &npcs, &[], 112);
+        assert_eq!(npcs.get("npc").unwrap().health, 60);
+    }
+
+    #[test]
+    fn test_only_changed_views_are_sent() {
+        let (_, npcs) = world_with(NpcKind::MonkSentinel);
+        let views = npcs.views_near(0, &Position::new(20.0, 0.0), 60.0);
+        assert_eq!(views.len(), 1);
+        assert!(npcs.record_sent("nym", &views).unwrap());
+        assert!(!npcs.record_sent("nym", &views).unwrap());
+
+        // Characters in other regions or out of view are left out
+        assert!(npcs
+            .views_near(1, &Position::new(0.0, 0.0), 60.0)
+            .is_empty());
+        assert!(npcs
+            .views_near(0, &Position::new(90.0, 90.0), 60.0)
+            .is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::fs;
@@ -8,8 +8,9 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::config::GameConfig;
-use crate::game_protocol::{Player, Position};
-use crate::world_lore::Faction;
+use crate::regions::Regions;
+use nymquest_protocol::game_protocol::{Player, Position, QuestStatus, TradeAssets};
+use nymquest_protocol::world_lore::{EquipmentSlot, Faction};
 
 /// Persistable game state structure that excludes sensitive runtime data
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,8 +40,35 @@ pub struct PersistedPlayer {
     pub level: u8,
     /// The faction the player belongs to
     pub faction: Faction,
+    /// Item ID -> number of unused copies carried
+    #[serde(default)]
+    pub inventory: BTreeMap<String, u32>,
+    /// Item ID -> time (seconds) the effect of a used copy wears off
+    #[serde(default)]
+    pub active_items: BTreeMap<String, u64>,
+    /// Equipment slot -> ID of the item worn in it
+    #[serde(default)]
+    pub equipment: BTreeMap<EquipmentSlot, String>,
+    /// Credits earned alongside experience, spent in trades
+    #[serde(default)]
+    pub credits: u32,
+    /// What the player put up in an unfinished trade; it goes back to them on restore
+    #[serde(default)]
+    pub escrow: TradeAssets,
+    /// Standing with each faction
+    #[serde(default)]
+    pub reputation: BTreeMap<Faction, i32>,
+    /// Factions that already handed the player their signature item
+    #[serde(default)]
+    pub rewarded_factions: BTreeSet<Faction>,
+    /// Quest ID -> progress on every quest the player accepted
+    #[serde(default)]
+    pub quests: BTreeMap<String, QuestStatus>,
     /// Timestamp when player was last active (for cleanup purposes)
     pub last_active: u64,
+    /// Hash of the player's reclaim token; the token itself is never stored
+    #[serde(default)]
+    pub reclaim_token_hash: Option<String>,
 }
 
 /// Snapshot of relevant game configuration for validation during recovery
@@ -50,6 +78,8 @@ pub struct GameConfigSnapshot {
     pub world_min_x: f32,
     pub world_max_y: f32,
     pub world_min_y: f32,
+    #[serde(default)]
+    pub world_regions: Vec<String>,
     pub initial_player_health: u32,
     pub max_players: usize,
 }
@@ -61,6 +91,7 @@ impl From<&GameConfig> for GameConfigSnapshot {
             world_min_x: config.world_min_x,
             world_max_y: config.world_max_y,
             world_min_y: config.world_min_y,
+            world_regions: config.world_regions.clone(),
             initial_player_health: config.initial_player_health,
             max_players: config.max_players,
         }
@@ -123,10 +154,11 @@ impl GameStatePersistence {
         }
     }
 
-    /// Save game state to disk
+    /// Save game state to disk, with the reclaim token hashes keyed by player ID
     pub async fn save_state(
         &self,
         players: &HashMap<String, Player>,
+        reclaim_token_hashes: &HashMap<String, String>,
         config: &GameConfig,
     ) -> Result<()> {
         if !self.enabled {
@@ -152,7 +184,16 @@ impl GameStatePersistence {
                     experience: player.experience,
                     level: player.level,
                     faction: player.faction.clone(), // Store player's faction
-                    last_active: now,                // Mark as active during save
+                    inventory: player.inventory.clone(),
+                    active_items: player.active_items.clone(),
+                    equipment: player.equipment.clone(),
+                    credits: player.credits,
+                    escrow: player.escrow.clone(),
+                    reputation: player.reputation.clone(),
+                    rewarded_factions: player.rewarded_factions.clone(),
+                    quests: player.quests.clone(),
+                    last_active: now, // Mark as active during save
+                    reclaim_token_hash: reclaim_token_hashes.get(id).cloned(),
                 };
                 (id.clone(), persisted)
             })
@@ -297,6 +338,7 @@ impl GameStatePersistence {
             || config_snapshot.world_min_x != current_config.world_min_x
             || config_snapshot.world_max_y != current_config.world_max_y
             || config_snapshot.world_min_y != current_config.world_min_y
+            || config_snapshot.world_regions != current_config.world_regions
         {
             warn!("World boundaries changed since last save - players may need repositioning");
         }
@@ -310,8 +352,9 @@ impl GameStatePersistence {
         }
 
         // Validate player positions are still within current world boundaries
+        let regions = Regions::from_config(current_config);
         for (player_id, player) in &state.players {
-            if !current_config.is_position_valid(player.position.x, player.position.y) {
+            if !regions.contains(&player.position) {
                 warn!(
                     "Player {} position ({}, {}) is outside current world boundaries",
                     player_id, player.position.x, player.position.y
@@ -346,7 +389,7 @@ impl GameStatePersistence {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::game_protocol::Position;
+    use nymquest_protocol::game_protocol::Position;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -375,14 +418,38 @@ mod tests {
             position: Position::new(10.0, 20.0),
             health: 100,
             last_attack_time: 1234567890,
+            last_damage_time: 1234567890,
             experience: 50,
             level: 1,
             faction: Faction::Independent,
+            inventory: BTreeMap::from([("zk_prover".to_string(), 2)]),
+            active_items: BTreeMap::from([("mixnet_relay".to_string(), 1234567999)]),
+            equipment: BTreeMap::from([(EquipmentSlot::AttackTool, "packet_sniffer".to_string())]),
+            credits: 30,
+            escrow: TradeAssets {
+                credits: 12,
+                items: BTreeMap::from([("zk_prover".to_string(), 1)]),
+            },
+            reputation: BTreeMap::from([(Faction::AlgorithmMonks, 120)]),
+            rewarded_factions: BTreeSet::from([Faction::AlgorithmMonks]),
+            quests: BTreeMap::from([(
+                "first_contact".to_string(),
+                QuestStatus {
+                    progress: vec![1],
+                    completed: true,
+                },
+            )]),
+            effects: BTreeMap::new(),
         };
         players.insert("player1".to_string(), player);
+        let reclaim_token_hashes =
+            HashMap::from([("player1".to_string(), "dG9rZW4gaGFzaA==".to_string())]);
 
         // Save state
-        assert!(persistence.save_state(&players, &config).await.is_ok());
+        assert!(persistence
+            .save_state(&players, &reclaim_token_hashes, &config)
+            .await
+            .is_ok());
 
         // Load state
         let loaded_state = persistence.load_state(&config).await.unwrap();
@@ -396,6 +463,24 @@ mod tests {
         assert_eq!(loaded_player.name, "Test Player");
         assert_eq!(loaded_player.position.x, 10.0);
         assert_eq!(loaded_player.position.y, 20.0);
+        assert_eq!(loaded_player.inventory["zk_prover"], 2);
+        assert_eq!(loaded_player.active_items["mixnet_relay"], 1234567999);
+        assert_eq!(
+            loaded_player.equipment[&EquipmentSlot::AttackTool],
+            "packet_sniffer"
+        );
+        assert_eq!(loaded_player.credits, 30);
+        assert_eq!(loaded_player.escrow.credits, 12);
+        assert_eq!(loaded_player.escrow.items["zk_prover"], 1);
+        assert_eq!(loaded_player.reputation[&Faction::AlgorithmMonks], 120);
+        assert!(loaded_player
+            .rewarded_factions
+            .contains(&Faction::AlgorithmMonks));
+        assert!(loaded_player.quests["first_contact"].completed);
+        assert_eq!(
+            loaded_player.reclaim_token_hash.as_deref(),
+            Some("dG9rZW4gaGFzaA==")
+        );
     }
 
     #[tokio::test]
@@ -407,7 +492,10 @@ mod tests {
         let players = HashMap::new();
 
         // Operations should succeed but do nothing
-        assert!(persistence.save_state(&players, &config).await.is_ok());
+        assert!(persistence
+            .save_state(&players, &HashMap::new(), &config)
+            .await
+            .is_ok());
         let loaded = persistence.load_state(&config).await.unwrap();
         assert!(loaded.is_none());
     }
@@ -442,7 +530,16 @@ mod tests {
                 experience: 25,
                 level: 1,
                 faction: Faction::Nyms, // Adding default test faction
+                inventory: BTreeMap::new(),
+                active_items: BTreeMap::new(),
+                equipment: BTreeMap::new(),
+                credits: 0,
+                escrow: TradeAssets::default(),
+                reputation: BTreeMap::new(),
+                rewarded_factions: BTreeSet::new(),
+                quests: BTreeMap::new(),
                 last_active: now,
+                reclaim_token_hash: None,
             },
         );
 
@@ -459,7 +556,16 @@ mod tests {
                 experience: 75,
                 level: 2,
                 faction: Faction::CorporateHegemony, // Adding test faction
-                last_active: now - 3600,             // 1 hour ago
+                inventory: BTreeMap::new(),
+                active_items: BTreeMap::new(),
+                equipment: BTreeMap::new(),
+                credits: 0,
+                escrow: TradeAssets::default(),
+                reputation: BTreeMap::new(),
+                rewarded_factions: BTreeSet::new(),
+                quests: BTreeMap::new(),
+                last_active: now - 3600, // 1 hour ago
+                reclaim_token_hash: None,
             },
         );
 
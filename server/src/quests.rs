@@ -0,0 +1,317 @@
+//! Quests and how their objectives progress
+//!
+//! Quests are read at startup from the JSON file at `quest_file`, a list of
+//! quest definitions (see `QuestDefinition`), each with objectives of one of
+//! the types `reach`, `defeat`, `whisper` and `stay_hidden`. A player is offered
+//! every quest of their faction, and those without one, that they have not
+//! taken on yet, and can work on any number at once. Objectives progress as the
+//! simulation reports what players did: defeating NPCs, whispering to them, and
+//! once per second where they stand, how surveilled that spot is and whether
+//! they are exposed. A quest is complete once all of its objectives are met,
+//! and can only be completed once. Progress is kept with the player and
+//! persisted with them.
+
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use tracing::debug;
+
+use nymquest_protocol::game_protocol::{
+    LootDrop, NpcKind, Player, Position, QuestDefinition, QuestObjective, QuestStatus,
+};
+use nymquest_protocol::world_lore::generate_crypto_items;
+
+/// Something a player did that may count toward their quests
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestEvent<'a> {
+    /// The player defeated an NPC of this kind
+    Defeated(NpcKind),
+    /// The player whispered to the NPC with this display ID
+    Whispered(&'a str),
+    /// A second passed with the player at `position`, under `surveillance`
+    Second {
+        position: &'a Position,
+        surveillance: f32,
+        exposed: bool,
+    },
+}
+
+/// A quest whose progress changed, and what it handed out if that completed it
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestUpdate {
+    pub quest: QuestDefinition,
+    pub status: QuestStatus,
+    /// Experience the player gained for completing the quest, after faction modifiers
+    pub experience: u32,
+    /// Item IDs the player received for completing the quest
+    pub items: Vec<String>,
+    /// Reward items that did not fit in the inventory, left on the ground at the player's feet
+    pub dropped: Vec<LootDrop>,
+    /// Drops that vanished to make room for them on a full ground
+    pub removed: Vec<LootDrop>,
+}
+
+/// Read the quest definitions from `path`; without a file there are no quests
+pub fn load(path: &str) -> Result<Vec<QuestDefinition>> {
+    match fs::read_to_string(Path::new(path)) {
+        Ok(text) => {
+            let quests = QuestDefinition::parse_all(&text)
+                .map_err(|e| anyhow!("Invalid quest file {}: {}", path, e))?;
+            let catalog = generate_crypto_items();
+            for quest in &quests {
+                if let Some(item) = quest
+                    .reward_items
+                    .iter()
+                    .find(|item| !catalog.contains_key(*item))
+                {
+                    return Err(anyhow!(
+                        "Quest '{}' in {} rewards unknown item '{}'",
+                        quest.id,
+                        path,
+                        item
+                    ));
+                }
+            }
+            Ok(quests)
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            debug!("No quest file at {}, no quests are offered", path);
+            Ok(Vec::new())
+        }
+        Err(e) => Err(anyhow!("Failed to read quest file {}: {}", path, e)),
+    }
+}
+
+/// Quests `player` can take on, in the order they are defined
+pub fn offers(quests: &[QuestDefinition], player: &Player) -> Vec<QuestDefinition> {
+    quests
+        .iter()
+        .filter(|quest| {
+            quest.is_offered_to(&player.faction) && !player.quests.contains_key(&quest.id)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Progress of a quest that was just taken on
+pub fn start(quest: &QuestDefinition) -> QuestStatus {
+    QuestStatus {
+        progress: vec![0; quest.objectives.len()],
+        completed: false,
+    }
+}
+
+/// Advance the objectives of `quest` that `event` counts toward
+///
+/// Returns whether any progress changed. Staying hidden has to be done in one
+/// go, so seconds spent surveilled or exposed start that objective over.
+pub fn advance(quest: &QuestDefinition, status: &mut QuestStatus, event: &QuestEvent) -> bool {
+    if status.completed {
+        return false;
+    }
+    status.progress.resize(quest.objectives.len(), 0);
+
+    let mut changed = false;
+    for (objective, progress) in quest.objectives.iter().zip(status.progress.iter_mut()) {
+        let required = objective.required();
+        if *progress >= required {
+            continue;
+        }
+        let next = match (objective, event) {
+            (QuestObjective::Defeat { kind, .. }, QuestEvent::Defeated(defeated))
+                if kind == defeated =>
+            {
+                *progress + 1
+            }
+            (QuestObjective::Whisper { npc }, QuestEvent::Whispered(whispered))
+                if npc.eq_ignore_ascii_case(whispered) =>
+            {
+                required
+            }
+            (
+                QuestObjective::Reach { position, radius },
+                QuestEvent::Second { position: at, .. },
+            ) if position.distance_to(at) <= *radius => required,
+            (
+                QuestObjective::StayHidden {
+                    max_surveillance, ..
+                },
+                QuestEvent::Second {
+                    surveillance,
+                    exposed,
+                    ..
+                },
+            ) => {
+                if *exposed || surveillance > max_surveillance {
+                    0
+                } else {
+                    *progress + 1
+                }
+            }
+            _ => continue,
+        };
+        if next != *progress {
+            *progress = next;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Whether every objective of `quest` is met
+pub fn is_done(quest: &QuestDefinition, status: &QuestStatus) -> bool {
+    quest
+        .objectives
+        .iter()
+        .enumerate()
+        .all(|(index, objective)| {
+            status.progress.get(index).copied().unwrap_or(0) >= objective.required()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::world_lore::Faction;
+
+    fn quest() -> QuestDefinition {
+        QuestDefinition {
+            id: "ghost_run".to_string(),
+            name: "Ghost Run".to_string(),
+            description: "Get to the relay without being seen.".to_string(),
+            faction: Some(Faction::Nyms),
+            objectives: vec![
+                QuestObjective::Reach {
+                    position: Position::new(50.0, 50.0),
+                    radius: 10.0,
+                },
+                QuestObjective::StayHidden {
+                    max_surveillance: 0.3,
+                    seconds: 3,
+                },
+                QuestObjective::Defeat {
+                    kind: NpcKind::SurveillanceDrone,
+                    count: 2,
+                },
+                QuestObjective::Whisper {
+                    npc: "Broker1".to_string(),
+                },
+            ],
+            reward_experience: 50,
+            reward_items: Vec::new(),
+        }
+    }
+
+    fn second(position: &Position, surveillance: f32) -> QuestEvent<'_> {
+        QuestEvent::Second {
+            position,
+            surveillance,
+            exposed: false,
+        }
+    }
+
+    #[test]
+    fn test_objectives_progress_until_the_quest_is_done() {
+        let quest = quest();
+        let mut status = start(&quest);
+        let away = Position::new(0.0, 0.0);
+
+        // Hiding has to last without a break
+        assert!(advance(&quest, &mut status, &second(&away, 0.1)));
+        assert!(advance(&quest, &mut status, &second(&away, 0.1)));
+        assert!(advance(&quest, &mut status, &second(&away, 0.5)));
+        assert_eq!(status.progress, vec![0, 0, 0, 0]);
+        for _ in 0..3 {
+            advance(&quest, &mut status, &second(&away, 0.2));
+        }
+        assert!(!advance(
+            &quest,
+            &mut status,
+            &QuestEvent::Second {
+                position: &away,
+                surveillance: 0.0,
+                exposed: true,
+            }
+        ));
+        assert_eq!(status.progress, vec![0, 3, 0, 0]);
+
+        assert!(!advance(
+            &quest,
+            &mut status,
+            &QuestEvent::Defeated(NpcKind::DeepNetBroker)
+        ));
+        for _ in 0..3 {
+            advance(
+                &quest,
+                &mut status,
+                &QuestEvent::Defeated(NpcKind::SurveillanceDrone),
+            );
+        }
+        assert!(advance(
+            &quest,
+            &mut status,
+            &QuestEvent::Whispered("broker1")
+        ));
+        assert!(!is_done(&quest, &status));
+
+        assert!(advance(
+            &quest,
+            &mut status,
+            &second(&Position::new(45.0, 52.0), 0.9)
+        ));
+        assert_eq!(status.progress, vec![1, 3, 2, 1]);
+        assert!(is_done(&quest, &status));
+
+        // Completed quests stay as they are
+        status.completed = true;
+        assert!(!advance(
+            &quest,
+            &mut status,
+            &QuestEvent::Defeated(NpcKind::SurveillanceDrone)
+        ));
+    }
+
+    #[test]
+    fn test_quests_are_offered_by_faction_and_only_once() {
+        let open = QuestDefinition {
+            id: "open".to_string(),
+            faction: None,
+            ..quest()
+        };
+        let quests = vec![quest(), open.clone()];
+        let mut player = Player {
+            id: "p".to_string(),
+            display_id: "Player1".to_string(),
+            name: "alice".to_string(),
+            position: Position::new(0.0, 0.0),
+            health: 100,
+            level: 1,
+            experience: 0,
+            last_attack_time: 0,
+            last_damage_time: 0,
+            faction: Faction::CorporateHegemony,
+            inventory: Default::default(),
+            active_items: Default::default(),
+            equipment: Default::default(),
+            credits: 0,
+            escrow: Default::default(),
+            reputation: Default::default(),
+            rewarded_factions: Default::default(),
+            quests: Default::default(),
+            effects: Default::default(),
+        };
+        assert_eq!(offers(&quests, &player), vec![open.clone()]);
+
+        player.faction = Faction::Nyms;
+        player.quests.insert("open".to_string(), start(&open));
+        assert_eq!(offers(&quests, &player), vec![quest()]);
+    }
+
+    #[test]
+    fn test_missing_quest_files_mean_no_quests() {
+        assert!(load("/nonexistent/quests.json").unwrap().is_empty());
+        let shipped = load(concat!(env!("CARGO_MANIFEST_DIR"), "/quests.json")).unwrap();
+        assert!(!shipped.is_empty());
+    }
+}
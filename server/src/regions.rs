@@ -0,0 +1,366 @@
+//! Regions of the world and the edges between them
+//!
+//! Without `world_regions` the world is a single region spanning the
+//! configured world boundaries, as it always was. With them, the world is a
+//! row of regions laid out west to east in the configured order, each as wide
+//! and as tall as its lore boundaries and centred on y = 0, so every position
+//! belongs to exactly one region. Walking past the east or west edge of a
+//! region enters its neighbour; the outer edges of the row and the north and
+//! south edges of every region are walls. A region takes its security level,
+//! surveillance density and loot table from its lore. Players only see, fight
+//! and pick up loot within their own region, and `RegionIndex` keeps the
+//! players of each region so visibility only looks at the observer's region.
+//!
+//! Each region also has the terrain map loaded for it, if any. Players cannot
+//! walk or attack through its walls, slow terrain holds them back, nobody
+//! attacks or is attacked in its safe zones, and players and loot are placed
+//! outside walls.
+
+use std::collections::{HashMap, HashSet};
+
+use nymquest_protocol::game_protocol::{Position, TerrainMap, Tile, WorldBoundaries};
+use nymquest_protocol::world_lore::WorldRegion;
+use rand::Rng;
+
+use crate::config::GameConfig;
+
+/// Distance between the points checked along a line for walls
+const TERRAIN_SAMPLE_STEP: f32 = 1.0;
+
+/// Random spots tried before settling for one inside a wall
+const MAX_PLACEMENT_ATTEMPTS: usize = 100;
+
+/// One region of the world and the area it covers
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub lore: WorldRegion,
+    pub boundaries: WorldBoundaries,
+    pub terrain: TerrainMap,
+}
+
+/// Every region of the world, west to east
+pub struct Regions {
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Lay out the regions of a world with the given configuration
+    pub fn from_config(config: &GameConfig) -> Self {
+        let lore: Vec<WorldRegion> = config
+            .world_regions
+            .iter()
+            .filter_map(|name| WorldRegion::from_name(name))
+            .collect();
+        let terrain = |region: WorldRegion| {
+            let name = region.get_boundaries().name;
+            config
+                .terrain_maps
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| TerrainMap {
+                    region: name.to_string(),
+                    rows: Vec::new(),
+                })
+        };
+        if lore.is_empty() {
+            let region = config.lore_region();
+            return Self {
+                regions: vec![Region {
+                    lore: region,
+                    boundaries: config.world_boundaries(),
+                    terrain: terrain(region),
+                }],
+            };
+        }
+
+        // The first region keeps its lore position and the others line up east of it
+        let mut west_edge = lore[0].get_boundaries().min_x;
+        let regions = lore
+            .into_iter()
+            .map(|region| {
+                let bounds = region.get_boundaries();
+                let east_edge = west_edge + (bounds.max_x - bounds.min_x);
+                let region = Region {
+                    lore: region,
+                    terrain: terrain(region),
+                    boundaries: WorldBoundaries::for_region(
+                        Some(bounds.name),
+                        west_edge,
+                        east_edge,
+                        bounds.min_y,
+                        bounds.max_y,
+                    ),
+                };
+                west_edge = east_edge;
+                region
+            })
+            .collect();
+        Self { regions }
+    }
+
+    /// Every region, west to east
+    pub fn all(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// The region new players start in
+    pub fn home(&self) -> &Region {
+        &self.regions[0]
+    }
+
+    /// Index of the region `position` lies in, or of the nearest one to the west or east
+    ///
+    /// A position on the edge between two regions belongs to the eastern one.
+    pub fn index_at(&self, position: &Position) -> usize {
+        self.regions
+            .iter()
+            .rposition(|region| position.x >= region.boundaries.min_x)
+            .unwrap_or(0)
+    }
+
+    /// The region `position` lies in, or the nearest one to the west or east
+    pub fn region_at(&self, position: &Position) -> &Region {
+        &self.regions[self.index_at(position)]
+    }
+
+    /// Whether two positions lie in the same region
+    pub fn same_region(&self, a: &Position, b: &Position) -> bool {
+        self.index_at(a) == self.index_at(b)
+    }
+
+    /// Whether `position` lies inside one of the regions
+    pub fn contains(&self, position: &Position) -> bool {
+        self.region_at(position)
+            .boundaries
+            .is_position_valid(position.x, position.y)
+    }
+
+    /// Closest position to `position` inside the world
+    ///
+    /// Positions past the east or west edge of a region are inside its
+    /// neighbour, if it has one, so clamping a step is what carries players
+    /// from one region into the next.
+    pub fn clamp(&self, position: &Position) -> Position {
+        let west = self.regions[0].boundaries.min_x;
+        let east = self.regions[self.regions.len() - 1].boundaries.max_x;
+        let x = position.x.clamp(west, east);
+        let (x, y) = self
+            .region_at(&Position::new(x, position.y))
+            .boundaries
+            .clamp_position(x, position.y);
+        Position::new(x, y)
+    }
+
+    /// Terrain at `position`
+    pub fn tile_at(&self, position: &Position) -> Tile {
+        let region = self.region_at(position);
+        region
+            .terrain
+            .tile_at(&region.boundaries, position.x, position.y)
+    }
+
+    /// Whether `position` is in a safe zone, where nobody attacks or is attacked
+    pub fn is_safe(&self, position: &Position) -> bool {
+        self.tile_at(position) == Tile::Safe
+    }
+
+    /// Whether no wall stands on the straight line from `from` to `to`
+    ///
+    /// The line is checked every `TERRAIN_SAMPLE_STEP` world units, so it can
+    /// run across a region edge.
+    pub fn is_clear(&self, from: &Position, to: &Position) -> bool {
+        let steps = (from.distance_to(to) / TERRAIN_SAMPLE_STEP).ceil().max(1.0) as usize;
+        (0..=steps).all(|step| {
+            let t = step as f32 / steps as f32;
+            let point = Position::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t);
+            self.tile_at(&point) != Tile::Wall
+        })
+    }
+
+    /// A random position inside the region with index `index`, away from walls if possible
+    pub fn random_position<R: Rng>(&self, index: usize, rng: &mut R) -> Position {
+        let bounds = &self.regions[index.min(self.regions.len() - 1)].boundaries;
+        // The east edge belongs to the next region, so stay just short of it
+        let mut position = Position::new(0.0, 0.0);
+        for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+            position = Position::new(
+                rng.gen_range(bounds.min_x..bounds.max_x),
+                rng.gen_range(bounds.min_y..bounds.max_y),
+            );
+            if self.tile_at(&position) != Tile::Wall {
+                break;
+            }
+        }
+        position
+    }
+}
+
+/// Players of each region, by region index
+///
+/// Kept up to date as players join, move and leave.
+#[derive(Debug, Default)]
+pub struct RegionIndex {
+    /// Player IDs in each region that has players
+    players: HashMap<usize, HashSet<String>>,
+    /// Region each player is currently in
+    player_regions: HashMap<String, usize>,
+}
+
+impl RegionIndex {
+    /// Create an index without players
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a player or move them to `region`
+    pub fn update(&mut self, player_id: &str, region: usize) {
+        match self.player_regions.get(player_id) {
+            Some(current) if *current == region => return,
+            Some(_) => self.remove(player_id),
+            None => {}
+        }
+        self.players
+            .entry(region)
+            .or_default()
+            .insert(player_id.to_string());
+        self.player_regions.insert(player_id.to_string(), region);
+    }
+
+    /// Remove a player from the index
+    pub fn remove(&mut self, player_id: &str) {
+        if let Some(region) = self.player_regions.remove(player_id) {
+            if let Some(players) = self.players.get_mut(&region) {
+                players.remove(player_id);
+                if players.is_empty() {
+                    self.players.remove(&region);
+                }
+            }
+        }
+    }
+
+    /// Whether `player_id` is in `region`
+    pub fn contains(&self, region: usize, player_id: &str) -> bool {
+        self.player_regions.get(player_id) == Some(&region)
+    }
+
+    /// IDs of the players in `region`
+    pub fn players_in(&self, region: usize) -> Vec<&String> {
+        self.players
+            .get(&region)
+            .map_or_else(Vec::new, |players| players.iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn harbor_and_deep_net() -> Regions {
+        Regions::from_config(&GameConfig {
+            world_regions: vec!["Neon Harbor".to_string(), "Deep Net".to_string()],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_single_region_spans_the_world() {
+        let regions = Regions::from_config(&GameConfig::default());
+        assert_eq!(regions.all().len(), 1);
+        assert_eq!(regions.home().boundaries.min_x, -100.0);
+        assert_eq!(
+            regions.clamp(&Position::new(150.0, -150.0)),
+            Position::new(100.0, -100.0)
+        );
+    }
+
+    #[test]
+    fn test_regions_line_up_west_to_east() {
+        let regions = harbor_and_deep_net();
+        let deep_net = &regions.all()[1].boundaries;
+        assert_eq!(regions.all()[1].lore, WorldRegion::DeepNet);
+        assert_eq!((deep_net.min_x, deep_net.max_x), (100.0, 400.0));
+        assert_eq!((deep_net.min_y, deep_net.max_y), (-150.0, 150.0));
+        assert_eq!(deep_net.security_level, "Low");
+
+        assert_eq!(regions.index_at(&Position::new(99.0, 0.0)), 0);
+        assert_eq!(regions.index_at(&Position::new(100.0, 0.0)), 1);
+        assert!(!regions.same_region(&Position::new(90.0, 0.0), &Position::new(110.0, 0.0)));
+
+        // Neon Harbor is shorter than the Deep Net
+        assert!(regions.contains(&Position::new(200.0, 140.0)));
+        assert!(!regions.contains(&Position::new(50.0, 140.0)));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let position = regions.random_position(1, &mut rng);
+            assert_eq!(regions.index_at(&position), 1);
+        }
+    }
+
+    #[test]
+    fn test_walls_block_lines_and_placement() {
+        let mut config = GameConfig::default();
+        // A wall runs down the middle of Neon Harbor, with a gap at the south end
+        let rows = ["..#..", "..#..", "..#..", "..#..", "....~"];
+        config.terrain_maps.insert(
+            "Neon Harbor".to_string(),
+            TerrainMap::parse("Neon Harbor", &rows.join("\n")).unwrap(),
+        );
+        let regions = Regions::from_config(&config);
+
+        assert_eq!(regions.tile_at(&Position::new(0.0, 0.0)), Tile::Wall);
+        assert_eq!(regions.tile_at(&Position::new(90.0, 90.0)), Tile::Slow);
+        assert!(!regions.is_clear(&Position::new(-50.0, 0.0), &Position::new(50.0, 0.0)));
+        assert!(regions.is_clear(&Position::new(-50.0, 90.0), &Position::new(50.0, 90.0)));
+        assert!(regions.is_clear(&Position::new(-50.0, 0.0), &Position::new(-50.0, 90.0)));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let position = regions.random_position(0, &mut rng);
+            assert_ne!(regions.tile_at(&position), Tile::Wall);
+        }
+    }
+
+    #[test]
+    fn test_region_index_follows_players() {
+        let mut index = RegionIndex::new();
+        index.update("a", 0);
+        index.update("b", 0);
+        index.update("a", 1);
+        assert_eq!(index.players_in(0), vec!["b"]);
+        assert!(index.contains(1, "a"));
+        assert!(!index.contains(0, "a"));
+
+        index.remove("a");
+        assert!(index.players_in(1).is_empty());
+        assert!(!index.contains(1, "a"));
+    }
+
+    #[test]
+    fn test_clamping_crosses_region_edges() {
+        let regions = harbor_and_deep_net();
+
+        // Walking east out of Neon Harbor enters the Deep Net
+        let entered = regions.clamp(&Position::new(105.0, 20.0));
+        assert_eq!(entered, Position::new(105.0, 20.0));
+        assert_eq!(regions.index_at(&entered), 1);
+
+        // Walking west out of the Deep Net lands inside Neon Harbor's shorter bounds
+        assert_eq!(
+            regions.clamp(&Position::new(95.0, 140.0)),
+            Position::new(95.0, 100.0)
+        );
+
+        // The outer edges are walls
+        assert_eq!(
+            regions.clamp(&Position::new(450.0, 0.0)),
+            Position::new(400.0, 0.0)
+        );
+        assert_eq!(
+            regions.clamp(&Position::new(-120.0, 0.0)),
+            Position::new(-100.0, 0.0)
+        );
+    }
+}
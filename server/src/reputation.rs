@@ -0,0 +1,134 @@
+//! Faction reputation
+//!
+//! Every player has a standing with each faction, starting at 0 and kept
+//! between `MIN_REPUTATION` and `MAX_REPUTATION`. Hitting a member of a faction
+//! costs reputation with it, and defeating one costs more but earns reputation
+//! with the faction's rival, for whom that is a task done. Completing a trade
+//! that moved anything earns a little reputation with the partner's faction.
+//! Once a player's standing reaches `reputation_emote_threshold` they can
+//! perform the faction's emote, and the first time it reaches
+//! `reputation_item_threshold` the faction hands them its signature item.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nymquest_protocol::game_protocol::{EmoteType, FactionStanding};
+use nymquest_protocol::world_lore::Faction;
+
+/// Lowest standing a player can have with a faction
+pub const MIN_REPUTATION: i32 = -1000;
+/// Highest standing a player can have with a faction
+pub const MAX_REPUTATION: i32 = 1000;
+
+/// Reputation lost with a faction for hitting one of its members
+const ATTACK_PENALTY: i32 = 2;
+/// Further reputation lost with a faction for defeating one of its members
+const DEFEAT_PENALTY: i32 = 10;
+/// Reputation earned with a faction for defeating a member of its rival
+const RIVAL_DEFEAT_REWARD: i32 = 15;
+/// Reputation earned with the partner's faction for completing a trade
+const TRADE_REWARD: i32 = 5;
+
+/// What a change in reputation unlocked for a player
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Unlocks {
+    /// Faction emotes the player can now perform
+    pub emotes: Vec<EmoteType>,
+    /// Factions that just handed the player their signature item
+    pub items: Vec<Faction>,
+}
+
+impl Unlocks {
+    /// Whether nothing was unlocked
+    pub fn is_empty(&self) -> bool {
+        self.emotes.is_empty() && self.items.is_empty()
+    }
+}
+
+/// Reputation changes for hitting a member of `target`, defeating them if `defeated`
+pub fn for_attack(target: &Faction, defeated: bool) -> Vec<(Faction, i32)> {
+    if !defeated {
+        return vec![(target.clone(), -ATTACK_PENALTY)];
+    }
+
+    let mut changes = vec![(target.clone(), -ATTACK_PENALTY - DEFEAT_PENALTY)];
+    if let Some(rival) = target.rival() {
+        changes.push((rival, RIVAL_DEFEAT_REWARD));
+    }
+    changes
+}
+
+/// Reputation changes for completing a trade with a member of `partner`
+pub fn for_trade(partner: &Faction) -> Vec<(Faction, i32)> {
+    vec![(partner.clone(), TRADE_REWARD)]
+}
+
+/// Apply `delta` to a standing, keeping it within the reputation range
+pub fn adjusted(score: i32, delta: i32) -> i32 {
+    score
+        .saturating_add(delta)
+        .clamp(MIN_REPUTATION, MAX_REPUTATION)
+}
+
+/// Standing with every faction, in display order
+pub fn standings(
+    reputation: &BTreeMap<Faction, i32>,
+    rewarded_factions: &BTreeSet<Faction>,
+    emote_threshold: i32,
+) -> Vec<FactionStanding> {
+    Faction::ALL
+        .into_iter()
+        .map(|faction| {
+            let score = reputation.get(&faction).copied().unwrap_or(0);
+            FactionStanding {
+                emote_unlocked: score >= emote_threshold,
+                item_unlocked: rewarded_factions.contains(&faction),
+                faction,
+                score,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defeats_anger_the_faction_and_please_its_rival() {
+        assert_eq!(
+            for_attack(&Faction::Nyms, false),
+            vec![(Faction::Nyms, -ATTACK_PENALTY)]
+        );
+        assert_eq!(
+            for_attack(&Faction::Nyms, true),
+            vec![
+                (Faction::Nyms, -ATTACK_PENALTY - DEFEAT_PENALTY),
+                (Faction::CorporateHegemony, RIVAL_DEFEAT_REWARD),
+            ]
+        );
+        // Nobody counts Independents as enemies
+        assert_eq!(for_attack(&Faction::Independent, true).len(), 1);
+
+        assert_eq!(adjusted(MAX_REPUTATION - 1, TRADE_REWARD), MAX_REPUTATION);
+        assert_eq!(adjusted(MIN_REPUTATION, -ATTACK_PENALTY), MIN_REPUTATION);
+    }
+
+    #[test]
+    fn test_standings_cover_every_faction() {
+        let reputation = BTreeMap::from([(Faction::AlgorithmMonks, 30)]);
+        let rewarded = BTreeSet::from([Faction::AlgorithmMonks]);
+        let standings = standings(&reputation, &rewarded, 25);
+
+        assert_eq!(standings.len(), Faction::ALL.len());
+        let monks = standings
+            .iter()
+            .find(|standing| standing.faction == Faction::AlgorithmMonks)
+            .unwrap();
+        assert!(monks.emote_unlocked && monks.item_unlocked);
+        assert_eq!(monks.score, 30);
+        assert!(standings
+            .iter()
+            .filter(|standing| standing.faction != Faction::AlgorithmMonks)
+            .all(|standing| standing.score == 0 && !standing.emote_unlocked));
+    }
+}
@@ -0,0 +1,336 @@
+//! Per-connection session keys
+//!
+//! Every connection authenticates with its own HMAC key, agreed during
+//! `Register` (or `Resume`) from the client's session public key and the server
+//! identity (see `nymquest_protocol::session`). A session is opened by the first
+//! correctly authenticated `Register` or `Resume` from a connection, and every later
+//! message from that connection must verify against the session's key.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use nymquest_protocol::game_protocol::ClientMessage;
+use nymquest_protocol::message_auth::{AuthKey, AuthenticatedMessage};
+use nymquest_protocol::session::{ServerIdentity, ServerPublicKey};
+use nymquest_protocol::transport::SenderTag;
+
+/// How long a session may exist without a registered player before it is pruned
+const UNREGISTERED_SESSION_GRACE: Duration = Duration::from_secs(60);
+
+struct Session {
+    /// Public key the client sent in `Register`
+    client_public_key: String,
+    auth_key: AuthKey,
+    opened_at: Instant,
+}
+
+/// Session keys of all connected clients
+pub struct SessionManager {
+    identity: ServerIdentity,
+    sessions: Mutex<HashMap<SenderTag, Session>>,
+}
+
+impl SessionManager {
+    /// Create a session manager for the server with `identity`
+    pub fn new(identity: ServerIdentity) -> Self {
+        Self {
+            identity,
+            sessions: Mutex::new(HashMap::with_capacity(256)),
+        }
+    }
+
+    /// Public key clients use to agree session keys with this server
+    pub fn public_key(&self) -> ServerPublicKey {
+        self.identity.public_key()
+    }
+
+    /// Verify a message from the connection `tag`, opening its session on `Register` or `Resume`
+    ///
+    /// Returns false for messages that fail authentication or expired, and for
+    /// messages from connections without a session.
+    pub fn verify(
+        &self,
+        tag: &SenderTag,
+        message: &AuthenticatedMessage<ClientMessage>,
+    ) -> Result<bool> {
+        let mut sessions = self.lock()?;
+
+        if let Some(session_public_key) = message.message.session_public_key() {
+            match sessions.get(tag) {
+                // Resent registrations carry the same key
+                Some(session) if session.client_public_key == session_public_key => {}
+                Some(_) => {
+                    warn!("Rejected Register with a new session key on an established connection");
+                    return Ok(false);
+                }
+                None => {
+                    // A captured Register replayed from another connection must not
+                    // open a second session with the same key
+                    if sessions
+                        .values()
+                        .any(|session| session.client_public_key == session_public_key)
+                    {
+                        warn!("Rejected Register reusing another connection's session key");
+                        return Ok(false);
+                    }
+
+                    let auth_key = self.identity.accept_session(session_public_key)?;
+                    if !message.verify(&auth_key)? {
+                        return Ok(false);
+                    }
+
+                    sessions.insert(
+                        *tag,
+                        Session {
+                            client_public_key: session_public_key.to_string(),
+                            auth_key,
+                            opened_at: Instant::now(),
+                        },
+                    );
+                    debug!("Opened session for connection {}", tag);
+                    return Ok(true);
+                }
+            }
+        }
+
+        match sessions.get(tag) {
+            Some(session) => message.verify(&session.auth_key),
+            None => {
+                debug!("Received message from connection {} without a session", tag);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Authenticate a message for the connection `tag` with its session key and
+    /// sign it with the server identity
+    pub fn authenticate<M: Serialize>(
+        &self,
+        tag: &SenderTag,
+        message: M,
+    ) -> Result<AuthenticatedMessage<M>> {
+        let sessions = self.lock()?;
+        let session = sessions
+            .get(tag)
+            .ok_or_else(|| anyhow!("No session for connection {}", tag))?;
+        AuthenticatedMessage::new(message, &session.auth_key)?.sign(&self.identity)
+    }
+
+    /// Authenticate and sign a message for the connection `tag` that expires after `ttl_seconds`
+    pub fn authenticate_with_expiration<M: Serialize>(
+        &self,
+        tag: &SenderTag,
+        message: M,
+        ttl_seconds: u64,
+    ) -> Result<AuthenticatedMessage<M>> {
+        let sessions = self.lock()?;
+        let session = sessions
+            .get(tag)
+            .ok_or_else(|| anyhow!("No session for connection {}", tag))?;
+        AuthenticatedMessage::new_with_expiration(message, &session.auth_key, ttl_seconds)?
+            .sign(&self.identity)
+    }
+
+    /// Forget the session of a connection that has gone away
+    pub fn close(&self, tag: &SenderTag) {
+        if let Ok(mut sessions) = self.lock() {
+            if sessions.remove(tag).is_some() {
+                debug!("Closed session for connection {}", tag);
+            }
+        }
+    }
+
+    /// Rotate every session key that is due; returns how many rotated
+    pub fn rotate_keys(&self) -> Result<usize> {
+        let mut rotated = 0;
+        for session in self.lock()?.values_mut() {
+            if session.auth_key.check_and_rotate()? {
+                rotated += 1;
+            }
+        }
+        if rotated > 0 {
+            info!("Rotated {} session keys", rotated);
+        }
+        Ok(rotated)
+    }
+
+    /// Drop sessions whose connection no longer has a player, after a grace
+    /// period that covers the time between opening a session and registering
+    pub fn prune<F>(&self, has_player: F) -> Result<usize>
+    where
+        F: Fn(&SenderTag) -> bool,
+    {
+        let mut sessions = self.lock()?;
+        let before = sessions.len();
+        sessions.retain(|tag, session| {
+            has_player(tag) || session.opened_at.elapsed() < UNREGISTERED_SESSION_GRACE
+        });
+        let pruned = before - sessions.len();
+        if pruned > 0 {
+            debug!("Pruned {} sessions without players", pruned);
+        }
+        Ok(pruned)
+    }
+
+    /// Number of open sessions
+    pub fn session_count(&self) -> usize {
+        self.lock().map(|sessions| sessions.len()).unwrap_or(0)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<SenderTag, Session>>> {
+        self.sessions
+            .lock()
+            .map_err(|_| anyhow!("Session table lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::game_protocol::ProtocolVersion;
+    use nymquest_protocol::session::ClientHandshake;
+    use nymquest_protocol::world_lore::Faction;
+
+    fn register_message(handshake: &ClientHandshake) -> ClientMessage {
+        ClientMessage::Register {
+            name: "alice".to_string(),
+            faction: Faction::Nyms,
+            seq_num: 1,
+            protocol_version: ProtocolVersion::default(),
+            session_public_key: handshake.public_key(),
+        }
+    }
+
+    fn chat() -> ClientMessage {
+        ClientMessage::Chat {
+            message: "hi".to_string(),
+            seq_num: 2,
+        }
+    }
+
+    #[test]
+    fn test_register_opens_session() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let handshake = ClientHandshake::new();
+        let key = handshake.session_key(&sessions.public_key()).unwrap();
+        let tag = SenderTag::random();
+
+        // Nothing verifies before the session exists
+        let early = AuthenticatedMessage::new(chat(), &key).unwrap();
+        assert!(!sessions.verify(&tag, &early).unwrap());
+
+        let register = AuthenticatedMessage::new(register_message(&handshake), &key).unwrap();
+        assert!(sessions.verify(&tag, &register).unwrap());
+        assert!(sessions.verify(&tag, &early).unwrap());
+        assert_eq!(sessions.session_count(), 1);
+
+        // Replies verify with the client's copy of the key and carry the server signature
+        let reply = sessions.authenticate(&tag, "welcome".to_string()).unwrap();
+        assert!(reply.verify(&key).unwrap());
+        assert!(reply.verify_signature(&sessions.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_resume_opens_session() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let handshake = ClientHandshake::new();
+        let key = handshake.session_key(&sessions.public_key()).unwrap();
+        let tag = SenderTag::random();
+
+        let resume = ClientMessage::Resume {
+            token: "reclaim".to_string(),
+            seq_num: 1,
+            protocol_version: ProtocolVersion::default(),
+            session_public_key: handshake.public_key(),
+        };
+        let resume = AuthenticatedMessage::new(resume, &key).unwrap();
+        assert!(sessions.verify(&tag, &resume).unwrap());
+        assert!(sessions
+            .verify(&tag, &AuthenticatedMessage::new(chat(), &key).unwrap())
+            .unwrap());
+    }
+
+    #[test]
+    fn test_register_with_wrong_key_is_rejected() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let handshake = ClientHandshake::new();
+        // Tagged with a key agreed with some other server
+        let other_key = handshake
+            .session_key(&ServerIdentity::generate().public_key())
+            .unwrap();
+
+        let register = AuthenticatedMessage::new(register_message(&handshake), &other_key).unwrap();
+        assert!(!sessions.verify(&SenderTag::random(), &register).unwrap());
+        assert_eq!(sessions.session_count(), 0);
+    }
+
+    #[test]
+    fn test_replayed_register_cannot_open_second_session() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let handshake = ClientHandshake::new();
+        let key = handshake.session_key(&sessions.public_key()).unwrap();
+        let register = AuthenticatedMessage::new(register_message(&handshake), &key).unwrap();
+
+        let tag = SenderTag::random();
+        assert!(sessions.verify(&tag, &register).unwrap());
+        // Resending on the same connection is fine
+        assert!(sessions.verify(&tag, &register).unwrap());
+        // Replaying it from another connection is not
+        assert!(!sessions.verify(&SenderTag::random(), &register).unwrap());
+
+        // Nor is switching keys on an established connection
+        let other = ClientHandshake::new();
+        let other_key = other.session_key(&sessions.public_key()).unwrap();
+        let switch = AuthenticatedMessage::new(register_message(&other), &other_key).unwrap();
+        assert!(!sessions.verify(&tag, &switch).unwrap());
+    }
+
+    #[test]
+    fn test_sessions_are_isolated() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let alice = ClientHandshake::new();
+        let mallory = ClientHandshake::new();
+        let alice_key = alice.session_key(&sessions.public_key()).unwrap();
+        let mallory_key = mallory.session_key(&sessions.public_key()).unwrap();
+        let alice_tag = SenderTag::random();
+        let mallory_tag = SenderTag::random();
+
+        let register_alice =
+            AuthenticatedMessage::new(register_message(&alice), &alice_key).unwrap();
+        let register_mallory =
+            AuthenticatedMessage::new(register_message(&mallory), &mallory_key).unwrap();
+        assert!(sessions.verify(&alice_tag, &register_alice).unwrap());
+        assert!(sessions.verify(&mallory_tag, &register_mallory).unwrap());
+
+        // Mallory's key does not authenticate messages on alice's connection
+        let forged = AuthenticatedMessage::new(chat(), &mallory_key).unwrap();
+        assert!(!sessions.verify(&alice_tag, &forged).unwrap());
+
+        // And messages for alice do not verify with mallory's key
+        let to_alice = sessions
+            .authenticate(&alice_tag, "secret".to_string())
+            .unwrap();
+        assert!(!to_alice.verify(&mallory_key).unwrap());
+    }
+
+    #[test]
+    fn test_closed_and_pruned_sessions() {
+        let sessions = SessionManager::new(ServerIdentity::generate());
+        let handshake = ClientHandshake::new();
+        let key = handshake.session_key(&sessions.public_key()).unwrap();
+        let tag = SenderTag::random();
+        let register = AuthenticatedMessage::new(register_message(&handshake), &key).unwrap();
+        assert!(sessions.verify(&tag, &register).unwrap());
+
+        // Fresh sessions survive pruning while the client registers
+        assert_eq!(sessions.prune(|_| false).unwrap(), 0);
+
+        sessions.close(&tag);
+        assert_eq!(sessions.session_count(), 0);
+        assert!(sessions.authenticate(&tag, "gone".to_string()).is_err());
+    }
+}
@@ -0,0 +1,3938 @@
+//! Fixed-rate game simulation
+//!
+//! Handlers only validate player actions and queue them as intents. The event
+//! loop runs a simulation tick `simulation_tick_rate_hz` times per second. A tick
+//! first ends item effects, surveillance exposures, status effects and cooldowns
+//! that ran out, respawning downed players whose countdown is over, and calls off
+//! trades a party left. It then applies the queued intents in arrival order.
+//! Once per simulated second it regenerates health, deals damage over time and
+//! updates territory, characters and quests, and on their own intervals it runs
+//! surveillance checks and spawns loot. It returns the replies for each player
+//! and whether the world changed. Sending them is left to the caller, and the
+//! clock and random source are passed in, so ticks run without any networking
+//! and the same inputs give the same results.
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use tracing::{debug, error, info};
+
+use crate::abilities;
+use crate::game_state::{DamageOutcome, GameState};
+use crate::npcs::{self, Npc};
+use crate::quests::{self, QuestEvent, QuestUpdate};
+use crate::reputation;
+use crate::status_effects;
+use crate::territory::TerritoryChange;
+use crate::trading::{Confirmation, Trade, TradeRequest};
+use nymquest_protocol::game_protocol::{
+    Ability, Direction, LootDrop, Player, Position, ServerMessage, StatusEffectKind, Tile,
+    TradeAssets, WorldBoundaries,
+};
+use nymquest_protocol::world_lore::{EquipmentSlot, Faction};
+
+/// Most intents waiting for the next tick before new ones are refused
+const MAX_QUEUED_INTENTS: usize = 4096;
+
+/// Critical hit chance added per point of the attacker's active trust stat
+const ITEM_CRIT_CHANCE_PER_TRUST: f32 = 0.005;
+
+/// A validated action a player wants to take on the next tick
+#[derive(Debug, Clone)]
+pub enum PlayerIntent {
+    /// Move one step in a direction
+    Move { direction: Direction },
+    /// Attack the player or character with this display ID
+    Attack { target_display_id: String },
+    /// Use up a carried item to activate its stats
+    UseItem { item_id: String },
+    /// Throw away a carried item
+    DropItem { item_id: String },
+    /// Wear a carried item in the slot for its type
+    Equip { item_id: String },
+    /// Take off the item worn in a slot
+    Unequip { slot: EquipmentSlot },
+    /// Pick up the nearest item on the ground within reach
+    PickUp,
+    /// Ask the player with this display ID to trade, or agree to their request
+    TradeRequest { target_display_id: String },
+    /// Put credits and items up in the open trade, replacing the previous offer
+    TradeOffer {
+        target_display_id: String,
+        offer: TradeAssets,
+    },
+    /// Confirm both offers as of a trade revision
+    TradeAccept {
+        target_display_id: String,
+        revision: u32,
+    },
+    /// Call off a trade or decline a request
+    TradeCancel { target_display_id: String },
+    /// Take on an offered quest
+    AcceptQuest { quest_id: String },
+    /// Whisper to the NPC with this display ID
+    WhisperNpc { npc_display_id: String },
+    /// Use an ability on another player, or on yourself without a target
+    UseAbility {
+        ability: Ability,
+        target_display_id: Option<String>,
+    },
+}
+
+struct QueuedIntent {
+    player_id: String,
+    intent: PlayerIntent,
+}
+
+/// Result of one simulation tick
+#[derive(Debug, Default)]
+pub struct TickOutcome {
+    /// Messages for individual players by player ID, in the order they were produced
+    pub replies: Vec<(String, ServerMessage)>,
+    /// Whether any player changed, so a state update is due
+    pub state_changed: bool,
+    /// Where items on the ground appeared or disappeared, so players who can see
+    /// those spots need their loot lists again
+    pub loot_changed_at: Vec<Position>,
+}
+
+/// Queue of player intents and the tick counter
+pub struct Simulation {
+    intents: Mutex<VecDeque<QueuedIntent>>,
+    ticks: AtomicU64,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation {
+    /// Create a simulation with no pending intents
+    pub fn new() -> Self {
+        Self {
+            intents: Mutex::new(VecDeque::with_capacity(256)),
+            ticks: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue an intent of `player_id` for the next tick
+    pub fn enqueue(&self, player_id: &str, intent: PlayerIntent) -> Result<()> {
+        let mut intents = self.lock()?;
+        if intents.len() >= MAX_QUEUED_INTENTS {
+            return Err(anyhow!("Simulation intent queue is full"));
+        }
+        intents.push_back(QueuedIntent {
+            player_id: player_id.to_string(),
+            intent,
+        });
+        Ok(())
+    }
+
+    /// Number of intents waiting for the next tick
+    pub fn pending_intents(&self) -> usize {
+        self.lock().map(|intents| intents.len()).unwrap_or(0)
+    }
+
+    /// Number of ticks run so far
+    pub fn tick_count(&self) -> u64 {
+        self.ticks.load(Ordering::SeqCst)
+    }
+
+    /// Advance the world by one tick
+    ///
+    /// `now` is the current time in seconds, used for attack cooldowns and item effects, and `rng`
+    /// decides critical hits and loot. `next_seq` numbers the replies.
+    pub fn tick<R, F>(
+        &self,
+        game_state: &GameState,
+        now: u64,
+        rng: &mut R,
+        mut next_seq: F,
+    ) -> Result<TickOutcome>
+    where
+        R: Rng,
+        F: FnMut() -> u64,
+    {
+        let intents: Vec<QueuedIntent> = self.lock()?.drain(..).collect();
+        let tick = self.ticks.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut outcome = TickOutcome::default();
+
+        for (player_id, item) in game_state.expire_items(now) {
+            outcome.replies.push((
+                player_id.clone(),
+                ServerMessage::Event {
+                    message: format!("The effect of your {} wore off", item.name),
+                    seq_num: next_seq(),
+                },
+            ));
+            push_items(game_state, &mut outcome, &player_id, now, &mut next_seq);
+            outcome.state_changed = true;
+        }
+
+        // Exposures that ran out stop showing players to the Hegemony
+        let expired = game_state
+            .surveillance()
+            .expire(now, |player_id| game_state.get_player(player_id).is_some())?;
+        if !expired.is_empty() {
+            debug!("Surveillance exposure ended for {} players", expired.len());
+            outcome.state_changed = true;
+        }
+
+        // Status effects that wore off, such as stuns, end before anyone acts,
+        // and downed players whose countdown ran out respawn
+        for (player_id, kind) in game_state.expire_effects(now) {
+            let message = if kind == StatusEffectKind::Downed {
+                let Some(position) = game_state.respawn_player(&player_id) else {
+                    continue;
+                };
+                format!("You respawned at ({:.1}, {:.1})", position.x, position.y)
+            } else {
+                format!("{} wore off", kind.name())
+            };
+            outcome.replies.push((
+                player_id,
+                ServerMessage::Event {
+                    message,
+                    seq_num: next_seq(),
+                },
+            ));
+            outcome.state_changed = true;
+        }
+        game_state
+            .abilities()
+            .expire(now, |player_id| game_state.get_player(player_id).is_some())?;
+
+        // Trades one side left are called off before anyone acts on them
+        let abandoned = game_state
+            .trades()
+            .remove_abandoned(|player_id| game_state.get_player(player_id).is_some())?;
+        for trade in abandoned {
+            for (side, player_id) in trade.parties.iter().enumerate() {
+                if game_state.get_player(player_id).is_none() {
+                    continue;
+                }
+                let partner = &trade.display_ids[1 - side];
+                close_trade(
+                    game_state,
+                    &trade,
+                    player_id,
+                    format!("{} left the game", partner),
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                );
+            }
+        }
+
+        for QueuedIntent { player_id, intent } in intents {
+            match intent {
+                PlayerIntent::Move { direction } => apply_move(
+                    game_state,
+                    &player_id,
+                    direction,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::Attack { target_display_id } => apply_attack(
+                    game_state,
+                    &player_id,
+                    &target_display_id,
+                    now,
+                    rng,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::UseItem { item_id } => apply_use_item(
+                    game_state,
+                    &player_id,
+                    &item_id,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::DropItem { item_id } => apply_drop_item(
+                    game_state,
+                    &player_id,
+                    &item_id,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::Equip { item_id } => apply_equip(
+                    game_state,
+                    &player_id,
+                    &item_id,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::Unequip { slot } => apply_unequip(
+                    game_state,
+                    &player_id,
+                    slot,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::PickUp => {
+                    apply_pick_up(game_state, &player_id, now, &mut next_seq, &mut outcome)
+                }
+                PlayerIntent::TradeRequest { target_display_id } => apply_trade_request(
+                    game_state,
+                    &player_id,
+                    &target_display_id,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::TradeOffer {
+                    target_display_id,
+                    offer,
+                } => apply_trade_offer(
+                    game_state,
+                    &player_id,
+                    &target_display_id,
+                    &offer,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::TradeAccept {
+                    target_display_id,
+                    revision,
+                } => apply_trade_accept(
+                    game_state,
+                    &player_id,
+                    &target_display_id,
+                    revision,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::TradeCancel { target_display_id } => apply_trade_cancel(
+                    game_state,
+                    &player_id,
+                    &target_display_id,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::AcceptQuest { quest_id } => apply_accept_quest(
+                    game_state,
+                    &player_id,
+                    &quest_id,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::WhisperNpc { npc_display_id } => apply_whisper_npc(
+                    game_state,
+                    &player_id,
+                    &npc_display_id,
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+                PlayerIntent::UseAbility {
+                    ability,
+                    target_display_id,
+                } => apply_use_ability(
+                    game_state,
+                    &player_id,
+                    ability,
+                    target_display_id.as_deref(),
+                    now,
+                    &mut next_seq,
+                    &mut outcome,
+                ),
+            }
+        }
+
+        // Regeneration runs once per simulated second
+        let config = game_state.get_config();
+        if config.health_regen_per_second > 0
+            && tick.is_multiple_of(u64::from(config.simulation_tick_rate_hz))
+        {
+            let healed = game_state.regenerate_health(config.health_regen_per_second, now);
+            if healed > 0 {
+                debug!("Regenerated health for {} players", healed);
+                outcome.state_changed = true;
+            }
+        }
+
+        // Damage over time from status effects lands once per simulated second
+        if tick.is_multiple_of(u64::from(config.simulation_tick_rate_hz)) {
+            update_effects(game_state, now, rng, &mut next_seq, &mut outcome);
+        }
+
+        // Control points change hands once per simulated second
+        if tick.is_multiple_of(u64::from(config.simulation_tick_rate_hz)) {
+            update_territory(game_state, &mut next_seq, &mut outcome)?;
+        }
+
+        // Characters act once per simulated second
+        if config.enable_npcs && tick.is_multiple_of(u64::from(config.simulation_tick_rate_hz)) {
+            update_npcs(game_state, now, rng, &mut next_seq, &mut outcome)?;
+        }
+
+        // Quests check where players stand once per simulated second
+        if !config.quests.is_empty()
+            && tick.is_multiple_of(u64::from(config.simulation_tick_rate_hz))
+        {
+            update_quests(game_state, now, &mut next_seq, &mut outcome);
+        }
+
+        // Surveillance cameras sweep the world every check interval
+        let check_ticks =
+            config.surveillance_check_seconds * u64::from(config.simulation_tick_rate_hz);
+        if check_ticks > 0 && tick.is_multiple_of(check_ticks) {
+            check_surveillance(game_state, now, rng, &mut next_seq, &mut outcome)?;
+        }
+
+        // An item turns up at a random spot of a random region every spawn interval
+        let spawn_ticks =
+            config.loot_spawn_interval_seconds * u64::from(config.simulation_tick_rate_hz);
+        if spawn_ticks > 0 && tick.is_multiple_of(spawn_ticks) {
+            let regions = game_state.regions();
+            let region = rng.gen_range(0..regions.all().len());
+            let position = regions.random_position(region, rng);
+            if let Some(drop) = drop_loot(game_state, position, rng, &mut outcome)? {
+                debug!("Loot {} appeared at {:?}", drop.item_id, drop.position);
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, VecDeque<QueuedIntent>>> {
+        self.intents
+            .lock()
+            .map_err(|_| anyhow!("Simulation intent queue lock poisoned"))
+    }
+}
+
+/// Count who occupies every control point, telling everyone when a point changes hands
+fn update_territory<F>(
+    game_state: &GameState,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> Result<()>
+where
+    F: FnMut() -> u64,
+{
+    let territory = game_state.territory();
+    let capture_seconds = game_state.get_config().territory_capture_seconds;
+    let changes = territory.update(&game_state.get_faction_positions(), capture_seconds)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let announcements: Vec<String> = changes
+        .iter()
+        .map(|change| match change {
+            TerritoryChange::CaptureStarted {
+                point,
+                region,
+                faction,
+            } => format!(
+                "The {} are capturing the {} in {}",
+                faction.display_name(),
+                point,
+                region
+            ),
+            TerritoryChange::Captured {
+                point,
+                region,
+                faction,
+                previous: Some(previous),
+            } => format!(
+                "The {} took the {} in {} from the {}",
+                faction.display_name(),
+                point,
+                region,
+                previous.display_name()
+            ),
+            TerritoryChange::Captured {
+                point,
+                region,
+                faction,
+                ..
+            } => format!(
+                "The {} captured the {} in {}",
+                faction.display_name(),
+                point,
+                region
+            ),
+        })
+        .collect();
+    let control_points = territory.control_points()?;
+
+    for (player_id, _) in game_state.get_connections() {
+        for message in &announcements {
+            outcome.replies.push((
+                player_id.clone(),
+                ServerMessage::Event {
+                    message: message.clone(),
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+        outcome.replies.push((
+            player_id,
+            ServerMessage::TerritoryUpdate {
+                control_points: control_points.clone(),
+                capture_seconds,
+                seq_num: next_seq(),
+            },
+        ));
+    }
+
+    // Owners standing in a point they just took are harder to see from now on
+    if changes
+        .iter()
+        .any(|change| matches!(change, TerritoryChange::Captured { .. }))
+    {
+        outcome.state_changed = true;
+    }
+    Ok(())
+}
+
+/// Roll for every player outside the Hegemony who is not exposed yet whether surveillance catches them
+///
+/// Caught players and every Hegemony player online are told who was caught and where.
+fn check_surveillance<R, F>(
+    game_state: &GameState,
+    now: u64,
+    rng: &mut R,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> Result<()>
+where
+    R: Rng,
+    F: FnMut() -> u64,
+{
+    let config = game_state.get_config();
+    let surveillance = game_state.surveillance();
+    let regions = game_state.regions();
+
+    // Go through players in a fixed order so the same rolls expose the same players
+    let mut players: Vec<Player> = game_state.get_players().into_values().collect();
+    players.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut caught = Vec::new();
+    for player in players {
+        if player.faction == Faction::CorporateHegemony || surveillance.is_exposed(&player.id) {
+            continue;
+        }
+        let region = regions.region_at(&player.position);
+        let chance = config.exposure_chance(config.surveillance_at(
+            &region.boundaries,
+            &player.position,
+            &game_state.item_stats(&player),
+            &game_state.effective_modifiers(&player),
+        ));
+        if chance > 0.0 && rng.gen::<f32>() < chance {
+            surveillance.expose(&player.id, now + config.surveillance_exposure_seconds)?;
+            debug!("Surveillance exposed player {}", player.id);
+            caught.push((player, region.boundaries.name.clone()));
+        }
+    }
+    if caught.is_empty() {
+        return Ok(());
+    }
+
+    let hegemony: Vec<String> = game_state
+        .get_connections()
+        .into_iter()
+        .map(|(player_id, _)| player_id)
+        .filter(|player_id| {
+            game_state
+                .get_player(player_id)
+                .is_some_and(|player| player.faction == Faction::CorporateHegemony)
+        })
+        .collect();
+    for (player, region) in caught {
+        for recipient in std::iter::once(&player.id).chain(hegemony.iter()) {
+            outcome.replies.push((
+                recipient.clone(),
+                ServerMessage::SurveillanceAlert {
+                    display_id: player.display_id.clone(),
+                    name: player.name.clone(),
+                    faction: player.faction.clone(),
+                    position: player.position,
+                    region: region.clone(),
+                    duration_seconds: config.surveillance_exposure_seconds,
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+    }
+
+    // The Hegemony can see exposed players from afar
+    outcome.state_changed = true;
+    Ok(())
+}
+
+/// Deal every player under a damaging status effect a second's worth of its damage
+fn update_effects<R, F>(
+    game_state: &GameState,
+    now: u64,
+    rng: &mut R,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    R: Rng,
+    F: FnMut() -> u64,
+{
+    for (player_id, result) in game_state.apply_effect_damage(now, rng) {
+        outcome.replies.push((
+            player_id.clone(),
+            ServerMessage::Event {
+                message: if result.defeated {
+                    "💀 Corruption wore you down!".to_string()
+                } else {
+                    format!("☣️ Corruption cost you {} health points", result.damage)
+                },
+                seq_num: next_seq(),
+            },
+        ));
+        push_defeat(game_state, &player_id, &result, now, next_seq, outcome);
+        outcome.state_changed = true;
+    }
+}
+
+/// Tell a player what being defeated cost them and when they respawn, and show what they dropped
+fn push_defeat<F>(
+    game_state: &GameState,
+    player_id: &str,
+    result: &DamageOutcome,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if !result.defeated {
+        return;
+    }
+    let penalty = &result.penalty;
+    let mut events = Vec::new();
+    let respawn_seconds = game_state.get_config().respawn_seconds;
+    if respawn_seconds > 0 {
+        events.push(format!(
+            "💀 You are down! Respawning in {}s",
+            respawn_seconds
+        ));
+    }
+    if penalty.experience > 0 {
+        events.push(format!("You lost {} experience points", penalty.experience));
+    }
+    if let Some(drop) = &penalty.dropped {
+        events.push(format!("You dropped {} where you fell", drop.name));
+        outcome.loot_changed_at.push(drop.position);
+        outcome
+            .loot_changed_at
+            .extend(penalty.removed.iter().map(|old| old.position));
+    }
+    for message in events {
+        outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::Event {
+                message,
+                seq_num: next_seq(),
+            },
+        ));
+    }
+    if penalty.dropped.is_some() {
+        push_items(game_state, outcome, player_id, now, next_seq);
+    }
+}
+
+/// Let every character act for a second, then send players the characters around them that changed
+fn update_npcs<R, F>(
+    game_state: &GameState,
+    now: u64,
+    rng: &mut R,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> Result<()>
+where
+    R: Rng,
+    F: FnMut() -> u64,
+{
+    let config = game_state.get_config();
+    let npcs = game_state.npcs();
+
+    // Characters pick between players in a fixed order so the same rolls give the same fights,
+    // and leave alone those who are offline, down or in a safe zone
+    let regions = game_state.regions();
+    let connections = game_state.get_connections();
+    let mut players: Vec<Player> = game_state
+        .get_players()
+        .into_values()
+        .filter(|player| {
+            connections.iter().any(|(id, _)| id == &player.id)
+                && !player.effects.contains_key(&StatusEffectKind::Downed)
+                && !regions.is_safe(&player.position)
+        })
+        .collect();
+    players.sort_by(|a, b| a.id.cmp(&b.id));
+    let attacks = npcs.update(
+        &players,
+        regions,
+        now,
+        config.attack_cooldown_seconds,
+        config.npc_respawn_seconds,
+        rng,
+    )?;
+
+    for attack in attacks {
+        let result = game_state.apply_damage(&attack.target, &attack.npc, attack.damage, now, rng);
+        if result.damage == 0 {
+            continue;
+        }
+        // Players who respawned shake off whatever the hit would have left on them
+        let inflicted = npcs::inflicted_effect(attack.kind)
+            .filter(|_| !result.defeated)
+            .and_then(|(kind, seconds)| {
+                game_state
+                    .add_effect(&attack.target, kind, now + seconds)
+                    .map(|effect| (kind, effect))
+            });
+        outcome.replies.push((
+            attack.target.clone(),
+            ServerMessage::Event {
+                message: if result.defeated {
+                    format!(
+                        "💀 You were defeated by {} {}!",
+                        attack.kind.name(),
+                        attack.npc
+                    )
+                } else {
+                    format!(
+                        "⚠️ You are being attacked by {} {}! You lost {} health points.",
+                        attack.kind.name(),
+                        attack.npc,
+                        result.damage
+                    )
+                },
+                seq_num: next_seq(),
+            },
+        ));
+        if let Some((kind, effect)) = inflicted {
+            outcome.replies.push((
+                attack.target.clone(),
+                ServerMessage::Event {
+                    message: format!(
+                        "☣️ {} {} left you with {} x{}",
+                        attack.kind.name(),
+                        attack.npc,
+                        kind.name(),
+                        effect.stacks
+                    ),
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+        push_defeat(game_state, &attack.target, &result, now, next_seq, outcome);
+        outcome.state_changed = true;
+    }
+
+    npcs.retain_sent(|player_id| connections.iter().any(|(id, _)| id == player_id))?;
+    for (player_id, _) in connections {
+        let views = game_state.get_visible_npcs(&player_id);
+        if npcs.record_sent(&player_id, &views)? {
+            outcome.replies.push((
+                player_id,
+                ServerMessage::NpcUpdate {
+                    npcs: views,
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn apply_move<F>(
+    game_state: &GameState,
+    player_id: &str,
+    direction: Direction,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    // The player may have left since the intent was queued
+    let Some(player) = game_state.get_player(player_id) else {
+        return;
+    };
+    if reject_downed(game_state, player_id, "move", next_seq, outcome) {
+        return;
+    }
+    if player.effects.contains_key(&StatusEffectKind::Stun) {
+        reject(
+            outcome,
+            player_id,
+            "You are stunned and cannot move until it wears off.".to_string(),
+            next_seq,
+        );
+        return;
+    }
+
+    let config = game_state.get_config();
+    let regions = game_state.regions();
+    let (dx, dy) = direction.to_vector();
+    let mut speed = config.movement_speed * game_state.effective_modifiers(&player).movement_speed;
+    if regions.tile_at(&player.position) == Tile::Slow {
+        speed *= config.terrain_slow_factor;
+    }
+    // Stepping past the east or west edge of a region enters the next one
+    let new_position = regions.clamp(&Position::new(
+        player.position.x + dx * speed,
+        player.position.y + dy * speed,
+    ));
+    let region_before = regions.index_at(&player.position);
+
+    if !regions.is_clear(&player.position, &new_position) {
+        reject(
+            outcome,
+            player_id,
+            "Cannot move to that position - the way is blocked".to_string(),
+            next_seq,
+        );
+        return;
+    }
+
+    // Twice the radius keeps two players' circles apart
+    let min_distance = config.player_collision_radius * 2.0;
+    let collision_detected = game_state
+        .get_all_players_except(player_id)
+        .iter()
+        .any(|other| {
+            WorldBoundaries::would_positions_collide(&new_position, &other.position, min_distance)
+        });
+
+    if collision_detected {
+        reject(
+            outcome,
+            player_id,
+            "Cannot move to that position - another player is already there".to_string(),
+            next_seq,
+        );
+        return;
+    }
+
+    let loot_before = game_state.get_visible_loot(player_id);
+    if game_state.update_player_position(player_id, new_position) {
+        let region_after = regions.index_at(&new_position);
+        if region_after != region_before {
+            let region = &regions.all()[region_after];
+            info!("Player {} entered {}", player_id, region.boundaries.name);
+            outcome.replies.push((
+                player_id.to_string(),
+                ServerMessage::RegionChanged {
+                    world_boundaries: region.boundaries.clone(),
+                    seq_num: next_seq(),
+                },
+            ));
+            outcome.replies.push((
+                player_id.to_string(),
+                ServerMessage::Terrain {
+                    terrain: region.terrain.clone(),
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+        // Walking brings items on the ground into or out of view
+        let loot = game_state.get_visible_loot(player_id);
+        if loot != loot_before {
+            outcome.replies.push((
+                player_id.to_string(),
+                ServerMessage::Loot {
+                    drops: loot,
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+        outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::Event {
+                message: format!(
+                    "Moved {:?} to position ({:.1}, {:.1})",
+                    direction, new_position.x, new_position.y
+                ),
+                seq_num: next_seq(),
+            },
+        ));
+        outcome.state_changed = true;
+    }
+}
+
+fn apply_attack<R, F>(
+    game_state: &GameState,
+    attacker_id: &str,
+    target_display_id: &str,
+    now: u64,
+    rng: &mut R,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    R: Rng,
+    F: FnMut() -> u64,
+{
+    let Some(attacker) = game_state.get_player(attacker_id) else {
+        return;
+    };
+    if reject_downed(game_state, attacker_id, "attack", next_seq, outcome) {
+        return;
+    }
+
+    // The target may have left or been renamed since the intent was queued, be hidden, or be a
+    // character
+    let Some((target_id, target)) = game_state
+        .get_player_id_by_display_id(target_display_id)
+        .and_then(|target_id| game_state.get_player(&target_id).map(|t| (target_id, t)))
+        .filter(|(_, target)| !is_hidden_from(&attacker, target))
+    else {
+        match game_state
+            .npcs()
+            .get(target_display_id)
+            .filter(Npc::is_alive)
+        {
+            Some(npc) => attack_npc(game_state, &attacker, &npc, now, rng, next_seq, outcome),
+            None => reject(
+                outcome,
+                attacker_id,
+                format!("Attack failed: Player '{}' not found.", target_display_id),
+                next_seq,
+            ),
+        }
+        return;
+    };
+
+    if target.effects.contains_key(&StatusEffectKind::Downed) {
+        reject(
+            outcome,
+            attacker_id,
+            format!("Attack failed: {} is already down.", target_display_id),
+            next_seq,
+        );
+        return;
+    }
+
+    let config = game_state.get_config();
+    if !config.friendly_fire
+        && attacker_id != target_id
+        && attacker.faction.is_allied_with(&target.faction)
+    {
+        reject(
+            outcome,
+            attacker_id,
+            format!(
+                "Attack failed: {} is a fellow member of your faction.",
+                target_display_id
+            ),
+            next_seq,
+        );
+        return;
+    }
+
+    if !check_attack(
+        game_state,
+        &attacker,
+        target_display_id,
+        &target.position,
+        now,
+        next_seq,
+        outcome,
+    ) {
+        return;
+    }
+
+    info!(
+        "Player {} attacking player with display ID {}",
+        attacker_id, target_display_id
+    );
+    game_state.update_attack_time(attacker_id, now);
+    leave_ghost(game_state, attacker_id, next_seq, outcome);
+
+    let (damage, is_critical) = roll_damage(game_state, &attacker, rng);
+    let result = game_state.apply_damage(&target_id, attacker_id, damage, now, rng);
+    let changes = reputation::for_attack(&target.faction, result.defeated);
+    change_reputation(game_state, attacker_id, &changes, now, next_seq, outcome);
+
+    // The defeated player leaves an item from the region's loot table where they fell
+    if result.defeated {
+        match drop_loot(game_state, target.position, rng, outcome) {
+            Ok(Some(drop)) => debug!("{} dropped {}", target_id, drop.item_id),
+            Ok(None) => {}
+            Err(e) => error!("Failed to drop loot for {}: {}", target_id, e),
+        }
+    }
+
+    outcome.replies.push((
+        target_id.clone(),
+        ServerMessage::Event {
+            message: format!(
+                "⚠️ You are being attacked by {}! You lost {} health points.",
+                attacker.name, result.damage
+            ),
+            seq_num: next_seq(),
+        },
+    ));
+    push_defeat(game_state, &target_id, &result, now, next_seq, outcome);
+
+    push_hit(
+        &attacker,
+        &target.name,
+        result.damage,
+        result.defeated,
+        is_critical,
+        next_seq,
+        outcome,
+    );
+}
+
+/// Attack a character the way players attack each other, without reputation changes
+fn attack_npc<R, F>(
+    game_state: &GameState,
+    attacker: &Player,
+    npc: &Npc,
+    now: u64,
+    rng: &mut R,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    R: Rng,
+    F: FnMut() -> u64,
+{
+    if !check_attack(
+        game_state,
+        attacker,
+        &npc.display_id,
+        &npc.position,
+        now,
+        next_seq,
+        outcome,
+    ) {
+        return;
+    }
+
+    info!("Player {} attacking {}", attacker.id, npc.display_id);
+    game_state.update_attack_time(&attacker.id, now);
+    leave_ghost(game_state, &attacker.id, next_seq, outcome);
+
+    let (damage, is_critical) = roll_damage(game_state, attacker, rng);
+    let result = game_state.apply_damage(&npc.display_id, &attacker.id, damage, now, rng);
+
+    // Defeated characters leave loot just like players do
+    if result.defeated {
+        match drop_loot(game_state, npc.position, rng, outcome) {
+            Ok(Some(drop)) => debug!("{} dropped {}", npc.display_id, drop.item_id),
+            Ok(None) => {}
+            Err(e) => error!("Failed to drop loot for {}: {}", npc.display_id, e),
+        }
+    }
+
+    push_hit(
+        attacker,
+        &format!("{} {}", npc.kind.name(), npc.display_id),
+        result.damage,
+        result.defeated,
+        is_critical,
+        next_seq,
+        outcome,
+    );
+
+    if result.defeated {
+        let updates = game_state.advance_quests(&attacker.id, &QuestEvent::Defeated(npc.kind));
+        push_quest_updates(game_state, &attacker.id, updates, now, next_seq, outcome);
+    }
+}
+
+/// Check the attacker is off cooldown and the target is in reach and in sight, telling the attacker if not
+fn check_attack<F>(
+    game_state: &GameState,
+    attacker: &Player,
+    target_display_id: &str,
+    target_position: &Position,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> bool
+where
+    F: FnMut() -> u64,
+{
+    let config = game_state.get_config();
+    if attacker.effects.contains_key(&StatusEffectKind::Stun) {
+        reject(
+            outcome,
+            &attacker.id,
+            "You are stunned and cannot attack until it wears off.".to_string(),
+            next_seq,
+        );
+        return false;
+    }
+
+    // Safe zones protect those inside and keep them from striking out
+    let regions = game_state.regions();
+    if regions.is_safe(&attacker.position) {
+        reject(
+            outcome,
+            &attacker.id,
+            "Attack failed: You cannot attack from a safe zone.".to_string(),
+            next_seq,
+        );
+        return false;
+    }
+    if regions.is_safe(target_position) {
+        reject(
+            outcome,
+            &attacker.id,
+            format!("Attack failed: {} is in a safe zone.", target_display_id),
+            next_seq,
+        );
+        return false;
+    }
+
+    if !game_state.can_attack(&attacker.id, now) {
+        let time_since_last = now.saturating_sub(attacker.last_attack_time);
+        let remaining = config
+            .attack_cooldown_seconds
+            .saturating_sub(time_since_last);
+        reject(
+            outcome,
+            &attacker.id,
+            format!("Attack on cooldown! Wait {} more seconds.", remaining),
+            next_seq,
+        );
+        return false;
+    }
+
+    if !game_state
+        .regions()
+        .same_region(&attacker.position, target_position)
+    {
+        reject(
+            outcome,
+            &attacker.id,
+            format!("Attack failed: {} is in another region.", target_display_id),
+            next_seq,
+        );
+        return false;
+    }
+
+    let distance = attacker.position.distance_to(target_position);
+    if distance > config.attack_range {
+        reject(
+            outcome,
+            &attacker.id,
+            format!(
+                "Attack failed: Target is out of range ({:.1} > {:.1}).",
+                distance, config.attack_range
+            ),
+            next_seq,
+        );
+        return false;
+    }
+
+    if !game_state
+        .regions()
+        .is_clear(&attacker.position, target_position)
+    {
+        reject(
+            outcome,
+            &attacker.id,
+            format!(
+                "Attack failed: A wall blocks your line of sight to {}.",
+                target_display_id
+            ),
+            next_seq,
+        );
+        return false;
+    }
+    true
+}
+
+/// Roll the base damage of an attack and whether it is a critical hit
+fn roll_damage<R: Rng>(game_state: &GameState, attacker: &Player, rng: &mut R) -> (u32, bool) {
+    // Trust from active items makes critical hits more likely
+    let config = game_state.get_config();
+    let crit_chance = config.crit_chance
+        + game_state.item_stats(attacker).trust.max(0.0) * ITEM_CRIT_CHANCE_PER_TRUST;
+    let is_critical = rng.gen::<f32>() < crit_chance;
+    let damage = if is_critical {
+        (config.base_damage as f32 * config.crit_multiplier) as u32
+    } else {
+        config.base_damage
+    };
+    (damage, is_critical)
+}
+
+/// Tell the attacker how their attack on `target_name` went
+fn push_hit<F>(
+    attacker: &Player,
+    target_name: &str,
+    damage: u32,
+    defeated: bool,
+    is_critical: bool,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let message = if defeated {
+        format!(
+            "You defeated {}{}",
+            target_name,
+            if is_critical {
+                " with a critical hit!"
+            } else {
+                "!"
+            }
+        )
+    } else {
+        format!(
+            "You hit {} for {} damage{}",
+            target_name,
+            damage,
+            if is_critical { " (CRITICAL HIT!)" } else { "" }
+        )
+    };
+    outcome.replies.push((
+        attacker.id.clone(),
+        ServerMessage::Event {
+            message,
+            seq_num: next_seq(),
+        },
+    ));
+    outcome.state_changed = true;
+}
+
+/// Use an ability once it is unlocked and off cooldown, on a target in range if it needs one
+fn apply_use_ability<F>(
+    game_state: &GameState,
+    player_id: &str,
+    ability: Ability,
+    target_display_id: Option<&str>,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let Some(player) = game_state.get_player(player_id) else {
+        return;
+    };
+    let name = ability.name();
+    let abilities_state = game_state.abilities();
+    if reject_downed(
+        game_state,
+        player_id,
+        &format!("use {}", name),
+        next_seq,
+        outcome,
+    ) {
+        return;
+    }
+    if player.effects.contains_key(&StatusEffectKind::Stun) {
+        reject(
+            outcome,
+            player_id,
+            format!(
+                "You are stunned and cannot use {} until it wears off.",
+                name
+            ),
+            next_seq,
+        );
+        return;
+    }
+
+    let unlock_level = ability.unlock_level(&player.faction);
+    if player.level < unlock_level {
+        reject(
+            outcome,
+            player_id,
+            format!(
+                "{} unlocks at level {} for the {}.",
+                name,
+                unlock_level,
+                player.faction.display_name()
+            ),
+            next_seq,
+        );
+        return;
+    }
+
+    match abilities_state.remaining(player_id, ability, now) {
+        Ok(0) => {}
+        Ok(remaining) => {
+            reject(
+                outcome,
+                player_id,
+                format!("{} on cooldown! Wait {} more seconds.", name, remaining),
+                next_seq,
+            );
+            return;
+        }
+        Err(e) => {
+            error!("Failed to check cooldown of {}: {}", name, e);
+            return;
+        }
+    }
+
+    // Hack needs another player, DataDrop may heal one, and the others only affect the user
+    let target = match target_display_id {
+        None if ability.needs_target() => {
+            reject(
+                outcome,
+                player_id,
+                format!("{} needs a target: /ability {} <player_id>", name, name),
+                next_seq,
+            );
+            return;
+        }
+        None => player.clone(),
+        Some(_) if matches!(ability, Ability::Encrypt | Ability::Ghost) => {
+            reject(
+                outcome,
+                player_id,
+                format!("{} can only be used on yourself.", name),
+                next_seq,
+            );
+            return;
+        }
+        Some(target_display_id) => {
+            match game_state
+                .get_player_id_by_display_id(target_display_id)
+                .and_then(|target_id| game_state.get_player(&target_id))
+                .filter(|target| !is_hidden_from(&player, target))
+            {
+                Some(target) => target,
+                None => {
+                    reject(
+                        outcome,
+                        player_id,
+                        format!("{} failed: Player '{}' not found.", name, target_display_id),
+                        next_seq,
+                    );
+                    return;
+                }
+            }
+        }
+    };
+    if let Some(refusal) = check_ability_target(game_state, &player, &target, ability) {
+        reject(
+            outcome,
+            player_id,
+            format!("{} failed: {}", name, refusal),
+            next_seq,
+        );
+        return;
+    }
+
+    let spec = abilities::spec(ability);
+    if let Err(e) = abilities_state.start_cooldown(player_id, ability, now) {
+        error!("Failed to use {} for {}: {}", name, player_id, e);
+        return;
+    }
+    if let Some(kind) = spec.effect {
+        game_state.add_effect(&target.id, kind, now + spec.duration_seconds);
+        outcome.state_changed = true;
+    }
+    info!("Player {} used {} on {}", player_id, name, target.id);
+
+    // What the user, and the other player if there is one, are told
+    let events: Vec<(&str, String)> = match ability {
+        Ability::Encrypt => vec![(
+            player_id,
+            format!(
+                "Encrypt shields you from {:.0}% of the damage you take for {}s",
+                (1.0 - status_effects::SHIELD_DAMAGE_TAKEN) * 100.0,
+                spec.duration_seconds
+            ),
+        )],
+        Ability::Ghost => vec![(
+            player_id,
+            format!(
+                "You ghost out of sight for {}s; attacking reveals you",
+                spec.duration_seconds
+            ),
+        )],
+        Ability::Hack => vec![
+            (
+                player_id,
+                format!(
+                    "You hacked {}; they are stunned for {}s",
+                    target.name, spec.duration_seconds
+                ),
+            ),
+            (
+                &target.id,
+                format!(
+                    "⚠️ {} hacked you! You cannot move, attack or use abilities for {}s.",
+                    player.name, spec.duration_seconds
+                ),
+            ),
+        ],
+        Ability::DataDrop => {
+            outcome.state_changed = true;
+            let healed = game_state.heal(&target.id, spec.heal).unwrap_or(0);
+            if target.id == player.id {
+                vec![(
+                    player_id,
+                    format!("DataDrop restored {} health points", healed),
+                )]
+            } else {
+                vec![
+                    (
+                        player_id,
+                        format!(
+                            "DataDrop restored {} health points to {}",
+                            healed, target.name
+                        ),
+                    ),
+                    (
+                        &target.id,
+                        format!(
+                            "{} restored {} of your health points with DataDrop",
+                            player.name, healed
+                        ),
+                    ),
+                ]
+            }
+        }
+    };
+    for (recipient, message) in events {
+        outcome.replies.push((
+            recipient.to_string(),
+            ServerMessage::Event {
+                message,
+                seq_num: next_seq(),
+            },
+        ));
+    }
+    push_cooldowns(game_state, player_id, now, next_seq, outcome);
+}
+
+/// Why `target` cannot be the target of `player`'s ability, if they cannot
+fn check_ability_target(
+    game_state: &GameState,
+    player: &Player,
+    target: &Player,
+    ability: Ability,
+) -> Option<String> {
+    if target.id == player.id {
+        return ability
+            .needs_target()
+            .then(|| "You cannot use it on yourself.".to_string());
+    }
+
+    if target.effects.contains_key(&StatusEffectKind::Downed) {
+        return Some(format!("{} is down.", target.display_id));
+    }
+
+    let allied = player.faction.is_allied_with(&target.faction);
+    match ability {
+        Ability::DataDrop if !allied => {
+            return Some(format!("{} is not an ally.", target.display_id));
+        }
+        Ability::Hack if allied && !game_state.get_config().friendly_fire => {
+            return Some(format!(
+                "{} is a fellow member of your faction.",
+                target.display_id
+            ));
+        }
+        _ => {}
+    }
+
+    let regions = game_state.regions();
+    if !regions.same_region(&player.position, &target.position) {
+        return Some(format!("{} is in another region.", target.display_id));
+    }
+    if ability.needs_target()
+        && (regions.is_safe(&player.position) || regions.is_safe(&target.position))
+    {
+        return Some("Nobody can be hacked in a safe zone.".to_string());
+    }
+    let range = abilities::spec(ability).range;
+    let distance = player.position.distance_to(&target.position);
+    if distance > range {
+        return Some(format!(
+            "Target is out of range ({:.1} > {:.1}).",
+            distance, range
+        ));
+    }
+    if !regions.is_clear(&player.position, &target.position) {
+        return Some(format!(
+            "A wall blocks your line of sight to {}.",
+            target.display_id
+        ));
+    }
+    None
+}
+
+/// Whether stealth hides `target` from `player`, who then cannot find them to attack or target
+fn is_hidden_from(player: &Player, target: &Player) -> bool {
+    status_effects::is_hidden(
+        &target.effects,
+        player.position.distance_to(&target.position),
+    )
+}
+
+/// End a player's Ghost because they gave themselves away
+fn leave_ghost<F>(
+    game_state: &GameState,
+    player_id: &str,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if game_state.remove_effect(player_id, StatusEffectKind::Stealth) {
+        outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::Event {
+                message: "Attacking revealed you; Ghost ended".to_string(),
+                seq_num: next_seq(),
+            },
+        ));
+        outcome.state_changed = true;
+    }
+}
+
+/// Send a player the cooldown of every ability
+fn push_cooldowns<F>(
+    game_state: &GameState,
+    player_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    match game_state.abilities().cooldowns(player_id, now) {
+        Ok(cooldowns) => outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::AbilityCooldowns {
+                cooldowns,
+                seq_num: next_seq(),
+            },
+        )),
+        Err(e) => error!("Failed to look up cooldowns of {}: {}", player_id, e),
+    }
+}
+
+/// Count every second a player spends somewhere toward their quests
+fn update_quests<F>(game_state: &GameState, now: u64, next_seq: &mut F, outcome: &mut TickOutcome)
+where
+    F: FnMut() -> u64,
+{
+    let config = game_state.get_config();
+    let mut players: Vec<Player> = game_state
+        .get_players()
+        .into_values()
+        .filter(|player| player.quests.values().any(|status| !status.completed))
+        .collect();
+    players.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for player in players {
+        let region = game_state.regions().region_at(&player.position);
+        let event = QuestEvent::Second {
+            position: &player.position,
+            surveillance: config.surveillance_at(
+                &region.boundaries,
+                &player.position,
+                &game_state.item_stats(&player),
+                &game_state.effective_modifiers(&player),
+            ),
+            exposed: game_state.surveillance().is_exposed(&player.id),
+        };
+        let updates = game_state.advance_quests(&player.id, &event);
+        push_quest_updates(game_state, &player.id, updates, now, next_seq, outcome);
+    }
+}
+
+fn apply_accept_quest<F>(
+    game_state: &GameState,
+    player_id: &str,
+    quest_id: &str,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "take on quests", next_seq, outcome) {
+        return;
+    }
+    let Some(quest) = game_state.accept_quest(player_id, quest_id) else {
+        reject(
+            outcome,
+            player_id,
+            format!("Quest '{}' is not on offer to you.", quest_id),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::QuestProgress {
+            progress: quests::start(&quest).progress,
+            quest,
+            seq_num: next_seq(),
+        },
+    ));
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::QuestOffer {
+            quests: game_state.get_quest_offers(player_id),
+            seq_num: next_seq(),
+        },
+    ));
+}
+
+fn apply_whisper_npc<F>(
+    game_state: &GameState,
+    player_id: &str,
+    npc_display_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "whisper", next_seq, outcome) {
+        return;
+    }
+    // NPCs only hear players who can see them
+    let Some(npc) = game_state
+        .get_visible_npcs(player_id)
+        .into_iter()
+        .find(|npc| npc.display_id.eq_ignore_ascii_case(npc_display_id))
+    else {
+        reject(
+            outcome,
+            player_id,
+            format!("{} is not within earshot.", npc_display_id),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::WhisperMessage {
+            sender_name: npc.display_id.clone(),
+            message: npcs::whisper_reply(npc.kind).to_string(),
+            seq_num: next_seq(),
+        },
+    ));
+    let updates = game_state.advance_quests(player_id, &QuestEvent::Whispered(&npc.display_id));
+    push_quest_updates(game_state, player_id, updates, now, next_seq, outcome);
+}
+
+fn apply_use_item<F>(
+    game_state: &GameState,
+    player_id: &str,
+    item_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "use items", next_seq, outcome) {
+        return;
+    }
+    let Some(item) = game_state.use_item(player_id, item_id, now) else {
+        reject(
+            outcome,
+            player_id,
+            format!("You are not carrying '{}'", item_id),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::ItemUsed {
+            item_id: item_id.to_string(),
+            name: item.name,
+            active_seconds: game_state.get_config().item_effect_seconds,
+            seq_num: next_seq(),
+        },
+    ));
+    push_items(game_state, outcome, player_id, now, next_seq);
+    // Active items change how far away others see the player
+    outcome.state_changed = true;
+}
+
+fn apply_drop_item<F>(
+    game_state: &GameState,
+    player_id: &str,
+    item_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "drop items", next_seq, outcome) {
+        return;
+    }
+    let Some(item) = game_state.drop_item(player_id, item_id) else {
+        reject(
+            outcome,
+            player_id,
+            format!("You are not carrying '{}'", item_id),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::ItemDropped {
+            item_id: item_id.to_string(),
+            name: item.name,
+            seq_num: next_seq(),
+        },
+    ));
+    push_items(game_state, outcome, player_id, now, next_seq);
+}
+
+fn apply_equip<F>(
+    game_state: &GameState,
+    player_id: &str,
+    item_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "equip items", next_seq, outcome) {
+        return;
+    }
+    let Some((slot, item)) = game_state.equip_item(player_id, item_id) else {
+        reject(
+            outcome,
+            player_id,
+            format!("You are not carrying '{}'", item_id),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Event {
+            message: format!("You equipped {} in your {} slot", item.name, slot.name()),
+            seq_num: next_seq(),
+        },
+    ));
+    push_items(game_state, outcome, player_id, now, next_seq);
+    // Worn items change how far away others see the player
+    outcome.state_changed = true;
+}
+
+fn apply_unequip<F>(
+    game_state: &GameState,
+    player_id: &str,
+    slot: EquipmentSlot,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "take off items", next_seq, outcome) {
+        return;
+    }
+    let Some(player) = game_state.get_player(player_id) else {
+        return;
+    };
+    if !player.equipment.contains_key(&slot) {
+        reject(
+            outcome,
+            player_id,
+            format!("Nothing is equipped in your {} slot", slot.name()),
+            next_seq,
+        );
+        return;
+    }
+    let Some(item) = game_state.unequip_item(player_id, slot) else {
+        reject(
+            outcome,
+            player_id,
+            "Your inventory is full".to_string(),
+            next_seq,
+        );
+        return;
+    };
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Event {
+            message: format!("You took off {}", item.name),
+            seq_num: next_seq(),
+        },
+    ));
+    push_items(game_state, outcome, player_id, now, next_seq);
+    outcome.state_changed = true;
+}
+
+fn apply_pick_up<F>(
+    game_state: &GameState,
+    player_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "pick anything up", next_seq, outcome) {
+        return;
+    }
+    let Some(player) = game_state.get_player(player_id) else {
+        return;
+    };
+
+    let radius = game_state.get_config().pickup_radius;
+    let regions = game_state.regions();
+    let nearest = match game_state.ground_loot().within(&player.position, radius) {
+        // Items across a region edge are out of reach
+        Ok(drops) => drops
+            .into_iter()
+            .filter(|drop| regions.same_region(&drop.position, &player.position))
+            .min_by(|a, b| {
+                let distance_a = a.position.distance_to(&player.position);
+                distance_a.total_cmp(&b.position.distance_to(&player.position))
+            }),
+        Err(e) => {
+            error!("Failed to look for loot near {}: {}", player_id, e);
+            return;
+        }
+    };
+    let Some(drop) = nearest else {
+        reject(
+            outcome,
+            player_id,
+            format!("There is nothing to pick up within {:.0} units", radius),
+            next_seq,
+        );
+        return;
+    };
+
+    if !game_state.add_item(player_id, &drop.item_id, 1) {
+        reject(
+            outcome,
+            player_id,
+            "Your inventory is full".to_string(),
+            next_seq,
+        );
+        return;
+    }
+    if let Err(e) = game_state.ground_loot().take(drop.drop_id) {
+        error!("Failed to remove picked up loot {}: {}", drop.drop_id, e);
+    }
+
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Event {
+            message: format!("You picked up {}", drop.name),
+            seq_num: next_seq(),
+        },
+    ));
+    push_items(game_state, outcome, player_id, now, next_seq);
+    outcome.loot_changed_at.push(drop.position);
+}
+
+fn apply_trade_request<F>(
+    game_state: &GameState,
+    player_id: &str,
+    target_display_id: &str,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "trade", next_seq, outcome) {
+        return;
+    }
+    let Some(player) = game_state.get_player(player_id) else {
+        return;
+    };
+    let Some((target_id, target)) = game_state
+        .get_player_id_by_display_id(target_display_id)
+        .and_then(|target_id| game_state.get_player(&target_id).map(|t| (target_id, t)))
+    else {
+        reject(
+            outcome,
+            player_id,
+            format!("Trade failed: Player '{}' not found.", target_display_id),
+            next_seq,
+        );
+        return;
+    };
+    if target_id == player_id {
+        reject(
+            outcome,
+            player_id,
+            "You cannot trade with yourself".to_string(),
+            next_seq,
+        );
+        return;
+    }
+
+    let request = game_state.trades().request(
+        player_id,
+        &player.display_id,
+        &target_id,
+        &target.display_id,
+    );
+    match request {
+        Ok(TradeRequest::Requested) => {
+            outcome.replies.push((
+                player_id.to_string(),
+                ServerMessage::Event {
+                    message: format!("You asked {} to trade", target.display_id),
+                    seq_num: next_seq(),
+                },
+            ));
+            outcome.replies.push((
+                target_id,
+                ServerMessage::Event {
+                    message: format!(
+                        "{} wants to trade with you. Type /trade {} to accept or /cancel to decline",
+                        player.display_id, player.display_id
+                    ),
+                    seq_num: next_seq(),
+                },
+            ));
+        }
+        Ok(TradeRequest::Opened(trade)) => send_trade_update(game_state, &trade, next_seq, outcome),
+        Ok(TradeRequest::AlreadyTrading) => reject(
+            outcome,
+            player_id,
+            "You are already in a trade; cancel it first".to_string(),
+            next_seq,
+        ),
+        Ok(TradeRequest::PartnerBusy) => reject(
+            outcome,
+            player_id,
+            format!("{} is already in a trade", target.display_id),
+            next_seq,
+        ),
+        Err(e) => error!("Failed to record trade request of {}: {}", player_id, e),
+    }
+}
+
+fn apply_trade_offer<F>(
+    game_state: &GameState,
+    player_id: &str,
+    target_display_id: &str,
+    offer: &TradeAssets,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "trade", next_seq, outcome) {
+        return;
+    }
+    if open_trade_with(game_state, player_id, target_display_id, next_seq, outcome).is_none() {
+        return;
+    }
+    if !game_state.set_trade_offer(player_id, offer) {
+        reject(
+            outcome,
+            player_id,
+            "You do not have everything you offered".to_string(),
+            next_seq,
+        );
+        return;
+    }
+
+    match game_state.trades().offer_changed(player_id) {
+        Ok(Some(trade)) => send_trade_update(game_state, &trade, next_seq, outcome),
+        Ok(None) => {}
+        Err(e) => error!("Failed to update trade of {}: {}", player_id, e),
+    }
+    push_items(game_state, outcome, player_id, now, next_seq);
+}
+
+fn apply_trade_accept<F>(
+    game_state: &GameState,
+    player_id: &str,
+    target_display_id: &str,
+    revision: u32,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "trade", next_seq, outcome) {
+        return;
+    }
+    if open_trade_with(game_state, player_id, target_display_id, next_seq, outcome).is_none() {
+        return;
+    }
+
+    let trade = match game_state.trades().confirm(player_id, revision) {
+        Ok(Some(Confirmation::Stale)) => {
+            reject(
+                outcome,
+                player_id,
+                "The offers changed; check them again before accepting".to_string(),
+                next_seq,
+            );
+            return;
+        }
+        Ok(Some(Confirmation::Waiting(trade))) => {
+            send_trade_update(game_state, &trade, next_seq, outcome);
+            return;
+        }
+        Ok(Some(Confirmation::Ready(trade))) => trade,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to confirm trade of {}: {}", player_id, e);
+            return;
+        }
+    };
+
+    let [first_id, second_id] = &trade.parties;
+    // Only trades that move something count toward reputation
+    let parties: Vec<Player> = trade
+        .parties
+        .iter()
+        .filter_map(|party| game_state.get_player(party))
+        .collect();
+    let moved_anything = parties.iter().any(|party| !party.escrow.is_empty());
+    if !game_state.complete_trade(first_id, second_id) {
+        // Nothing moved, so both sides can change their offers and try again
+        if let Ok(Some(trade)) = game_state.trades().unconfirm(player_id) {
+            send_trade_update(game_state, &trade, next_seq, outcome);
+        }
+        for party in &trade.parties {
+            reject(
+                outcome,
+                party,
+                "The trade does not fit in both inventories".to_string(),
+                next_seq,
+            );
+        }
+        return;
+    }
+    if let Err(e) = game_state.trades().remove(player_id) {
+        error!("Failed to remove completed trade of {}: {}", player_id, e);
+    }
+
+    for (side, party) in trade.parties.iter().enumerate() {
+        outcome.replies.push((
+            party.clone(),
+            ServerMessage::TradeClosed {
+                partner_display_id: trade.display_ids[1 - side].clone(),
+                completed: true,
+                reason: "Trade completed".to_string(),
+                seq_num: next_seq(),
+            },
+        ));
+        push_items(game_state, outcome, party, now, next_seq);
+    }
+    if let (true, [first, second]) = (moved_anything, parties.as_slice()) {
+        for (player, partner) in [(first, second), (second, first)] {
+            let changes = reputation::for_trade(&partner.faction);
+            change_reputation(game_state, &player.id, &changes, now, next_seq, outcome);
+        }
+    }
+}
+
+fn apply_trade_cancel<F>(
+    game_state: &GameState,
+    player_id: &str,
+    target_display_id: &str,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    if reject_downed(game_state, player_id, "trade", next_seq, outcome) {
+        return;
+    }
+    let Some(trade) = trade_with(game_state, player_id, target_display_id) else {
+        reject(
+            outcome,
+            player_id,
+            format!("You are not trading with {}", target_display_id),
+            next_seq,
+        );
+        return;
+    };
+    if let Err(e) = game_state.trades().remove(player_id) {
+        error!("Failed to remove cancelled trade of {}: {}", player_id, e);
+        return;
+    }
+
+    for (side, party) in trade.parties.iter().enumerate() {
+        let reason = if party == player_id {
+            "You cancelled the trade".to_string()
+        } else {
+            format!("{} cancelled the trade", trade.display_ids[1 - side])
+        };
+        close_trade(game_state, &trade, party, reason, now, next_seq, outcome);
+    }
+}
+
+/// The trade of `player_id` with the player `partner_display_id`, open or not
+fn trade_with(game_state: &GameState, player_id: &str, partner_display_id: &str) -> Option<Trade> {
+    let trade = match game_state.trades().find(player_id) {
+        Ok(trade) => trade?,
+        Err(e) => {
+            error!("Failed to look up trade of {}: {}", player_id, e);
+            return None;
+        }
+    };
+    let (_, partner) = trade.partner_of(player_id)?;
+    partner
+        .eq_ignore_ascii_case(partner_display_id)
+        .then_some(trade)
+}
+
+/// Like `trade_with`, but only for open trades, telling the player when there is none
+fn open_trade_with<F>(
+    game_state: &GameState,
+    player_id: &str,
+    partner_display_id: &str,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> Option<Trade>
+where
+    F: FnMut() -> u64,
+{
+    let trade = trade_with(game_state, player_id, partner_display_id).filter(|trade| trade.open);
+    if trade.is_none() {
+        reject(
+            outcome,
+            player_id,
+            format!("You have no open trade with {}", partner_display_id),
+            next_seq,
+        );
+    }
+    trade
+}
+
+/// Send both sides of a trade the offers and confirmations as they are now
+fn send_trade_update<F>(
+    game_state: &GameState,
+    trade: &Trade,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let offers = trade.parties.clone().map(|party| {
+        game_state
+            .get_player(&party)
+            .map(|player| player.escrow)
+            .unwrap_or_default()
+    });
+    for (side, party) in trade.parties.iter().enumerate() {
+        let other = 1 - side;
+        outcome.replies.push((
+            party.clone(),
+            ServerMessage::TradeUpdate {
+                partner_display_id: trade.display_ids[other].clone(),
+                revision: trade.revision,
+                own_offer: offers[side].clone(),
+                partner_offer: offers[other].clone(),
+                own_confirmed: trade.confirmed[side],
+                partner_confirmed: trade.confirmed[other],
+                seq_num: next_seq(),
+            },
+        ));
+    }
+}
+
+/// Give `player_id` back what they put up in `trade` and tell them it is over
+fn close_trade<F>(
+    game_state: &GameState,
+    trade: &Trade,
+    player_id: &str,
+    reason: String,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let Some(side) = trade.side(player_id) else {
+        return;
+    };
+    game_state.return_escrow(player_id);
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::TradeClosed {
+            partner_display_id: trade.display_ids[1 - side].clone(),
+            completed: false,
+            reason,
+            seq_num: next_seq(),
+        },
+    ));
+    // Only an open trade can have taken anything out of the inventory
+    if trade.open {
+        push_items(game_state, outcome, player_id, now, next_seq);
+    }
+}
+
+/// Put an item drawn from the region's loot table on the ground at `position`
+fn drop_loot<R: Rng>(
+    game_state: &GameState,
+    position: Position,
+    rng: &mut R,
+    outcome: &mut TickOutcome,
+) -> Result<Option<LootDrop>> {
+    let Some(item) = game_state
+        .loot_table_at(&position)
+        .roll(rng)
+        .and_then(|item_id| game_state.item_catalog().get(item_id))
+    else {
+        return Ok(None);
+    };
+    let (drop, removed) = game_state.ground_loot().place(item, position)?;
+    outcome.loot_changed_at.push(position);
+    outcome
+        .loot_changed_at
+        .extend(removed.iter().map(|old| old.position));
+    Ok(Some(drop))
+}
+
+/// Send a player their inventory and equipment as they are after this tick's changes
+fn push_items<F>(
+    game_state: &GameState,
+    outcome: &mut TickOutcome,
+    player_id: &str,
+    now: u64,
+    next_seq: &mut F,
+) where
+    F: FnMut() -> u64,
+{
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Inventory {
+            items: game_state.get_inventory(player_id, now),
+            credits: game_state.get_credits(player_id),
+            seq_num: next_seq(),
+        },
+    ));
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Equipment {
+            slots: game_state.get_equipment(player_id),
+            stats: game_state.get_item_stats(player_id),
+            seq_num: next_seq(),
+        },
+    ));
+}
+
+/// Change a player's reputation and tell them their standings and whatever it unlocked
+fn change_reputation<F>(
+    game_state: &GameState,
+    player_id: &str,
+    changes: &[(Faction, i32)],
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let Some(unlocks) = game_state.adjust_reputation(player_id, changes) else {
+        return;
+    };
+
+    for emote in &unlocks.emotes {
+        outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::Event {
+                message: format!(
+                    "Your standing with the {} lets you {} {} (/emote {})",
+                    emote
+                        .required_faction()
+                        .map_or("", |faction| faction.display_name()),
+                    emote.display_icon(),
+                    emote.display_text(),
+                    format!("{:?}", emote).to_lowercase()
+                ),
+                seq_num: next_seq(),
+            },
+        ));
+    }
+    for faction in &unlocks.items {
+        let item_id = faction.starter_item_id();
+        let item = game_state
+            .item_catalog()
+            .get(item_id)
+            .map_or(item_id, |item| item.name.as_str());
+        outcome.replies.push((
+            player_id.to_string(),
+            ServerMessage::Event {
+                message: format!(
+                    "The {} entrusted you with their signature {}",
+                    faction.display_name(),
+                    item
+                ),
+                seq_num: next_seq(),
+            },
+        ));
+    }
+    if !unlocks.items.is_empty() {
+        push_items(game_state, outcome, player_id, now, next_seq);
+    }
+
+    let config = game_state.get_config();
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::ReputationUpdate {
+            standings: game_state.get_standings(player_id),
+            emote_threshold: config.reputation_emote_threshold,
+            item_threshold: config.reputation_item_threshold,
+            seq_num: next_seq(),
+        },
+    ));
+}
+
+/// Tell a player how their quests progressed, and hand over the rewards of those they completed
+fn push_quest_updates<F>(
+    game_state: &GameState,
+    player_id: &str,
+    updates: Vec<QuestUpdate>,
+    now: u64,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) where
+    F: FnMut() -> u64,
+{
+    let mut rewarded_items = false;
+    for update in updates {
+        let dropped = update.dropped;
+        outcome
+            .loot_changed_at
+            .extend(update.removed.iter().map(|old| old.position));
+        let message = if update.status.completed {
+            rewarded_items |= !update.items.is_empty();
+            outcome.state_changed = true;
+            ServerMessage::QuestComplete {
+                quest_id: update.quest.id.clone(),
+                name: update.quest.name.clone(),
+                experience: update.experience,
+                items: update
+                    .items
+                    .iter()
+                    .map(|item_id| {
+                        game_state
+                            .item_catalog()
+                            .get(item_id)
+                            .map_or(item_id.clone(), |item| item.name.clone())
+                    })
+                    .collect(),
+                seq_num: next_seq(),
+            }
+        } else {
+            ServerMessage::QuestProgress {
+                quest: update.quest,
+                progress: update.status.progress,
+                seq_num: next_seq(),
+            }
+        };
+        outcome.replies.push((player_id.to_string(), message));
+        for drop in dropped {
+            outcome.replies.push((
+                player_id.to_string(),
+                ServerMessage::Event {
+                    message: format!(
+                        "Your inventory is full, so {} was left on the ground at your feet",
+                        drop.name
+                    ),
+                    seq_num: next_seq(),
+                },
+            ));
+            outcome.loot_changed_at.push(drop.position);
+        }
+    }
+    if rewarded_items {
+        push_items(game_state, outcome, player_id, now, next_seq);
+    }
+}
+
+/// Tell a player their intent could not be applied
+fn reject<F>(outcome: &mut TickOutcome, player_id: &str, message: String, next_seq: &mut F)
+where
+    F: FnMut() -> u64,
+{
+    outcome.replies.push((
+        player_id.to_string(),
+        ServerMessage::Error {
+            message,
+            seq_num: next_seq(),
+        },
+    ));
+}
+
+/// Refuse an intent from a player who is down; returns whether they were
+///
+/// Downed players do nothing until they respawn, so they cannot, say, pick up
+/// what they dropped when they fell.
+fn reject_downed<F>(
+    game_state: &GameState,
+    player_id: &str,
+    action: &str,
+    next_seq: &mut F,
+    outcome: &mut TickOutcome,
+) -> bool
+where
+    F: FnMut() -> u64,
+{
+    if !game_state.has_effect(player_id, StatusEffectKind::Downed) {
+        return false;
+    }
+    reject(
+        outcome,
+        player_id,
+        format!("You are down and cannot {} until you respawn.", action),
+        next_seq,
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use nymquest_protocol::game_protocol::{
+        ControlPoint, NpcKind, QuestDefinition, QuestObjective, TerrainMap,
+    };
+    use nymquest_protocol::transport::SenderTag;
+    use nymquest_protocol::world_lore::{Faction, ItemStats};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // New players may attack straight away, measured from the real clock
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    // Faction rules have their own test; everywhere else every faction plays alike
+    fn world(config: GameConfig) -> GameState {
+        GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::new(),
+            friendly_fire: true,
+            enable_npcs: false,
+            ..config
+        })
+    }
+
+    fn spawn(game_state: &GameState, name: &str, x: f32, y: f32) -> String {
+        let player_id = game_state.add_player(name.to_string(), Faction::Nyms, SenderTag::random());
+        assert!(game_state.update_player_position(&player_id, Position::new(x, y)));
+        player_id
+    }
+
+    fn run_tick(game_state: &GameState, now: u64) -> TickOutcome {
+        let mut seq = 0;
+        game_state
+            .simulation()
+            .tick(game_state, now, &mut StdRng::seed_from_u64(7), || {
+                seq += 1;
+                seq
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_intents_wait_for_the_tick() {
+        let game_state = world(GameConfig::default());
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Move {
+                    direction: Direction::Right,
+                },
+            )
+            .unwrap();
+        assert_eq!(game_state.simulation().pending_intents(), 1);
+        assert_eq!(
+            game_state.get_player(&alice).unwrap().position,
+            Position::new(0.0, 0.0)
+        );
+
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.state_changed);
+        assert_eq!(game_state.simulation().pending_intents(), 0);
+        let speed = game_state.get_config().movement_speed;
+        assert_eq!(
+            game_state.get_player(&alice).unwrap().position,
+            Position::new(speed, 0.0)
+        );
+        assert!(matches!(
+            outcome.replies.as_slice(),
+            [(id, ServerMessage::Event { message, .. })] if *id == alice && message.starts_with("Moved")
+        ));
+
+        // Nothing queued and nobody hurt, nothing to send
+        let outcome = run_tick(&game_state, now());
+        assert!(!outcome.state_changed && outcome.replies.is_empty());
+    }
+
+    #[test]
+    fn test_intents_apply_in_arrival_order() {
+        let game_state = world(GameConfig::default());
+        let speed = game_state.get_config().movement_speed;
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 2.0 * speed, 0.0);
+
+        // Both step into the same spot; whoever was queued first gets it
+        let simulation = game_state.simulation();
+        simulation
+            .enqueue(
+                &bob,
+                PlayerIntent::Move {
+                    direction: Direction::Left,
+                },
+            )
+            .unwrap();
+        simulation
+            .enqueue(
+                &alice,
+                PlayerIntent::Move {
+                    direction: Direction::Right,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().position,
+            Position::new(speed, 0.0)
+        );
+        assert_eq!(
+            game_state.get_player(&alice).unwrap().position,
+            Position::new(0.0, 0.0)
+        );
+        assert!(outcome.replies.iter().any(|(id, message)| *id == alice
+            && matches!(message, ServerMessage::Error { message, .. } if message.contains("another player"))));
+    }
+
+    #[test]
+    fn test_attacks_respect_range_and_cooldown() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        let carol = spawn(&game_state, "carol", 90.0, 90.0);
+        let display_id = |id: &str| game_state.get_player(id).unwrap().display_id;
+        let attack = |target: &str| PlayerIntent::Attack {
+            target_display_id: display_id(target),
+        };
+
+        let simulation = game_state.simulation();
+        simulation.enqueue(&alice, attack(&bob)).unwrap();
+        // The second attack in the same tick is still on cooldown
+        simulation.enqueue(&alice, attack(&bob)).unwrap();
+        simulation.enqueue(&bob, attack(&carol)).unwrap();
+        let start = now();
+        let outcome = run_tick(&game_state, start);
+
+        let base_damage = game_state.get_config().base_damage;
+        let initial_health = game_state.get_config().initial_player_health;
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            initial_health - base_damage
+        );
+        assert_eq!(
+            game_state.get_player(&carol).unwrap().health,
+            initial_health
+        );
+        let errors: Vec<&str> = outcome
+            .replies
+            .iter()
+            .filter_map(|(_, message)| match message {
+                ServerMessage::Error { message, .. } => Some(message.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(errors[0].starts_with("Attack on cooldown"));
+        assert!(errors[1].contains("out of range"));
+        assert!(outcome.replies.iter().any(|(id, message)| *id == bob
+            && matches!(message, ServerMessage::Event { message, .. } if message.contains("attacked by alice"))));
+
+        // Once the cooldown has passed alice can attack again
+        simulation.enqueue(&alice, attack(&bob)).unwrap();
+        run_tick(
+            &game_state,
+            start + game_state.get_config().attack_cooldown_seconds,
+        );
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            initial_health - 2 * base_damage
+        );
+    }
+
+    #[test]
+    fn test_factions_change_the_rules() {
+        let game_state = GameState::new_with_config(GameConfig {
+            crit_chance: 0.0,
+            ..GameConfig::default()
+        });
+        let join = |name: &str, faction: Faction, x: f32| {
+            let player_id = game_state.add_player(name.to_string(), faction, SenderTag::random());
+            assert!(game_state.update_player_position(&player_id, Position::new(x, 0.0)));
+            player_id
+        };
+        let cipher = join("cipher", Faction::CipherCollective, 0.0);
+        let ally = join("ally", Faction::CipherCollective, 20.0);
+        let corp = join("corp", Faction::CorporateHegemony, -20.0);
+        let display_id = |id: &str| game_state.get_player(id).unwrap().display_id;
+        let config = game_state.get_config().clone();
+
+        // Fellow faction members cannot hurt each other
+        let simulation = game_state.simulation();
+        simulation
+            .enqueue(
+                &cipher,
+                PlayerIntent::Attack {
+                    target_display_id: display_id(&ally),
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert_eq!(
+            game_state.get_player(&ally).unwrap().health,
+            config.initial_player_health
+        );
+        assert!(outcome.replies.iter().any(|(id, message)| *id == cipher
+            && matches!(message, ServerMessage::Error { message, .. } if message.contains("fellow member"))));
+
+        // Cipher hits harder, the Hegemony takes less, and experience follows the damage
+        simulation
+            .enqueue(
+                &cipher,
+                PlayerIntent::Attack {
+                    target_display_id: display_id(&corp),
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, now());
+        let damage = (config.base_damage as f32
+            * config
+                .faction_modifiers(&Faction::CipherCollective)
+                .damage_dealt
+            * config
+                .faction_modifiers(&Faction::CorporateHegemony)
+                .damage_taken)
+            .round() as u32;
+        assert_eq!(
+            game_state.get_player(&corp).unwrap().health,
+            config.initial_player_health - damage
+        );
+        assert_eq!(game_state.get_player(&cipher).unwrap().experience, damage);
+
+        // The Hegemony moves slower than everyone else
+        simulation
+            .enqueue(
+                &corp,
+                PlayerIntent::Move {
+                    direction: Direction::Up,
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, now());
+        let step = config.movement_speed
+            * config
+                .faction_modifiers(&Faction::CorporateHegemony)
+                .movement_speed;
+        assert_eq!(
+            game_state.get_player(&corp).unwrap().position,
+            Position::new(-20.0, -step)
+        );
+    }
+
+    #[test]
+    fn test_health_regenerates_once_per_second() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            simulation_tick_rate_hz: 4,
+            health_regen_per_second: 3,
+            health_regen_delay_seconds: 0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: game_state.get_player(&bob).unwrap().display_id,
+                },
+            )
+            .unwrap();
+
+        let damaged =
+            game_state.get_config().initial_player_health - game_state.get_config().base_damage;
+        for _ in 0..3 {
+            run_tick(&game_state, now());
+        }
+        assert_eq!(game_state.get_player(&bob).unwrap().health, damaged);
+
+        assert!(run_tick(&game_state, now()).state_changed);
+        assert_eq!(game_state.get_player(&bob).unwrap().health, damaged + 3);
+        assert_eq!(game_state.simulation().tick_count(), 4);
+
+        // Nobody regenerates past their maximum health
+        for _ in 0..4 * 10 {
+            run_tick(&game_state, now());
+        }
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            game_state.get_config().initial_player_health
+        );
+        assert_eq!(
+            game_state.get_player(&alice).unwrap().health,
+            game_state.get_config().initial_player_health
+        );
+    }
+
+    #[test]
+    fn test_factions_capture_control_points() {
+        let game_state = world(GameConfig {
+            simulation_tick_rate_hz: 1,
+            territory_capture_seconds: 3,
+            ..GameConfig::default()
+        });
+        let nym = spawn(&game_state, "nym", 0.0, 0.0);
+        let territory_updates = |outcome: &TickOutcome| -> Vec<Vec<ControlPoint>> {
+            outcome
+                .replies
+                .iter()
+                .filter_map(|(id, message)| match message {
+                    ServerMessage::TerritoryUpdate { control_points, .. } if *id == nym => {
+                        Some(control_points.clone())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Players who are down or offline hold nothing, so they do not contest the point
+        let downed = game_state.add_player(
+            "downed".to_string(),
+            Faction::CorporateHegemony,
+            SenderTag::random(),
+        );
+        assert!(game_state.update_player_position(&downed, Position::new(0.0, 0.0)));
+        game_state.add_effect(&downed, StatusEffectKind::Downed, now() + 60);
+        let mut parked = game_state.get_player(&downed).unwrap();
+        parked.id = "parked".to_string();
+        parked.display_id = "Parked1".to_string();
+        parked.effects.clear();
+        game_state.restore_player(parked.id.clone(), parked, None);
+
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.replies.iter().any(|(id, message)| *id == nym
+            && matches!(message, ServerMessage::Event { message, .. } if message == "The Nyms are capturing the Mixnet Core in Neon Harbor")));
+        assert_eq!(
+            territory_updates(&outcome)[0][0].capturing,
+            Some(Faction::Nyms)
+        );
+
+        // Nothing to tell while the capture goes on
+        assert!(territory_updates(&run_tick(&game_state, now())).is_empty());
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.state_changed);
+        assert_eq!(territory_updates(&outcome)[0][0].owner, Some(Faction::Nyms));
+
+        // Owners inside their point earn more and draw less attention
+        let config = game_state.get_config();
+        let modifiers = game_state.effective_modifiers(&game_state.get_player(&nym).unwrap());
+        assert_eq!(modifiers.experience_gain, config.territory_xp_bonus);
+        assert_eq!(
+            modifiers.surveillance_exposure,
+            config.territory_surveillance_factor
+        );
+
+        // ...but not outside it, and not for other factions
+        assert!(game_state.update_player_position(&nym, Position::new(0.0, 40.0)));
+        let modifiers = game_state.effective_modifiers(&game_state.get_player(&nym).unwrap());
+        assert_eq!(modifiers.experience_gain, 1.0);
+        let corp = game_state.add_player(
+            "corp".to_string(),
+            Faction::CorporateHegemony,
+            SenderTag::random(),
+        );
+        assert!(game_state.update_player_position(&corp, Position::new(10.0, 0.0)));
+        let modifiers = game_state.effective_modifiers(&game_state.get_player(&corp).unwrap());
+        assert_eq!(modifiers.experience_gain, 1.0);
+    }
+
+    #[test]
+    fn test_players_cross_into_neighbouring_regions() {
+        let game_state = world(GameConfig {
+            world_regions: vec!["Neon Harbor".to_string(), "Deep Net".to_string()],
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 95.0, 0.0);
+        let bob = spawn(&game_state, "bob", 80.0, 0.0);
+        let bob_display_id = game_state.get_player(&bob).unwrap().display_id;
+        assert!(game_state
+            .get_visible_player_views(&alice)
+            .contains_key(&bob_display_id));
+        assert_eq!(game_state.get_region_player_ids(0).len(), 2);
+
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Move {
+                    direction: Direction::Right,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(game_state.get_player(&alice).unwrap().position.x > 100.0);
+        let entered = outcome
+            .replies
+            .iter()
+            .find_map(|(id, message)| match message {
+                ServerMessage::RegionChanged {
+                    world_boundaries, ..
+                } if *id == alice => Some(world_boundaries.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(entered.name, "Deep Net");
+        assert_eq!((entered.min_x, entered.max_x), (100.0, 400.0));
+        assert_eq!(game_state.get_region_player_ids(0), vec![bob.clone()]);
+        assert_eq!(game_state.get_region_player_ids(1), vec![alice.clone()]);
+
+        // Players in other regions are out of sight and out of reach, however close
+        assert!(!game_state
+            .get_visible_player_views(&alice)
+            .contains_key(&bob_display_id));
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: bob_display_id,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.replies.iter().any(|(id, message)| *id == alice
+            && matches!(message, ServerMessage::Error { message, .. } if message.contains("another region"))));
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            game_state.get_config().initial_player_health
+        );
+    }
+
+    #[test]
+    fn test_terrain_blocks_and_slows_players() {
+        let mut config = GameConfig {
+            attack_range: 100.0,
+            ..GameConfig::default()
+        };
+        // A wall runs down the middle of Neon Harbor, with a gap at the south end
+        let rows = ["..#..", "..#..", "..#..", "..#..", "....~"];
+        config.terrain_maps.insert(
+            "Neon Harbor".to_string(),
+            TerrainMap::parse("Neon Harbor", &rows.join("\n")).unwrap(),
+        );
+        let game_state = world(config);
+        let alice = spawn(&game_state, "alice", -30.0, 0.0);
+        let bob = spawn(&game_state, "bob", 30.0, 0.0);
+        let carol = spawn(&game_state, "carol", 90.0, 90.0);
+        let bob_display_id = game_state.get_player(&bob).unwrap().display_id;
+        let rejected = |outcome: &TickOutcome, text: &str| {
+            outcome.replies.iter().any(|(id, message)| *id == alice
+                && matches!(message, ServerMessage::Error { message, .. } if message.contains(text)))
+        };
+
+        // Walls stop players walking into them...
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Move {
+                    direction: Direction::Right,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(rejected(&outcome, "the way is blocked"));
+        assert_eq!(
+            game_state.get_player(&alice).unwrap().position,
+            Position::new(-30.0, 0.0)
+        );
+
+        // ...and attacks through them
+        game_state
+            .simulation()
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: bob_display_id,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(rejected(&outcome, "line of sight"));
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            game_state.get_config().initial_player_health
+        );
+
+        // Slow terrain cuts a step short
+        game_state
+            .simulation()
+            .enqueue(
+                &carol,
+                PlayerIntent::Move {
+                    direction: Direction::Left,
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, now());
+        let config = game_state.get_config();
+        assert_eq!(
+            game_state.get_player(&carol).unwrap().position.x,
+            90.0 - config.movement_speed * config.terrain_slow_factor
+        );
+    }
+
+    #[test]
+    fn test_safe_zones_refuse_attacks() {
+        let mut config = GameConfig {
+            attack_range: 100.0,
+            ..GameConfig::default()
+        };
+        // The west of Neon Harbor is a safe zone
+        let rows = ["++...", "++...", "++...", "++...", "++..."];
+        config.terrain_maps.insert(
+            "Neon Harbor".to_string(),
+            TerrainMap::parse("Neon Harbor", &rows.join("\n")).unwrap(),
+        );
+        let game_state = world(config);
+        let alice = spawn(&game_state, "alice", -30.0, 0.0);
+        let bob = spawn(&game_state, "bob", 30.0, 0.0);
+        let alice_display_id = game_state.get_player(&alice).unwrap().display_id;
+        let bob_display_id = game_state.get_player(&bob).unwrap().display_id;
+        let rejected = |outcome: &TickOutcome, player_id: &str, text: &str| {
+            outcome.replies.iter().any(|(id, message)| id == player_id
+                && matches!(message, ServerMessage::Error { message, .. } if message.contains(text)))
+        };
+
+        for (attacker, target) in [(&alice, &bob_display_id), (&bob, &alice_display_id)] {
+            game_state
+                .simulation()
+                .enqueue(
+                    attacker,
+                    PlayerIntent::Attack {
+                        target_display_id: target.clone(),
+                    },
+                )
+                .unwrap();
+        }
+        let outcome = run_tick(&game_state, now());
+        assert!(rejected(&outcome, &alice, "cannot attack from a safe zone"));
+        assert!(rejected(&outcome, &bob, "is in a safe zone"));
+        for player_id in [&alice, &bob] {
+            assert_eq!(
+                game_state.get_player(player_id).unwrap().health,
+                game_state.get_config().initial_player_health
+            );
+        }
+    }
+
+    #[test]
+    fn test_players_fight_npcs() {
+        let game_state = GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::new(),
+            crit_chance: 0.0,
+            attack_cooldown_seconds: 0,
+            ..GameConfig::default()
+        });
+        let broker = game_state.npcs().get("Broker1").unwrap();
+        let hunter = spawn(&game_state, "hunter", broker.position.x, broker.position.y);
+        let attack = || {
+            game_state
+                .simulation()
+                .enqueue(
+                    &hunter,
+                    PlayerIntent::Attack {
+                        target_display_id: "BROKER1".to_string(),
+                    },
+                )
+                .unwrap();
+            run_tick(&game_state, now())
+        };
+        let said = |outcome: &TickOutcome, text: &str| {
+            outcome.replies.iter().any(|(id, message)| {
+                *id == hunter
+                    && matches!(message, ServerMessage::Event { message, .. } | ServerMessage::Error { message, .. } if message.contains(text))
+            })
+        };
+
+        // Characters are attacked by display ID, like players, and give experience
+        let outcome = attack();
+        assert!(said(
+            &outcome,
+            "You hit Deep Net Broker Broker1 for 10 damage"
+        ));
+        assert_eq!(game_state.npcs().get("broker1").unwrap().health, 50);
+        assert_eq!(game_state.get_player(&hunter).unwrap().experience, 10);
+
+        for _ in 0..4 {
+            attack();
+        }
+        let outcome = attack();
+        assert!(said(&outcome, "You defeated Deep Net Broker Broker1!"));
+        assert_eq!(game_state.get_player(&hunter).unwrap().experience, 80);
+        let outcome = attack();
+        assert!(said(&outcome, "'BROKER1' not found"));
+
+        // Once a second, players are sent the characters around them, without the defeated one
+        let outcome = (0..3).map(|_| run_tick(&game_state, now())).last().unwrap();
+        let npcs = outcome
+            .replies
+            .iter()
+            .find_map(|(id, message)| match message {
+                ServerMessage::NpcUpdate { npcs, .. } if *id == hunter => Some(npcs.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(npcs.iter().all(|npc| npc.display_id != "Broker1"));
+    }
+
+    #[test]
+    fn test_npcs_leave_offline_characters_alone() {
+        let game_state = GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::new(),
+            ..GameConfig::default()
+        });
+        let drone = game_state.npcs().get("Drone1").unwrap();
+        let online = spawn(&game_state, "online", drone.position.x, drone.position.y);
+
+        // A character restored after a restart has no connection until its owner resumes,
+        // and sorts first, so the drone would go for it if it counted
+        let parked_id = "00000000-0000-0000-0000-000000000000".to_string();
+        let mut parked = game_state.get_player(&online).unwrap();
+        parked.id = parked_id.clone();
+        parked.display_id = "Parked1".to_string();
+        game_state.restore_player(parked_id.clone(), parked.clone(), None);
+
+        for _ in 0..10 {
+            run_tick(&game_state, now());
+        }
+        assert_eq!(
+            game_state.get_player(&parked_id).unwrap().health,
+            parked.health
+        );
+        assert!(game_state.get_player(&online).unwrap().health < parked.health);
+    }
+
+    #[test]
+    fn test_quests_progress_and_reward_completion() {
+        let game_state = GameState::new_with_config(GameConfig {
+            faction_modifiers: HashMap::new(),
+            crit_chance: 0.0,
+            attack_cooldown_seconds: 0,
+            quests: vec![QuestDefinition {
+                id: "hit_job".to_string(),
+                name: "Hit Job".to_string(),
+                description: "Hear the broker out, then silence them.".to_string(),
+                faction: Some(Faction::Nyms),
+                objectives: vec![
+                    QuestObjective::Whisper {
+                        npc: "Broker1".to_string(),
+                    },
+                    QuestObjective::Defeat {
+                        kind: NpcKind::DeepNetBroker,
+                        count: 1,
+                    },
+                ],
+                reward_experience: 25,
+                reward_items: vec!["zk_prover".to_string()],
+            }],
+            ..GameConfig::default()
+        });
+        let broker = game_state.npcs().get("Broker1").unwrap();
+        let hunter = spawn(&game_state, "hunter", broker.position.x, broker.position.y);
+        let act = |intent: PlayerIntent| {
+            game_state.simulation().enqueue(&hunter, intent).unwrap();
+            run_tick(&game_state, now())
+        };
+        let accept = || {
+            act(PlayerIntent::AcceptQuest {
+                quest_id: "hit_job".to_string(),
+            })
+        };
+        let progress = |outcome: &TickOutcome| {
+            outcome
+                .replies
+                .iter()
+                .find_map(|(id, message)| match message {
+                    ServerMessage::QuestProgress { progress, .. } if *id == hunter => {
+                        Some(progress.clone())
+                    }
+                    _ => None,
+                })
+        };
+        assert_eq!(game_state.get_quest_offers(&hunter).len(), 1);
+
+        // Progress only counts once the quest is taken on
+        act(PlayerIntent::WhisperNpc {
+            npc_display_id: "Broker1".to_string(),
+        });
+        assert_eq!(progress(&accept()), Some(vec![0, 0]));
+        assert!(game_state.get_quest_offers(&hunter).is_empty());
+
+        // Characters whisper back
+        let outcome = act(PlayerIntent::WhisperNpc {
+            npc_display_id: "broker1".to_string(),
+        });
+        assert!(outcome.replies.iter().any(|(id, message)| *id == hunter
+            && matches!(message, ServerMessage::WhisperMessage { sender_name, .. } if sender_name == "Broker1")));
+        assert_eq!(progress(&outcome), Some(vec![1, 0]));
+
+        let experience = game_state.get_player(&hunter).unwrap().experience;
+        let attack = || {
+            act(PlayerIntent::Attack {
+                target_display_id: "Broker1".to_string(),
+            })
+        };
+        for _ in 0..5 {
+            attack();
+        }
+        let outcome = attack();
+        let completed = outcome
+            .replies
+            .iter()
+            .find_map(|(id, message)| match message {
+                ServerMessage::QuestComplete {
+                    quest_id,
+                    experience,
+                    items,
+                    ..
+                } if *id == hunter => Some((quest_id.clone(), *experience, items.clone())),
+                _ => None,
+            });
+        assert_eq!(
+            completed,
+            Some((
+                "hit_job".to_string(),
+                25,
+                vec![game_state.item_catalog()["zk_prover"].name.clone()]
+            ))
+        );
+        // 80 XP for the fight and 25 for the quest
+        assert_eq!(
+            game_state.get_player(&hunter).unwrap().experience,
+            experience + 105
+        );
+        assert!(game_state
+            .get_inventory(&hunter, now())
+            .iter()
+            .any(|entry| entry.item.id == "zk_prover"));
+
+        // Quests can only be completed once
+        let outcome = accept();
+        assert!(outcome.replies.iter().any(|(id, message)| *id == hunter
+            && matches!(message, ServerMessage::Error { message, .. } if message.contains("not on offer"))));
+    }
+
+    #[test]
+    fn test_abilities_have_cooldowns_and_effects() {
+        let game_state = world(GameConfig {
+            attack_cooldown_seconds: 0,
+            ..GameConfig::default()
+        });
+        let nym = spawn(&game_state, "nym", 0.0, 0.0);
+        let nym_display_id = game_state.get_player(&nym).unwrap().display_id;
+        let join = |name: &str, faction: Faction, x: f32| {
+            let player_id = game_state.add_player(name.to_string(), faction, SenderTag::random());
+            assert!(game_state.update_player_position(&player_id, Position::new(x, 0.0)));
+            let display_id = game_state.get_player(&player_id).unwrap().display_id;
+            (player_id, display_id)
+        };
+        let (corp, corp_display_id) = join("corp", Faction::CorporateHegemony, 10.0);
+        let (monk, _) = join("monk", Faction::AlgorithmMonks, -10.0);
+        let (healer, _) = join("healer", Faction::AlgorithmMonks, -5.0);
+        let start = now();
+        let act = |player_id: &str, intent: PlayerIntent, now: u64| {
+            game_state.simulation().enqueue(player_id, intent).unwrap();
+            run_tick(&game_state, now)
+        };
+        let use_ability = |player_id: &str, ability: Ability, target: Option<&str>, now: u64| {
+            let intent = PlayerIntent::UseAbility {
+                ability,
+                target_display_id: target.map(str::to_string),
+            };
+            act(player_id, intent, now)
+        };
+        let said = |outcome: &TickOutcome, player_id: &str, text: &str| {
+            outcome.replies.iter().any(|(id, message)| {
+                id == player_id
+                    && matches!(message, ServerMessage::Event { message, .. } | ServerMessage::Error { message, .. } if message.contains(text))
+            })
+        };
+
+        // Abilities of other factions unlock at higher levels
+        let outcome = use_ability(&nym, Ability::Hack, Some(&corp_display_id), start);
+        assert!(said(&outcome, &nym, "Hack unlocks at level 3 for the Nyms"));
+
+        // Hacked players cannot move or attack until it wears off
+        let outcome = use_ability(&corp, Ability::Hack, Some(&nym_display_id), start);
+        assert!(said(&outcome, &nym, "corp hacked you!"));
+        let cooldowns = outcome
+            .replies
+            .iter()
+            .find_map(|(id, message)| match message {
+                ServerMessage::AbilityCooldowns { cooldowns, .. } if *id == corp => {
+                    Some(cooldowns.clone())
+                }
+                _ => None,
+            });
+        assert_eq!(cooldowns.unwrap()[1].remaining_seconds, 20);
+        let outcome = act(
+            &nym,
+            PlayerIntent::Move {
+                direction: Direction::Right,
+            },
+            start,
+        );
+        assert!(said(&outcome, &nym, "cannot move"));
+        let outcome = use_ability(&corp, Ability::Hack, Some(&nym_display_id), start + 1);
+        assert!(said(
+            &outcome,
+            &corp,
+            "Hack on cooldown! Wait 19 more seconds."
+        ));
+        let outcome = run_tick(&game_state, start + 3);
+        assert!(said(&outcome, &nym, "Stun wore off"));
+        assert!(game_state.get_visible_player_views(&corp)[&nym_display_id]
+            .effects
+            .is_empty());
+
+        // Ghost hides the Nym from everyone further away than a few steps, until they attack
+        use_ability(&nym, Ability::Ghost, None, start + 3);
+        assert!(!game_state
+            .get_visible_player_views(&corp)
+            .contains_key(&nym_display_id));
+        let attack = PlayerIntent::Attack {
+            target_display_id: corp_display_id.clone(),
+        };
+        let outcome = act(&nym, attack, start + 4);
+        assert!(said(&outcome, &nym, "Ghost ended"));
+        assert!(game_state
+            .get_visible_player_views(&corp)
+            .contains_key(&nym_display_id));
+
+        // Encrypt halves the damage taken, and DataDrop heals allies only
+        let (cipher, _) = join("cipher", Faction::CipherCollective, 5.0);
+        let outcome = use_ability(&cipher, Ability::Encrypt, None, start + 5);
+        assert!(said(&outcome, &cipher, "Encrypt shields you from 50%"));
+        assert_eq!(
+            game_state
+                .apply_damage(&cipher, &corp, 20, start + 5, &mut StdRng::seed_from_u64(7))
+                .damage,
+            10
+        );
+        let outcome = use_ability(&healer, Ability::DataDrop, Some(&nym_display_id), start + 5);
+        assert!(said(&outcome, &healer, "is not an ally"));
+        game_state.apply_damage(&monk, &corp, 20, start + 5, &mut StdRng::seed_from_u64(7));
+        let monk_display_id = game_state.get_player(&monk).unwrap().display_id;
+        let outcome = use_ability(
+            &healer,
+            Ability::DataDrop,
+            Some(&monk_display_id),
+            start + 5,
+        );
+        assert!(said(
+            &outcome,
+            &monk,
+            "healer restored 20 of your health points"
+        ));
+        assert_eq!(game_state.get_player(&monk).unwrap().health, 100);
+    }
+
+    #[test]
+    fn test_stealthed_players_cannot_be_targeted_from_afar() {
+        let game_state = world(GameConfig {
+            attack_range: 100.0,
+            ..GameConfig::default()
+        });
+        let corp = game_state.add_player(
+            "corp".to_string(),
+            Faction::CorporateHegemony,
+            SenderTag::random(),
+        );
+        assert!(game_state.update_player_position(&corp, Position::new(0.0, 0.0)));
+        let nym = spawn(&game_state, "nym", 15.0, 0.0);
+        let nym_display_id = game_state.get_player(&nym).unwrap().display_id;
+        game_state.add_effect(&nym, StatusEffectKind::Stealth, now() + 60);
+
+        // Hidden players are as good as gone to attacks and abilities
+        for intent in [
+            PlayerIntent::Attack {
+                target_display_id: nym_display_id.clone(),
+            },
+            PlayerIntent::UseAbility {
+                ability: Ability::Hack,
+                target_display_id: Some(nym_display_id.clone()),
+            },
+        ] {
+            game_state.simulation().enqueue(&corp, intent).unwrap();
+        }
+        let outcome = run_tick(&game_state, now());
+        let not_found = format!("Player '{}' not found.", nym_display_id);
+        let refused = outcome
+            .replies
+            .iter()
+            .filter(|(id, message)| *id == corp
+                && matches!(message, ServerMessage::Error { message, .. } if message.ends_with(&not_found)))
+            .count();
+        assert_eq!(refused, 2);
+        assert!(!game_state.has_effect(&nym, StatusEffectKind::Stun));
+
+        // Close enough to be seen, they can be hit again
+        assert!(game_state.update_player_position(&nym, Position::new(4.0, 0.0)));
+        game_state
+            .simulation()
+            .enqueue(
+                &corp,
+                PlayerIntent::Attack {
+                    target_display_id: nym_display_id,
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, now());
+        assert!(
+            game_state.get_player(&nym).unwrap().health
+                < game_state.get_config().initial_player_health
+        );
+    }
+
+    #[test]
+    fn test_corruption_stacks_and_deals_damage_every_second() {
+        let game_state = world(GameConfig {
+            simulation_tick_rate_hz: 1,
+            health_regen_per_second: 0,
+            ..GameConfig::default()
+        });
+        let nym = spawn(&game_state, "nym", 0.0, 0.0);
+        let nym_display_id = game_state.get_player(&nym).unwrap().display_id;
+        let watcher = spawn(&game_state, "watcher", 5.0, 0.0);
+        let start = now();
+        game_state.add_effect(&nym, StatusEffectKind::Corruption, start + 2);
+        game_state.add_effect(&nym, StatusEffectKind::Corruption, start + 2);
+
+        // Others see the effect and how many stacks it has
+        assert_eq!(
+            game_state.get_visible_player_views(&watcher)[&nym_display_id].effects,
+            BTreeMap::from([(StatusEffectKind::Corruption, 2)])
+        );
+
+        let outcome = run_tick(&game_state, start);
+        assert!(outcome.state_changed);
+        assert!(outcome.replies.iter().any(|(id, message)| *id == nym
+            && matches!(message, ServerMessage::Event { message, .. } if message.contains("Corruption cost you 4 health points"))));
+        run_tick(&game_state, start + 1);
+        assert_eq!(game_state.get_player(&nym).unwrap().health, 92);
+
+        // Once it wears off the damage stops
+        let outcome = run_tick(&game_state, start + 2);
+        assert!(outcome.replies.iter().any(|(id, message)| *id == nym
+            && matches!(message, ServerMessage::Event { message, .. } if message == "Corruption wore off")));
+        assert!(!game_state.has_effect(&nym, StatusEffectKind::Corruption));
+        assert_eq!(game_state.get_player(&nym).unwrap().health, 92);
+    }
+
+    #[test]
+    fn test_surveillance_exposes_players_to_the_hegemony() {
+        let game_state = world(GameConfig {
+            simulation_tick_rate_hz: 1,
+            surveillance_check_seconds: 2,
+            surveillance_exposure_chance: 1.0,
+            surveillance_exposure_seconds: 5,
+            ..GameConfig::default()
+        });
+        let nym = spawn(&game_state, "nym", 0.0, 0.0);
+        let nym_display_id = game_state.get_player(&nym).unwrap().display_id;
+        let corp = game_state.add_player(
+            "corp".to_string(),
+            Faction::CorporateHegemony,
+            SenderTag::random(),
+        );
+        assert!(game_state.update_player_position(&corp, Position::new(-90.0, 90.0)));
+        assert!(!game_state
+            .get_visible_player_views(&corp)
+            .contains_key(&nym_display_id));
+
+        // The Mixnet Core is watched closely, so the cameras catch the Nym sooner or later
+        let start = now();
+        let mut alerts = Vec::new();
+        for second in 0..100 {
+            let outcome = run_tick(&game_state, start + second);
+            alerts = outcome
+                .replies
+                .iter()
+                .filter_map(|(id, message)| match message {
+                    ServerMessage::SurveillanceAlert {
+                        display_id, name, ..
+                    } => Some((id.clone(), display_id.clone(), name.clone())),
+                    _ => None,
+                })
+                .collect();
+            if !alerts.is_empty() {
+                assert!(outcome.state_changed);
+                break;
+            }
+        }
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts
+            .iter()
+            .all(|(_, display_id, name)| *display_id == nym_display_id && name == "nym"));
+        assert!(alerts.iter().any(|(id, _, _)| *id == nym));
+        assert!(alerts.iter().any(|(id, _, _)| *id == corp));
+
+        // The Hegemony is never caught by its own cameras and sees exposed players from afar
+        assert!(game_state.surveillance().is_exposed(&nym));
+        assert!(!game_state.surveillance().is_exposed(&corp));
+        assert!(game_state
+            .get_visible_player_views(&corp)
+            .contains_key(&nym_display_id));
+
+        // Until the exposure runs out, checked on a tick without a camera sweep
+        let outcome = run_tick(&game_state, now() + 60);
+        assert!(outcome.state_changed);
+        assert!(!game_state.surveillance().is_exposed(&nym));
+        assert!(!game_state
+            .get_visible_player_views(&corp)
+            .contains_key(&nym_display_id));
+    }
+
+    #[test]
+    fn test_used_items_wear_off() {
+        let game_state = world(GameConfig {
+            item_effect_seconds: 30,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        assert!(game_state.add_item(&alice, "zk_prover", 2));
+        let start = now();
+
+        let simulation = game_state.simulation();
+        for _ in 0..2 {
+            simulation
+                .enqueue(
+                    &alice,
+                    PlayerIntent::UseItem {
+                        item_id: "zk_prover".to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        simulation
+            .enqueue(
+                &alice,
+                PlayerIntent::DropItem {
+                    item_id: "zk_prover".to_string(),
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, start);
+
+        // Using a second copy extends the effect, so there is none left to drop
+        let used = outcome
+            .replies
+            .iter()
+            .filter(|(_, message)| matches!(message, ServerMessage::ItemUsed { .. }))
+            .count();
+        assert_eq!(used, 2);
+        assert!(matches!(
+            outcome.replies.last(),
+            Some((_, ServerMessage::Error { message, .. })) if message.contains("not carrying")
+        ));
+        let player = game_state.get_player(&alice).unwrap();
+        assert_eq!(game_state.item_stats(&player).trust, 20.0);
+        // Sorted by item ID, after the starter item
+        let inventory = game_state.get_inventory(&alice, start + 10);
+        assert_eq!(inventory[1].item.id, "zk_prover");
+        assert_eq!(
+            (inventory[1].quantity, inventory[1].active_seconds),
+            (0, 20)
+        );
+
+        assert!(run_tick(&game_state, start + 29).replies.is_empty());
+        let outcome = run_tick(&game_state, start + 30);
+        assert!(outcome.state_changed);
+        assert!(matches!(
+            outcome.replies.first(),
+            Some((_, ServerMessage::Event { message, .. })) if message.contains("wore off")
+        ));
+        let player = game_state.get_player(&alice).unwrap();
+        assert_eq!(game_state.item_stats(&player), ItemStats::default());
+        // The starter item is still carried
+        assert_eq!(game_state.get_inventory(&alice, start + 30).len(), 1);
+    }
+
+    #[test]
+    fn test_active_items_change_damage() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        let start = now();
+        assert!(game_state.add_item(&alice, "key_fragmenter", 1));
+        assert!(game_state
+            .use_item(&alice, "key_fragmenter", start)
+            .is_some());
+
+        let base_damage = game_state.get_config().base_damage;
+        let mut rng = StdRng::seed_from_u64(7);
+        let boosted = game_state.apply_damage(&bob, &alice, base_damage, start, &mut rng);
+        // 45 attack adds 11 damage
+        assert_eq!(boosted.damage, base_damage + 11);
+
+        assert!(game_state.add_item(&bob, "quantum_shield", 1));
+        assert!(game_state.use_item(&bob, "quantum_shield", start).is_some());
+        let shielded = game_state.apply_damage(&bob, &alice, base_damage, start, &mut rng);
+        // 35 defense stops 8 of it
+        assert_eq!(shielded.damage, base_damage + 11 - 8);
+        assert!(!shielded.defeated);
+    }
+
+    #[test]
+    fn test_inventory_is_limited() {
+        let game_state = world(GameConfig {
+            max_inventory_items: 3,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+
+        // The starter item takes one of the three slots
+        assert!(game_state.add_item(&alice, "quantum_shield", 2));
+        assert!(!game_state.add_item(&alice, "quantum_shield", 1));
+        assert!(!game_state.add_item(&alice, "no_such_item", 1));
+        assert!(game_state.drop_item(&alice, "quantum_shield").is_some());
+        assert!(game_state.add_item(&alice, "satoshi_key", 1));
+    }
+
+    #[test]
+    fn test_equipped_items_change_damage() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        assert!(game_state.add_item(&alice, "packet_sniffer", 1));
+        assert!(game_state.add_item(&alice, "key_fragmenter", 1));
+        assert!(game_state.add_item(&bob, "quantum_shield", 1));
+
+        let simulation = game_state.simulation();
+        let equip = |item_id: &str| PlayerIntent::Equip {
+            item_id: item_id.to_string(),
+        };
+        simulation.enqueue(&alice, equip("packet_sniffer")).unwrap();
+        // Equipping another attack tool swaps the first one back into the inventory
+        simulation.enqueue(&alice, equip("key_fragmenter")).unwrap();
+        simulation.enqueue(&bob, equip("quantum_shield")).unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.state_changed);
+        assert!(outcome.replies.iter().any(|(id, message)| *id == bob
+            && matches!(message, ServerMessage::Equipment { slots, stats, .. }
+                if slots.contains_key(&EquipmentSlot::DefenseTool) && stats.defense == 35.0)));
+
+        let equipment = game_state.get_equipment(&alice);
+        assert_eq!(equipment[&EquipmentSlot::AttackTool].id, "key_fragmenter");
+        let carried = game_state.get_player(&alice).unwrap().inventory;
+        assert_eq!(carried.get("packet_sniffer"), Some(&1));
+        assert_eq!(carried.get("key_fragmenter"), None);
+
+        // 45 attack adds 11 damage and 35 defense stops 8
+        let base_damage = game_state.get_config().base_damage;
+        simulation
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: game_state.get_player(&bob).unwrap().display_id,
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, now());
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().health,
+            game_state.get_config().initial_player_health - (base_damage + 11 - 8)
+        );
+
+        simulation
+            .enqueue(
+                &bob,
+                PlayerIntent::Unequip {
+                    slot: EquipmentSlot::DefenseTool,
+                },
+            )
+            .unwrap();
+        simulation
+            .enqueue(
+                &bob,
+                PlayerIntent::Unequip {
+                    slot: EquipmentSlot::DefenseTool,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(matches!(
+            outcome.replies.last(),
+            Some((_, ServerMessage::Error { message, .. })) if message.contains("Nothing is equipped")
+        ));
+        assert!(game_state.get_equipment(&bob).is_empty());
+        assert_eq!(game_state.get_item_stats(&bob), ItemStats::default());
+    }
+
+    #[test]
+    fn test_loot_drops_and_pickups() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            initial_player_health: 5,
+            simulation_tick_rate_hz: 10,
+            loot_spawn_interval_seconds: 1,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        let simulation = game_state.simulation();
+
+        // Defeated players leave an item where they fell
+        simulation
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: game_state.get_player(&bob).unwrap().display_id,
+                },
+            )
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert_eq!(outcome.loot_changed_at, vec![Position::new(20.0, 0.0)]);
+        let drops = game_state.get_visible_loot(&alice);
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].position, Position::new(20.0, 0.0));
+
+        // Picking it up needs the player to be within reach
+        simulation.enqueue(&alice, PlayerIntent::PickUp).unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(matches!(
+            outcome.replies.as_slice(),
+            [(_, ServerMessage::Error { message, .. })] if message.contains("nothing to pick up")
+        ));
+        assert!(outcome.loot_changed_at.is_empty());
+
+        assert!(game_state.update_player_position(&alice, Position::new(15.0, 0.0)));
+        simulation.enqueue(&alice, PlayerIntent::PickUp).unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert_eq!(outcome.loot_changed_at, vec![Position::new(20.0, 0.0)]);
+        assert!(outcome.replies.iter().any(|(_, message)| matches!(
+            message,
+            ServerMessage::Event { message, .. } if message.starts_with("You picked up")
+        )));
+        assert_eq!(game_state.ground_loot().drop_count(), 0);
+        let carried = game_state.get_player(&alice).unwrap().inventory;
+        assert!(carried.contains_key(&drops[0].item_id));
+
+        // Another item turns up somewhere once per spawn interval
+        for _ in 0..6 {
+            assert!(run_tick(&game_state, now()).loot_changed_at.is_empty());
+        }
+        let outcome = run_tick(&game_state, now());
+        assert_eq!(outcome.loot_changed_at.len(), 1);
+        assert!(game_state
+            .get_config()
+            .is_position_valid(outcome.loot_changed_at[0].x, outcome.loot_changed_at[0].y));
+        assert_eq!(game_state.ground_loot().drop_count(), 1);
+    }
+
+    #[test]
+    fn test_downed_players_cannot_pick_up_what_they_dropped() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            initial_player_health: 5,
+            loot_spawn_interval_seconds: 0,
+            respawn_seconds: 5,
+            death_drops_item: true,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        let simulation = game_state.simulation();
+        let defeated_at = now();
+
+        simulation
+            .enqueue(
+                &alice,
+                PlayerIntent::Attack {
+                    target_display_id: game_state.get_player(&bob).unwrap().display_id,
+                },
+            )
+            .unwrap();
+        run_tick(&game_state, defeated_at);
+        assert!(game_state.get_player(&bob).unwrap().inventory.is_empty());
+        let drops = game_state.ground_loot().drop_count();
+        assert_eq!(drops, 2);
+
+        // Everything but waiting is refused until the player respawns
+        for intent in [
+            PlayerIntent::PickUp,
+            PlayerIntent::UseItem {
+                item_id: Faction::Nyms.starter_item_id().to_string(),
+            },
+        ] {
+            simulation.enqueue(&bob, intent).unwrap();
+        }
+        let outcome = run_tick(&game_state, defeated_at);
+        let refused = outcome
+            .replies
+            .iter()
+            .filter(|(id, message)| *id == bob
+                && matches!(message, ServerMessage::Error { message, .. } if message.starts_with("You are down")))
+            .count();
+        assert_eq!(refused, 2);
+        assert!(game_state.get_player(&bob).unwrap().inventory.is_empty());
+        assert_eq!(game_state.ground_loot().drop_count(), drops);
+
+        run_tick(&game_state, defeated_at + 5);
+        assert!(!game_state.has_effect(&bob, StatusEffectKind::Downed));
+        assert!(game_state.update_player_position(&bob, Position::new(20.0, 0.0)));
+        simulation.enqueue(&bob, PlayerIntent::PickUp).unwrap();
+        run_tick(&game_state, defeated_at + 5);
+        assert_eq!(game_state.ground_loot().drop_count(), drops - 1);
+    }
+
+    #[test]
+    fn test_defeats_change_reputation() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            initial_player_health: 10,
+            reputation_emote_threshold: 15,
+            reputation_item_threshold: 15,
+            // The Hegemony player is back on their feet for the second defeat
+            respawn_seconds: 0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let corp = game_state.add_player(
+            "corp".to_string(),
+            Faction::CorporateHegemony,
+            SenderTag::random(),
+        );
+        assert!(game_state.update_player_position(&corp, Position::new(20.0, 0.0)));
+        let attack = || PlayerIntent::Attack {
+            target_display_id: game_state.get_player(&corp).unwrap().display_id,
+        };
+
+        // Defeating a member of the Hegemony angers it and pleases its rivals, the Nyms
+        game_state.simulation().enqueue(&alice, attack()).unwrap();
+        let start = now();
+        let outcome = run_tick(&game_state, start);
+        let player = game_state.get_player(&alice).unwrap();
+        assert!(player.reputation[&Faction::CorporateHegemony] < 0);
+        assert_eq!(
+            game_state.get_reputation(&alice, &Faction::Nyms),
+            game_state.get_config().reputation_item_threshold
+        );
+        let alice_events: Vec<&str> = outcome
+            .replies
+            .iter()
+            .filter_map(|(id, message)| match message {
+                ServerMessage::Event { message, .. } if *id == alice => Some(message.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(alice_events
+            .iter()
+            .any(|event| event.contains("/emote cloak")));
+        assert!(alice_events.iter().any(|event| event.contains("signature")));
+        assert!(outcome.replies.iter().any(|(id, message)| *id == alice
+            && matches!(message, ServerMessage::ReputationUpdate { standings, .. }
+                if standings.iter().any(|standing| standing.faction == Faction::Nyms && standing.item_unlocked))));
+
+        // The signature item joins the starter copy, and only the first time
+        assert_eq!(player.inventory[Faction::Nyms.starter_item_id()], 2);
+        // Defeated players respawn elsewhere
+        assert!(game_state.update_player_position(&corp, Position::new(20.0, 0.0)));
+        game_state.simulation().enqueue(&alice, attack()).unwrap();
+        run_tick(
+            &game_state,
+            start + game_state.get_config().attack_cooldown_seconds,
+        );
+        let player = game_state.get_player(&alice).unwrap();
+        assert!(
+            player.reputation[&Faction::Nyms] > game_state.get_config().reputation_item_threshold
+        );
+        assert_eq!(player.inventory[Faction::Nyms.starter_item_id()], 2);
+    }
+
+    #[test]
+    fn test_trades_swap_escrowed_assets() {
+        let game_state = world(GameConfig {
+            crit_chance: 0.0,
+            ..GameConfig::default()
+        });
+        let alice = spawn(&game_state, "alice", 0.0, 0.0);
+        let bob = spawn(&game_state, "bob", 20.0, 0.0);
+        let alice_display = game_state.get_player(&alice).unwrap().display_id;
+        let bob_display = game_state.get_player(&bob).unwrap().display_id;
+        let simulation = game_state.simulation();
+
+        // Credits are earned alongside experience
+        let attack = PlayerIntent::Attack {
+            target_display_id: bob_display.clone(),
+        };
+        simulation.enqueue(&alice, attack).unwrap();
+        run_tick(&game_state, now());
+        let credits = game_state.get_credits(&alice);
+        assert!(credits > 0);
+        assert!(game_state.add_item(&bob, "quantum_shield", 1));
+
+        // The trade opens once both sides asked for it
+        let request = |target: &str| PlayerIntent::TradeRequest {
+            target_display_id: target.to_string(),
+        };
+        simulation.enqueue(&alice, request(&bob_display)).unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.replies.iter().any(|(player_id, message)| player_id == &bob
+            && matches!(message, ServerMessage::Event { message, .. } if message.contains("wants to trade"))));
+        simulation.enqueue(&bob, request(&alice_display)).unwrap();
+        let outcome = run_tick(&game_state, now());
+        let updates = outcome
+            .replies
+            .iter()
+            .filter(|(_, message)| {
+                matches!(message, ServerMessage::TradeUpdate { revision: 0, .. })
+            })
+            .count();
+        assert_eq!(updates, 2);
+
+        // Offered assets leave the inventory, and nobody can offer what they do not have
+        let offer = |target: &str, credits: u32, item_id: &str| PlayerIntent::TradeOffer {
+            target_display_id: target.to_string(),
+            offer: TradeAssets {
+                credits,
+                items: BTreeMap::from([(item_id.to_string(), 1)]),
+            },
+        };
+        simulation
+            .enqueue(&alice, offer(&bob_display, credits, "mixnet_relay"))
+            .unwrap();
+        simulation
+            .enqueue(&bob, offer(&alice_display, 0, "quantum_shield"))
+            .unwrap();
+        simulation
+            .enqueue(&alice, offer(&bob_display, credits + 1, "mixnet_relay"))
+            .unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.replies.iter().any(|(player_id, message)| player_id == &alice
+            && matches!(message, ServerMessage::Error { message, .. } if message.contains("do not have everything"))));
+        let player = game_state.get_player(&alice).unwrap();
+        assert_eq!(player.credits, 0);
+        assert_eq!(player.escrow.credits, credits);
+        assert!(!player.inventory.contains_key("mixnet_relay"));
+        assert_eq!(
+            game_state
+                .use_item(&alice, "mixnet_relay", now())
+                .map(|i| i.id),
+            None
+        );
+
+        // Accepting an outdated revision does nothing; both accepting the current one swaps
+        let accept = |target: &str, revision: u32| PlayerIntent::TradeAccept {
+            target_display_id: target.to_string(),
+            revision,
+        };
+        simulation.enqueue(&alice, accept(&bob_display, 1)).unwrap();
+        let outcome = run_tick(&game_state, now());
+        assert!(matches!(
+            outcome.replies.as_slice(),
+            [(_, ServerMessage::Error { message, .. })] if message.contains("offers changed")
+        ));
+        simulation.enqueue(&alice, accept(&bob_display, 2)).unwrap();
+        simulation.enqueue(&bob, accept(&alice_display, 2)).unwrap();
+        let outcome = run_tick(&game_state, now());
+        let completed = outcome
+            .replies
+            .iter()
+            .filter(|(_, message)| {
+                matches!(
+                    message,
+                    ServerMessage::TradeClosed {
+                        completed: true,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(completed, 2);
+        let (alice_player, bob_player) = (
+            game_state.get_player(&alice).unwrap(),
+            game_state.get_player(&bob).unwrap(),
+        );
+        assert_eq!(alice_player.inventory.get("quantum_shield"), Some(&1));
+        assert_eq!(bob_player.inventory.get("mixnet_relay"), Some(&2));
+        assert_eq!(bob_player.credits, credits);
+        assert!(alice_player.escrow.is_empty() && bob_player.escrow.is_empty());
+        assert_eq!(game_state.trades().trade_count(), 0);
+
+        // When one side leaves, the other gets back what they put up
+        simulation.enqueue(&alice, request(&bob_display)).unwrap();
+        simulation.enqueue(&bob, request(&alice_display)).unwrap();
+        simulation
+            .enqueue(&bob, offer(&alice_display, 0, "mixnet_relay"))
+            .unwrap();
+        run_tick(&game_state, now());
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().inventory["mixnet_relay"],
+            1
+        );
+        let alice_tag = game_state.get_connection_tag(&alice).unwrap();
+        assert!(game_state.remove_player(&alice_tag).is_some());
+        let outcome = run_tick(&game_state, now());
+        assert!(outcome.replies.iter().any(|(player_id, message)| player_id == &bob
+            && matches!(message, ServerMessage::TradeClosed { completed: false, reason, .. } if reason.contains("left the game"))));
+        assert_eq!(
+            game_state.get_player(&bob).unwrap().inventory["mixnet_relay"],
+            2
+        );
+        assert_eq!(game_state.trades().trade_count(), 0);
+    }
+}
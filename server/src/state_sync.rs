@@ -0,0 +1,312 @@
+//! Snapshot and delta encoding of the player list sent to each connection
+//!
+//! Sending every player to every connection after each action costs O(N²)
+//! mixnet traffic. Instead a connection gets a full `GameState` snapshot (a
+//! keyframe) when it has no acknowledged baseline and on the periodic keyframe
+//! interval, and a `GameStateDelta` against the latest snapshot it acknowledged
+//! otherwise. Deltas never build on unacknowledged state, so one that is lost or
+//! reordered on the mixnet does not break the ones after it.
+//!
+//! Each connection's player list only holds the players within its view, so
+//! the table also remembers who each connection could see last time, to tell
+//! it when others come into or go out of view.
+
+use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::debug;
+
+use nymquest_protocol::game_protocol::{PlayerView, ServerMessage};
+use nymquest_protocol::transport::SenderTag;
+
+/// Player list as sent to one connection
+pub type PlayerSnapshot = Arc<HashMap<String, PlayerView>>;
+
+/// How many sent but unacknowledged states to remember per connection
+const MAX_UNACKED_STATES: usize = 32;
+
+#[derive(Default)]
+struct Baseline {
+    /// Latest state the client acknowledged, by sequence number
+    acked: Option<(u64, PlayerSnapshot)>,
+    /// States sent since, oldest first
+    unacked: VecDeque<(u64, PlayerSnapshot)>,
+    /// Display IDs of the other players in the last update sent
+    visible: Option<HashSet<String>>,
+}
+
+/// Per-connection baselines for state updates
+pub struct StateSync {
+    baselines: Mutex<HashMap<SenderTag, Baseline>>,
+}
+
+impl Default for StateSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateSync {
+    /// Create an empty baseline table
+    pub fn new() -> Self {
+        Self {
+            baselines: Mutex::new(HashMap::with_capacity(256)),
+        }
+    }
+
+    /// Build the next state update for the connection `tag`
+    ///
+    /// Returns a snapshot when `keyframe` is set or the connection has no
+    /// acknowledged baseline, a delta otherwise, and `None` when nothing changed
+    /// since the baseline. `next_seq` is only called for messages that will be sent.
+    pub fn next_update<F>(
+        &self,
+        tag: &SenderTag,
+        players: &PlayerSnapshot,
+        keyframe: bool,
+        next_seq: F,
+    ) -> Result<Option<ServerMessage>>
+    where
+        F: FnOnce() -> u64,
+    {
+        let mut baselines = self.lock()?;
+        let baseline = baselines.entry(*tag).or_default();
+
+        let message = match (&baseline.acked, keyframe) {
+            (Some((baseline_seq, acked)), false) => {
+                let mut updated: Vec<PlayerView> = players
+                    .values()
+                    .filter(|player| acked.get(&player.display_id) != Some(*player))
+                    .cloned()
+                    .collect();
+                let mut removed: Vec<String> = acked
+                    .keys()
+                    .filter(|display_id| !players.contains_key(*display_id))
+                    .cloned()
+                    .collect();
+                if updated.is_empty() && removed.is_empty() {
+                    return Ok(None);
+                }
+                updated.sort_by(|a, b| a.display_id.cmp(&b.display_id));
+                removed.sort();
+
+                ServerMessage::GameStateDelta {
+                    baseline_seq: *baseline_seq,
+                    updated,
+                    removed,
+                    seq_num: next_seq(),
+                }
+            }
+            _ => ServerMessage::GameState {
+                players: players.as_ref().clone(),
+                seq_num: next_seq(),
+            },
+        };
+
+        baseline
+            .unacked
+            .push_back((message.get_seq_num(), Arc::clone(players)));
+        while baseline.unacked.len() > MAX_UNACKED_STATES {
+            baseline.unacked.pop_front();
+        }
+
+        Ok(Some(message))
+    }
+
+    /// Record that the connection `tag` acknowledged the state update `seq_num`
+    ///
+    /// Returns false if no such update is waiting for acknowledgement, e.g. for
+    /// a duplicate acknowledgement or one older than the current baseline.
+    pub fn acknowledge(&self, tag: &SenderTag, seq_num: u64) -> Result<bool> {
+        let mut baselines = self.lock()?;
+        let Some(baseline) = baselines.get_mut(tag) else {
+            return Ok(false);
+        };
+        let Some(index) = baseline.unacked.iter().position(|(seq, _)| *seq == seq_num) else {
+            return Ok(false);
+        };
+
+        // Updates sent before the acknowledged one can no longer become the baseline
+        let acked = baseline.unacked.drain(..=index).next_back();
+        baseline.acked = acked;
+        debug!("Connection {} acknowledged state {}", tag, seq_num);
+        Ok(true)
+    }
+
+    /// Record which other players the connection `tag` can see now
+    ///
+    /// Returns the display IDs that came into view and those that went out of
+    /// view since the last call, sorted. The first call only records the set,
+    /// since the first snapshot introduces everyone in view.
+    pub fn update_visibility(
+        &self,
+        tag: &SenderTag,
+        visible: HashSet<String>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let mut baselines = self.lock()?;
+        let baseline = baselines.entry(*tag).or_default();
+
+        let (mut entered, mut left) = match &baseline.visible {
+            Some(previous) => (
+                visible.difference(previous).cloned().collect::<Vec<_>>(),
+                previous.difference(&visible).cloned().collect::<Vec<_>>(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        baseline.visible = Some(visible);
+
+        entered.sort();
+        left.sort();
+        Ok((entered, left))
+    }
+
+    /// Forget the baseline of a connection that has gone away
+    pub fn forget(&self, tag: &SenderTag) {
+        if let Ok(mut baselines) = self.lock() {
+            baselines.remove(tag);
+        }
+    }
+
+    /// Number of connections with a baseline or updates in flight
+    pub fn connection_count(&self) -> usize {
+        self.lock().map(|baselines| baselines.len()).unwrap_or(0)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<SenderTag, Baseline>>> {
+        self.baselines
+            .lock()
+            .map_err(|_| anyhow!("State baseline lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nymquest_protocol::game_protocol::Position;
+    use nymquest_protocol::world_lore::Faction;
+
+    fn player(display_id: &str, x: f32) -> PlayerView {
+        PlayerView {
+            display_id: display_id.to_string(),
+            name: display_id.to_lowercase(),
+            position: Position::new(x, 0.0),
+            health: 100,
+            level: 1,
+            faction: Faction::Nyms,
+            effects: Default::default(),
+        }
+    }
+
+    fn snapshot(players: &[PlayerView]) -> PlayerSnapshot {
+        Arc::new(
+            players
+                .iter()
+                .map(|player| (player.display_id.clone(), player.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_delta_after_acknowledged_snapshot() {
+        let sync = StateSync::new();
+        let tag = SenderTag::random();
+        let first = snapshot(&[player("Alpha", 0.0), player("Beta", 0.0)]);
+
+        // Without an acknowledged baseline every update is a snapshot
+        let update = sync.next_update(&tag, &first, false, || 1).unwrap();
+        assert!(matches!(
+            update,
+            Some(ServerMessage::GameState { seq_num: 1, .. })
+        ));
+        let update = sync.next_update(&tag, &first, false, || 2).unwrap();
+        assert!(matches!(
+            update,
+            Some(ServerMessage::GameState { seq_num: 2, .. })
+        ));
+        assert!(sync.acknowledge(&tag, 2).unwrap());
+        // The older snapshot was superseded
+        assert!(!sync.acknowledge(&tag, 1).unwrap());
+
+        // Nothing changed, nothing to send
+        assert!(sync
+            .next_update(&tag, &first, false, || 3)
+            .unwrap()
+            .is_none());
+
+        let second = snapshot(&[player("Alpha", 1.0), player("Gamma", 0.0)]);
+        match sync.next_update(&tag, &second, false, || 4).unwrap() {
+            Some(ServerMessage::GameStateDelta {
+                baseline_seq,
+                updated,
+                removed,
+                seq_num,
+            }) => {
+                assert_eq!(baseline_seq, 2);
+                assert_eq!(updated, vec![player("Alpha", 1.0), player("Gamma", 0.0)]);
+                assert_eq!(removed, vec!["Beta".to_string()]);
+                assert_eq!(seq_num, 4);
+            }
+            other => panic!("Unexpected update {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unacknowledged_deltas_repeat_changes() {
+        let sync = StateSync::new();
+        let tag = SenderTag::random();
+        sync.next_update(&tag, &snapshot(&[player("Alpha", 0.0)]), false, || 1)
+            .unwrap();
+        sync.acknowledge(&tag, 1).unwrap();
+
+        let moved = snapshot(&[player("Alpha", 1.0)]);
+        sync.next_update(&tag, &moved, false, || 2).unwrap();
+        // The first delta was not acknowledged, so the next one still carries its change
+        let update = sync.next_update(&tag, &moved, false, || 3).unwrap();
+        assert!(
+            matches!(update, Some(ServerMessage::GameStateDelta { baseline_seq: 1, ref updated, .. })
+            if updated.len() == 1)
+        );
+
+        // Keyframes are full snapshots regardless of the baseline
+        let update = sync.next_update(&tag, &moved, true, || 4).unwrap();
+        assert!(matches!(update, Some(ServerMessage::GameState { .. })));
+    }
+
+    #[test]
+    fn test_visibility_changes() {
+        let sync = StateSync::new();
+        let tag = SenderTag::random();
+        let visible = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+
+        // The first snapshot introduces everyone, so there is nothing to announce
+        let (entered, left) = sync.update_visibility(&tag, visible(&["Alpha"])).unwrap();
+        assert!(entered.is_empty() && left.is_empty());
+
+        let (entered, left) = sync
+            .update_visibility(&tag, visible(&["Beta", "Gamma"]))
+            .unwrap();
+        assert_eq!(entered, vec!["Beta".to_string(), "Gamma".to_string()]);
+        assert_eq!(left, vec!["Alpha".to_string()]);
+
+        let (entered, left) = sync
+            .update_visibility(&tag, visible(&["Beta", "Gamma"]))
+            .unwrap();
+        assert!(entered.is_empty() && left.is_empty());
+    }
+
+    #[test]
+    fn test_forgotten_connections_start_over() {
+        let sync = StateSync::new();
+        let tag = SenderTag::random();
+        let players = snapshot(&[player("Alpha", 0.0)]);
+        sync.next_update(&tag, &players, false, || 1).unwrap();
+        sync.acknowledge(&tag, 1).unwrap();
+        assert_eq!(sync.connection_count(), 1);
+
+        sync.forget(&tag);
+        assert_eq!(sync.connection_count(), 0);
+        assert!(!sync.acknowledge(&tag, 1).unwrap());
+        let update = sync.next_update(&tag, &players, false, || 2).unwrap();
+        assert!(matches!(update, Some(ServerMessage::GameState { .. })));
+    }
+}
@@ -0,0 +1,171 @@
+//! Time-limited status effects on players
+//!
+//! Effects are kept on the player with how many stacks they have and when they
+//! wear off, and everyone who can see the player sees them. Reapplying an
+//! effect follows its `Stacking` rule: shields and stealth start over, a stun
+//! only lasts longer if the new one would, and corruption gains a stack, up to
+//! `MAX_CORRUPTION_STACKS`, and starts over. Shields cut the damage players
+//! take to `SHIELD_DAMAGE_TAKEN`, stuns stop them moving, attacking and using
+//! abilities, stealth hides them from players further away than
+//! `STEALTH_VIEW_DISTANCE`, and once per simulated second corruption deals
+//! `CORRUPTION_DAMAGE_PER_STACK` for every stack. Defeated players are downed
+//! for `respawn_seconds`, which clears every other effect, and can do nothing
+//! until it wears off and they respawn. The simulation removes effects on the
+//! first tick after they wear off. Effects are not persisted.
+
+use std::collections::BTreeMap;
+
+use nymquest_protocol::game_protocol::{StatusEffect, StatusEffectKind};
+
+/// Share of damage still taken while shielded
+pub const SHIELD_DAMAGE_TAKEN: f32 = 0.5;
+/// Distance from which other players still see a player in stealth
+pub const STEALTH_VIEW_DISTANCE: f32 = 5.0;
+/// Damage per second dealt by each stack of corruption
+pub const CORRUPTION_DAMAGE_PER_STACK: u32 = 2;
+/// Most stacks of corruption a player can carry
+pub const MAX_CORRUPTION_STACKS: u32 = 3;
+
+/// What happens when an effect a player already has is applied again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// The effect starts over
+    Refresh,
+    /// The effect lasts until the later of the two
+    Longest,
+    /// The effect gains a stack, up to `max`, and starts over
+    Stack { max: u32 },
+}
+
+/// How an effect stacks
+pub fn stacking(kind: StatusEffectKind) -> Stacking {
+    match kind {
+        StatusEffectKind::Shield | StatusEffectKind::Stealth | StatusEffectKind::Downed => {
+            Stacking::Refresh
+        }
+        StatusEffectKind::Stun => Stacking::Longest,
+        StatusEffectKind::Corruption => Stacking::Stack {
+            max: MAX_CORRUPTION_STACKS,
+        },
+    }
+}
+
+/// Apply `kind` until `until` on top of `effects`; returns the effect as it now is
+pub fn add(
+    effects: &mut BTreeMap<StatusEffectKind, StatusEffect>,
+    kind: StatusEffectKind,
+    until: u64,
+) -> StatusEffect {
+    let effect = effects.entry(kind).or_insert(StatusEffect {
+        stacks: 0,
+        expires_at: until,
+    });
+    match stacking(kind) {
+        Stacking::Refresh => {
+            effect.stacks = 1;
+            effect.expires_at = until;
+        }
+        Stacking::Longest => {
+            effect.stacks = 1;
+            effect.expires_at = effect.expires_at.max(until);
+        }
+        Stacking::Stack { max } => {
+            effect.stacks = (effect.stacks + 1).min(max);
+            effect.expires_at = until;
+        }
+    }
+    *effect
+}
+
+/// Remove the effects that wore off by `now`; returns which they were
+pub fn expire(
+    effects: &mut BTreeMap<StatusEffectKind, StatusEffect>,
+    now: u64,
+) -> Vec<StatusEffectKind> {
+    let mut expired = Vec::new();
+    effects.retain(|kind, effect| {
+        let active = effect.expires_at > now;
+        if !active {
+            expired.push(*kind);
+        }
+        active
+    });
+    expired
+}
+
+/// Share of damage a player under `effects` still takes
+pub fn damage_taken(effects: &BTreeMap<StatusEffectKind, StatusEffect>) -> f32 {
+    if effects.contains_key(&StatusEffectKind::Shield) {
+        SHIELD_DAMAGE_TAKEN
+    } else {
+        1.0
+    }
+}
+
+/// Whether a player under `effects` is hidden from someone `distance` away
+///
+/// Hidden players cannot be seen, attacked or targeted by abilities.
+pub fn is_hidden(effects: &BTreeMap<StatusEffectKind, StatusEffect>, distance: f32) -> bool {
+    effects.contains_key(&StatusEffectKind::Stealth) && distance > STEALTH_VIEW_DISTANCE
+}
+
+/// Damage a player under `effects` takes every second
+pub fn damage_per_second(effects: &BTreeMap<StatusEffectKind, StatusEffect>) -> u32 {
+    effects
+        .get(&StatusEffectKind::Corruption)
+        .map_or(0, |effect| effect.stacks * CORRUPTION_DAMAGE_PER_STACK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_stack_and_wear_off() {
+        let mut effects = BTreeMap::new();
+        assert_eq!(damage_taken(&effects), 1.0);
+        assert_eq!(damage_per_second(&effects), 0);
+
+        // Shields start over, stuns keep the longer duration
+        add(&mut effects, StatusEffectKind::Shield, 110);
+        assert_eq!(
+            add(&mut effects, StatusEffectKind::Shield, 105).expires_at,
+            105
+        );
+        add(&mut effects, StatusEffectKind::Stun, 108);
+        assert_eq!(
+            add(&mut effects, StatusEffectKind::Stun, 104).expires_at,
+            108
+        );
+        assert_eq!(damage_taken(&effects), SHIELD_DAMAGE_TAKEN);
+        assert!(!is_hidden(&effects, 50.0));
+        add(&mut effects, StatusEffectKind::Stealth, 105);
+        assert!(!is_hidden(&effects, STEALTH_VIEW_DISTANCE));
+        assert!(is_hidden(&effects, STEALTH_VIEW_DISTANCE + 1.0));
+
+        // Corruption stacks up to its cap and starts over
+        for until in 100..106 {
+            add(&mut effects, StatusEffectKind::Corruption, until);
+        }
+        let corruption = effects[&StatusEffectKind::Corruption];
+        assert_eq!(corruption.stacks, MAX_CORRUPTION_STACKS);
+        assert_eq!(corruption.expires_at, 105);
+        assert_eq!(
+            damage_per_second(&effects),
+            MAX_CORRUPTION_STACKS * CORRUPTION_DAMAGE_PER_STACK
+        );
+
+        assert!(expire(&mut effects, 104).is_empty());
+        assert_eq!(
+            expire(&mut effects, 105),
+            vec![
+                StatusEffectKind::Shield,
+                StatusEffectKind::Stealth,
+                StatusEffectKind::Corruption
+            ]
+        );
+        assert_eq!(damage_taken(&effects), 1.0);
+        assert_eq!(damage_per_second(&effects), 0);
+        assert_eq!(expire(&mut effects, 200), vec![StatusEffectKind::Stun]);
+    }
+}
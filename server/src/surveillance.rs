@@ -0,0 +1,112 @@
+//! Surveillance cameras that expose players to the Corporate Hegemony
+//!
+//! Every `surveillance_check_seconds` each player outside the Hegemony is
+//! caught on camera with a chance of `surveillance_exposure_chance` times the
+//! surveillance risk at their position, as given by
+//! `WorldBoundaries::calculate_surveillance_risk`. Privacy from worn and
+//! active items shields them from it, and their faction's surveillance
+//! exposure, lowered inside control points it owns, scales it. A player who
+//! is caught stays exposed for `surveillance_exposure_seconds`: every
+//! Hegemony player is told their display ID, name, faction and whereabouts,
+//! and Hegemony players in the same region can see them wherever they are.
+//! Exposures are not persisted.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Players caught on camera and when their exposure ends
+pub struct Surveillance {
+    exposed_until: Mutex<HashMap<String, u64>>,
+}
+
+impl Default for Surveillance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Surveillance {
+    /// Create a surveillance network that has caught nobody yet
+    pub fn new() -> Self {
+        Self {
+            exposed_until: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Expose `player_id` until `until`; returns false if they already were
+    pub fn expose(&self, player_id: &str, until: u64) -> Result<bool> {
+        let mut exposed = self.lock()?;
+        let newly_exposed = !exposed.contains_key(player_id);
+        exposed.insert(player_id.to_string(), until);
+        Ok(newly_exposed)
+    }
+
+    /// Whether `player_id` is exposed
+    pub fn is_exposed(&self, player_id: &str) -> bool {
+        self.lock()
+            .map(|exposed| exposed.contains_key(player_id))
+            .unwrap_or(false)
+    }
+
+    /// Player IDs of everyone exposed
+    pub fn exposed_players(&self) -> Result<Vec<String>> {
+        Ok(self.lock()?.keys().cloned().collect())
+    }
+
+    /// End the exposures that ran out by `now`, and those of players for whom `exists` is false
+    pub fn expire<F>(&self, now: u64, exists: F) -> Result<Vec<String>>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut exposed = self.lock()?;
+        let expired: Vec<String> = exposed
+            .iter()
+            .filter(|(player_id, until)| **until <= now || !exists(player_id))
+            .map(|(player_id, _)| player_id.clone())
+            .collect();
+        for player_id in &expired {
+            exposed.remove(player_id);
+        }
+        Ok(expired)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, u64>>> {
+        self.exposed_until
+            .lock()
+            .map_err(|_| anyhow!("Surveillance lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exposures_run_out() {
+        let surveillance = Surveillance::new();
+        assert!(surveillance.expose("a", 130).unwrap());
+        assert!(!surveillance.expose("a", 140).unwrap());
+        assert!(surveillance.expose("b", 130).unwrap());
+        assert!(surveillance.is_exposed("a"));
+
+        // Being caught again extends the exposure, and players who left are forgotten
+        assert_eq!(
+            surveillance
+                .expire(130, |player_id| player_id != "c")
+                .unwrap(),
+            vec!["b".to_string()]
+        );
+        assert_eq!(
+            surveillance.exposed_players().unwrap(),
+            vec!["a".to_string()]
+        );
+        assert_eq!(
+            surveillance
+                .expire(135, |player_id| player_id != "a")
+                .unwrap(),
+            vec!["a".to_string()]
+        );
+        assert!(!surveillance.is_exposed("a"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Control points and the factions that hold them
+//!
+//! Every region has five control points at fixed spots: one in its middle
+//! and one in the middle of each of its quarters. Players within `control_point_radius` of
+//! a point occupy it. Once per simulated second, a faction that occupies a
+//! point on its own and does not hold it yet makes capture progress, and after
+//! `territory_capture_seconds` the point is theirs. Progress pauses while
+//! another faction is inside too and is lost once the capturing faction
+//! leaves. Independents answer to nobody, so they contest points but never
+//! capture them. Members of the owner faction standing inside the point earn
+//! more experience and draw less surveillance. Ownership is not persisted.
+
+use anyhow::{anyhow, Result};
+use std::collections::BTreeSet;
+use std::sync::{Mutex, MutexGuard};
+use tracing::debug;
+
+use nymquest_protocol::game_protocol::{ControlPoint, Position};
+use nymquest_protocol::world_lore::Faction;
+
+use crate::regions::Regions;
+
+/// Names of the control points and where they sit, as fractions of the way
+/// from the middle of the map to its edges
+const CONTROL_POINTS: [(&str, f32, f32); 5] = [
+    ("Mixnet Core", 0.0, 0.0),
+    ("Relay Spire", 0.5, -0.5),
+    ("Data Vault", -0.5, -0.5),
+    ("Signal Yard", -0.5, 0.5),
+    ("Cipher Gate", 0.5, 0.5),
+];
+
+/// What happened to a control point during an update
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerritoryChange {
+    /// A faction started taking the point over
+    CaptureStarted {
+        point: String,
+        region: String,
+        faction: Faction,
+    },
+    /// A faction took the point from its previous owner, if any
+    Captured {
+        point: String,
+        region: String,
+        faction: Faction,
+        previous: Option<Faction>,
+    },
+}
+
+/// Every control point in the world and who holds it
+pub struct Territory {
+    points: Mutex<Vec<ControlPoint>>,
+}
+
+impl Territory {
+    /// Lay out the control points of every region, each claiming the area within `radius`;
+    /// there are none if the radius is 0
+    pub fn new(regions: &Regions, radius: f32) -> Self {
+        let mut points = Vec::new();
+        if radius > 0.0 {
+            for region in regions.all() {
+                let bounds = &region.boundaries;
+                let center_x = (bounds.min_x + bounds.max_x) / 2.0;
+                let center_y = (bounds.min_y + bounds.max_y) / 2.0;
+                let half_width = (bounds.max_x - bounds.min_x) / 2.0;
+                let half_height = (bounds.max_y - bounds.min_y) / 2.0;
+
+                points.extend(CONTROL_POINTS.iter().map(|(name, x, y)| ControlPoint {
+                    name: name.to_string(),
+                    region: bounds.name.clone(),
+                    position: Position::new(center_x + x * half_width, center_y + y * half_height),
+                    radius,
+                    owner: None,
+                    capturing: None,
+                    progress_seconds: 0,
+                }));
+            }
+        }
+
+        Self {
+            points: Mutex::new(points),
+        }
+    }
+
+    /// Every control point, region by region in a fixed order
+    pub fn control_points(&self) -> Result<Vec<ControlPoint>> {
+        Ok(self.lock()?.clone())
+    }
+
+    /// Owner of the control point `position` lies in, if it lies in an owned one
+    pub fn owner_at(&self, position: &Position) -> Result<Option<Faction>> {
+        Ok(self
+            .lock()?
+            .iter()
+            .find(|point| position.distance_to(&point.position) <= point.radius)
+            .and_then(|point| point.owner.clone()))
+    }
+
+    /// Advance every control point by one second, given where players of which factions stand
+    pub fn update(
+        &self,
+        occupants: &[(Position, Faction)],
+        capture_seconds: u32,
+    ) -> Result<Vec<TerritoryChange>> {
+        let mut points = self.lock()?;
+        let mut changes = Vec::new();
+
+        for point in points.iter_mut() {
+            let present: BTreeSet<&Faction> = occupants
+                .iter()
+                .filter(|(position, _)| position.distance_to(&point.position) <= point.radius)
+                .map(|(_, faction)| faction)
+                .collect();
+
+            // Only a faction alone on the point can make progress
+            let mut factions = present.iter();
+            let challenger = match (factions.next(), factions.next()) {
+                (Some(faction), None) if **faction != Faction::Independent => {
+                    Some((*faction).clone())
+                }
+                _ => None,
+            };
+            let contested = present.len() > 1;
+
+            match challenger {
+                Some(faction) if point.owner.as_ref() != Some(&faction) => {
+                    if point.capturing.as_ref() != Some(&faction) {
+                        point.capturing = Some(faction.clone());
+                        point.progress_seconds = 0;
+                        changes.push(TerritoryChange::CaptureStarted {
+                            point: point.name.clone(),
+                            region: point.region.clone(),
+                            faction: faction.clone(),
+                        });
+                    }
+                    point.progress_seconds += 1;
+
+                    if point.progress_seconds >= capture_seconds {
+                        let previous = point.owner.replace(faction.clone());
+                        point.capturing = None;
+                        point.progress_seconds = 0;
+                        debug!("{} captured {}", faction.name(), point.name);
+                        changes.push(TerritoryChange::Captured {
+                            point: point.name.clone(),
+                            region: point.region.clone(),
+                            faction,
+                            previous,
+                        });
+                    }
+                }
+                // A fight over the point keeps the progress made so far
+                _ if contested
+                    && point
+                        .capturing
+                        .as_ref()
+                        .is_some_and(|f| present.contains(f)) => {}
+                _ => {
+                    point.capturing = None;
+                    point.progress_seconds = 0;
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Vec<ControlPoint>>> {
+        self.points
+            .lock()
+            .map_err(|_| anyhow!("Territory lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+
+    fn territory() -> Territory {
+        Territory::new(&Regions::from_config(&GameConfig::default()), 20.0)
+    }
+
+    fn core(territory: &Territory) -> ControlPoint {
+        territory.control_points().unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_control_points_follow_the_map() {
+        let points = territory().control_points().unwrap();
+        assert_eq!(points.len(), CONTROL_POINTS.len());
+        assert_eq!(points[0].position, Position::new(0.0, 0.0));
+        assert_eq!(points[1].position, Position::new(50.0, -50.0));
+
+        // Every region gets its own points
+        let regions = Regions::from_config(&GameConfig {
+            world_regions: vec!["Neon Harbor".to_string(), "Deep Net".to_string()],
+            ..Default::default()
+        });
+        let points = Territory::new(&regions, 20.0).control_points().unwrap();
+        assert_eq!(points.len(), 2 * CONTROL_POINTS.len());
+        assert_eq!(points[5].region, "Deep Net");
+        assert_eq!(points[5].position, Position::new(250.0, 0.0));
+
+        let disabled = Territory::new(&regions, 0.0);
+        assert!(disabled.control_points().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lone_faction_captures_point() {
+        let territory = territory();
+        let center = Position::new(0.0, 0.0);
+        let nyms = [(center, Faction::Nyms)];
+
+        assert_eq!(
+            territory.update(&nyms, 3).unwrap(),
+            vec![TerritoryChange::CaptureStarted {
+                point: "Mixnet Core".to_string(),
+                region: "Neon Harbor".to_string(),
+                faction: Faction::Nyms,
+            }]
+        );
+        assert!(territory.update(&nyms, 3).unwrap().is_empty());
+
+        // Someone else showing up freezes the progress
+        let contested = [(center, Faction::Nyms), (center, Faction::Independent)];
+        assert!(territory.update(&contested, 3).unwrap().is_empty());
+        assert_eq!(core(&territory).progress_seconds, 2);
+
+        assert_eq!(
+            territory.update(&nyms, 3).unwrap(),
+            vec![TerritoryChange::Captured {
+                point: "Mixnet Core".to_string(),
+                region: "Neon Harbor".to_string(),
+                faction: Faction::Nyms,
+                previous: None,
+            }]
+        );
+        assert_eq!(territory.owner_at(&center).unwrap(), Some(Faction::Nyms));
+        assert_eq!(
+            territory.owner_at(&Position::new(30.0, 30.0)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_progress_is_lost_when_capturers_leave() {
+        let territory = territory();
+        let center = Position::new(0.0, 0.0);
+
+        territory
+            .update(&[(center, Faction::CorporateHegemony)], 3)
+            .unwrap();
+        assert_eq!(core(&territory).progress_seconds, 1);
+        territory.update(&[], 3).unwrap();
+        assert_eq!(core(&territory).capturing, None);
+        assert_eq!(core(&territory).progress_seconds, 0);
+
+        // Independents alone never capture anything
+        for _ in 0..5 {
+            territory
+                .update(&[(center, Faction::Independent)], 3)
+                .unwrap();
+        }
+        assert_eq!(core(&territory).owner, None);
+        assert_eq!(core(&territory).capturing, None);
+    }
+}
@@ -0,0 +1,291 @@
+//! Player-to-player trades
+//!
+//! A trade opens once two players have asked to trade with each other. Each
+//! side then puts up credits and items, which the server moves out of their
+//! inventory into their escrow, persisted with the player, so nothing offered
+//! can be used, dropped or offered twice. Every change to an offer bumps the
+//! trade's revision and clears both confirmations, and a confirmation only
+//! counts for the revision it names, so nobody accepts an offer they have not
+//! seen. Once both sides confirmed the same revision, `GameState::complete_trade`
+//! swaps the escrows under a single write lock. A cancelled trade, or one whose
+//! other side left the game, hands each escrow back to its owner.
+
+use anyhow::{anyhow, Result};
+use std::sync::{Mutex, MutexGuard};
+use tracing::debug;
+
+/// A trade between two players, open or waiting for the second to agree
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    /// Player IDs of both sides; the first asked for the trade
+    pub parties: [String; 2],
+    /// Display IDs of both sides, for telling one about the other after they left
+    pub display_ids: [String; 2],
+    /// Whether the second player agreed to trade
+    pub open: bool,
+    /// Bumped whenever either offer changes
+    pub revision: u32,
+    /// Which sides confirmed the current revision
+    pub confirmed: [bool; 2],
+}
+
+impl Trade {
+    /// Index of `player_id` in `parties`, if they take part
+    pub fn side(&self, player_id: &str) -> Option<usize> {
+        self.parties.iter().position(|party| party == player_id)
+    }
+
+    /// Player ID and display ID of the other side from `player_id`
+    pub fn partner_of(&self, player_id: &str) -> Option<(&str, &str)> {
+        let other = 1 - self.side(player_id)?;
+        Some((&self.parties[other], &self.display_ids[other]))
+    }
+}
+
+/// What asking another player to trade did
+#[derive(Debug, Clone, PartialEq)]
+pub enum TradeRequest {
+    /// A new request is waiting for the other player to agree
+    Requested,
+    /// The other player had asked first, so the trade is now open
+    Opened(Trade),
+    /// The asking player is already in another trade
+    AlreadyTrading,
+    /// The other player is already in another trade
+    PartnerBusy,
+}
+
+/// What confirming an open trade did
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    /// The offers changed since the revision that was confirmed
+    Stale,
+    /// Confirmed; the other side has yet to
+    Waiting(Trade),
+    /// Both sides confirmed the current revision, so the swap is due
+    Ready(Trade),
+}
+
+/// Every trade in progress; a player takes part in at most one
+pub struct Trades {
+    trades: Mutex<Vec<Trade>>,
+}
+
+impl Default for Trades {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trades {
+    /// Create an empty trade book
+    pub fn new() -> Self {
+        Self {
+            trades: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The trade `player_id` takes part in, open or not
+    pub fn find(&self, player_id: &str) -> Result<Option<Trade>> {
+        Ok(self
+            .lock()?
+            .iter()
+            .find(|trade| trade.side(player_id).is_some())
+            .cloned())
+    }
+
+    /// Record that `from` asked `to` to trade, opening the trade if `to` had asked `from` first
+    pub fn request(
+        &self,
+        from_id: &str,
+        from_display_id: &str,
+        to_id: &str,
+        to_display_id: &str,
+    ) -> Result<TradeRequest> {
+        let mut trades = self.lock()?;
+
+        if let Some(trade) = trades
+            .iter_mut()
+            .find(|trade| trade.side(from_id).is_some())
+        {
+            if !trade.open && trade.parties[0] == to_id && trade.parties[1] == from_id {
+                trade.open = true;
+                debug!("Trade between {} and {} opened", to_id, from_id);
+                return Ok(TradeRequest::Opened(trade.clone()));
+            }
+            return Ok(TradeRequest::AlreadyTrading);
+        }
+        if trades.iter().any(|trade| trade.side(to_id).is_some()) {
+            return Ok(TradeRequest::PartnerBusy);
+        }
+
+        trades.push(Trade {
+            parties: [from_id.to_string(), to_id.to_string()],
+            display_ids: [from_display_id.to_string(), to_display_id.to_string()],
+            open: false,
+            revision: 0,
+            confirmed: [false; 2],
+        });
+        Ok(TradeRequest::Requested)
+    }
+
+    /// Start a new revision of the open trade of `player_id` after an offer changed
+    pub fn offer_changed(&self, player_id: &str) -> Result<Option<Trade>> {
+        let mut trades = self.lock()?;
+        Ok(Self::open_trade_mut(&mut trades, player_id).map(|trade| {
+            trade.revision += 1;
+            trade.confirmed = [false; 2];
+            trade.clone()
+        }))
+    }
+
+    /// Confirm revision `revision` of the open trade of `player_id`; `None` without one
+    pub fn confirm(&self, player_id: &str, revision: u32) -> Result<Option<Confirmation>> {
+        let mut trades = self.lock()?;
+        let Some(trade) = Self::open_trade_mut(&mut trades, player_id) else {
+            return Ok(None);
+        };
+        if trade.revision != revision {
+            return Ok(Some(Confirmation::Stale));
+        }
+
+        if let Some(side) = trade.side(player_id) {
+            trade.confirmed[side] = true;
+        }
+        if trade.confirmed == [true; 2] {
+            Ok(Some(Confirmation::Ready(trade.clone())))
+        } else {
+            Ok(Some(Confirmation::Waiting(trade.clone())))
+        }
+    }
+
+    /// Clear both confirmations of the open trade of `player_id`, e.g. after a failed swap
+    pub fn unconfirm(&self, player_id: &str) -> Result<Option<Trade>> {
+        let mut trades = self.lock()?;
+        Ok(Self::open_trade_mut(&mut trades, player_id).map(|trade| {
+            trade.confirmed = [false; 2];
+            trade.clone()
+        }))
+    }
+
+    /// Remove the trade `player_id` takes part in, open or not
+    pub fn remove(&self, player_id: &str) -> Result<Option<Trade>> {
+        let mut trades = self.lock()?;
+        let index = trades
+            .iter()
+            .position(|trade| trade.side(player_id).is_some());
+        Ok(index.map(|index| trades.swap_remove(index)))
+    }
+
+    /// Remove the trades one of whose sides no longer `exists`
+    pub fn remove_abandoned<F>(&self, exists: F) -> Result<Vec<Trade>>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut trades = self.lock()?;
+        let (abandoned, kept) = trades
+            .drain(..)
+            .partition(|trade| !trade.parties.iter().all(|party| exists(party)));
+        *trades = kept;
+        Ok(abandoned)
+    }
+
+    /// Number of trades in progress, open or not
+    pub fn trade_count(&self) -> usize {
+        self.lock().map(|trades| trades.len()).unwrap_or(0)
+    }
+
+    fn open_trade_mut<'a>(trades: &'a mut [Trade], player_id: &str) -> Option<&'a mut Trade> {
+        trades
+            .iter_mut()
+            .find(|trade| trade.open && trade.side(player_id).is_some())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Vec<Trade>>> {
+        self.trades
+            .lock()
+            .map_err(|_| anyhow!("Trade book lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trades_open_when_both_ask() {
+        let trades = Trades::new();
+        assert_eq!(
+            trades.request("a", "Player1", "b", "Player2").unwrap(),
+            TradeRequest::Requested
+        );
+        // Nobody else can trade with either side meanwhile
+        assert_eq!(
+            trades.request("c", "Player3", "b", "Player2").unwrap(),
+            TradeRequest::PartnerBusy
+        );
+        assert_eq!(
+            trades.request("a", "Player1", "c", "Player3").unwrap(),
+            TradeRequest::AlreadyTrading
+        );
+        // Offers need an open trade
+        assert!(trades.offer_changed("a").unwrap().is_none());
+
+        match trades.request("b", "Player2", "a", "Player1").unwrap() {
+            TradeRequest::Opened(trade) => {
+                assert_eq!(trade.partner_of("b"), Some(("a", "Player1")));
+                assert_eq!(trade.partner_of("a"), Some(("b", "Player2")));
+                assert_eq!(trade.partner_of("c"), None);
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(trades.trade_count(), 1);
+    }
+
+    #[test]
+    fn test_confirmations_follow_revisions() {
+        let trades = Trades::new();
+        trades.request("a", "Player1", "b", "Player2").unwrap();
+        trades.request("b", "Player2", "a", "Player1").unwrap();
+
+        let revision = trades.offer_changed("a").unwrap().unwrap().revision;
+        assert!(matches!(
+            trades.confirm("a", revision).unwrap(),
+            Some(Confirmation::Waiting(_))
+        ));
+
+        // A new offer clears the confirmation, and confirming the old revision does nothing
+        let trade = trades.offer_changed("b").unwrap().unwrap();
+        assert_eq!(trade.confirmed, [false; 2]);
+        assert_eq!(
+            trades.confirm("a", revision).unwrap(),
+            Some(Confirmation::Stale)
+        );
+
+        trades.confirm("a", trade.revision).unwrap();
+        assert!(matches!(
+            trades.confirm("b", trade.revision).unwrap(),
+            Some(Confirmation::Ready(_))
+        ));
+        assert_eq!(
+            trades.unconfirm("b").unwrap().unwrap().confirmed,
+            [false; 2]
+        );
+        assert!(trades.confirm("c", 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_abandoned_trades_are_removed() {
+        let trades = Trades::new();
+        trades.request("a", "Player1", "b", "Player2").unwrap();
+        trades.request("c", "Player3", "d", "Player4").unwrap();
+
+        let abandoned = trades.remove_abandoned(|party| party != "b").unwrap();
+        assert_eq!(abandoned.len(), 1);
+        assert_eq!(abandoned[0].parties, ["a".to_string(), "b".to_string()]);
+        assert!(trades.find("a").unwrap().is_none());
+
+        assert!(trades.remove("d").unwrap().is_some());
+        assert_eq!(trades.trade_count(), 0);
+    }
+}
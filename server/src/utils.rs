@@ -1,12 +1,12 @@
-use crate::message_auth::AuthKey;
 use anyhow::{Context, Result};
+use nymquest_protocol::session::ServerPublicKey;
 use std::fs::File;
 use std::io::Write;
 use tracing::info;
 
-/// Save the server address and authentication key using the cross-platform discovery mechanism
+/// Save the server address and public key using the cross-platform discovery mechanism
 /// This replaces the previous hardcoded path approach with a robust, production-ready solution
-pub fn save_server_address(address: &str, auth_key: &AuthKey) -> Result<()> {
+pub fn save_server_address(address: &str, public_key: &ServerPublicKey) -> Result<()> {
     use crate::discovery;
 
     // Get the appropriate file path using the discovery mechanism
@@ -23,18 +23,15 @@ pub fn save_server_address(address: &str, auth_key: &AuthKey) -> Result<()> {
     let mut file = File::create(&file_path)
         .with_context(|| format!("Failed to create server address file at {file_path:?}"))?;
 
-    // Write server address and authentication key in the standard format
-    writeln!(file, "{};{}", address, auth_key.to_base64())
-        .with_context(|| "Failed to write server address and auth key to file")?;
+    // Write server address and public key in the standard format
+    writeln!(file, "{};{}", address, public_key)
+        .with_context(|| "Failed to write server address and public key to file")?;
 
     // Ensure data is written to disk
     file.sync_all()
         .with_context(|| "Failed to sync server address file to disk")?;
 
-    info!(
-        "Server address and authentication key saved to: {:?}",
-        file_path
-    );
+    info!("Server address and public key saved to: {:?}", file_path);
 
     // Log discovery information for debugging
     info!(